@@ -0,0 +1,278 @@
+// achievements.rs - 成就系统：根据游戏事件判定解锁，游戏中弹出提示，并提供成就陈列界面
+use crate::difficulty::GameDifficulty;
+use crate::player_profile::{self, GameKind, GameResult};
+use crate::poker_hand::HandStrength;
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// 成就进度文件名
+const ACHIEVEMENTS_FILE: &str = "achievements.json";
+/// 解锁提示的显示时间
+const TOAST_DURATION: Duration = Duration::from_secs(4);
+/// 解锁提示开始淡出的时间
+const TOAST_FADE: Duration = Duration::from_millis(600);
+
+/// 游戏中产生的事件，由各游戏记录后交给主程序判定成就
+pub enum GameEvent {
+    /// 一局（德州扑克为一场）结束
+    Finished(GameResult),
+    /// 神经衰弱通关
+    MemoryCleared {
+        difficulty: GameDifficulty,
+        mismatches: usize, // 翻错的次数
+    },
+    /// 猜数字破解密码
+    CodeCracked {
+        difficulty: GameDifficulty,
+        attempts: usize,
+        hints_used: usize,
+    },
+    /// 德州扑克赢下一手（对手弃牌时没有牌型）
+    HandWon {
+        strength: Option<HandStrength>,
+        pot: u32,
+    },
+    /// 21点赢下一手
+    BlackjackHandWon {
+        blackjack: bool, // 是否以黑杰克获胜
+    },
+    /// 纸牌接龙（克朗代克、空当接龙、蜘蛛纸牌）通关
+    SolitaireCleared {
+        time_secs: Option<u64>,
+    },
+    /// 斗地主、锄大地、红心大战赢下一局
+    RoundWon {
+        game: GameKind,
+        spring: bool, // 斗地主是否打成春天或反春
+    },
+    /// 红心大战全收
+    MoonShot,
+}
+
+/// 成就定义
+pub struct Achievement {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub target: u32, // 解锁所需的进度
+}
+
+/// 全部成就
+pub const ACHIEVEMENTS: &[Achievement] = &[
+    Achievement { id: "first_win", name: "初出茅庐", description: "赢得任意一局游戏", target: 1 },
+    Achievement { id: "games_played", name: "百战老将", description: "累计完成 100 局游戏", target: 100 },
+    Achievement { id: "memory_flawless", name: "过目不忘", description: "困难难度神经衰弱零失误通关", target: 1 },
+    Achievement { id: "memory_speed", name: "闪电记忆", description: "30 秒内通关神经衰弱", target: 1 },
+    Achievement { id: "memory_clears", name: "记忆大师", description: "通关神经衰弱 10 次", target: 10 },
+    Achievement { id: "code_three", name: "神机妙算", description: "3 次以内破解密码", target: 1 },
+    Achievement { id: "code_no_hint", name: "独立破译", description: "困难难度不使用提示破解密码", target: 1 },
+    Achievement { id: "code_wins", name: "破译专家", description: "破解密码 20 次", target: 20 },
+    Achievement { id: "straight_flush", name: "天选之牌", description: "以同花顺赢下一手德州扑克", target: 1 },
+    Achievement { id: "big_pot", name: "孤注一掷", description: "赢下 300 筹码以上的底池", target: 1 },
+    Achievement { id: "holdem_hands", name: "牌桌常客", description: "德州扑克赢下 50 手", target: 50 },
+    Achievement { id: "holdem_bust", name: "横扫牌桌", description: "让 AI 的筹码归零", target: 1 },
+    Achievement { id: "blackjack_natural", name: "天生赢家", description: "以黑杰克赢下一手21点", target: 1 },
+    Achievement { id: "blackjack_hands", name: "庄家克星", description: "21点赢下 50 手", target: 50 },
+    Achievement { id: "solitaire_speed", name: "快手收牌", description: "5 分钟内通关任意一款纸牌接龙", target: 1 },
+    Achievement { id: "solitaire_clears", name: "接龙达人", description: "通关纸牌接龙 10 次", target: 10 },
+    Achievement { id: "doudizhu_spring", name: "春风得意", description: "斗地主赢下一局春天或反春", target: 1 },
+    Achievement { id: "round_wins", name: "牌桌霸主", description: "斗地主、锄大地、红心大战累计赢下 30 局", target: 30 },
+    Achievement { id: "moon_shot", name: "全收", description: "红心大战全收一次", target: 1 },
+];
+
+/// 根据事件计算各成就增加的进度
+fn progress_from_event(event: &GameEvent) -> Vec<(&'static str, u32)> {
+    let mut progress = Vec::new();
+    match event {
+        GameEvent::Finished(result) => {
+            progress.push(("games_played", 1));
+            if result.won {
+                progress.push(("first_win", 1));
+                match result.game {
+                    GameKind::Memory if result.time_secs.is_some_and(|secs| secs <= 30) => {
+                        progress.push(("memory_speed", 1));
+                    }
+                    GameKind::TexasHoldem => progress.push(("holdem_bust", 1)),
+                    _ => {}
+                }
+            }
+        }
+        GameEvent::MemoryCleared { difficulty, mismatches } => {
+            progress.push(("memory_clears", 1));
+            if *difficulty == GameDifficulty::Hard && *mismatches == 0 {
+                progress.push(("memory_flawless", 1));
+            }
+        }
+        GameEvent::CodeCracked { difficulty, attempts, hints_used } => {
+            progress.push(("code_wins", 1));
+            if *attempts <= 3 {
+                progress.push(("code_three", 1));
+            }
+            if *difficulty == GameDifficulty::Hard && *hints_used == 0 {
+                progress.push(("code_no_hint", 1));
+            }
+        }
+        GameEvent::HandWon { strength, pot } => {
+            progress.push(("holdem_hands", 1));
+            if *strength == Some(HandStrength::StraightFlush) {
+                progress.push(("straight_flush", 1));
+            }
+            if *pot >= 300 {
+                progress.push(("big_pot", 1));
+            }
+        }
+        GameEvent::BlackjackHandWon { blackjack } => {
+            progress.push(("blackjack_hands", 1));
+            if *blackjack {
+                progress.push(("blackjack_natural", 1));
+            }
+        }
+        GameEvent::SolitaireCleared { time_secs } => {
+            progress.push(("solitaire_clears", 1));
+            if time_secs.is_some_and(|secs| secs <= 300) {
+                progress.push(("solitaire_speed", 1));
+            }
+        }
+        GameEvent::RoundWon { game, spring } => {
+            progress.push(("round_wins", 1));
+            if *game == GameKind::DouDizhu && *spring {
+                progress.push(("doudizhu_spring", 1));
+            }
+        }
+        GameEvent::MoonShot => progress.push(("moon_shot", 1)),
+    }
+    progress
+}
+
+/// 成就进度（保存到本地）和解锁提示
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Achievements {
+    progress: BTreeMap<String, u32>,
+    #[serde(skip)]
+    toasts: VecDeque<(usize, Instant)>, // 正在显示的解锁提示（成就序号，解锁时间）
+}
+
+impl Achievements {
+    /// 读取成就进度
+    pub fn load() -> Self {
+        player_profile::load_data_file(ACHIEVEMENTS_FILE)
+    }
+
+    /// 保存成就进度
+    pub fn save(&self) -> std::io::Result<()> {
+        player_profile::save_data_file(ACHIEVEMENTS_FILE, self)
+    }
+
+    /// 某个成就的当前进度
+    fn progress_of(&self, achievement: &Achievement) -> u32 {
+        self.progress.get(achievement.id).copied().unwrap_or(0).min(achievement.target)
+    }
+
+    /// 处理一个游戏事件，返回进度是否有变化
+    pub fn handle_event(&mut self, event: &GameEvent) -> bool {
+        let mut changed = false;
+        for (id, amount) in progress_from_event(event) {
+            let Some(index) = ACHIEVEMENTS.iter().position(|achievement| achievement.id == id) else {
+                continue;
+            };
+            let achievement = &ACHIEVEMENTS[index];
+            let before = self.progress_of(achievement);
+            if before >= achievement.target {
+                continue;
+            }
+            let after = (before + amount).min(achievement.target);
+            self.progress.insert(id.to_string(), after);
+            changed = true;
+            if after >= achievement.target {
+                self.toasts.push_back((index, Instant::now()));
+            }
+        }
+        changed
+    }
+
+    /// 已解锁的成就数量
+    fn unlocked_count(&self) -> usize {
+        ACHIEVEMENTS
+            .iter()
+            .filter(|achievement| self.progress_of(achievement) >= achievement.target)
+            .count()
+    }
+
+    /// 在窗口右上角显示解锁提示（游戏进行中也会显示）
+    pub fn show_toasts(&mut self, ctx: &egui::Context) {
+        self.toasts.retain(|(_, since)| since.elapsed() < TOAST_DURATION);
+
+        for (slot, &(index, since)) in self.toasts.iter().enumerate() {
+            let achievement = &ACHIEVEMENTS[index];
+            let remaining = TOAST_DURATION.saturating_sub(since.elapsed());
+            let opacity = (remaining.as_secs_f32() / TOAST_FADE.as_secs_f32()).min(1.0);
+
+            egui::Area::new(egui::Id::new(("achievement_toast", slot)))
+                .order(egui::Order::Foreground)
+                .anchor(egui::Align2::RIGHT_TOP, egui::vec2(-20.0, 20.0 + slot as f32 * 70.0))
+                .interactable(false)
+                .show(ctx, |ui| {
+                    ui.multiply_opacity(opacity);
+                    egui::Frame::popup(ui.style()).show(ui, |ui| {
+                        ui.set_width(220.0);
+                        ui.colored_label(egui::Color32::GOLD, format!("🏆 成就解锁：{}", achievement.name));
+                        ui.label(achievement.description);
+                    });
+                });
+        }
+
+        if !self.toasts.is_empty() {
+            ctx.request_repaint_after(Duration::from_millis(50));
+        }
+    }
+
+    /// 显示成就陈列界面，返回是否要返回主菜单
+    pub fn show(&self, ui: &mut egui::Ui) -> bool {
+        let mut return_to_menu = false;
+
+        ui.vertical_centered(|ui| {
+            ui.add_space(20.0);
+            ui.heading("成就");
+            ui.add_space(10.0);
+            ui.label(format!("已解锁 {}/{}", self.unlocked_count(), ACHIEVEMENTS.len()));
+            ui.add_space(20.0);
+
+            egui::ScrollArea::vertical().max_height(ui.available_height() - 80.0).show(ui, |ui| {
+                for achievement in ACHIEVEMENTS {
+                    let progress = self.progress_of(achievement);
+                    let unlocked = progress >= achievement.target;
+
+                    ui.horizontal(|ui| {
+                        ui.add_space(ui.available_width() / 2.0 - 200.0);
+                        egui::Frame::group(ui.style()).show(ui, |ui| {
+                            ui.set_width(400.0);
+                            ui.vertical(|ui| {
+                                if unlocked {
+                                    ui.colored_label(egui::Color32::GOLD, format!("🏆 {}", achievement.name));
+                                } else {
+                                    ui.colored_label(egui::Color32::GRAY, achievement.name);
+                                }
+                                ui.label(achievement.description);
+                                ui.add(
+                                    egui::ProgressBar::new(progress as f32 / achievement.target as f32)
+                                        .text(format!("{}/{}", progress, achievement.target)),
+                                );
+                            });
+                        });
+                    });
+                    ui.add_space(8.0);
+                }
+            });
+
+            ui.add_space(10.0);
+            if ui.add_sized([150.0, 40.0], egui::Button::new("返回主菜单")).clicked() {
+                return_to_menu = true;
+            }
+        });
+
+        return_to_menu
+    }
+}
@@ -0,0 +1,62 @@
+// animation.rs
+use std::time::{Duration, Instant};
+
+/// 翻牌动画时长
+pub const FLIP_DURATION: Duration = Duration::from_millis(300);
+/// 配对成功后飞走动画时长
+pub const FLY_AWAY_DURATION: Duration = Duration::from_millis(450);
+
+/// 卡片动画类型
+#[derive(PartialEq, Clone, Copy)]
+pub enum CardAnimationKind {
+    Flip,    // 水平压缩翻面
+    FlyAway, // 向上飞走并淡出
+}
+
+/// 卡片动画
+#[derive(Clone, Copy)]
+pub struct CardAnimation {
+    pub kind: CardAnimationKind,
+    started: Instant,
+    duration: Duration,
+}
+
+impl CardAnimation {
+    /// 创建翻牌动画
+    pub fn flip() -> Self {
+        Self {
+            kind: CardAnimationKind::Flip,
+            started: Instant::now(),
+            duration: FLIP_DURATION,
+        }
+    }
+
+    /// 创建飞走动画
+    pub fn fly_away() -> Self {
+        Self {
+            kind: CardAnimationKind::FlyAway,
+            started: Instant::now(),
+            duration: FLY_AWAY_DURATION,
+        }
+    }
+
+    /// 动画进度（0.0 ~ 1.0）
+    pub fn progress(&self) -> f32 {
+        (self.started.elapsed().as_secs_f32() / self.duration.as_secs_f32()).min(1.0)
+    }
+
+    /// 动画是否已经结束
+    pub fn is_finished(&self) -> bool {
+        self.started.elapsed() >= self.duration
+    }
+}
+
+/// 翻牌动画的水平缩放比例：前半段从1压缩到0，后半段从0展开到1
+pub fn flip_scale(progress: f32) -> f32 {
+    (1.0 - 2.0 * progress).abs()
+}
+
+/// 翻牌动画是否仍在显示翻面前的一面
+pub fn flip_shows_old_face(progress: f32) -> bool {
+    progress < 0.5
+}
@@ -0,0 +1,509 @@
+// bigtwo.rs - 锄大地的牌型规则：牌的大小、牌型识别与比较、出牌限制和可出牌枚举，以及 AI 出牌策略
+use crate::poker_hand::{self, HandStrength};
+use crate::solitaire::{card_id, card_name, rank_of, suit_of};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// 持有方片3的一家先出，第一手必须包含它
+pub fn opening_card() -> usize {
+    card_id(3, 3)
+}
+
+/// 点数的大小：3 最小，然后 4 …… K、A，2 最大
+fn rank_order(rank: u8) -> usize {
+    (rank as usize + 10) % 13
+}
+
+/// 花色的大小：方片 < 梅花 < 红桃 < 黑桃
+fn suit_order(suit: u8) -> usize {
+    match suit {
+        3 => 0,
+        4 => 1,
+        2 => 2,
+        _ => 3,
+    }
+}
+
+/// 牌的大小（0..52，方片3 为 0，黑桃2 为 51），先比点数再比花色
+pub fn card_key(card: usize) -> usize {
+    rank_order(rank_of(card)) * 4 + suit_order(suit_of(card))
+}
+
+/// 手牌排序：从小到大
+pub fn sort_hand(hand: &mut [usize]) {
+    hand.sort_by_key(|&card| card_key(card));
+}
+
+/// 牌型（五张牌型按顺子 < 同花 < 葫芦 < 四带一 < 同花顺排列）
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum ComboKind {
+    Single,        // 单张
+    Pair,          // 对子
+    Triple,        // 三条
+    Straight,      // 顺子
+    Flush,         // 同花
+    FullHouse,     // 葫芦（三条带一对）
+    FourOfAKind,   // 四带一（铁支）
+    StraightFlush, // 同花顺
+}
+
+impl ComboKind {
+    /// 牌型的显示名称
+    pub fn label(self) -> &'static str {
+        match self {
+            ComboKind::Single => "单张",
+            ComboKind::Pair => "对子",
+            ComboKind::Triple => "三条",
+            ComboKind::Straight => "顺子",
+            ComboKind::Flush => "同花",
+            ComboKind::FullHouse => "葫芦",
+            ComboKind::FourOfAKind => "四带一",
+            ComboKind::StraightFlush => "同花顺",
+        }
+    }
+}
+
+/// 一手牌的牌型
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Combo {
+    pub kind: ComboKind,
+    pub top: usize, // 比较大小的关键牌（葫芦、四带一为三条、四条中最大的一张，其余为最大的一张）
+    pub len: usize, // 张数
+}
+
+impl Combo {
+    /// 能否压过另一手牌：张数必须相同，五张牌先比牌型再比关键牌
+    pub fn beats(&self, other: &Combo) -> bool {
+        self.len == other.len && (self.kind, card_key(self.top)) > (other.kind, card_key(other.top))
+    }
+
+    /// 牌型的描述，如“对子（最大 黑桃K）”
+    pub fn describe(&self) -> String {
+        format!("{}（最大 {}）", self.kind.label(), card_name(self.top))
+    }
+}
+
+/// 一组牌中最大的一张
+fn highest(cards: impl IntoIterator<Item = usize>) -> Option<usize> {
+    cards.into_iter().max_by_key(|&card| card_key(card))
+}
+
+/// 识别一组牌的牌型，不是合法牌型时返回 None
+///
+/// 五张牌的牌型复用德州扑克的 poker_hand 判断：顺子可以是 A-2-3-4-5 到 10-J-Q-K-A，不能绕过 K-A（如 J-Q-K-A-2）。
+pub fn classify(cards: &[usize]) -> Option<Combo> {
+    let len = cards.len();
+    let same_rank = cards.iter().all(|&card| rank_of(card) == rank_of(cards[0]));
+    let kind = match len {
+        1 => ComboKind::Single,
+        2 if same_rank => ComboKind::Pair,
+        3 if same_rank => ComboKind::Triple,
+        5 => {
+            let ranks_suits: Vec<(u8, u8)> = cards.iter().map(|&card| (rank_of(card), suit_of(card))).collect();
+            let result = poker_hand::evaluate_five_card_hand(&ranks_suits);
+            let kind = match result.hand_strength {
+                HandStrength::Straight => ComboKind::Straight,
+                HandStrength::Flush => ComboKind::Flush,
+                HandStrength::FullHouse => ComboKind::FullHouse,
+                HandStrength::FourOfAKind => ComboKind::FourOfAKind,
+                HandStrength::StraightFlush => ComboKind::StraightFlush,
+                _ => return None,
+            };
+            // 葫芦、四带一比三条或四条；顺子比顺序上最大的一张（A-2-3-4-5 比 5，10-J-Q-K-A 比 A）
+            let main_rank = match kind {
+                ComboKind::FullHouse | ComboKind::FourOfAKind => result.high_cards[0],
+                ComboKind::Straight | ComboKind::StraightFlush => {
+                    let ranks: Vec<u8> = cards.iter().map(|&card| rank_of(card)).collect();
+                    if ranks.contains(&1) && ranks.contains(&13) { 1 } else { *ranks.iter().max()? }
+                }
+                _ => return Some(Combo { kind, top: highest(cards.iter().copied())?, len }),
+            };
+            let top = highest(cards.iter().copied().filter(|&card| rank_of(card) == main_rank))?;
+            return Some(Combo { kind, top, len });
+        }
+        _ => return None,
+    };
+    Some(Combo { kind, top: highest(cards.iter().copied())?, len })
+}
+
+/// 出牌时的限制
+#[derive(Clone, Copy)]
+pub struct PlayRules {
+    pub to_beat: Option<Combo>,      // 要压的牌（None 表示自由出牌）
+    pub must_include: Option<usize>, // 必须包含的牌（第一手的方片3）
+    pub highest_single: bool,        // 下家报单：出单张时必须出手中最大的一张
+}
+
+impl PlayRules {
+    /// 检查一组牌能否出，能出时返回牌型
+    pub fn check(&self, hand: &[usize], cards: &[usize]) -> Result<Combo, &'static str> {
+        let combo = classify(cards).ok_or("选中的牌不是有效的牌型")?;
+        if let Some(card) = self.must_include
+            && !cards.contains(&card)
+        {
+            return Err("第一手必须包含方片3");
+        }
+        if let Some(last) = &self.to_beat
+            && !combo.beats(last)
+        {
+            return Err("压不过上一手（张数必须相同）");
+        }
+        if self.highest_single && combo.kind == ComboKind::Single && highest(hand.iter().copied()) != Some(combo.top) {
+            return Err("下家只剩一张牌，出单张必须出最大的一张");
+        }
+        Ok(combo)
+    }
+}
+
+/// 从 cards 中取 k 张的所有组合
+fn combinations(cards: &[usize], k: usize) -> Vec<Vec<usize>> {
+    if k == 0 {
+        return vec![Vec::new()];
+    }
+    if cards.len() < k {
+        return Vec::new();
+    }
+    let mut result: Vec<Vec<usize>> = combinations(&cards[1..], k - 1)
+        .into_iter()
+        .map(|mut rest| {
+            rest.insert(0, cards[0]);
+            rest
+        })
+        .collect();
+    result.extend(combinations(&cards[1..], k));
+    result
+}
+
+/// 列出手牌中所有能出的牌，按张数、牌型和大小排序
+pub fn candidate_plays(hand: &[usize], rules: &PlayRules) -> Vec<Vec<usize>> {
+    let mut sorted = hand.to_vec();
+    sort_hand(&mut sorted);
+
+    let mut shapes: Vec<Vec<usize>> = sorted.iter().map(|&card| vec![card]).collect();
+    for rank in 1..=13 {
+        let same: Vec<usize> = sorted.iter().copied().filter(|&card| rank_of(card) == rank).collect();
+        shapes.extend(combinations(&same, 2));
+        shapes.extend(combinations(&same, 3));
+    }
+    shapes.extend(combinations(&sorted, 5));
+
+    let mut plays: Vec<(Combo, Vec<usize>)> = shapes
+        .into_iter()
+        .filter(|shape| rules.to_beat.is_none_or(|last| last.len == shape.len()))
+        .filter_map(|shape| rules.check(hand, &shape).ok().map(|combo| (combo, shape)))
+        .collect();
+    plays.sort_by_key(|(combo, _)| (combo.len, combo.kind, card_key(combo.top)));
+    plays.into_iter().map(|(_, cards)| cards).collect()
+}
+
+/// 粗略估计出完这手牌还需要几手（越少越好），用于 AI 比较不同的出法
+pub fn estimate_turns(hand: &[usize]) -> usize {
+    let mut counts = [0usize; 13];
+    for &card in hand {
+        counts[rank_order(rank_of(card))] += 1;
+    }
+    let mut turns = 0;
+
+    // 顺子：3~7 到 10~A，单张占多数时拆出来作为一手
+    let mut found = true;
+    while found {
+        found = false;
+        for start in 0..=7 {
+            let window = start..start + 5;
+            let singles = window.clone().filter(|&order| counts[order] == 1).count();
+            if window.clone().all(|order| counts[order] > 0) && singles >= 3 {
+                for order in window {
+                    counts[order] -= 1;
+                }
+                turns += 1;
+                found = true;
+                break;
+            }
+        }
+    }
+
+    // 同一花色有 5 张以上单张时可以组成同花
+    let mut singles = counts.iter().filter(|&&count| count == 1).count();
+    for suit in 1..=4 {
+        let suited = hand
+            .iter()
+            .filter(|&&card| suit_of(card) == suit && counts[rank_order(rank_of(card))] == 1)
+            .count();
+        if suited >= 5 && singles >= 5 {
+            singles -= 5;
+            turns += 1;
+        }
+    }
+
+    // 四条带走一张单牌，三条和对子组成葫芦
+    let quads = counts.iter().filter(|&&count| count == 4).count();
+    let trios = counts.iter().filter(|&&count| count == 3).count();
+    let pairs = counts.iter().filter(|&&count| count == 2).count();
+    let singles = singles.saturating_sub(quads);
+    turns + quads + trios + pairs + singles - trios.min(pairs)
+}
+
+/// AI 出牌时能看到的局面
+pub struct AiView<'a> {
+    pub hand: &'a [usize],      // AI 自己的手牌
+    pub seat: usize,            // AI 的座位
+    pub hand_sizes: [usize; 4], // 四家剩余张数
+    pub rules: PlayRules,       // 出牌限制
+    pub played: &'a [usize],    // 已经打出的牌（记牌用）
+    pub skill: f32,             // AI 水平（0~1）
+}
+
+impl AiView<'_> {
+    /// 其他三家中最少的剩余张数
+    fn opponent_min_cards(&self) -> usize {
+        (0..4).filter(|&seat| seat != self.seat).map(|seat| self.hand_sizes[seat]).min().unwrap_or(13)
+    }
+
+    /// 根据记牌判断一手单张或对子是否已经是场上最大（其他牌型不做判断）
+    fn is_unbeatable(&self, combo: &Combo) -> bool {
+        let seen = |card: usize| self.hand.contains(&card) || self.played.contains(&card);
+        let unseen: Vec<usize> = (0..52).filter(|&card| !seen(card)).collect();
+        match combo.kind {
+            ComboKind::Single => unseen.iter().all(|&card| card_key(card) < card_key(combo.top)),
+            ComboKind::Pair => (1..=13).all(|rank| {
+                let cards: Vec<usize> = unseen.iter().copied().filter(|&card| rank_of(card) == rank).collect();
+                cards.len() < 2 || rank_order(rank) < rank_order(rank_of(combo.top))
+            }),
+            _ => false,
+        }
+    }
+}
+
+/// 从手牌中去掉要出的牌
+fn remaining(hand: &[usize], play: &[usize]) -> Vec<usize> {
+    hand.iter().copied().filter(|card| !play.contains(card)).collect()
+}
+
+/// AI 出牌：返回要出的牌，None 表示不出
+///
+/// 水平低的 AI 优先甩掉最小的牌、偶尔放过能压的牌；水平越高越会按出牌后剩余手数选择出法，
+/// 必要时主动不出保留牌型，最高水平还会记牌，留着 2 到最后，有人快出完时顶大牌。
+pub fn choose_play(view: &AiView) -> Option<Vec<usize>> {
+    let candidates = candidate_plays(view.hand, &view.rules);
+    if let Some(all) = candidates.iter().find(|play| play.len() == view.hand.len()) {
+        return Some(all.clone());
+    }
+    let leading = view.rules.to_beat.is_none();
+    if candidates.is_empty() {
+        return None;
+    }
+
+    if view.skill < 0.4 {
+        if !leading && view.hand.len() > 4 && rand::rng().random_bool(0.15) {
+            return None;
+        }
+        // 跟牌出最小的，自由出牌时把最小的牌连同能组成的最长牌型一起出
+        if !leading {
+            return candidates.into_iter().next();
+        }
+        // 下家报单时最小的单张可能出不了，这时出最小的一种出法
+        let lowest = *view.hand.iter().min_by_key(|&&card| card_key(card))?;
+        let with_lowest = candidates.iter().filter(|play| play.contains(&lowest)).max_by_key(|play| play.len());
+        return with_lowest.or(candidates.first()).cloned();
+    }
+
+    let opponent_min = view.opponent_min_cards();
+    let (best_score, best) = candidates
+        .into_iter()
+        .map(|play| (score_play(view, &play, leading, opponent_min), play))
+        .max_by(|a, b| a.0.total_cmp(&b.0))?;
+
+    // 不出的代价：有人快出完时必须压
+    if !leading {
+        let pass_penalty = if opponent_min <= 3 { 25.0 } else { 4.0 };
+        let pass_score = -(estimate_turns(view.hand) as f32) * 10.0 - pass_penalty;
+        if best_score < pass_score {
+            return None;
+        }
+    }
+    Some(best)
+}
+
+/// 给一种出法打分：出完后剩余手数越少越好，尽量保留大牌
+fn score_play(view: &AiView, play: &[usize], leading: bool, opponent_min: usize) -> f32 {
+    let Some(combo) = classify(play) else {
+        return f32::MIN;
+    };
+    let rest = remaining(view.hand, play);
+    let strength = (card_key(combo.top) / 4) as f32;
+    let mut score = -(estimate_turns(&rest) as f32) * 10.0;
+    score -= strength * if leading { 0.5 } else { 1.0 };
+    if leading {
+        score += play.len() as f32;
+    }
+
+    // 有人快出完时不出他能接的小单张、小对子
+    let risky = match combo.kind {
+        ComboKind::Single => opponent_min == 1,
+        ComboKind::Pair => opponent_min <= 2,
+        _ => false,
+    };
+    if leading && risky {
+        let safe = if view.skill >= 0.75 {
+            view.is_unbeatable(&combo)
+        } else {
+            rest.iter().all(|&card| card_key(card) < card_key(combo.top))
+        };
+        if !safe {
+            score -= 30.0;
+        }
+    }
+
+    if view.skill >= 0.75 {
+        // 手牌还多时留着 2
+        let twos = play.iter().filter(|&&card| rank_of(card) == 2).count();
+        if rest.len() > 4 {
+            score -= twos as f32 * 8.0;
+        }
+        if !leading && opponent_min <= 2 {
+            score += strength * 3.0;
+        }
+        if leading && !rest.is_empty() && view.is_unbeatable(&combo) {
+            score += 3.0;
+        }
+    }
+    score
+}
+
+/// 一局结束时剩余张数的扣分：不足 10 张每张 1 分，10~12 张翻倍，13 张（一张没出）三倍
+pub fn penalty(cards_left: usize) -> i32 {
+    let cards = cards_left as i32;
+    match cards_left {
+        0..=9 => cards,
+        10..=12 => cards * 2,
+        _ => cards * 3,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 按 (点数, 花色) 列出一组牌，花色 1 黑桃、2 红桃、3 方片、4 梅花
+    fn cards(list: &[(u8, u8)]) -> Vec<usize> {
+        list.iter().map(|&(rank, suit)| card_id(rank, suit)).collect()
+    }
+
+    fn combo(list: &[(u8, u8)]) -> Combo {
+        classify(&cards(list)).expect("应为有效牌型")
+    }
+
+    #[test]
+    fn card_order() {
+        assert_eq!(card_key(opening_card()), 0);
+        assert_eq!(card_key(card_id(2, 1)), 51);
+        // 同点数比花色：方片 < 梅花 < 红桃 < 黑桃
+        let keys: Vec<usize> = cards(&[(9, 3), (9, 4), (9, 2), (9, 1)]).into_iter().map(card_key).collect();
+        assert!(keys.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(card_key(card_id(1, 1)) < card_key(card_id(2, 3)));
+    }
+
+    #[test]
+    fn classify_every_kind() {
+        use ComboKind::*;
+        let kind_of = |list: &[(u8, u8)]| classify(&cards(list)).map(|combo| (combo.kind, combo.top, combo.len));
+        assert_eq!(kind_of(&[(7, 2)]), Some((Single, card_id(7, 2), 1)));
+        assert_eq!(kind_of(&[(7, 2), (7, 3)]), Some((Pair, card_id(7, 2), 2)));
+        assert_eq!(kind_of(&[(7, 2), (7, 3), (7, 1)]), Some((Triple, card_id(7, 1), 3)));
+        assert_eq!(kind_of(&[(5, 1), (6, 2), (7, 3), (8, 4), (9, 2)]), Some((Straight, card_id(9, 2), 5)));
+        assert_eq!(kind_of(&[(2, 4), (6, 4), (9, 4), (11, 4), (13, 4)]), Some((Flush, card_id(2, 4), 5)));
+        assert_eq!(kind_of(&[(4, 1), (4, 2), (4, 3), (12, 1), (12, 4)]), Some((FullHouse, card_id(4, 1), 5)));
+        assert_eq!(kind_of(&[(10, 1), (10, 2), (10, 3), (10, 4), (3, 3)]), Some((FourOfAKind, card_id(10, 1), 5)));
+        assert_eq!(kind_of(&[(5, 2), (6, 2), (7, 2), (8, 2), (9, 2)]), Some((StraightFlush, card_id(9, 2), 5)));
+    }
+
+    #[test]
+    fn classify_rejects_illegal_hands() {
+        assert_eq!(classify(&[]), None);
+        assert_eq!(classify(&cards(&[(7, 2), (8, 2)])), None);
+        assert_eq!(classify(&cards(&[(7, 2), (7, 3), (8, 2)])), None);
+        assert_eq!(classify(&cards(&[(7, 1), (7, 2), (7, 3), (7, 4)])), None);
+        // 两对、三条带两张单牌都不是五张牌型
+        assert_eq!(classify(&cards(&[(7, 1), (7, 2), (8, 3), (8, 4), (9, 1)])), None);
+        assert_eq!(classify(&cards(&[(7, 1), (7, 2), (7, 3), (8, 4), (9, 1)])), None);
+        // 顺子不能绕过 K-A
+        assert_eq!(classify(&cards(&[(11, 1), (12, 2), (13, 3), (1, 4), (2, 1)])), None);
+    }
+
+    #[test]
+    fn straights_rank_by_their_top_card() {
+        let wheel = combo(&[(1, 1), (2, 1), (3, 2), (4, 3), (5, 4)]);
+        let six_high = combo(&[(2, 3), (3, 2), (4, 3), (5, 4), (6, 3)]);
+        let broadway = combo(&[(10, 3), (11, 3), (12, 2), (13, 4), (1, 3)]);
+        assert_eq!(wheel.top, card_id(5, 4));
+        assert_eq!(six_high.top, card_id(6, 3));
+        assert_eq!(broadway.top, card_id(1, 3));
+        assert!(six_high.beats(&wheel));
+        assert!(broadway.beats(&six_high));
+        assert!(!wheel.beats(&six_high));
+        assert!(!six_high.beats(&broadway));
+    }
+
+    #[test]
+    fn five_card_kinds_are_ordered() {
+        let straight = combo(&[(9, 1), (10, 1), (11, 1), (12, 1), (13, 2)]);
+        let flush = combo(&[(3, 3), (5, 3), (7, 3), (9, 3), (11, 3)]);
+        let full_house = combo(&[(3, 1), (3, 2), (3, 4), (4, 1), (4, 2)]);
+        let quads = combo(&[(3, 1), (3, 2), (3, 3), (3, 4), (4, 1)]);
+        let straight_flush = combo(&[(3, 4), (4, 4), (5, 4), (6, 4), (7, 4)]);
+        let ordered = [straight, flush, full_house, quads, straight_flush];
+        for (index, lower) in ordered.iter().enumerate() {
+            for higher in &ordered[index + 1..] {
+                assert!(higher.beats(lower), "{} 应压过 {}", higher.describe(), lower.describe());
+                assert!(!lower.beats(higher), "{} 不应压过 {}", lower.describe(), higher.describe());
+            }
+        }
+    }
+
+    #[test]
+    fn beats_requires_same_length_and_compares_suits() {
+        let spade_pair = combo(&[(13, 1), (13, 3)]);
+        let heart_pair = combo(&[(13, 2), (13, 4)]);
+        assert!(spade_pair.beats(&heart_pair));
+        assert!(!heart_pair.beats(&spade_pair));
+        assert!(!spade_pair.beats(&spade_pair));
+        assert!(combo(&[(2, 3)]).beats(&combo(&[(1, 1)])));
+        assert!(!combo(&[(2, 1), (2, 2)]).beats(&combo(&[(3, 3)])));
+        assert!(!combo(&[(2, 1), (2, 2), (2, 3)]).beats(&combo(&[(3, 3), (3, 4)])));
+    }
+
+    #[test]
+    fn first_play_must_include_three_of_diamonds() {
+        let hand = cards(&[(3, 3), (3, 4), (4, 1), (9, 2)]);
+        let rules = PlayRules { to_beat: None, must_include: Some(opening_card()), highest_single: false };
+        assert!(rules.check(&hand, &cards(&[(4, 1)])).is_err());
+        assert!(rules.check(&hand, &cards(&[(3, 4)])).is_err());
+        assert_eq!(rules.check(&hand, &cards(&[(3, 3)])).map(|combo| combo.kind), Ok(ComboKind::Single));
+        assert_eq!(rules.check(&hand, &cards(&[(3, 3), (3, 4)])).map(|combo| combo.kind), Ok(ComboKind::Pair));
+    }
+
+    #[test]
+    fn single_must_be_highest_when_next_player_has_one_card() {
+        let hand = cards(&[(5, 3), (5, 1), (9, 2), (2, 4)]);
+        let rules = PlayRules { to_beat: None, must_include: None, highest_single: true };
+        assert!(rules.check(&hand, &cards(&[(9, 2)])).is_err());
+        assert!(rules.check(&hand, &cards(&[(2, 4)])).is_ok());
+        // 出对子不受限制
+        assert!(rules.check(&hand, &cards(&[(5, 3), (5, 1)])).is_ok());
+
+        let to_beat = PlayRules { to_beat: Some(combo(&[(8, 1)])), ..rules };
+        assert!(to_beat.check(&hand, &cards(&[(9, 2)])).is_err());
+        assert!(to_beat.check(&hand, &cards(&[(2, 4)])).is_ok());
+        assert!(to_beat.check(&hand, &cards(&[(5, 1)])).is_err());
+    }
+
+    #[test]
+    fn penalty_multiplies_for_many_cards() {
+        assert_eq!(penalty(0), 0);
+        assert_eq!(penalty(1), 1);
+        assert_eq!(penalty(9), 9);
+        assert_eq!(penalty(10), 20);
+        assert_eq!(penalty(12), 24);
+        assert_eq!(penalty(13), 39);
+    }
+}
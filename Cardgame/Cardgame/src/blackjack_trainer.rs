@@ -0,0 +1,779 @@
+// blackjack_trainer.rs - 21点训练：基本策略练习和 Hi-Lo 算牌练习，正确率保存到本地
+use crate::card::Card;
+use crate::game4::{self, card_value, hand_total};
+use crate::player_profile;
+use crate::util::get_card_image_path;
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+/// 训练数据文件名
+const TRAINER_FILE: &str = "trainer.json";
+/// 卡片背面图片
+const CARD_BACK_PATH: &str = "assets/card_back/default.png";
+/// 卡片显示大小
+const CARD_SIZE: egui::Vec2 = egui::vec2(80.0, 120.0);
+/// 基本策略练习：牌靴剩余不足这些牌时重新洗牌
+const STRATEGY_RESHUFFLE: usize = 20;
+/// 算牌练习的切牌位置（发出这一比例的牌后重新洗牌）
+const COUNT_PENETRATION: f32 = 0.75;
+/// 统计界面列出的常见错误个数
+const TOP_MISTAKES: usize = 5;
+
+/// 玩家的决策
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Decision {
+    Hit,       // 要牌
+    Stand,     // 停牌
+    Double,    // 加倍
+    Split,     // 分牌
+    Surrender, // 投降
+}
+
+impl Decision {
+    /// 决策的显示名称
+    pub fn label(self) -> &'static str {
+        match self {
+            Decision::Hit => "要牌",
+            Decision::Stand => "停牌",
+            Decision::Double => "加倍",
+            Decision::Split => "分牌",
+            Decision::Surrender => "投降",
+        }
+    }
+}
+
+/// 策略表中的一格：不能加倍时退而求其次
+enum ChartEntry {
+    Hit,
+    Stand,
+    DoubleOrHit,
+    DoubleOrStand,
+}
+
+/// 训练使用的规则
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TrainerRules {
+    pub decks: u32,                // 牌靴中的牌副数
+    pub dealer_hits_soft_17: bool, // 庄家软17是否要牌
+    pub double_after_split: bool,  // 分牌后能否加倍（影响分牌策略）
+    pub surrender_allowed: bool,   // 是否允许投降
+}
+
+impl Default for TrainerRules {
+    fn default() -> Self {
+        Self {
+            decks: 6,
+            dealer_hits_soft_17: false,
+            double_after_split: true,
+            surrender_allowed: true,
+        }
+    }
+}
+
+/// 庄家明牌的点数（A 记为 11）
+fn dealer_value(rank: u8) -> u32 {
+    if rank == 1 { 11 } else { card_value(rank) }
+}
+
+/// 按基本策略给出决策，单副和双副牌在多副牌策略上加入各自的偏差
+/// ranks 为玩家手牌，dealer_rank 为庄家明牌，first_decision 表示是否为拿到前两张牌后的第一次决策（只有这时能加倍、分牌和投降）
+pub fn basic_strategy(ranks: &[u8], dealer_rank: u8, rules: &TrainerRules, first_decision: bool) -> Decision {
+    let dealer = dealer_value(dealer_rank);
+    let (total, soft) = hand_total(ranks);
+    let first = first_decision && ranks.len() == 2;
+    let pair = first && card_value(ranks[0]) == card_value(ranks[1]);
+
+    if first && rules.surrender_allowed && should_surrender(ranks, total, soft, dealer, rules) {
+        return Decision::Surrender;
+    }
+    if pair && should_split(dealer_value(ranks[0]), dealer, rules) {
+        return Decision::Split;
+    }
+
+    let entry = if soft {
+        soft_chart(total, dealer, rules)
+    } else {
+        hard_chart(total, dealer, rules)
+    };
+    match entry {
+        ChartEntry::Hit => Decision::Hit,
+        ChartEntry::Stand => Decision::Stand,
+        ChartEntry::DoubleOrHit if first => Decision::Double,
+        ChartEntry::DoubleOrHit => Decision::Hit,
+        ChartEntry::DoubleOrStand if first => Decision::Double,
+        ChartEntry::DoubleOrStand => Decision::Stand,
+    }
+}
+
+/// 投降：硬16对9/10/A、硬15对10；庄家软17要牌时再加硬15对A、硬17对A、一对8对A
+fn should_surrender(ranks: &[u8], total: u32, soft: bool, dealer: u32, rules: &TrainerRules) -> bool {
+    if soft {
+        return false;
+    }
+    let pair_of_eights = ranks == [8, 8];
+    let h17 = rules.dealer_hits_soft_17;
+    match total {
+        16 if pair_of_eights => h17 && dealer == 11,
+        16 => dealer >= 9,
+        15 => dealer == 10 || (h17 && dealer == 11),
+        17 => h17 && dealer == 11,
+        _ => false,
+    }
+}
+
+/// 分牌策略，pair 为对子中一张牌的点数（A 记为 11）
+/// 分牌后可加倍时，一两副牌再加一对6对7、一对7对8，单副牌再加一对3对8、一对4对4
+fn should_split(pair: u32, dealer: u32, rules: &TrainerRules) -> bool {
+    let das = rules.double_after_split;
+    let few_decks = das && rules.decks <= 2;
+    let single_deck = das && rules.decks == 1;
+    match pair {
+        11 | 8 => true,
+        10 | 5 => false,
+        9 => matches!(dealer, 2..=6 | 8 | 9),
+        7 => dealer <= 7 || (few_decks && dealer == 8),
+        6 => if das { dealer <= 6 || (few_decks && dealer == 7) } else { (3..=6).contains(&dealer) },
+        4 => das && ((5..=6).contains(&dealer) || (single_deck && dealer == 4)),
+        3 if single_deck => dealer <= 8,
+        _ => if das { dealer <= 7 } else { (4..=7).contains(&dealer) },
+    }
+}
+
+/// 软牌策略，单副牌再加软19对6、软17对2、软13和软14对4加倍
+fn soft_chart(total: u32, dealer: u32, rules: &TrainerRules) -> ChartEntry {
+    let h17 = rules.dealer_hits_soft_17;
+    let single_deck = rules.decks == 1;
+    match total {
+        20.. => ChartEntry::Stand,
+        19 if (h17 || single_deck) && dealer == 6 => ChartEntry::DoubleOrStand,
+        19 => ChartEntry::Stand,
+        18 => match dealer {
+            2 if !h17 => ChartEntry::Stand,
+            2..=6 => ChartEntry::DoubleOrStand,
+            7 | 8 => ChartEntry::Stand,
+            _ => ChartEntry::Hit,
+        },
+        17 if (3..=6).contains(&dealer) || (single_deck && dealer == 2) => ChartEntry::DoubleOrHit,
+        15 | 16 if (4..=6).contains(&dealer) => ChartEntry::DoubleOrHit,
+        13 | 14 if (5..=6).contains(&dealer) || (single_deck && dealer == 4) => ChartEntry::DoubleOrHit,
+        _ => ChartEntry::Hit,
+    }
+}
+
+/// 硬牌策略，一两副牌再加硬11对A、硬9对2加倍，单副牌再加硬8对5和6加倍
+fn hard_chart(total: u32, dealer: u32, rules: &TrainerRules) -> ChartEntry {
+    let few_decks = rules.decks <= 2;
+    match total {
+        17.. => ChartEntry::Stand,
+        13..=16 if dealer <= 6 => ChartEntry::Stand,
+        12 if (4..=6).contains(&dealer) => ChartEntry::Stand,
+        11 if dealer == 11 && !rules.dealer_hits_soft_17 && !few_decks => ChartEntry::Hit,
+        11 => ChartEntry::DoubleOrHit,
+        10 if dealer <= 9 => ChartEntry::DoubleOrHit,
+        9 if (3..=6).contains(&dealer) || (few_decks && dealer == 2) => ChartEntry::DoubleOrHit,
+        8 if rules.decks == 1 && (5..=6).contains(&dealer) => ChartEntry::DoubleOrHit,
+        _ => ChartEntry::Hit,
+    }
+}
+
+/// 局面的描述（用于统计常见错误），如“硬16 对 10”“一对8 对 A”
+fn situation(ranks: &[u8], dealer_rank: u8, first_decision: bool) -> String {
+    let dealer = if dealer_rank == 1 { "A".to_string() } else { card_value(dealer_rank).to_string() };
+    let (total, soft) = hand_total(ranks);
+    let hand = if first_decision && ranks.len() == 2 && card_value(ranks[0]) == card_value(ranks[1]) {
+        if ranks[0] == 1 { "一对A".to_string() } else { format!("一对{}", card_value(ranks[0])) }
+    } else if soft {
+        format!("软{}", total)
+    } else {
+        format!("硬{}", total)
+    };
+    format!("{} 对 {}", hand, dealer)
+}
+
+/// Hi-Lo 计数值：2~6 为 +1，7~9 为 0，10/J/Q/K/A 为 -1
+pub fn hi_lo_value(rank: u8) -> i32 {
+    match rank {
+        2..=6 => 1,
+        7..=9 => 0,
+        _ => -1,
+    }
+}
+
+/// 训练统计
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct TrainerStats {
+    decisions: u32,                 // 基本策略练习的决策次数
+    correct_decisions: u32,         // 符合基本策略的决策次数
+    mistakes: BTreeMap<String, u32>, // 各局面的出错次数
+    count_quizzes: u32,             // 算牌练习的答题次数
+    running_correct: u32,           // 流水数答对次数
+    true_correct: u32,              // 真数答对次数
+}
+
+/// 算牌练习设置
+#[derive(Clone, Copy, Serialize, Deserialize)]
+struct DrillSettings {
+    cards_per_round: usize, // 每轮发牌张数
+    interval_ms: u64,       // 发牌间隔（毫秒）
+}
+
+impl Default for DrillSettings {
+    fn default() -> Self {
+        Self {
+            cards_per_round: 20,
+            interval_ms: 800,
+        }
+    }
+}
+
+/// 保存到本地的训练数据
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct TrainerData {
+    rules: TrainerRules,
+    drill: DrillSettings,
+    stats: TrainerStats,
+}
+
+/// 训练模式
+#[derive(PartialEq, Clone, Copy)]
+enum TrainerMode {
+    Strategy, // 基本策略练习
+    Counting, // 算牌练习
+}
+
+/// 算牌练习的阶段
+#[derive(PartialEq, Clone, Copy)]
+enum DrillPhase {
+    Idle,     // 等待开始
+    Dealing,  // 正在发牌
+    Quiz,     // 回答流水数和真数
+    Answered, // 已公布答案
+}
+
+/// 21点训练界面
+pub struct BlackjackTrainer {
+    data: TrainerData,
+    mode: TrainerMode,
+    next_card_id: usize,
+
+    // 基本策略练习
+    strategy_shoe: Vec<(u8, u8)>,
+    dealer_card: Option<Card>,
+    player_cards: Vec<Card>,
+    first_decision: bool,      // 是否为本题的第一次决策
+    question_over: bool,       // 本题是否已结束
+    feedback: Option<(bool, String)>, // 上一次决策的结果（是否正确，说明）
+
+    // 算牌练习
+    count_shoe: Vec<(u8, u8)>,
+    running_count: i32,
+    drill_phase: DrillPhase,
+    drill_card: Option<Card>,
+    drill_dealt: usize,
+    drill_timer: Option<Instant>,
+    running_answer: i32,
+    true_answer: i32,
+    drill_feedback: String,
+    save_error: Option<String>,
+}
+
+impl BlackjackTrainer {
+    pub fn new() -> Self {
+        Self {
+            data: player_profile::load_data_file(TRAINER_FILE),
+            mode: TrainerMode::Strategy,
+            next_card_id: 0,
+            strategy_shoe: Vec::new(),
+            dealer_card: None,
+            player_cards: Vec::new(),
+            first_decision: true,
+            question_over: true,
+            feedback: None,
+            count_shoe: Vec::new(),
+            running_count: 0,
+            drill_phase: DrillPhase::Idle,
+            drill_card: None,
+            drill_dealt: 0,
+            drill_timer: None,
+            running_answer: 0,
+            true_answer: 0,
+            drill_feedback: String::new(),
+            save_error: None,
+        }
+    }
+
+    /// 保存规则、设置和统计，失败时在训练界面显示原因
+    fn save(&mut self) {
+        self.save_error = player_profile::save_data_file(TRAINER_FILE, &self.data)
+            .err()
+            .map(|error| format!("保存训练数据失败: {}", error));
+    }
+
+    /// 从牌靴发一张明牌
+    fn draw_card(&mut self, ctx: &egui::Context, counting: bool) -> Option<Card> {
+        let shoe = if counting { &mut self.count_shoe } else { &mut self.strategy_shoe };
+        let (rank, suit) = shoe.pop()?;
+        let mut card = Card::new(ctx, self.next_card_id, rank, suit, CARD_BACK_PATH, &get_card_image_path(rank, suit)).ok()?;
+        self.next_card_id += 1;
+        card.flip_to(true);
+        Some(card)
+    }
+
+    /// 显示训练界面，返回是否要返回主菜单
+    pub fn show(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut return_to_menu = false;
+        let ctx = ui.ctx().clone();
+
+        ui.vertical_centered(|ui| {
+            ui.add_space(20.0);
+            ui.heading("21点训练");
+            ui.add_space(10.0);
+
+            ui.horizontal(|ui| {
+                ui.add_space(ui.available_width() / 2.0 - 90.0);
+                ui.selectable_value(&mut self.mode, TrainerMode::Strategy, "基本策略练习");
+                ui.selectable_value(&mut self.mode, TrainerMode::Counting, "算牌练习");
+            });
+            ui.add_space(10.0);
+
+            self.show_rules(ui);
+            ui.add_space(10.0);
+
+            match self.mode {
+                TrainerMode::Strategy => self.show_strategy(ui, &ctx),
+                TrainerMode::Counting => self.show_counting(ui, &ctx),
+            }
+
+            if let Some(error) = &self.save_error {
+                ui.add_space(10.0);
+                ui.colored_label(egui::Color32::RED, error);
+            }
+
+            ui.add_space(20.0);
+            if ui.add_sized([150.0, 40.0], egui::Button::new("返回主菜单")).clicked() {
+                return_to_menu = true;
+                self.drill_phase = DrillPhase::Idle;
+                self.drill_timer = None;
+                self.save();
+            }
+        });
+
+        return_to_menu
+    }
+
+    /// 显示规则设置，修改后重新洗牌
+    fn show_rules(&mut self, ui: &mut egui::Ui) {
+        let before = self.data.rules;
+        ui.horizontal(|ui| {
+            ui.add_space(ui.available_width() / 2.0 - 330.0);
+            let rules = &mut self.data.rules;
+            ui.add(egui::Slider::new(&mut rules.decks, 1..=8).text("副牌"));
+            ui.checkbox(&mut rules.dealer_hits_soft_17, "庄家软17要牌");
+            ui.checkbox(&mut rules.double_after_split, "分牌后可加倍");
+            ui.checkbox(&mut rules.surrender_allowed, "允许投降");
+        });
+        if self.data.rules != before {
+            self.strategy_shoe.clear();
+            self.question_over = true;
+            self.reset_drill_shoe();
+            self.drill_phase = DrillPhase::Idle;
+        }
+    }
+
+    /// 基本策略练习：发两张牌和庄家明牌，选择决策后对照基本策略
+    fn show_strategy(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
+        let stats = &self.data.stats;
+        ui.label(format!(
+            "决策正确率: {}/{}（{:.0}%）",
+            stats.correct_decisions,
+            stats.decisions,
+            stats.correct_decisions as f32 * 100.0 / stats.decisions.max(1) as f32
+        ));
+        let mut mistakes: Vec<(&String, &u32)> = stats.mistakes.iter().collect();
+        mistakes.sort_by(|a, b| b.1.cmp(a.1));
+        if !mistakes.is_empty() {
+            let top: Vec<String> = mistakes
+                .iter()
+                .take(TOP_MISTAKES)
+                .map(|(situation, count)| format!("{}（{}次）", situation, count))
+                .collect();
+            ui.colored_label(egui::Color32::ORANGE, format!("常见错误: {}", top.join("，")));
+        }
+        ui.add_space(10.0);
+
+        if self.dealer_card.is_none() {
+            self.new_question(ctx);
+        }
+
+        if let Some(card) = &self.dealer_card {
+            ui.label("庄家明牌");
+            card.render(ui, CARD_SIZE, egui::Sense::hover());
+        }
+        ui.add_space(10.0);
+        let ranks: Vec<u8> = self.player_cards.iter().map(|card| card.rank).collect();
+        let (total, soft) = hand_total(&ranks);
+        ui.label(format!("你的手牌: {}{}点", if soft { "软" } else { "" }, total));
+        ui.horizontal(|ui| {
+            let width = self.player_cards.len() as f32 * (CARD_SIZE.x + 8.0);
+            ui.add_space((ui.available_width() - width) / 2.0);
+            for card in &self.player_cards {
+                card.render(ui, CARD_SIZE, egui::Sense::hover());
+            }
+        });
+        ui.add_space(10.0);
+
+        if let Some((correct, text)) = &self.feedback {
+            let color = if *correct { egui::Color32::DARK_GREEN } else { egui::Color32::RED };
+            ui.colored_label(color, text);
+        }
+        ui.add_space(10.0);
+
+        if self.question_over {
+            let next = ui.input(|input| input.key_pressed(egui::Key::Enter) || input.key_pressed(egui::Key::Space));
+            if ui.add_sized([150.0, 40.0], egui::Button::new("下一题 (Enter)")).clicked() || next {
+                self.new_question(ctx);
+            }
+            return;
+        }
+
+        let first = self.first_decision;
+        let pair = first && ranks.len() == 2 && card_value(ranks[0]) == card_value(ranks[1]);
+        let options = [
+            (Decision::Hit, true, egui::Key::H),
+            (Decision::Stand, true, egui::Key::S),
+            (Decision::Double, first, egui::Key::D),
+            (Decision::Split, pair, egui::Key::P),
+            (Decision::Surrender, first && self.data.rules.surrender_allowed, egui::Key::R),
+        ];
+        let mut chosen = None;
+        ui.horizontal(|ui| {
+            ui.add_space(ui.available_width() / 2.0 - 270.0);
+            for (decision, enabled, key) in options {
+                let text = format!("{} ({})", decision.label(), key.name());
+                let pressed = enabled && ui.input(|input| input.key_pressed(key));
+                if ui.add_enabled(enabled, egui::Button::new(text).min_size(egui::vec2(100.0, 40.0))).clicked() || pressed {
+                    chosen = Some(decision);
+                }
+            }
+        });
+        if let Some(decision) = chosen {
+            self.answer(ctx, decision);
+        }
+    }
+
+    /// 出一道新题
+    fn new_question(&mut self, ctx: &egui::Context) {
+        if self.strategy_shoe.len() < STRATEGY_RESHUFFLE {
+            self.strategy_shoe = game4::build_shoe(self.data.rules.decks);
+        }
+        self.player_cards.clear();
+        self.dealer_card = None;
+        self.feedback = None;
+        self.first_decision = true;
+        self.question_over = false;
+
+        for _ in 0..2 {
+            if let Some(card) = self.draw_card(ctx, false) {
+                self.player_cards.push(card);
+            }
+        }
+        self.dealer_card = self.draw_card(ctx, false);
+        // 黑杰克不需要决策
+        if hand_total(&self.ranks()).0 == 21 {
+            self.feedback = Some((true, "黑杰克！直接赢得 3:2 赔付".to_string()));
+            self.question_over = true;
+        }
+    }
+
+    /// 玩家手牌的点数
+    fn ranks(&self) -> Vec<u8> {
+        self.player_cards.iter().map(|card| card.rank).collect()
+    }
+
+    /// 对照基本策略判定一次决策；要牌后继续作答，其余决策结束本题
+    fn answer(&mut self, ctx: &egui::Context, decision: Decision) {
+        let Some(dealer_rank) = self.dealer_card.as_ref().map(|card| card.rank) else {
+            return;
+        };
+        let ranks = self.ranks();
+        let expected = basic_strategy(&ranks, dealer_rank, &self.data.rules, self.first_decision);
+        let situation = situation(&ranks, dealer_rank, self.first_decision);
+
+        let stats = &mut self.data.stats;
+        stats.decisions += 1;
+        if decision == expected {
+            stats.correct_decisions += 1;
+            self.feedback = Some((true, format!("正确！{}：{}", situation, expected.label())));
+        } else {
+            *stats.mistakes.entry(situation.clone()).or_default() += 1;
+            self.feedback = Some((
+                false,
+                format!("偏离基本策略！{}：应该{}，你选择了{}", situation, expected.label(), decision.label()),
+            ));
+        }
+        self.save();
+
+        self.first_decision = false;
+        match decision {
+            Decision::Hit => {
+                if let Some(card) = self.draw_card(ctx, false) {
+                    self.player_cards.push(card);
+                }
+                self.question_over = hand_total(&self.ranks()).0 >= 21;
+            }
+            Decision::Double => {
+                if let Some(card) = self.draw_card(ctx, false) {
+                    self.player_cards.push(card);
+                }
+                self.question_over = true;
+            }
+            Decision::Stand | Decision::Split | Decision::Surrender => self.question_over = true,
+        }
+    }
+
+    /// 重新洗算牌练习的牌靴，流水数从 0 开始
+    fn reset_drill_shoe(&mut self) {
+        self.count_shoe = game4::build_shoe(self.data.rules.decks);
+        self.running_count = 0;
+    }
+
+    /// 剩余副数（按半副取整，用于计算真数）
+    fn remaining_decks(&self) -> f32 {
+        ((self.count_shoe.len() as f32 / 52.0 * 2.0).round() / 2.0).max(0.5)
+    }
+
+    /// 当前真数：流水数 ÷ 剩余副数，四舍五入取整
+    fn true_count(&self) -> i32 {
+        (self.running_count as f32 / self.remaining_decks()).round() as i32
+    }
+
+    /// 算牌练习：按间隔快速发牌，发完后回答流水数和真数
+    fn show_counting(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
+        let stats = &self.data.stats;
+        let quizzes = stats.count_quizzes.max(1) as f32;
+        ui.label(format!(
+            "答题 {} 次，流水数正确率 {:.0}%，真数正确率 {:.0}%",
+            stats.count_quizzes,
+            stats.running_correct as f32 * 100.0 / quizzes,
+            stats.true_correct as f32 * 100.0 / quizzes
+        ));
+        ui.label("Hi-Lo 计数：2~6 为 +1，7~9 为 0，10/J/Q/K/A 为 -1；流水数从新牌靴开始累计");
+        ui.add_space(10.0);
+
+        self.update_drill(ctx);
+
+        match self.drill_phase {
+            DrillPhase::Idle | DrillPhase::Answered => {
+                if self.drill_phase == DrillPhase::Answered {
+                    ui.colored_label(egui::Color32::BLUE, &self.drill_feedback);
+                    ui.add_space(10.0);
+                }
+                ui.horizontal(|ui| {
+                    ui.add_space(ui.available_width() / 2.0 - 250.0);
+                    let drill = &mut self.data.drill;
+                    ui.add(egui::Slider::new(&mut drill.cards_per_round, 5..=52).text("每轮张数"));
+                    ui.add(egui::Slider::new(&mut drill.interval_ms, 250..=2000).suffix("毫秒").text("发牌间隔"));
+                });
+                ui.add_space(10.0);
+                if ui.add_sized([150.0, 40.0], egui::Button::new("开始发牌")).clicked() {
+                    self.start_drill();
+                }
+            }
+            DrillPhase::Dealing => {
+                ui.label(format!("已发 {}/{} 张", self.drill_dealt, self.data.drill.cards_per_round));
+                if let Some(card) = &self.drill_card {
+                    card.render(ui, egui::vec2(120.0, 180.0), egui::Sense::hover());
+                }
+            }
+            DrillPhase::Quiz => {
+                ui.label(format!("牌靴剩余约 {:.1} 副（真数 = 流水数 ÷ 剩余副数，四舍五入取整）", self.remaining_decks()));
+                ui.add_space(10.0);
+                ui.horizontal(|ui| {
+                    ui.add_space(ui.available_width() / 2.0 - 140.0);
+                    ui.label("流水数:");
+                    ui.add(egui::DragValue::new(&mut self.running_answer).speed(0.2));
+                    ui.add_space(20.0);
+                    ui.label("真数:");
+                    ui.add(egui::DragValue::new(&mut self.true_answer).speed(0.2));
+                });
+                ui.add_space(10.0);
+                if ui.add_sized([150.0, 40.0], egui::Button::new("提交答案")).clicked() {
+                    self.check_drill_answer();
+                }
+            }
+        }
+    }
+
+    /// 开始一轮发牌，到切牌位置后重新洗牌
+    fn start_drill(&mut self) {
+        let total = self.data.rules.decks as usize * 52;
+        let cut = (total as f32 * (1.0 - COUNT_PENETRATION)) as usize;
+        let mut notice = String::new();
+        if self.count_shoe.len() < cut.max(self.data.drill.cards_per_round) {
+            self.reset_drill_shoe();
+            notice = "新牌靴，流水数从 0 开始".to_string();
+        }
+        self.drill_feedback = notice;
+        self.drill_phase = DrillPhase::Dealing;
+        self.drill_dealt = 0;
+        self.drill_card = None;
+        self.drill_timer = None;
+    }
+
+    /// 按间隔发下一张牌，发完后进入答题
+    fn update_drill(&mut self, ctx: &egui::Context) {
+        if self.drill_phase != DrillPhase::Dealing {
+            return;
+        }
+        let interval = Duration::from_millis(self.data.drill.interval_ms);
+        if let Some(timer) = self.drill_timer
+            && timer.elapsed() < interval
+        {
+            ctx.request_repaint_after(interval - timer.elapsed());
+            return;
+        }
+
+        if self.drill_dealt >= self.data.drill.cards_per_round {
+            self.drill_card = None;
+            self.drill_timer = None;
+            self.running_answer = 0;
+            self.true_answer = 0;
+            self.drill_phase = DrillPhase::Quiz;
+            return;
+        }
+
+        self.drill_card = self.draw_card(ctx, true);
+        if let Some(card) = &self.drill_card {
+            self.running_count += hi_lo_value(card.rank);
+        }
+        self.drill_dealt += 1;
+        self.drill_timer = Some(Instant::now());
+        ctx.request_repaint_after(interval);
+    }
+
+    /// 判定流水数和真数并记录
+    fn check_drill_answer(&mut self) {
+        let true_count = self.true_count();
+        let running_ok = self.running_answer == self.running_count;
+        let true_ok = self.true_answer == true_count;
+        let mark = |ok: bool| if ok { "✔" } else { "✘" };
+
+        let stats = &mut self.data.stats;
+        stats.count_quizzes += 1;
+        stats.running_correct += running_ok as u32;
+        stats.true_correct += true_ok as u32;
+        self.save();
+
+        self.drill_feedback = format!(
+            "流水数 {} 正确答案 {}（你的答案 {}）；真数 {} 正确答案 {}（你的答案 {}）",
+            mark(running_ok),
+            self.running_count,
+            self.running_answer,
+            mark(true_ok),
+            true_count,
+            self.true_answer
+        );
+        self.drill_phase = DrillPhase::Answered;
+    }
+}
+
+impl Default for BlackjackTrainer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Decision::*;
+
+    /// 六副牌、允许投降的规则，按庄家软17规则和分牌后能否加倍区分
+    fn rules(dealer_hits_soft_17: bool, double_after_split: bool) -> TrainerRules {
+        TrainerRules { dealer_hits_soft_17, double_after_split, ..TrainerRules::default() }
+    }
+
+    #[test]
+    fn soft_17_rule_changes_chart() {
+        // (玩家手牌, 庄家明牌, 庄家软17停牌时, 庄家软17要牌时)
+        let table: &[(&[u8], u8, Decision, Decision)] = &[
+            (&[6, 5], 1, Hit, Double),
+            (&[1, 7], 2, Stand, Double),
+            (&[1, 8], 6, Stand, Double),
+            (&[8, 8], 1, Split, Surrender),
+            (&[10, 6], 10, Surrender, Surrender),
+            (&[10, 5], 1, Hit, Surrender),
+            (&[10, 7], 1, Stand, Surrender),
+            (&[10, 6], 7, Hit, Hit),
+            (&[1, 1], 1, Split, Split),
+        ];
+        for &(ranks, dealer, s17, h17) in table {
+            assert_eq!(basic_strategy(ranks, dealer, &rules(false, true), true), s17, "S17 {:?} 对 {}", ranks, dealer);
+            assert_eq!(basic_strategy(ranks, dealer, &rules(true, true), true), h17, "H17 {:?} 对 {}", ranks, dealer);
+        }
+    }
+
+    #[test]
+    fn double_after_split_changes_splits() {
+        // (对子, 庄家明牌, 分牌后可加倍时, 分牌后不能加倍时)
+        let table: &[(&[u8], u8, Decision, Decision)] = &[
+            (&[2, 2], 2, Split, Hit),
+            (&[3, 3], 3, Split, Hit),
+            (&[4, 4], 5, Split, Hit),
+            (&[6, 6], 2, Split, Hit),
+            (&[2, 2], 4, Split, Split),
+            (&[6, 6], 6, Split, Split),
+            (&[5, 5], 6, Double, Double),
+            (&[9, 9], 7, Stand, Stand),
+            (&[10, 13], 6, Stand, Stand),
+            (&[8, 8], 10, Split, Split),
+        ];
+        for &(ranks, dealer, das, no_das) in table {
+            assert_eq!(basic_strategy(ranks, dealer, &rules(false, true), true), das, "DAS {:?} 对 {}", ranks, dealer);
+            assert_eq!(basic_strategy(ranks, dealer, &rules(false, false), true), no_das, "NDAS {:?} 对 {}", ranks, dealer);
+        }
+    }
+
+    #[test]
+    fn few_decks_add_deviations() {
+        // (玩家手牌, 庄家明牌, 六副牌, 双副牌, 单副牌)，庄家软17停牌、分牌后可加倍
+        let table: &[(&[u8], u8, Decision, Decision, Decision)] = &[
+            (&[6, 5], 1, Hit, Double, Double),
+            (&[5, 4], 2, Hit, Double, Double),
+            (&[5, 3], 5, Hit, Hit, Double),
+            (&[1, 6], 2, Hit, Hit, Double),
+            (&[1, 8], 6, Stand, Stand, Double),
+            (&[1, 2], 4, Hit, Hit, Double),
+            (&[6, 6], 7, Hit, Split, Split),
+            (&[7, 7], 8, Hit, Split, Split),
+            (&[3, 3], 8, Hit, Hit, Split),
+            (&[4, 4], 4, Hit, Hit, Split),
+            (&[10, 6], 10, Surrender, Surrender, Surrender),
+        ];
+        let with_decks = |decks| TrainerRules { decks, ..rules(false, true) };
+        for &(ranks, dealer, six, two, one) in table {
+            assert_eq!(basic_strategy(ranks, dealer, &with_decks(6), true), six, "六副 {:?} 对 {}", ranks, dealer);
+            assert_eq!(basic_strategy(ranks, dealer, &with_decks(2), true), two, "双副 {:?} 对 {}", ranks, dealer);
+            assert_eq!(basic_strategy(ranks, dealer, &with_decks(1), true), one, "单副 {:?} 对 {}", ranks, dealer);
+        }
+        // 分牌后不能加倍时没有分牌偏差
+        let no_das = TrainerRules { decks: 1, ..rules(false, false) };
+        assert_eq!(basic_strategy(&[6, 6], 7, &no_das, true), Hit);
+    }
+
+    #[test]
+    fn surrender_needs_first_decision_and_permission() {
+        let no_surrender = TrainerRules { surrender_allowed: false, ..rules(true, true) };
+        assert_eq!(basic_strategy(&[10, 6], 10, &no_surrender, true), Hit);
+        assert_eq!(basic_strategy(&[8, 8], 1, &no_surrender, true), Split);
+        // 要过牌之后不能投降、加倍或分牌
+        assert_eq!(basic_strategy(&[10, 4, 2], 10, &rules(true, true), true), Hit);
+        assert_eq!(basic_strategy(&[10, 6], 10, &rules(true, true), false), Hit);
+        assert_eq!(basic_strategy(&[1, 2, 5], 2, &rules(true, true), true), Stand);
+        assert_eq!(basic_strategy(&[3, 4, 4], 1, &rules(true, true), true), Hit);
+    }
+}
@@ -1,14 +1,20 @@
+use crate::animation::{self, CardAnimation, CardAnimationKind};
 use eframe::egui::{self, TextureHandle, Vec2};
 use image::ImageError;
 
+/// 小王、大王的点数（王没有花色，花色记为 0）
+pub const BLACK_JOKER: u8 = 14;
+pub const RED_JOKER: u8 = 15;
+
 #[derive(Clone)]
 pub struct Card {
     pub id: usize,
     pub is_face_up: bool,
-    pub rank: u8, // 1~13
-    pub suit: u8, // 1~4
+    pub rank: u8, // 1~13，小王 14、大王 15
+    pub suit: u8, // 1~4，王为 0
     pub back_tex: TextureHandle,
     pub face_tex: TextureHandle,
+    pub animation: Option<CardAnimation>,
 }
 
 impl Card {
@@ -21,8 +27,12 @@ impl Card {
         back_path: &str,
         face_path: &str,
     ) -> Result<Self, ImageError> {
-        assert!((1..=13).contains(&rank), "Rank must be between 1 and 13");
-        assert!((1..=4).contains(&suit), "Suit must be between 1 and 4");
+        if rank == BLACK_JOKER || rank == RED_JOKER {
+            assert!(suit == 0, "Jokers have no suit");
+        } else {
+            assert!((1..=13).contains(&rank), "Rank must be between 1 and 13");
+            assert!((1..=4).contains(&suit), "Suit must be between 1 and 4");
+        }
         
         let back_tex = Self::load_texture_from_file(ctx, back_path)?;
         let face_tex = Self::load_texture_from_file(ctx, face_path)?;
@@ -34,6 +44,7 @@ impl Card {
             suit,
             back_tex,
             face_tex,
+            animation: None,
         })
     }
 
@@ -49,26 +60,118 @@ impl Card {
 
     /// 显示卡片并处理点击事件
     pub fn show(&mut self, ui: &mut egui::Ui, size: Vec2) -> egui::Response {
-        let texture = if self.is_face_up {
+        let response = self.render(ui, size, egui::Sense::click());
+
+        if response.clicked() && !self.is_face_up && !self.is_animating() {
+            self.flip_to(true);
+        }
+
+        response
+    }
+
+    /// 只绘制卡片（含动画），不修改卡片状态
+    pub fn render(&self, ui: &mut egui::Ui, size: Vec2, sense: egui::Sense) -> egui::Response {
+        let texture = if self.visible_side().0 {
             &self.face_tex
         } else {
             &self.back_tex
         };
 
-        let image_button = egui::ImageButton::new(egui::Image::new(texture).max_size(size))
-            .frame(false)
-            .sense(egui::Sense::click());
+        // 保持纹理比例，缩放到不超过给定大小
+        let texture_size = texture.size_vec2();
+        let scale = (size.x / texture_size.x).min(size.y / texture_size.y).min(1.0);
+        let (rect, response) = ui.allocate_exact_size(texture_size * scale, sense);
 
-        let response = ui.add(image_button);
-
-        if response.clicked() {
-            
-           if!self.is_face_up{self.is_face_up = !self.is_face_up;}
+        if ui.is_rect_visible(rect) {
+            self.paint(ui.painter(), rect);
+        } else if self.is_animating() {
+            ui.ctx().request_repaint();
         }
 
         response
     }
- 
 
+    /// 在指定位置绘制卡片（含动画），用于自由摆放卡片的牌桌
+    pub fn paint(&self, painter: &egui::Painter, rect: egui::Rect) {
+        let (showing_face, scale_x) = self.visible_side();
+        let texture = if showing_face {
+            &self.face_tex
+        } else {
+            &self.back_tex
+        };
+
+        let mut draw_rect = egui::Rect::from_center_size(
+            rect.center(),
+            egui::vec2(rect.width() * scale_x, rect.height()),
+        );
+        let mut tint = egui::Color32::WHITE;
+
+        if let Some(anim) = self.animation
+            && anim.kind == CardAnimationKind::FlyAway
+        {
+            let progress = anim.progress();
+            let shrink = 1.0 - progress * 0.5;
+            draw_rect = egui::Rect::from_center_size(
+                rect.center() - egui::vec2(0.0, rect.height() * progress),
+                rect.size() * shrink,
+            );
+            tint = egui::Color32::from_white_alpha(((1.0 - progress) * 255.0) as u8);
+        }
+
+        painter.image(
+            texture.id(),
+            draw_rect,
+            egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+            tint,
+        );
 
-}
\ No newline at end of file
+        if self.is_animating() {
+            painter.ctx().request_repaint();
+        }
+    }
+
+    /// 当前显示的一面和水平缩放比例：翻牌动画前半段仍显示原来的一面
+    fn visible_side(&self) -> (bool, f32) {
+        match self.animation {
+            Some(anim) if anim.kind == CardAnimationKind::Flip && !anim.is_finished() => {
+                let progress = anim.progress();
+                let showing_face = if animation::flip_shows_old_face(progress) {
+                    !self.is_face_up
+                } else {
+                    self.is_face_up
+                };
+                (showing_face, animation::flip_scale(progress))
+            }
+            _ => (self.is_face_up, 1.0),
+        }
+    }
+
+    /// 翻到指定的一面（带翻牌动画）
+    pub fn flip_to(&mut self, face_up: bool) {
+        if self.is_face_up != face_up {
+            self.is_face_up = face_up;
+            self.animation = Some(CardAnimation::flip());
+        }
+    }
+
+    /// 开始飞走动画
+    pub fn fly_away(&mut self) {
+        self.animation = Some(CardAnimation::fly_away());
+    }
+
+    /// 是否正在播放动画
+    pub fn is_animating(&self) -> bool {
+        self.animation.is_some_and(|anim| !anim.is_finished())
+    }
+
+    /// 飞走动画是否已经播放完毕
+    pub fn has_flown_away(&self) -> bool {
+        self.animation
+            .is_some_and(|anim| anim.kind == CardAnimationKind::FlyAway && anim.is_finished())
+    }
+}
+
+/// 是否有卡片正在播放动画：动画期间各游戏应暂停接受点击和按钮操作
+pub fn any_animating<'a>(cards: impl IntoIterator<Item = &'a Card>) -> bool {
+    cards.into_iter().any(Card::is_animating)
+}
@@ -0,0 +1,141 @@
+// card_game.rs - 通用游戏接口和游戏注册表：注册后的游戏自动出现在主菜单上
+use crate::achievements::GameEvent;
+use crate::card::Card;
+use crate::difficulty::{DifficultyProfile, DifficultySelection};
+use crate::game1::MemoryGame;
+use crate::game10::HeartsGame;
+use crate::game2::GuessNumberGame;
+use crate::game3::TexasHoldemGame;
+use crate::game4::BlackjackGame;
+use crate::game5::KlondikeGame;
+use crate::game6::FreeCellGame;
+use crate::game7::SpiderGame;
+use crate::game8::DouDizhuGame;
+use crate::game9::BigTwoGame;
+use crate::leaderboard::LeaderboardEntry;
+use crate::player_profile::{GameKind, GameResult};
+use crate::save_game::SavedSession;
+use eframe::egui;
+
+/// 主菜单卡片默认的正面图片
+pub const DEFAULT_MENU_FACE: &str = "assets/card_face/JOKER-A.png";
+
+/// 每款游戏都要实现的接口
+pub trait CardGame {
+    /// 游戏种类（用于统计、排行榜和存档）
+    fn kind(&self) -> GameKind;
+
+    /// 游戏名称
+    fn name(&self) -> &'static str {
+        self.kind().label()
+    }
+
+    /// 游戏规则说明
+    fn rules(&self) -> Vec<String>;
+
+    /// 难度选择状态和自定义难度参数
+    fn difficulty(&mut self) -> (&mut DifficultySelection, &mut dyn DifficultyProfile);
+
+    /// 显示通用的难度选择界面，返回 (是否返回主菜单, 是否开始游戏)
+    fn show_difficulty_selection(&mut self, ui: &mut egui::Ui) -> (bool, bool) {
+        let name = self.name();
+        let rules = self.rules();
+        let (selection, custom) = self.difficulty();
+        selection.show(ui, name, &rules, custom)
+    }
+
+    /// 主菜单卡片的正面图片
+    fn menu_face(&self) -> &'static str {
+        DEFAULT_MENU_FACE
+    }
+
+    /// 设置主菜单卡片（用于复用纹理）
+    fn set_main_menu_cards(&mut self, _cards: &[Card]) {}
+
+    /// 显示游戏界面，返回是否要返回主菜单
+    fn show(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) -> bool;
+
+    /// 重置到难度选择界面
+    fn reset(&mut self);
+
+    /// 保存进行中的一局，没有进行中的游戏时返回 None
+    fn save_state(&self) -> Option<SavedSession> {
+        None
+    }
+
+    /// 从存档恢复一局（存档属于其他游戏时忽略）
+    fn restore_state(&mut self, _ctx: &egui::Context, _session: SavedSession) {}
+
+    /// 取出刚结束的一局结果
+    fn take_result(&mut self) -> Option<GameResult> {
+        None
+    }
+
+    /// 取出本帧产生的游戏事件
+    fn take_events(&mut self) -> Vec<GameEvent> {
+        Vec::new()
+    }
+
+    /// 取出玩家提交的排行榜成绩
+    fn take_leaderboard_entry(&mut self) -> Option<(GameKind, LeaderboardEntry)> {
+        None
+    }
+
+    /// 取出中途返回主菜单时保存的存档
+    fn take_suspended_session(&mut self) -> Option<SavedSession> {
+        None
+    }
+}
+
+/// 游戏注册表，按主菜单上的顺序保存所有游戏
+pub struct GameRegistry {
+    games: Vec<Box<dyn CardGame>>,
+}
+
+impl GameRegistry {
+    /// 注册所有游戏
+    pub fn new() -> Self {
+        Self {
+            games: vec![
+                Box::new(MemoryGame::new()),
+                Box::new(GuessNumberGame::new()),
+                Box::new(TexasHoldemGame::new()),
+                Box::new(BlackjackGame::new()),
+                Box::new(KlondikeGame::new()),
+                Box::new(FreeCellGame::new()),
+                Box::new(SpiderGame::new()),
+                Box::new(DouDizhuGame::new()),
+                Box::new(BigTwoGame::new()),
+                Box::new(HeartsGame::new()),
+            ],
+        }
+    }
+
+    /// 所有游戏
+    pub fn games(&self) -> &[Box<dyn CardGame>] {
+        &self.games
+    }
+
+    /// 按游戏种类查找游戏
+    pub fn get(&self, kind: GameKind) -> Option<&dyn CardGame> {
+        self.games.iter().find(|game| game.kind() == kind).map(|game| game.as_ref())
+    }
+
+    /// 按游戏种类查找游戏（可修改）
+    pub fn get_mut(&mut self, kind: GameKind) -> Option<&mut (dyn CardGame + 'static)> {
+        self.games.iter_mut().find(|game| game.kind() == kind).map(|game| game.as_mut())
+    }
+
+    /// 把主菜单卡片交给各游戏
+    pub fn set_main_menu_cards(&mut self, cards: &[Card]) {
+        for game in &mut self.games {
+            game.set_main_menu_cards(cards);
+        }
+    }
+}
+
+impl Default for GameRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
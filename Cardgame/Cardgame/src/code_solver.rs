@@ -0,0 +1,367 @@
+// code_solver.rs - 猜数字求解器（Knuth 极小化极大策略，候选较多时在后台线程枚举）
+use crate::game2::{score_guess, CodeRules};
+use rand::seq::IndexedRandom;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// 最多保留的候选密码数量，超过时视为“可能性过多”
+const MAX_CANDIDATES: usize = 200_000;
+/// 后台搜索候选密码时最多访问的节点数
+const MAX_SEARCH_NODES: usize = 3_000_000;
+/// 在界面线程中直接搜索的节点数，超过时转到后台线程继续
+const QUICK_SEARCH_NODES: usize = 50_000;
+/// 极小化极大搜索时参与评估的猜测数量上限
+const MAX_GUESS_POOL: usize = 300;
+/// 评估每个猜测时使用的候选答案数量上限
+const MAX_ANSWER_SAMPLE: usize = 2000;
+
+/// 候选太多、无法枚举时提示和矛盾检测的说明
+pub const TOO_MANY_NOTICE: &str = "候选太多，暂不可用";
+
+/// 一次猜测及其反馈 (猜测, (A, B))
+pub type Feedback = (Vec<u8>, (usize, usize));
+
+/// 搜索提前停止的原因
+enum SearchStop {
+    TooMany,   // 候选超过 MAX_CANDIDATES
+    Aborted,   // 访问的节点超过上限
+    Cancelled, // 后台任务已取消
+}
+
+/// 候选密码的枚举状态
+#[derive(Clone)]
+enum Candidates {
+    Ready(Vec<Vec<u8>>),       // 已枚举
+    TooMany,                   // 候选过多，暂未枚举
+    Aborted,                   // 搜索量过大，放弃枚举
+    Searching(Arc<SearchJob>), // 后台线程正在枚举
+}
+
+/// 后台枚举任务，所有引用都丢弃时自动取消
+struct SearchJob {
+    result: Arc<Mutex<Option<Candidates>>>,
+    cancel: Arc<AtomicBool>,
+}
+
+impl SearchJob {
+    /// 在后台线程开始枚举
+    fn spawn(rules: CodeRules, history: Vec<Feedback>) -> Self {
+        let result = Arc::new(Mutex::new(None));
+        let cancel = Arc::new(AtomicBool::new(false));
+        let (sender, cancelled) = (Arc::clone(&result), Arc::clone(&cancel));
+        thread::spawn(move || {
+            let candidates = match search_candidates(&rules, &history, MAX_SEARCH_NODES, &cancelled) {
+                Ok(candidates) => Candidates::Ready(candidates),
+                Err(SearchStop::TooMany) => Candidates::TooMany,
+                Err(SearchStop::Aborted) => Candidates::Aborted,
+                Err(SearchStop::Cancelled) => return,
+            };
+            if let Ok(mut slot) = sender.lock() {
+                *slot = Some(candidates);
+            }
+        });
+        Self { result, cancel }
+    }
+
+    /// 取出枚举结果（尚未完成时返回 None）
+    fn take(&self) -> Option<Candidates> {
+        self.result.lock().ok()?.take()
+    }
+}
+
+impl Drop for SearchJob {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+/// 猜数字求解器：维护与所有历史反馈一致的候选密码集合
+#[derive(Clone)]
+pub struct CodeSolver {
+    rules: CodeRules,
+    history: Vec<Feedback>,
+    candidates: Candidates,
+}
+
+impl CodeSolver {
+    /// 创建求解器
+    pub fn new(rules: CodeRules) -> Self {
+        let mut solver = Self {
+            rules,
+            history: Vec::new(),
+            candidates: Candidates::TooMany,
+        };
+        solver.start_search();
+        solver
+    }
+
+    /// 加入一次猜测的反馈并缩小候选集合
+    pub fn add_feedback(&mut self, guess: &[u8], score: (usize, usize)) {
+        self.history.push((guess.to_vec(), score));
+        match &mut self.candidates {
+            Candidates::Ready(candidates) => candidates.retain(|code| score_guess(guess, code) == score),
+            // 之前候选过多或仍在搜索，加入新反馈后重新枚举
+            _ => self.start_search(),
+        }
+    }
+
+    /// 枚举候选密码：先在当前线程搜索少量节点，搜不完时交给后台线程
+    fn start_search(&mut self) {
+        let not_cancelled = AtomicBool::new(false);
+        self.candidates = match search_candidates(&self.rules, &self.history, QUICK_SEARCH_NODES, &not_cancelled) {
+            Ok(candidates) => Candidates::Ready(candidates),
+            Err(SearchStop::TooMany) => Candidates::TooMany,
+            Err(_) => Candidates::Searching(Arc::new(SearchJob::spawn(self.rules, self.history.clone()))),
+        };
+    }
+
+    /// 取出后台枚举的结果，返回是否刚刚完成（界面每帧调用）
+    pub fn poll(&mut self) -> bool {
+        let Candidates::Searching(job) = &self.candidates else {
+            return false;
+        };
+        match job.take() {
+            Some(candidates) => {
+                self.candidates = candidates;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 后台是否正在枚举候选密码
+    pub fn is_searching(&self) -> bool {
+        matches!(self.candidates, Candidates::Searching(_))
+    }
+
+    /// 剩余可能的密码数量，None 表示尚未枚举
+    pub fn remaining(&self) -> Option<usize> {
+        self.candidates().map(|candidates| candidates.len())
+    }
+
+    /// 当前的候选密码，None 表示尚未枚举
+    pub fn candidates(&self) -> Option<&[Vec<u8>]> {
+        match &self.candidates {
+            Candidates::Ready(candidates) => Some(candidates),
+            _ => None,
+        }
+    }
+
+    /// 剩余数量的显示文字
+    pub fn remaining_text(&self) -> String {
+        match &self.candidates {
+            Candidates::Ready(candidates) => format!("{} 种", candidates.len()),
+            Candidates::TooMany => format!("超过 {} 种", MAX_CANDIDATES),
+            Candidates::Aborted => "搜索量过大，无法统计".to_string(),
+            Candidates::Searching(_) => "计算中…".to_string(),
+        }
+    }
+
+    /// 候选过多或搜索量过大而未能枚举：此时既不能给出提示，也无法检测反馈矛盾
+    pub fn is_too_many(&self) -> bool {
+        matches!(self.candidates, Candidates::TooMany | Candidates::Aborted)
+    }
+
+    /// 反馈是否互相矛盾（不存在符合所有反馈的密码）
+    pub fn is_inconsistent(&self) -> bool {
+        self.remaining() == Some(0)
+    }
+
+    /// 建议下一次猜测：在候选集合上做极小化极大，使最坏情况下剩余的候选最少
+    pub fn suggest(&self) -> Option<Vec<u8>> {
+        let candidates = self.candidates()?;
+        match candidates.len() {
+            0 => return None,
+            1 | 2 => return Some(candidates[0].clone()),
+            _ => {}
+        }
+
+        let mut rng = rand::rng();
+        let guess_pool: Vec<&Vec<u8>> = if candidates.len() <= MAX_GUESS_POOL {
+            candidates.iter().collect()
+        } else {
+            candidates.choose_multiple(&mut rng, MAX_GUESS_POOL).collect()
+        };
+        let answers: Vec<&Vec<u8>> = if candidates.len() <= MAX_ANSWER_SAMPLE {
+            candidates.iter().collect()
+        } else {
+            candidates.choose_multiple(&mut rng, MAX_ANSWER_SAMPLE).collect()
+        };
+
+        // 按 (A, B) 结果划分候选答案，统计最大的一组
+        let side = self.rules.length + 1;
+        let mut partitions = vec![0usize; side * side];
+        let mut best: Option<(&Vec<u8>, usize)> = None;
+        for guess in guess_pool {
+            partitions.fill(0);
+            for answer in &answers {
+                let (a_count, b_count) = score_guess(guess, answer);
+                partitions[a_count * side + b_count] += 1;
+            }
+            let worst_case = partitions.iter().copied().max().unwrap_or(0);
+            if best.is_none_or(|(_, best_worst)| worst_case < best_worst) {
+                best = Some((guess, worst_case));
+            }
+        }
+
+        best.map(|(guess, _)| guess.clone())
+    }
+}
+
+/// 深度优先枚举符合所有反馈的密码，最多访问 max_nodes 个节点
+fn search_candidates(
+    rules: &CodeRules,
+    history: &[Feedback],
+    max_nodes: usize,
+    cancel: &AtomicBool,
+) -> Result<Vec<Vec<u8>>, SearchStop> {
+    let mut search = Search { rules, history, max_nodes, cancel, nodes: 0, result: Vec::new() };
+    let mut current = Vec::with_capacity(rules.length);
+    search.visit(&mut current)?;
+    Ok(search.result)
+}
+
+/// 深度优先搜索的状态
+struct Search<'a> {
+    rules: &'a CodeRules,
+    history: &'a [Feedback],
+    max_nodes: usize,
+    cancel: &'a AtomicBool,
+    nodes: usize,
+    result: Vec<Vec<u8>>,
+}
+
+impl Search<'_> {
+    /// 递归搜索，超过上限或被取消时提前停止
+    fn visit(&mut self, current: &mut Vec<u8>) -> Result<(), SearchStop> {
+        self.nodes += 1;
+        if self.nodes > self.max_nodes {
+            return Err(SearchStop::Aborted);
+        }
+        if self.nodes.is_multiple_of(4096) && self.cancel.load(Ordering::Relaxed) {
+            return Err(SearchStop::Cancelled);
+        }
+
+        let length = self.rules.length;
+        if current.len() == length {
+            if self.history.iter().all(|(guess, score)| score_guess(guess, current) == *score) {
+                self.result.push(current.clone());
+                if self.result.len() > MAX_CANDIDATES {
+                    return Err(SearchStop::TooMany);
+                }
+            }
+            return Ok(());
+        }
+
+        for digit in 0..self.rules.alphabet_size {
+            if !self.rules.allow_repeats && current.contains(&digit) {
+                continue;
+            }
+            current.push(digit);
+            // 用位置完全正确的数量（A）剪枝
+            let feasible = self.history.iter().all(|(guess, (a, _))| {
+                let exact = current.iter().zip(guess).filter(|(c, g)| c == g).count();
+                exact <= *a && exact + (length - current.len()) >= *a
+            });
+            if feasible {
+                self.visit(current)?;
+            }
+            current.pop();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn score_guess_counts_duplicates_once() {
+        // 重复数字只能与目标中同样多的数字配对
+        assert_eq!(score_guess(&[1, 1, 2, 2], &[1, 2, 1, 3]), (1, 2));
+        assert_eq!(score_guess(&[0, 0, 0, 0], &[0, 1, 2, 3]), (1, 0));
+        assert_eq!(score_guess(&[0, 1, 2, 3], &[0, 0, 0, 0]), (1, 0));
+        assert_eq!(score_guess(&[1, 1, 1, 2], &[2, 1, 1, 1]), (2, 2));
+        assert_eq!(score_guess(&[1, 2, 3, 4], &[4, 3, 2, 1]), (0, 4));
+        assert_eq!(score_guess(&[5, 5, 5, 5], &[0, 1, 2, 3]), (0, 0));
+    }
+
+    #[test]
+    fn feedback_with_duplicates_keeps_secret() {
+        let rules = CodeRules { length: 4, alphabet_size: 6, allow_repeats: true };
+        let secret = [1, 2, 1, 3];
+        let mut solver = CodeSolver::new(rules);
+        assert_eq!(solver.remaining(), Some(1296));
+        for guess in [[1, 1, 2, 2], [0, 0, 0, 0], [3, 3, 1, 1]] {
+            solver.add_feedback(&guess, score_guess(&guess, &secret));
+            assert!(solver.candidates().unwrap().iter().any(|code| code == &secret));
+        }
+        assert!(!solver.is_inconsistent());
+    }
+
+    /// 候选不超过 MAX_GUESS_POOL 时求解过程是确定的，
+    /// 3 位 6 个数字（可重复）的所有密码都能在 6 次内猜中
+    #[test]
+    fn solver_finds_every_secret_within_bound() {
+        let rules = CodeRules { length: 3, alphabet_size: 6, allow_repeats: true };
+        let secrets = CodeSolver::new(rules).candidates().unwrap().to_vec();
+        assert_eq!(secrets.len(), 216);
+        for secret in secrets {
+            let mut solver = CodeSolver::new(rules);
+            let mut guesses = 0;
+            loop {
+                guesses += 1;
+                let guess = solver.suggest().expect("候选集合不应为空");
+                let score = score_guess(&guess, &secret);
+                if score.0 == rules.length {
+                    break;
+                }
+                solver.add_feedback(&guess, score);
+                assert!(guesses < 6, "密码 {:?} 超过 6 次仍未猜中", secret);
+            }
+        }
+    }
+
+    /// 等待后台枚举完成
+    fn wait(solver: &mut CodeSolver) {
+        let start = std::time::Instant::now();
+        while solver.is_searching() {
+            assert!(start.elapsed().as_secs() < 60, "后台枚举超时");
+            solver.poll();
+            thread::sleep(std::time::Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn large_rules_are_enumerated_in_background() {
+        let rules = CodeRules { length: 8, alphabet_size: 10, allow_repeats: true };
+        let mut solver = CodeSolver::new(rules);
+        assert!(solver.is_searching());
+        assert_eq!(solver.remaining(), None);
+        assert_eq!(solver.suggest(), None);
+        wait(&mut solver);
+        assert_eq!(solver.remaining_text(), format!("超过 {} 种", MAX_CANDIDATES));
+        assert!(solver.is_too_many());
+
+        // 只有 B 的反馈几乎无法剪枝，节点用完时显示“无法统计”而不是“超过”
+        solver.add_feedback(&[0, 1, 2, 3, 4, 5, 6, 7], (0, 8));
+        wait(&mut solver);
+        assert_eq!(solver.remaining(), None);
+        assert_eq!(solver.remaining_text(), "搜索量过大，无法统计");
+        assert!(solver.is_too_many());
+        assert!(!solver.is_inconsistent());
+    }
+
+    #[test]
+    fn contradictory_feedback_is_inconsistent() {
+        let rules = CodeRules { length: 3, alphabet_size: 4, allow_repeats: false };
+        let mut solver = CodeSolver::new(rules);
+        solver.add_feedback(&[0, 1, 2], (3, 0));
+        assert_eq!(solver.remaining(), Some(1));
+        solver.add_feedback(&[0, 1, 2], (0, 3));
+        assert!(solver.is_inconsistent());
+        assert_eq!(solver.suggest(), None);
+    }
+}
@@ -1,12 +1,136 @@
 // difficulty.rs
+use crate::player_profile::{self, GameKind};
 use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
 use std::time::Instant;
 
-#[derive(PartialEq, Clone, Copy)]
+/// 自适应难度的目标胜率
+const ADAPTIVE_TARGET_WIN_RATE: f32 = 0.6;
+/// 自适应难度每局的调整幅度
+const ADAPTIVE_STEP: f32 = 0.2;
+/// 记录的最近战绩局数
+const RECENT_RESULTS: usize = 10;
+/// 自适应难度数据文件名
+const ADAPTIVE_FILE: &str = "adaptive_difficulty.json";
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Serialize, Deserialize)]
 pub enum GameDifficulty {
     Easy,    // 简单难度
     Medium,  // 中等难度
     Hard,    // 困难难度
+    Custom,  // 自定义难度
+    Adaptive, // 自适应难度
+}
+
+impl GameDifficulty {
+    /// 难度的显示名称
+    pub fn label(self) -> &'static str {
+        match self {
+            GameDifficulty::Easy => "简单难度",
+            GameDifficulty::Medium => "中等难度",
+            GameDifficulty::Hard => "困难难度",
+            GameDifficulty::Custom => "自定义难度",
+            GameDifficulty::Adaptive => "自适应难度",
+        }
+    }
+}
+
+/// 在简单（level = 0）和困难（level = 1）的参数之间线性插值
+pub fn lerp(easy: f32, hard: f32, level: f32) -> f32 {
+    easy + (hard - easy) * level
+}
+
+/// 自适应难度：记录最近战绩，按阶梯法调整强度，使胜率趋近目标
+#[derive(Clone, Serialize, Deserialize)]
+pub struct AdaptiveDifficulty {
+    pub level: f32,              // 当前强度（0 = 简单，1 = 困难）
+    pub recent: VecDeque<bool>,  // 最近的胜负记录
+}
+
+impl AdaptiveDifficulty {
+    pub fn new() -> Self {
+        Self {
+            level: 0.5,
+            recent: VecDeque::new(),
+        }
+    }
+
+    /// 记录一局结果，adjust 为 true 时调整强度
+    /// 赢一局升高 step×(1-目标胜率)，输一局降低 step×目标胜率，长期胜率会稳定在目标附近
+    pub fn record(&mut self, won: bool, adjust: bool) {
+        self.recent.push_back(won);
+        if self.recent.len() > RECENT_RESULTS {
+            self.recent.pop_front();
+        }
+        if adjust {
+            let delta = if won {
+                ADAPTIVE_STEP * (1.0 - ADAPTIVE_TARGET_WIN_RATE)
+            } else {
+                -ADAPTIVE_STEP * ADAPTIVE_TARGET_WIN_RATE
+            };
+            self.level = (self.level + delta).clamp(0.0, 1.0);
+        }
+    }
+
+    /// 最近战绩的显示文字
+    pub fn summary(&self) -> String {
+        let wins = self.recent.iter().filter(|&&won| won).count();
+        format!(
+            "最近{}局: {}胜{}负，当前强度 {:.0}%",
+            self.recent.len(),
+            wins,
+            self.recent.len() - wins,
+            self.level * 100.0
+        )
+    }
+}
+
+impl Default for AdaptiveDifficulty {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 每款游戏的自适应难度数据（本地 JSON 文件）
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct AdaptiveRecords {
+    games: BTreeMap<GameKind, AdaptiveDifficulty>,
+}
+
+impl AdaptiveRecords {
+    /// 读取某款游戏的自适应难度，没有记录时使用初始值
+    fn load(game: GameKind) -> AdaptiveDifficulty {
+        let records: Self = player_profile::load_data_file(ADAPTIVE_FILE);
+        records.games.get(&game).cloned().unwrap_or_default()
+    }
+
+    /// 保存某款游戏的自适应难度，其他游戏的记录保持不变
+    fn save(game: GameKind, adaptive: &AdaptiveDifficulty) -> std::io::Result<()> {
+        let mut records: Self = player_profile::load_data_file(ADAPTIVE_FILE);
+        records.games.insert(game, adaptive.clone());
+        player_profile::save_data_file(ADAPTIVE_FILE, &records)
+    }
+}
+
+/// 每款游戏的难度参数，预设难度之外还可以在编辑器中自定义
+pub trait DifficultyProfile {
+    /// 预设难度对应的参数（自定义和自适应难度以中等难度为初始值）
+    fn preset(difficulty: GameDifficulty) -> Self
+    where
+        Self: Sized;
+
+    /// 自适应难度在某个强度下的参数（0 = 简单，1 = 困难）
+    fn adaptive(level: f32) -> Self
+    where
+        Self: Sized;
+
+    /// 将参数重置为某个预设难度
+    fn load_preset(&mut self, difficulty: GameDifficulty);
+
+    /// 显示自定义难度编辑器
+    fn show_editor(&mut self, ui: &mut egui::Ui);
 }
 
 /// 难度选择界面
@@ -15,24 +139,69 @@ pub struct DifficultySelection {
     pub transition_timer: Option<Instant>,
     pub transition_progress: f32,
     pub transition_complete: bool,
+    pub custom_editor_open: bool,
+    pub adaptive: AdaptiveDifficulty, // 自适应难度（重置时保留战绩）
+    game: GameKind,                   // 自适应难度数据所属的游戏
+    save_error: Option<String>,       // 最近一次保存自适应难度失败的原因
 }
 
 impl DifficultySelection {
-    pub fn new() -> Self {
+    /// 创建难度选择界面，并读取该游戏保存的自适应难度
+    pub fn new(game: GameKind) -> Self {
         Self {
             selected_difficulty: None,
             transition_timer: None,
             transition_progress: 0.0,
             transition_complete: false,
+            custom_editor_open: false,
+            adaptive: AdaptiveRecords::load(game),
+            game,
+            save_error: None,
+        }
+    }
+
+    /// 当前选择的难度参数：预设难度使用预设值，自定义难度使用编辑器中的参数
+    pub fn profile<P: DifficultyProfile + Clone>(&self, custom: &P) -> P {
+        match self.selected_difficulty {
+            Some(GameDifficulty::Custom) => custom.clone(),
+            Some(GameDifficulty::Adaptive) => P::adaptive(self.adaptive.level),
+            Some(difficulty) => P::preset(difficulty),
+            None => P::preset(GameDifficulty::Easy),
         }
     }
 
+    /// 记录一局的胜负，自适应难度下同时调整强度，并保存到本地
+    pub fn record_result(&mut self, won: bool) {
+        let adjust = self.selected_difficulty == Some(GameDifficulty::Adaptive);
+        self.adaptive.record(won, adjust);
+        self.save_error = AdaptiveRecords::save(self.game, &self.adaptive)
+            .err()
+            .map(|error| format!("保存自适应难度失败: {}", error));
+    }
+
+    /// 恢复存档中的难度（自适应难度同时恢复当时的强度），不播放过渡动画
+    pub fn restore(&mut self, difficulty: GameDifficulty, adaptive_level: f32) {
+        self.reset();
+        self.selected_difficulty = Some(difficulty);
+        if difficulty == GameDifficulty::Adaptive {
+            self.adaptive.level = adaptive_level;
+        }
+    }
+
+    /// 选择难度并开始过渡动画
+    fn select(&mut self, difficulty: GameDifficulty) {
+        self.selected_difficulty = Some(difficulty);
+        self.transition_timer = Some(Instant::now());
+        self.custom_editor_open = false;
+    }
+
     /// 显示难度选择界面
     pub fn show(
         &mut self, 
         ui: &mut egui::Ui, 
         game_name: &str, 
-        rules: &[&str]
+        rules: &[String],
+        custom: &mut dyn DifficultyProfile,
     ) -> (bool, bool) {
         let mut return_to_menu = false;
         let mut start_game = false;
@@ -64,28 +233,63 @@ impl DifficultySelection {
             ui.horizontal(|ui| {
                 ui.add_space(ui.available_width() / 2.0 - 100.0);
                 ui.vertical(|ui| {
-                    if self.centered_button(ui, "简单难度", 200.0, 60.0).clicked() {
-                        self.selected_difficulty = Some(GameDifficulty::Easy);
-                        self.transition_timer = Some(Instant::now());
-                        start_game = true;
+                    for difficulty in [GameDifficulty::Easy, GameDifficulty::Medium, GameDifficulty::Hard] {
+                        if self.centered_button(ui, difficulty.label(), 200.0, 60.0).clicked() {
+                            self.select(difficulty);
+                            start_game = true;
+                        }
+                        ui.add_space(20.0);
                     }
-                    ui.add_space(20.0);
 
-                    if self.centered_button(ui, "中等难度", 200.0, 60.0).clicked() {
-                        self.selected_difficulty = Some(GameDifficulty::Medium);
-                        self.transition_timer = Some(Instant::now());
+                    if self.centered_button(ui, GameDifficulty::Adaptive.label(), 200.0, 60.0).clicked() {
+                        self.select(GameDifficulty::Adaptive);
                         start_game = true;
                     }
+                    ui.label(self.adaptive.summary());
+                    if let Some(error) = &self.save_error {
+                        ui.colored_label(egui::Color32::RED, error);
+                    }
                     ui.add_space(20.0);
 
-                    if self.centered_button(ui, "困难难度", 200.0, 60.0).clicked() {
-                        self.selected_difficulty = Some(GameDifficulty::Hard);
-                        self.transition_timer = Some(Instant::now());
-                        start_game = true;
+                    let editor_text = if self.custom_editor_open { "收起自定义难度" } else { "自定义难度" };
+                    if self.centered_button(ui, editor_text, 200.0, 40.0).clicked() {
+                        self.custom_editor_open = !self.custom_editor_open;
                     }
                 });
             });
 
+            // 自定义难度编辑器
+            if self.custom_editor_open {
+                ui.add_space(20.0);
+                ui.horizontal(|ui| {
+                    ui.add_space(ui.available_width() / 2.0 - 200.0);
+                    egui::Frame::group(ui.style()).show(ui, |ui| {
+                        ui.set_width(400.0);
+                        ui.vertical(|ui| {
+                            ui.colored_label(egui::Color32::LIGHT_BLUE, "自定义难度参数:");
+                            ui.add_space(10.0);
+                            custom.show_editor(ui);
+                            ui.add_space(10.0);
+
+                            ui.horizontal(|ui| {
+                                ui.label("载入预设:");
+                                for difficulty in [GameDifficulty::Easy, GameDifficulty::Medium, GameDifficulty::Hard] {
+                                    if ui.button(difficulty.label()).clicked() {
+                                        custom.load_preset(difficulty);
+                                    }
+                                }
+                            });
+                            ui.add_space(10.0);
+
+                            if self.centered_button(ui, "以自定义难度开始", 200.0, 40.0).clicked() {
+                                self.select(GameDifficulty::Custom);
+                                start_game = true;
+                            }
+                        });
+                    });
+                });
+            }
+
             ui.add_space(40.0);
 
             ui.horizontal(|ui| {
@@ -133,12 +337,7 @@ impl DifficultySelection {
                         ui.colored_label(egui::Color32::WHITE, loading_text);
                         
                         if let Some(difficulty) = self.selected_difficulty {
-                            let difficulty_text = match difficulty {
-                                GameDifficulty::Easy => "简单难度",
-                                GameDifficulty::Medium => "中等难度", 
-                                GameDifficulty::Hard => "困难难度",
-                            };
-                            ui.colored_label(egui::Color32::LIGHT_BLUE, difficulty_text);
+                            ui.colored_label(egui::Color32::LIGHT_BLUE, difficulty.label());
                         }
                     });
                 });
@@ -167,6 +366,7 @@ impl DifficultySelection {
         self.transition_timer = None;
         self.transition_progress = 0.0;
         self.transition_complete = false;
+        self.custom_editor_open = false;
     }
 
     /// 创建居中的按钮
@@ -180,10 +380,4 @@ impl DifficultySelection {
             )
         )
     }
-}
-
-impl Default for DifficultySelection {
-    fn default() -> Self {
-        Self::new()
-    }
 }
\ No newline at end of file
@@ -0,0 +1,696 @@
+// doudizhu.rs - 斗地主的牌型规则：牌值、牌型识别与比较、可出牌枚举，以及 AI 叫分和出牌策略
+use crate::solitaire::{rank_of, BLACK_JOKER_ID, RED_JOKER_ID};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::HashSet;
+
+/// 牌值的个数：3~K、A、2、小王、大王
+const VALUES: usize = 15;
+/// 能连成顺子、连对、飞机的最大牌值（A，2 和王不能连）
+const MAX_CHAIN_VALUE: usize = 11;
+
+/// 卡片在斗地主中的牌值：3 为 0 …… K 为 10，A 为 11，2 为 12，小王 13，大王 14
+pub fn value_of(card: usize) -> usize {
+    match card {
+        BLACK_JOKER_ID => 13,
+        RED_JOKER_ID => 14,
+        _ => match rank_of(card) {
+            1 => 11,
+            2 => 12,
+            rank => rank as usize - 3,
+        },
+    }
+}
+
+/// 牌值的显示名称
+pub fn value_name(value: usize) -> &'static str {
+    const NAMES: [&str; VALUES] = ["3", "4", "5", "6", "7", "8", "9", "10", "J", "Q", "K", "A", "2", "小王", "大王"];
+    NAMES[value]
+}
+
+/// 手牌排序：牌值从大到小，同牌值按花色
+pub fn sort_hand(hand: &mut [usize]) {
+    hand.sort_by_key(|&card| (Reverse(value_of(card)), card));
+}
+
+/// 每种牌值的张数
+fn value_counts(cards: &[usize]) -> [u8; VALUES] {
+    let mut counts = [0; VALUES];
+    for &card in cards {
+        counts[value_of(card)] += 1;
+    }
+    counts
+}
+
+/// 牌型
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum ComboKind {
+    Single,          // 单张
+    Pair,            // 对子
+    Trio,            // 三张
+    TrioSingle,      // 三带一
+    TrioPair,        // 三带二（带一对）
+    Straight,        // 顺子（5 张及以上连续单张）
+    PairStraight,    // 连对（3 对及以上连续对子）
+    Airplane,        // 飞机（2 组及以上连续三张）
+    AirplaneSingles, // 飞机带单张翅膀
+    AirplanePairs,   // 飞机带对子翅膀
+    FourTwoSingles,  // 四带二（两张单牌）
+    FourTwoPairs,    // 四带两对
+    Bomb,            // 炸弹
+    Rocket,          // 王炸
+}
+
+impl ComboKind {
+    /// 牌型的显示名称
+    pub fn label(self) -> &'static str {
+        match self {
+            ComboKind::Single => "单张",
+            ComboKind::Pair => "对子",
+            ComboKind::Trio => "三张",
+            ComboKind::TrioSingle => "三带一",
+            ComboKind::TrioPair => "三带二",
+            ComboKind::Straight => "顺子",
+            ComboKind::PairStraight => "连对",
+            ComboKind::Airplane => "飞机",
+            ComboKind::AirplaneSingles => "飞机带单",
+            ComboKind::AirplanePairs => "飞机带对",
+            ComboKind::FourTwoSingles => "四带二",
+            ComboKind::FourTwoPairs => "四带两对",
+            ComboKind::Bomb => "炸弹",
+            ComboKind::Rocket => "王炸",
+        }
+    }
+}
+
+/// 一手牌的牌型
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Combo {
+    pub kind: ComboKind,
+    pub value: usize,  // 主体的牌值（连牌为最小的一组）
+    pub length: usize, // 主体的组数（顺子张数、连对对数、飞机组数，其余为 1）
+}
+
+impl Combo {
+    /// 是否为炸弹或王炸（计入倍数，可以压任何其他牌型）
+    pub fn is_bomb(&self) -> bool {
+        matches!(self.kind, ComboKind::Bomb | ComboKind::Rocket)
+    }
+
+    /// 能否压过另一手牌：王炸最大，炸弹压非炸弹，其余必须牌型和组数相同且主体更大
+    pub fn beats(&self, other: &Combo) -> bool {
+        match (self.kind, other.kind) {
+            (_, ComboKind::Rocket) => false,
+            (ComboKind::Rocket, _) => true,
+            (ComboKind::Bomb, ComboKind::Bomb) => self.value > other.value,
+            (ComboKind::Bomb, _) => true,
+            _ => self.kind == other.kind && self.length == other.length && self.value > other.value,
+        }
+    }
+
+    /// 牌型的描述，如“顺子 3~7”“对 K”
+    pub fn describe(&self) -> String {
+        match self.kind {
+            ComboKind::Rocket => "王炸".to_string(),
+            ComboKind::Straight | ComboKind::PairStraight | ComboKind::Airplane | ComboKind::AirplaneSingles | ComboKind::AirplanePairs => {
+                format!(
+                    "{} {}~{}",
+                    self.kind.label(),
+                    value_name(self.value),
+                    value_name(self.value + self.length - 1)
+                )
+            }
+            _ => format!("{} {}", self.kind.label(), value_name(self.value)),
+        }
+    }
+}
+
+/// 识别一组牌的牌型，不是合法牌型时返回 None
+pub fn classify(cards: &[usize]) -> Option<Combo> {
+    let counts = value_counts(cards);
+    let n = cards.len();
+    let max_count = *counts.iter().max()?;
+    // 张数为 count 的最大牌值
+    let top = |count: u8| (0..VALUES).rev().find(|&value| counts[value] == count);
+    let combo = |kind, value, length| Some(Combo { kind, value, length });
+    // 带牌不能是王炸（大小王一起出只能作为王炸）
+    let rocket_attached = counts[13] == 1 && counts[14] == 1;
+
+    match (n, max_count) {
+        (0, _) => return None,
+        (1, _) => return combo(ComboKind::Single, top(1)?, 1),
+        (2, 2) => return combo(ComboKind::Pair, top(2)?, 1),
+        (2, 1) if counts[13] == 1 && counts[14] == 1 => return combo(ComboKind::Rocket, 14, 1),
+        (3, 3) => return combo(ComboKind::Trio, top(3)?, 1),
+        (4, 4) => return combo(ComboKind::Bomb, top(4)?, 1),
+        (4, 3) => return combo(ComboKind::TrioSingle, top(3)?, 1),
+        (5, 3) if counts.contains(&2) => return combo(ComboKind::TrioPair, top(3)?, 1),
+        (6, 4) if !rocket_attached => return combo(ComboKind::FourTwoSingles, top(4)?, 1),
+        // 两个炸弹一起出时，较大的四张为主体，较小的四张当作两对带出
+        (8, 4) if counts.iter().all(|&count| count % 2 == 0) => {
+            return combo(ComboKind::FourTwoPairs, top(4)?, 1);
+        }
+        _ => {}
+    }
+
+    // 顺子、连对：所有牌值连续且不超过 A
+    let used: Vec<usize> = (0..VALUES).filter(|&value| counts[value] > 0).collect();
+    let chained = used.last().is_some_and(|&last| last <= MAX_CHAIN_VALUE)
+        && used.windows(2).all(|pair| pair[1] == pair[0] + 1);
+    if chained {
+        if max_count == 1 && n >= 5 {
+            return combo(ComboKind::Straight, used[0], n);
+        }
+        if used.iter().all(|&value| counts[value] == 2) && used.len() >= 3 {
+            return combo(ComboKind::PairStraight, used[0], used.len());
+        }
+    }
+
+    airplane(&counts, n)
+}
+
+/// 识别飞机：优先取组数最多、牌值最大的连续三张作为主体，剩下的牌作翅膀
+fn airplane(counts: &[u8; VALUES], n: usize) -> Option<Combo> {
+    for length in (2..=n / 3).rev() {
+        if length > MAX_CHAIN_VALUE + 1 {
+            continue;
+        }
+        for start in (0..=MAX_CHAIN_VALUE + 1 - length).rev() {
+            let body = start..start + length;
+            if !body.clone().all(|value| counts[value] >= 3) {
+                continue;
+            }
+            let wings = n - 3 * length;
+            let kind = if wings == 0 {
+                ComboKind::Airplane
+            } else if wings == length {
+                // 翅膀不能是王炸
+                if counts[13] == 1 && counts[14] == 1 {
+                    continue;
+                }
+                ComboKind::AirplaneSingles
+            } else if wings == 2 * length {
+                let mut rest = *counts;
+                for value in body {
+                    rest[value] -= 3;
+                }
+                if rest.iter().any(|&count| count % 2 != 0) {
+                    continue;
+                }
+                ComboKind::AirplanePairs
+            } else {
+                continue;
+            };
+            return Some(Combo { kind, value: start, length });
+        }
+    }
+    None
+}
+
+/// 挑选带牌：count 组、每组 width 张，不用 exclude 中的牌值，优先不拆牌、牌值小的
+fn pick_kickers(counts: &[u8; VALUES], exclude: &[usize], count: usize, width: u8) -> Option<Vec<usize>> {
+    let mut values: Vec<usize> = (0..VALUES)
+        .filter(|value| !exclude.contains(value) && counts[*value] >= width)
+        // 王不能作为对子带出
+        .filter(|&value| width == 1 || value < 13)
+        .collect();
+    values.sort_by_key(|&value| (counts[value] != width, counts[value] == 4, value));
+    // 大小王不能一起作为带牌，只保留排在前面的一张王
+    if let Some(joker) = values.iter().copied().find(|&value| value >= 13) {
+        values.retain(|&value| value < 13 || value == joker);
+    }
+    if values.len() < count {
+        return None;
+    }
+    Some(values[..count].iter().flat_map(|&value| std::iter::repeat_n(value, width as usize)).collect())
+}
+
+/// 列出手牌中所有能出的牌（to_beat 为要压的牌，None 表示自由出牌），按牌型大小排序
+///
+/// 带牌只取最省的一种搭配，同一牌型不会重复列出。
+pub fn candidate_plays(hand: &[usize], to_beat: Option<&Combo>) -> Vec<Vec<usize>> {
+    let counts = value_counts(hand);
+    let mut shapes: Vec<Vec<usize>> = Vec::new();
+
+    for value in 0..VALUES {
+        let count = counts[value];
+        if count >= 1 {
+            shapes.push(vec![value]);
+        }
+        if count >= 2 {
+            shapes.push(vec![value; 2]);
+        }
+        if count >= 3 {
+            shapes.push(vec![value; 3]);
+            for width in [1, 2] {
+                if let Some(kickers) = pick_kickers(&counts, &[value], 1, width) {
+                    shapes.push([vec![value; 3], kickers].concat());
+                }
+            }
+        }
+        if count == 4 {
+            shapes.push(vec![value; 4]);
+            for width in [1, 2] {
+                if let Some(kickers) = pick_kickers(&counts, &[value], 2, width) {
+                    shapes.push([vec![value; 4], kickers].concat());
+                }
+            }
+        }
+    }
+    if counts[13] == 1 && counts[14] == 1 {
+        shapes.push(vec![13, 14]);
+    }
+
+    // 顺子、连对、飞机
+    for start in 0..=MAX_CHAIN_VALUE {
+        for (width, min_length) in [(1, 5), (2, 3), (3, 2)] {
+            let mut end = start;
+            while end <= MAX_CHAIN_VALUE && counts[end] >= width {
+                end += 1;
+                let length = end - start;
+                if length < min_length {
+                    continue;
+                }
+                let body: Vec<usize> = (start..end).flat_map(|value| std::iter::repeat_n(value, width as usize)).collect();
+                if width == 3 {
+                    let body_values: Vec<usize> = (start..end).collect();
+                    for wing in [1, 2] {
+                        if let Some(kickers) = pick_kickers(&counts, &body_values, length, wing) {
+                            shapes.push([body.clone(), kickers].concat());
+                        }
+                    }
+                }
+                shapes.push(body);
+            }
+        }
+    }
+
+    let mut plays: Vec<(Combo, Vec<usize>)> = shapes
+        .into_iter()
+        .filter_map(|shape| {
+            let cards = cards_for(hand, &shape);
+            let combo = classify(&cards)?;
+            to_beat.is_none_or(|other| combo.beats(other)).then_some((combo, cards))
+        })
+        .collect();
+    plays.sort_by_key(|(combo, cards)| (combo.is_bomb(), combo.value, cards.len()));
+    // 同一牌型可能由不同的搭配得到，只保留排在前面的一种
+    let mut seen = HashSet::new();
+    plays.retain(|(combo, _)| seen.insert(*combo));
+    plays.into_iter().map(|(_, cards)| cards).collect()
+}
+
+/// 按牌值从手牌中取出对应的牌
+fn cards_for(hand: &[usize], values: &[usize]) -> Vec<usize> {
+    let mut taken: Vec<usize> = Vec::with_capacity(values.len());
+    for &value in values {
+        if let Some(&card) = hand.iter().find(|&&card| value_of(card) == value && !taken.contains(&card)) {
+            taken.push(card);
+        }
+    }
+    taken
+}
+
+/// 粗略估计出完这手牌还需要几手（越少越好），用于 AI 比较不同的拆牌方式
+pub fn estimate_turns(hand: &[usize]) -> usize {
+    let mut counts = value_counts(hand);
+    let mut turns = 0;
+    if counts[13] == 1 && counts[14] == 1 {
+        counts[13] = 0;
+        counts[14] = 0;
+        turns += 1;
+    }
+    for count in counts.iter_mut().filter(|count| **count == 4) {
+        *count = 0;
+        turns += 1;
+    }
+
+    // 顺子：单张占多数的连续 5 张以上拆出来作为一手
+    let mut start = 0;
+    while start <= MAX_CHAIN_VALUE {
+        let mut end = start;
+        while end <= MAX_CHAIN_VALUE && counts[end] >= 1 {
+            end += 1;
+        }
+        let length = end - start;
+        let singles = (start..end).filter(|&value| counts[value] == 1).count();
+        if length >= 5 && singles * 2 >= length {
+            for count in &mut counts[start..end] {
+                *count -= 1;
+            }
+            turns += 1;
+            continue;
+        }
+        start = end.max(start + 1);
+    }
+
+    // 每组三张可以带走一张单牌或一对
+    let trios = counts.iter().filter(|&&count| count == 3).count();
+    let pairs = counts.iter().filter(|&&count| count == 2).count();
+    let singles = counts.iter().filter(|&&count| count == 1).count();
+    turns + trios + (pairs + singles).saturating_sub(trios)
+}
+
+/// AI 叫分：按大牌和炸弹估算牌力，水平低的 AI 判断会有偏差；返回 0 表示不叫
+pub fn choose_bid(hand: &[usize], current: u32, skill: f32) -> u32 {
+    let counts = value_counts(hand);
+    let mut strength = counts[14] as i32 * 4 + counts[13] as i32 * 3 + counts[12] as i32 * 2 + counts[11] as i32;
+    strength += counts[..13].iter().filter(|&&count| count == 4).count() as i32 * 4;
+    let noise = if skill < 0.4 {
+        2
+    } else if skill < 0.75 {
+        1
+    } else {
+        0
+    };
+    strength += rand::rng().random_range(-noise..=noise);
+
+    let wanted = match strength {
+        9.. => 3,
+        7..=8 => 2,
+        5..=6 => 1,
+        _ => 0,
+    };
+    if wanted > current { wanted } else { 0 }
+}
+
+/// AI 出牌时能看到的局面
+pub struct AiView<'a> {
+    pub hand: &'a [usize],                // AI 自己的手牌
+    pub seat: usize,                      // AI 的座位
+    pub landlord: usize,                  // 地主的座位
+    pub hand_sizes: [usize; 3],           // 三家剩余张数
+    pub last_play: Option<(usize, Combo)>, // 要压的牌：谁出的、牌型（None 表示自由出牌）
+    pub played: &'a [usize],              // 已经打出的牌（记牌用）
+    pub skill: f32,                       // AI 水平（0~1）
+}
+
+impl AiView<'_> {
+    /// 某家是否为 AI 的对手（地主与农民互为对手）
+    fn is_enemy(&self, seat: usize) -> bool {
+        seat != self.seat && (seat == self.landlord) != (self.seat == self.landlord)
+    }
+
+    /// 对手中最少的剩余张数
+    fn enemy_min_cards(&self) -> usize {
+        (0..3).filter(|&seat| self.is_enemy(seat)).map(|seat| self.hand_sizes[seat]).min().unwrap_or(20)
+    }
+
+    /// 除了自己的手牌和已经打出的牌，其余每种牌值还有几张
+    fn unseen_counts(&self) -> [u8; VALUES] {
+        let mut unseen = [4; VALUES];
+        unseen[13] = 1;
+        unseen[14] = 1;
+        for &card in self.hand.iter().chain(self.played) {
+            unseen[value_of(card)] -= 1;
+        }
+        unseen
+    }
+
+    /// 根据记牌判断一手单张或对子是否已经是场上最大（其他牌型不做判断）
+    fn is_unbeatable(&self, combo: &Combo) -> bool {
+        let unseen = self.unseen_counts();
+        let bomb_left = unseen[..13].contains(&4) || (unseen[13] == 1 && unseen[14] == 1);
+        let width = match combo.kind {
+            ComboKind::Single => 1,
+            ComboKind::Pair => 2,
+            _ => return false,
+        };
+        !bomb_left && (combo.value + 1..VALUES).all(|value| unseen[value] < width)
+    }
+}
+
+/// 从手牌中去掉要出的牌
+fn remaining(hand: &[usize], play: &[usize]) -> Vec<usize> {
+    hand.iter().copied().filter(|card| !play.contains(card)).collect()
+}
+
+/// AI 出牌：返回要出的牌，None 表示不出
+///
+/// 水平低的 AI 总是出最小的牌、偶尔放过能压的牌、不配合队友；水平越高越会按拆牌后的手数选择出法，
+/// 不压队友、留着炸弹，最高水平还会记牌，对手快出完时顶大牌，队友只剩一张时送单张。
+pub fn choose_play(view: &AiView) -> Option<Vec<usize>> {
+    let to_beat = view.last_play.map(|(_, combo)| combo);
+    let mut candidates = candidate_plays(view.hand, to_beat.as_ref());
+    if let Some(all) = candidates.iter().find(|play| play.len() == view.hand.len()) {
+        return Some(all.clone());
+    }
+    let mut rng = rand::rng();
+    let leading = view.last_play.is_none();
+    let enemy_min = view.enemy_min_cards();
+
+    if let Some((who, _)) = view.last_play {
+        // 不压队友的牌
+        if !view.is_enemy(who) && view.skill >= 0.4 {
+            return None;
+        }
+        if view.skill < 0.4 && view.hand.len() > 5 && rng.random_bool(0.15) {
+            return None;
+        }
+        // 炸弹留到对手快出完、或者炸完自己也快出完时再用
+        candidates.retain(|play| {
+            let bomb = classify(play).is_some_and(|combo| combo.is_bomb());
+            !bomb
+                || enemy_min <= 4
+                || estimate_turns(&remaining(view.hand, play)) <= 2
+                || (view.skill < 0.4 && rng.random_bool(0.3))
+        });
+    }
+    if candidates.is_empty() {
+        return None;
+    }
+
+    if view.skill < 0.4 {
+        // 出最小的牌，自由出牌时同样大小优先出张数多的
+        return candidates
+            .into_iter()
+            .min_by_key(|play| {
+                let combo = classify(play);
+                let value = combo.map_or(0, |combo| combo.value);
+                (combo.is_some_and(|combo| combo.is_bomb()), value, Reverse(play.len()))
+            });
+    }
+
+    // 队友下家只剩一张时送一张最小的单牌
+    let partner_next = (view.seat + 1) % 3;
+    if leading && view.skill >= 0.75 && !view.is_enemy(partner_next) && view.hand_sizes[partner_next] == 1 {
+        let smallest = *view.hand.iter().min_by_key(|&&card| value_of(card))?;
+        return Some(vec![smallest]);
+    }
+
+    candidates.into_iter().max_by(|a, b| {
+        let score_a = score_play(view, a, leading, enemy_min);
+        let score_b = score_play(view, b, leading, enemy_min);
+        score_a.total_cmp(&score_b)
+    })
+}
+
+/// 给一种出法打分：出完后剩余手数越少越好，尽量保留大牌和炸弹
+fn score_play(view: &AiView, play: &[usize], leading: bool, enemy_min: usize) -> f32 {
+    let Some(combo) = classify(play) else {
+        return f32::MIN;
+    };
+    let rest = remaining(view.hand, play);
+    let mut score = -(estimate_turns(&rest) as f32) * 10.0;
+    score -= combo.value as f32 * if leading { 1.0 } else { 0.6 };
+    if combo.is_bomb() {
+        score -= 15.0;
+    }
+    if leading {
+        score += play.len() as f32 * 0.5;
+    }
+
+    // 对手快出完时不出他能接的小单张、小对子
+    let risky = match combo.kind {
+        ComboKind::Single => enemy_min == 1,
+        ComboKind::Pair => enemy_min <= 2,
+        _ => false,
+    };
+    if leading && risky {
+        let safe = if view.skill >= 0.75 {
+            view.is_unbeatable(&combo)
+        } else {
+            rest.iter().all(|&card| value_of(card) <= combo.value)
+        };
+        if !safe {
+            score -= 30.0;
+        }
+    }
+
+    if view.skill >= 0.75 {
+        // 压对手的牌时，对手快出完就顶最大的
+        if !leading && enemy_min <= 2 {
+            score += combo.value as f32 * 2.0;
+        }
+        // 出场上最大的牌能保住出牌权
+        if leading && !rest.is_empty() && view.is_unbeatable(&combo) {
+            score += 3.0;
+        }
+    }
+    score
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solitaire::card_id;
+
+    /// 按牌值列出一组牌（同一牌值依次取不同花色），13、14 为小王、大王
+    fn cards(values: &[usize]) -> Vec<usize> {
+        let mut used = [0u8; VALUES];
+        values
+            .iter()
+            .map(|&value| {
+                used[value] += 1;
+                match value {
+                    13 => BLACK_JOKER_ID,
+                    14 => RED_JOKER_ID,
+                    _ => card_id(((value + 2) % 13) as u8 + 1, used[value]),
+                }
+            })
+            .collect()
+    }
+
+    fn kind_of(values: &[usize]) -> Option<(ComboKind, usize, usize)> {
+        classify(&cards(values)).map(|combo| (combo.kind, combo.value, combo.length))
+    }
+
+    #[test]
+    fn value_mapping() {
+        assert_eq!(value_of(card_id(3, 1)), 0);
+        assert_eq!(value_of(card_id(13, 2)), 10);
+        assert_eq!(value_of(card_id(1, 3)), 11);
+        assert_eq!(value_of(card_id(2, 4)), 12);
+        assert_eq!(value_of(BLACK_JOKER_ID), 13);
+        assert_eq!(value_of(RED_JOKER_ID), 14);
+        assert_eq!(cards(&[0, 11, 12]).iter().map(|&card| value_of(card)).collect::<Vec<_>>(), vec![0, 11, 12]);
+    }
+
+    #[test]
+    fn classify_every_kind() {
+        use ComboKind::*;
+        assert_eq!(kind_of(&[5]), Some((Single, 5, 1)));
+        assert_eq!(kind_of(&[14]), Some((Single, 14, 1)));
+        assert_eq!(kind_of(&[7, 7]), Some((Pair, 7, 1)));
+        assert_eq!(kind_of(&[2, 2, 2]), Some((Trio, 2, 1)));
+        assert_eq!(kind_of(&[2, 2, 2, 9]), Some((TrioSingle, 2, 1)));
+        assert_eq!(kind_of(&[2, 2, 2, 9, 9]), Some((TrioPair, 2, 1)));
+        assert_eq!(kind_of(&[3, 4, 5, 6, 7, 8]), Some((Straight, 3, 6)));
+        assert_eq!(kind_of(&[7, 8, 9, 10, 11]), Some((Straight, 7, 5)));
+        assert_eq!(kind_of(&[0, 0, 1, 1, 2, 2]), Some((PairStraight, 0, 3)));
+        assert_eq!(kind_of(&[4, 4, 4, 5, 5, 5]), Some((Airplane, 4, 2)));
+        assert_eq!(kind_of(&[4, 4, 4, 5, 5, 5, 0, 9]), Some((AirplaneSingles, 4, 2)));
+        assert_eq!(kind_of(&[4, 4, 4, 5, 5, 5, 0, 0, 9, 9]), Some((AirplanePairs, 4, 2)));
+        assert_eq!(kind_of(&[6, 6, 6, 6, 0, 1]), Some((FourTwoSingles, 6, 1)));
+        assert_eq!(kind_of(&[6, 6, 6, 6, 0, 0, 1, 1]), Some((FourTwoPairs, 6, 1)));
+        assert_eq!(kind_of(&[12, 12, 12, 12]), Some((Bomb, 12, 1)));
+        assert_eq!(kind_of(&[13, 14]), Some((Rocket, 14, 1)));
+    }
+
+    #[test]
+    fn classify_rejects_illegal_hands() {
+        assert_eq!(kind_of(&[]), None);
+        assert_eq!(kind_of(&[3, 4]), None);
+        assert_eq!(kind_of(&[3, 4, 5, 6]), None);
+        // 2 和王不能连
+        assert_eq!(kind_of(&[8, 9, 10, 11, 12]), None);
+        assert_eq!(kind_of(&[10, 10, 11, 11, 12, 12]), None);
+        assert_eq!(kind_of(&[11, 11, 11, 12, 12, 12]), None);
+        // 三带二的对子必须是同一牌值，两张王不算对子
+        assert_eq!(kind_of(&[2, 2, 2, 13, 14]), None);
+        // 炸弹带王炸、飞机带王炸都不合法
+        assert_eq!(kind_of(&[6, 6, 6, 6, 13, 14]), None);
+        assert_eq!(kind_of(&[4, 4, 4, 5, 5, 5, 13, 14]), None);
+        // 四带两对的对子不能是单张
+        assert_eq!(kind_of(&[6, 6, 6, 6, 0, 1, 2, 3]), None);
+    }
+
+    #[test]
+    fn classify_two_bombs_uses_higher_quad() {
+        assert_eq!(kind_of(&[3, 3, 3, 3, 9, 9, 9, 9]), Some((ComboKind::FourTwoPairs, 9, 1)));
+        assert_eq!(kind_of(&[9, 9, 9, 9, 3, 3, 3, 3]), Some((ComboKind::FourTwoPairs, 9, 1)));
+    }
+
+    #[test]
+    fn beats_rules() {
+        let combo = |values: &[usize]| classify(&cards(values)).unwrap();
+        let rocket = combo(&[13, 14]);
+        let small_bomb = combo(&[0, 0, 0, 0]);
+        let big_bomb = combo(&[12, 12, 12, 12]);
+        let straight = combo(&[0, 1, 2, 3, 4]);
+        let higher_straight = combo(&[1, 2, 3, 4, 5]);
+        let longer_straight = combo(&[1, 2, 3, 4, 5, 6]);
+
+        assert!(rocket.beats(&big_bomb));
+        assert!(!big_bomb.beats(&rocket));
+        assert!(big_bomb.beats(&small_bomb));
+        assert!(!small_bomb.beats(&big_bomb));
+        assert!(small_bomb.beats(&straight));
+        assert!(!straight.beats(&small_bomb));
+        assert!(higher_straight.beats(&straight));
+        assert!(!straight.beats(&higher_straight));
+        assert!(!longer_straight.beats(&straight));
+        assert!(!combo(&[9, 9]).beats(&combo(&[5])));
+        assert!(combo(&[9, 9]).beats(&combo(&[5, 5])));
+        assert!(!combo(&[5, 5]).beats(&combo(&[5, 5])));
+    }
+
+    #[test]
+    fn candidate_plays_are_unique_and_legal() {
+        let hand = cards(&[0, 0, 0, 1, 1, 1, 2, 2, 3, 4, 5, 6, 6, 6, 6, 13, 14]);
+        let plays = candidate_plays(&hand, None);
+        let mut seen = HashSet::new();
+        for play in &plays {
+            let combo = classify(play).expect("候选必须是合法牌型");
+            assert!(seen.insert(combo), "重复的牌型 {:?}", combo);
+            assert!(play.iter().all(|card| hand.contains(card)));
+        }
+        let kinds: HashSet<ComboKind> = seen.iter().map(|combo| combo.kind).collect();
+        for kind in [
+            ComboKind::Single,
+            ComboKind::Pair,
+            ComboKind::Trio,
+            ComboKind::TrioSingle,
+            ComboKind::TrioPair,
+            ComboKind::Straight,
+            ComboKind::Airplane,
+            ComboKind::AirplaneSingles,
+            ComboKind::FourTwoSingles,
+            ComboKind::Bomb,
+            ComboKind::Rocket,
+        ] {
+            assert!(kinds.contains(&kind), "缺少牌型 {:?}", kind);
+        }
+    }
+
+    #[test]
+    fn candidate_plays_only_beat_target() {
+        let hand = cards(&[0, 3, 3, 8, 8, 10, 11, 11, 11, 11]);
+        let target = classify(&cards(&[5, 5])).unwrap();
+        let plays = candidate_plays(&hand, Some(&target));
+        let combos: Vec<Combo> = plays.iter().map(|play| classify(play).unwrap()).collect();
+        assert!(combos.iter().all(|combo| combo.beats(&target)));
+        // 对 8、对 A 和 A 炸弹，按大小排序且炸弹在最后
+        assert_eq!(combos.iter().map(|combo| (combo.kind, combo.value)).collect::<Vec<_>>(), vec![
+            (ComboKind::Pair, 8),
+            (ComboKind::Pair, 11),
+            (ComboKind::Bomb, 11),
+        ]);
+        let rocket = classify(&cards(&[13, 14])).unwrap();
+        assert!(candidate_plays(&hand, Some(&rocket)).is_empty());
+    }
+
+    #[test]
+    fn four_with_two_never_takes_the_rocket() {
+        let hand = cards(&[6, 6, 6, 6, 13, 14, 0]);
+        let plays = candidate_plays(&hand, None);
+        let four_two: Vec<&Vec<usize>> = plays
+            .iter()
+            .filter(|play| classify(play).is_some_and(|combo| combo.kind == ComboKind::FourTwoSingles))
+            .collect();
+        assert_eq!(four_two.len(), 1);
+        let jokers = four_two[0].iter().filter(|&&card| card >= BLACK_JOKER_ID).count();
+        assert_eq!(jokers, 1);
+    }
+}
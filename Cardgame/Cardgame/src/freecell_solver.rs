@@ -0,0 +1,418 @@
+// freecell_solver.rs - 空当接龙求解器（后台线程深度优先搜索，判断局面是否有解并给出提示）
+use crate::game6::{FreeCellBoard, Pile};
+use crate::solitaire::{is_red, rank_of, suit_of};
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+
+/// 搜索时最多访问的局面数，超过时视为“无法判断”
+const MAX_STATES: usize = 2_000_000;
+/// 空当和牌列中表示空位的值
+const EMPTY: u8 = u8::MAX;
+
+/// 求解结果
+#[derive(Clone, Copy)]
+pub enum SolverResult {
+    Solvable { hint: Option<Hint>, steps: usize }, // 有解：第一步提示（已完成时为空）和找到的解法步数（不含自动收牌）
+    Unsolvable,                                    // 已穷举所有局面，无解
+    Unknown,                                       // 搜索量超过上限，无法判断
+}
+
+/// 提示的一步：把某叠牌从第 index 张开始移到目标位置
+#[derive(Clone, Copy, PartialEq)]
+pub struct Hint {
+    pub from: Pile,
+    pub index: usize,
+    pub to: Pile,
+}
+
+/// 后台求解任务，丢弃时自动取消
+pub struct SolverJob {
+    receiver: mpsc::Receiver<SolverResult>,
+    cancel: Arc<AtomicBool>,
+}
+
+impl SolverJob {
+    /// 在后台线程开始求解
+    pub fn spawn(board: &FreeCellBoard) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let board = board.clone();
+        let cancelled = Arc::clone(&cancel);
+        thread::spawn(move || {
+            if let Some(result) = solve(&board, &cancelled) {
+                let _ = sender.send(result);
+            }
+        });
+        Self { receiver, cancel }
+    }
+
+    /// 取出求解结果（尚未完成时返回 None）
+    pub fn poll(&self) -> Option<SolverResult> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+impl Drop for SolverJob {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+/// 求解器中的位置
+#[derive(Clone, Copy, PartialEq)]
+enum Spot {
+    Cell(usize),
+    Foundation,
+    Column(usize),
+}
+
+/// 求解器中的一步：从某处移动若干张牌到另一处
+#[derive(Clone, Copy)]
+struct SolverMove {
+    from: Spot,
+    count: usize,
+    to: Spot,
+}
+
+/// 紧凑的局面表示（收牌区按花色记录张数）
+#[derive(Clone)]
+struct State {
+    cells: Vec<u8>,
+    foundations: [u8; 4],
+    columns: [Vec<u8>; 8],
+}
+
+impl State {
+    fn from_board(board: &FreeCellBoard) -> Self {
+        let mut foundations = [0; 4];
+        for &id in board.foundations.iter().flatten() {
+            foundations[suit_of(id) as usize - 1] += 1;
+        }
+        Self {
+            cells: board.cells.iter().map(|cell| cell.map_or(EMPTY, |id| id as u8)).collect(),
+            foundations,
+            columns: board.columns.clone().map(|column| column.into_iter().map(|id| id as u8).collect()),
+        }
+    }
+
+    /// 某张牌能否放进收牌区
+    fn fits_foundation(&self, card: u8) -> bool {
+        self.foundations[suit_of(card as usize) as usize - 1] + 1 == rank_of(card as usize)
+    }
+
+    /// 某张牌能否接在牌列上（空列任何牌都可以）
+    fn fits_column(&self, card: u8, column: usize) -> bool {
+        match self.columns[column].last() {
+            None => true,
+            Some(&top) => {
+                is_red(top as usize) != is_red(card as usize) && rank_of(card as usize) + 1 == rank_of(top as usize)
+            }
+        }
+    }
+
+    /// 牌列顶部连续有序（红黑相间、点数递减）的张数
+    fn run_length(&self, column: usize) -> usize {
+        run_length(&self.columns[column])
+    }
+
+    /// 一次最多能移动的张数
+    fn capacity(&self, to_empty_column: bool) -> usize {
+        let free_cells = self.cells.iter().filter(|&&cell| cell == EMPTY).count();
+        let empty_columns = self.columns.iter().filter(|column| column.is_empty()).count() - usize::from(to_empty_column);
+        (free_cells + 1) << empty_columns
+    }
+
+    /// 是否全部收齐
+    fn is_solved(&self) -> bool {
+        self.foundations.iter().all(|&count| count == 13)
+    }
+
+    /// 所有合法的下一步
+    fn moves(&self) -> Vec<SolverMove> {
+        let mut moves = Vec::new();
+        let first_empty_cell = self.cells.iter().position(|&cell| cell == EMPTY);
+        let first_empty_column = self.columns.iter().position(Vec::is_empty);
+
+        // 空当中的牌：收牌或放到牌列
+        for (cell, &card) in self.cells.iter().enumerate() {
+            if card == EMPTY {
+                continue;
+            }
+            if self.fits_foundation(card) {
+                moves.push(SolverMove { from: Spot::Cell(cell), count: 1, to: Spot::Foundation });
+            }
+            for column in 0..8 {
+                let target_empty = self.columns[column].is_empty();
+                if self.fits_column(card, column) && (!target_empty || first_empty_column == Some(column)) {
+                    moves.push(SolverMove { from: Spot::Cell(cell), count: 1, to: Spot::Column(column) });
+                }
+            }
+        }
+
+        // 牌列顶部的牌：收牌、移到其他牌列或空当
+        for from in 0..8 {
+            let Some(&top) = self.columns[from].last() else {
+                continue;
+            };
+            if self.fits_foundation(top) {
+                moves.push(SolverMove { from: Spot::Column(from), count: 1, to: Spot::Foundation });
+            }
+            let len = self.columns[from].len();
+            let run = self.run_length(from);
+            for to in (0..8).filter(|&to| to != from) {
+                let target_empty = self.columns[to].is_empty();
+                if target_empty && first_empty_column != Some(to) {
+                    continue;
+                }
+                let max_count = run.min(self.capacity(target_empty));
+                for count in 1..=max_count {
+                    // 整列移到空列没有意义
+                    if target_empty && count == len {
+                        continue;
+                    }
+                    if self.fits_column(self.columns[from][len - count], to) {
+                        moves.push(SolverMove { from: Spot::Column(from), count, to: Spot::Column(to) });
+                    }
+                }
+            }
+            if let Some(cell) = first_empty_cell {
+                moves.push(SolverMove { from: Spot::Column(from), count: 1, to: Spot::Cell(cell) });
+            }
+        }
+
+        moves
+    }
+
+    /// 执行一步，然后自动收掉安全的牌
+    fn apply(&self, step: SolverMove) -> Self {
+        let mut next = self.clone();
+        let cards: Vec<u8> = match step.from {
+            Spot::Cell(cell) => vec![std::mem::replace(&mut next.cells[cell], EMPTY)],
+            Spot::Column(column) => {
+                let len = next.columns[column].len();
+                next.columns[column].split_off(len - step.count)
+            }
+            Spot::Foundation => unreachable!("不会从收牌区移出"),
+        };
+        match step.to {
+            Spot::Cell(cell) => next.cells[cell] = cards[0],
+            Spot::Column(column) => next.columns[column].extend(cards),
+            Spot::Foundation => next.foundations[suit_of(cards[0] as usize) as usize - 1] += 1,
+        }
+        next.auto_play();
+        next
+    }
+
+    /// 自动收掉所有安全的牌（与游戏中的自动收牌规则相同）
+    fn auto_play(&mut self) {
+        loop {
+            let counts = self.foundations;
+            let safe = |card: u8| self.fits_foundation(card) && is_safe_to_collect(card as usize, &counts);
+            if let Some(cell) = self.cells.iter().position(|&card| card != EMPTY && safe(card)) {
+                let card = std::mem::replace(&mut self.cells[cell], EMPTY);
+                self.foundations[suit_of(card as usize) as usize - 1] += 1;
+            } else if let Some(column) = (0..8).find(|&column| self.columns[column].last().is_some_and(|&card| safe(card))) {
+                let card = self.columns[column].pop().unwrap_or(EMPTY);
+                self.foundations[suit_of(card as usize) as usize - 1] += 1;
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// 与空当、牌列顺序无关的完整局面编码：收牌区、排序后的空当、排序后的牌列（以 EMPTY 分隔）
+    fn key(&self) -> Vec<u8> {
+        let mut cells = self.cells.clone();
+        cells.sort_unstable();
+        let mut columns: Vec<&Vec<u8>> = self.columns.iter().collect();
+        columns.sort_unstable();
+
+        let mut key = Vec::with_capacity(4 + cells.len() + 52 + columns.len());
+        key.extend_from_slice(&self.foundations);
+        key.extend_from_slice(&cells);
+        for column in columns {
+            key.extend_from_slice(column);
+            key.push(EMPTY);
+        }
+        key
+    }
+
+    /// 局面评估（越小越接近完成），用于决定搜索顺序
+    fn heuristic(&self) -> i32 {
+        let collected: i32 = self.foundations.iter().map(|&count| count as i32).sum();
+        // 压在更小点数上面的牌越多，越难解开
+        let blocking: i32 = self
+            .columns
+            .iter()
+            .map(|column| {
+                let mut lowest = u8::MAX;
+                let mut blocking = 0;
+                for &card in column {
+                    let rank = rank_of(card as usize);
+                    if rank > lowest {
+                        blocking += 1;
+                    }
+                    lowest = lowest.min(rank);
+                }
+                blocking
+            })
+            .sum();
+        let used_cells = self.cells.iter().filter(|&&cell| cell != EMPTY).count() as i32;
+        let empty_columns = self.columns.iter().filter(|column| column.is_empty()).count() as i32;
+        (52 - collected) * 100 + blocking * 10 + used_cells * 3 - empty_columns * 5
+    }
+}
+
+/// 牌列顶部连续有序（红黑相间、点数递减）的张数
+pub fn run_length(column: &[impl Copy + Into<usize>]) -> usize {
+    let mut length = usize::from(!column.is_empty());
+    for pair in column.windows(2).rev() {
+        let (below, above) = (pair[0].into(), pair[1].into());
+        if is_red(below) != is_red(above) && rank_of(above) + 1 == rank_of(below) {
+            length += 1;
+        } else {
+            break;
+        }
+    }
+    length
+}
+
+/// 收牌是否安全：A 和 2 总是安全，其他牌要等两种异色花色都收到比它小 1 的点数
+pub fn is_safe_to_collect(card: usize, foundations: &[u8; 4]) -> bool {
+    let rank = rank_of(card);
+    rank <= 2
+        || (1..=4u8)
+            .filter(|&suit| matches!(suit, 2 | 3) != is_red(card))
+            .all(|suit| foundations[suit as usize - 1] + 1 >= rank)
+}
+
+/// 深度优先搜索（子局面按评估排序），返回 None 表示被取消
+fn solve(board: &FreeCellBoard, cancel: &AtomicBool) -> Option<SolverResult> {
+    let start = State::from_board(board);
+    if start.is_solved() {
+        return Some(SolverResult::Solvable { hint: None, steps: 0 });
+    }
+
+    // 搜索栈：每层保存局面、排好序的候选步和下一个要尝试的位置
+    struct Frame {
+        state: State,
+        children: Vec<(SolverMove, State)>,
+        next: usize,
+    }
+
+    let mut visited = HashSet::new();
+    visited.insert(start.key());
+    let expand = |state: &State, visited: &mut HashSet<Vec<u8>>| {
+        let mut children: Vec<(i32, SolverMove, State)> = state
+            .moves()
+            .into_iter()
+            .map(|step| {
+                let child = state.apply(step);
+                (child.heuristic(), step, child)
+            })
+            .collect();
+        children.retain(|(_, _, child)| visited.insert(child.key()));
+        children.sort_by_key(|(score, _, _)| *score);
+        children.into_iter().map(|(_, step, child)| (step, child)).collect()
+    };
+
+    let children = expand(&start, &mut visited);
+    let mut stack = vec![Frame { state: start, children, next: 0 }];
+    while let Some(frame) = stack.last_mut() {
+        if cancel.load(Ordering::Relaxed) {
+            return None;
+        }
+        if visited.len() > MAX_STATES {
+            return Some(SolverResult::Unknown);
+        }
+        if frame.next >= frame.children.len() {
+            stack.pop();
+            continue;
+        }
+
+        let (_, child) = &frame.children[frame.next];
+        frame.next += 1;
+        if child.is_solved() {
+            let first = stack[0].children[stack[0].next - 1].0;
+            let hint = to_hint(board, &stack[0].state, first);
+            // 栈里每层都走了一步，层数就是找到的这条解法的步数
+            return Some(SolverResult::Solvable { hint: Some(hint), steps: stack.len() });
+        }
+        let state = child.clone();
+        let children = expand(&state, &mut visited);
+        stack.push(Frame { state, children, next: 0 });
+    }
+
+    Some(SolverResult::Unsolvable)
+}
+
+/// 把求解器的一步换算成牌桌上的提示
+fn to_hint(board: &FreeCellBoard, state: &State, step: SolverMove) -> Hint {
+    let (from, index, card) = match step.from {
+        Spot::Cell(cell) => (Pile::Cell(cell), 0, state.cells[cell] as usize),
+        Spot::Column(column) => {
+            let index = state.columns[column].len() - step.count;
+            (Pile::Column(column), index, state.columns[column][index] as usize)
+        }
+        Spot::Foundation => unreachable!("不会从收牌区移出"),
+    };
+    let to = match step.to {
+        Spot::Cell(cell) => Pile::Cell(cell),
+        Spot::Column(column) => Pile::Column(column),
+        Spot::Foundation => Pile::Foundation(board.foundation_for(card).unwrap_or(0)),
+    };
+    Hint { from, index, to }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solve_deal(deal_number: u32) -> SolverResult {
+        let board = FreeCellBoard::deal(deal_number, 4);
+        solve(&board, &AtomicBool::new(false)).expect("未取消时应返回结果")
+    }
+
+    #[test]
+    fn key_ignores_cell_and_column_order() {
+        let state = State::from_board(&FreeCellBoard::deal(1, 4));
+        let mut swapped = state.clone();
+        swapped.columns.swap(0, 7);
+        swapped.cells[3] = swapped.columns[2].pop().unwrap();
+        let mut moved = state.clone();
+        moved.cells[0] = moved.columns[2].pop().unwrap();
+        moved.columns.swap(0, 7);
+        assert_eq!(swapped.key(), moved.key());
+        assert_ne!(state.key(), moved.key());
+    }
+
+    #[test]
+    fn deal_1_is_solvable() {
+        let board = FreeCellBoard::deal(1, 4);
+        let SolverResult::Solvable { hint: Some(hint), steps } = solve_deal(1) else {
+            panic!("1 号牌局应有解");
+        };
+        assert!(steps > 0);
+        assert!(matches!(hint.from, Pile::Column(_) | Pile::Cell(_)));
+        if let Pile::Column(column) = hint.from {
+            assert!(hint.index < board.columns[column].len());
+        }
+    }
+
+    #[test]
+    fn solved_board_needs_no_steps() {
+        let mut board = FreeCellBoard::deal(1, 4);
+        board.columns.iter_mut().for_each(Vec::clear);
+        board.foundations = std::array::from_fn(|suit| (0..13).map(|rank| suit * 13 + rank).collect());
+        let result = solve(&board, &AtomicBool::new(false)).expect("未取消时应返回结果");
+        assert!(matches!(result, SolverResult::Solvable { hint: None, steps: 0 }));
+    }
+
+    #[test]
+    fn deal_11982_is_unsolvable() {
+        assert!(matches!(solve_deal(11982), SolverResult::Unsolvable));
+    }
+}
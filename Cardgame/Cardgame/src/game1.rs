@@ -1,11 +1,25 @@
 // game1.rs
-use crate::card::Card;
-use crate::difficulty::{DifficultySelection, GameDifficulty};
+use crate::achievements::GameEvent;
+use crate::animation::FLIP_DURATION;
+use crate::card::{self, Card};
+use crate::card_game::CardGame;
+use crate::difficulty::{self, DifficultyProfile, DifficultySelection, GameDifficulty};
+use crate::leaderboard::{LeaderboardEntry, ScoreSubmission};
+use crate::player_profile::{GameKind, GameResult};
+use crate::save_game::{SavedCard, SavedSession};
+use crate::timer::{self, GameTimer, TimerSnapshot};
 use crate::util;
 use eframe::egui;
 use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::time::{Duration, Instant};
 
+/// 透视牌持续时间
+const PEEK_DURATION: Duration = Duration::from_secs(1);
+/// 加时牌增加的时间
+const TIME_BONUS: Duration = Duration::from_secs(15);
+
 /// 神经衰弱游戏状态
 #[derive(PartialEq)]
 enum MemoryGameState {
@@ -14,6 +28,118 @@ enum MemoryGameState {
     GameOver,            // 游戏结束状态
 }
 
+/// 特殊卡牌类型
+#[derive(PartialEq, Clone, Copy, Serialize, Deserialize)]
+enum SpecialCard {
+    Peek,        // 透视牌：翻开所有卡片1秒
+    ShuffleTrap, // 洗牌陷阱：重新洗乱剩余的背面卡片
+    TimeBonus,   // 加时牌：增加剩余时间
+}
+
+impl SpecialCard {
+    /// 特殊卡牌的正面图片（透视牌和加时牌共用红色 JOKER，靠标签区分）
+    fn face_path(self) -> &'static str {
+        match self {
+            SpecialCard::Peek | SpecialCard::TimeBonus => "assets/card_face/JOKER-A.png",
+            SpecialCard::ShuffleTrap => "assets/card_face/JOKER-B.png",
+        }
+    }
+
+    /// 正面标签的底色
+    fn label_color(self) -> egui::Color32 {
+        match self {
+            SpecialCard::Peek => egui::Color32::from_rgb(150, 40, 160),
+            SpecialCard::ShuffleTrap => egui::Color32::from_rgb(60, 60, 60),
+            SpecialCard::TimeBonus => egui::Color32::from_rgb(30, 90, 200),
+        }
+    }
+
+    /// 在卡片正面底部画出类型标签
+    fn paint_label(self, ui: &egui::Ui, rect: egui::Rect) {
+        let band = egui::Rect::from_min_max(egui::pos2(rect.left(), rect.bottom() - 22.0), rect.max);
+        ui.painter().rect_filled(band, 4.0, self.label_color());
+        ui.painter().text(
+            band.center(),
+            egui::Align2::CENTER_CENTER,
+            self.label(),
+            egui::FontId::proportional(14.0),
+            egui::Color32::WHITE,
+        );
+    }
+
+    /// 特殊卡牌的说明文字
+    fn label(self) -> &'static str {
+        match self {
+            SpecialCard::Peek => "透视",
+            SpecialCard::ShuffleTrap => "洗牌",
+            SpecialCard::TimeBonus => "加时",
+        }
+    }
+}
+
+/// 神经衰弱难度参数
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct MemoryProfile {
+    pub pairs: usize,          // 牌对数量
+    pub columns: usize,        // 每行卡片数
+    pub time_limit_secs: u64,  // 时间限制（秒）
+}
+
+impl DifficultyProfile for MemoryProfile {
+    fn preset(difficulty: GameDifficulty) -> Self {
+        match difficulty {
+            // 简单：8对牌，4×4网格，60秒
+            GameDifficulty::Easy => Self { pairs: 8, columns: 4, time_limit_secs: 60 },
+            // 中等：12对牌，4×6网格，90秒
+            GameDifficulty::Medium | GameDifficulty::Custom | GameDifficulty::Adaptive => Self { pairs: 12, columns: 6, time_limit_secs: 90 },
+            // 困难：18对牌，4×9网格，120秒
+            GameDifficulty::Hard => Self { pairs: 18, columns: 9, time_limit_secs: 120 },
+        }
+    }
+
+    fn adaptive(level: f32) -> Self {
+        let pairs = difficulty::lerp(8.0, 18.0, level).round() as usize;
+        let columns = match pairs {
+            0..=8 => 4,
+            9..=12 => 6,
+            _ => 9,
+        };
+        Self {
+            pairs,
+            columns,
+            time_limit_secs: difficulty::lerp(60.0, 120.0, level).round() as u64,
+        }
+    }
+
+    fn load_preset(&mut self, difficulty: GameDifficulty) {
+        *self = Self::preset(difficulty);
+    }
+
+    fn show_editor(&mut self, ui: &mut egui::Ui) {
+        // 每种点数每种颜色只有一对，最多26对
+        ui.add(egui::Slider::new(&mut self.pairs, 2..=26).text("牌对数量"));
+        ui.add(egui::Slider::new(&mut self.columns, 2..=13).text("每行卡片数"));
+        ui.add(egui::Slider::new(&mut self.time_limit_secs, 20..=600).suffix("秒").text("时间限制"));
+    }
+}
+
+/// 神经衰弱未完成一局的存档
+#[derive(Serialize, Deserialize)]
+pub struct MemorySave {
+    difficulty: GameDifficulty,
+    adaptive_level: f32,
+    custom_profile: MemoryProfile,
+    cards: Vec<Option<SavedCard>>, // 按位置保存，已配对的位置为 None
+    flipped_cards: Vec<usize>,
+    matched_pairs: usize,
+    moves_count: usize,
+    mismatches: usize,
+    power_ups_enabled: bool,
+    special_cards: HashMap<usize, SpecialCard>,
+    power_up_message: Option<String>,
+    timer: Option<TimerSnapshot>,
+}
+
 /// 神经衰弱游戏结构体
 pub struct MemoryGame {
     state: MemoryGameState,
@@ -26,9 +152,20 @@ pub struct MemoryGame {
     main_menu_cards: Vec<Card>,
     check_timer: Option<Instant>,
     hovered_card: Option<usize>,
-    game_timer: Option<Instant>,
-    time_remaining: Duration,
+    game_timer: Option<GameTimer>,
     game_won: bool,
+    power_ups_enabled: bool,                   // 是否启用特殊卡牌
+    special_cards: HashMap<usize, SpecialCard>, // 特殊卡牌（按卡片id索引）
+    peek_timer: Option<Instant>,               // 透视计时器
+    paused_since: Option<Instant>,             // 游戏计时器暂停的起点（透视和配对检查的计时随之顺延）
+    power_up_message: Option<String>,          // 最近触发的特殊卡牌提示
+    mismatch_delay_ms: u64,                    // 未配对卡片翻回前的展示时间
+    custom_profile: MemoryProfile,             // 自定义难度参数
+    finished_result: Option<GameResult>,       // 刚结束的一局结果，等待主程序记录
+    score_submission: ScoreSubmission,         // 结束界面的排行榜成绩提交
+    mismatches: usize,                         // 本局翻错的次数
+    events: Vec<GameEvent>,                    // 等待主程序处理的游戏事件
+    suspended_session: Option<SavedSession>,   // 中途返回主菜单时保存的存档
 }
 
 impl MemoryGame {
@@ -36,7 +173,7 @@ impl MemoryGame {
     pub fn new() -> Self {
         Self {
             state: MemoryGameState::DifficultySelection,
-            difficulty_selection: DifficultySelection::new(),
+            difficulty_selection: DifficultySelection::new(GameKind::Memory),
             game_cards: Vec::new(),
             flipped_cards: Vec::new(),
             matched_pairs: 0,
@@ -46,86 +183,30 @@ impl MemoryGame {
             check_timer: None,
             hovered_card: None,
             game_timer: None,
-            time_remaining: Duration::from_secs(0),
             game_won: false,
+            power_ups_enabled: false,
+            special_cards: HashMap::new(),
+            peek_timer: None,
+            paused_since: None,
+            power_up_message: None,
+            mismatch_delay_ms: 1000,
+            custom_profile: MemoryProfile::preset(GameDifficulty::Custom),
+            finished_result: None,
+            score_submission: ScoreSubmission::new(),
+            mismatches: 0,
+            events: Vec::new(),
+            suspended_session: None,
         }
     }
 
-    /// 设置主菜单卡片
-    pub fn set_main_menu_cards(&mut self, cards: Vec<Card>) {
-        self.main_menu_cards = cards;
-    }
-
-    /// 显示游戏界面，返回是否要返回主菜单
-    pub fn show(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) -> bool {
-        let mut return_to_menu = false;
-
-        // 处理过渡动画
-        if self.difficulty_selection.is_in_transition() {
-            if self.difficulty_selection.show_transition_animation(ui) {
-                self.state = MemoryGameState::GamePlaying;
-                self.initialize_game(ctx);
-            }
-            return return_to_menu;
-        }
-
-        // 检查是否需要处理匹配结果
-        if let Some(timer) = self.check_timer {
-            if timer.elapsed() >= Duration::from_millis(1000) {
-                self.process_match_result();
-                self.check_timer = None;
-            }
-        }
-
-        // 更新游戏计时器
-        if let Some(timer) = self.game_timer {
-            let elapsed = timer.elapsed();
-            let total_time = self.get_total_time();
-            
-            if elapsed < total_time {
-                self.time_remaining = total_time - elapsed;
-            } else {
-                // 时间到，游戏结束
-                self.time_remaining = Duration::from_secs(0);
-                self.state = MemoryGameState::GameOver;
-                self.game_won = false;
-                self.game_timer = None;
-            }
-        }
-
-        match self.state {
-            MemoryGameState::DifficultySelection => {
-                let rules = vec![
-                    "匹配相同数字且同颜色的牌对",
-                    "红桃和方片为红色，黑桃和梅花为黑色",
-                    "简单难度: 8对牌，60秒时间",
-                    "中等难度: 12对牌，90秒时间", 
-                    "困难难度: 18对牌，120秒时间",
-                    "在时间内匹配所有牌对即可获胜",
-                ];
-                let (menu_return, _) = 
-                    self.difficulty_selection.show(ui, "神经衰弱游戏", &rules);
-                return_to_menu = menu_return;
-            }
-            MemoryGameState::GamePlaying => {
-                return_to_menu = self.show_game_playing(ui, ctx);
-            }
-            MemoryGameState::GameOver => {
-                return_to_menu = self.show_game_over(ui);
-            }
-        }
-
-        return_to_menu
-    }
-
     /// 获取总游戏时间（根据难度）
     fn get_total_time(&self) -> Duration {
-        match self.difficulty_selection.selected_difficulty {
-            Some(GameDifficulty::Easy) => Duration::from_secs(60),   // 简单60秒
-            Some(GameDifficulty::Medium) => Duration::from_secs(90), // 中等90秒
-            Some(GameDifficulty::Hard) => Duration::from_secs(120),  // 困难120秒
-            None => Duration::from_secs(60),
-        }
+        Duration::from_secs(self.profile().time_limit_secs)
+    }
+
+    /// 当前难度的参数
+    fn profile(&self) -> MemoryProfile {
+        self.difficulty_selection.profile(&self.custom_profile)
     }
 
     /// 显示游戏结束界面
@@ -145,6 +226,9 @@ impl MemoryGame {
                         ui.colored_label(egui::Color32::RED, "时间到！游戏失败");
                         ui.label(format!("完成进度: {}/{} 对", self.matched_pairs, self.get_total_pairs()));
                     }
+
+                    ui.add_space(10.0);
+                    self.score_submission.show(ui);
                     
                     ui.add_space(20.0);
                     
@@ -171,12 +255,7 @@ impl MemoryGame {
 
     /// 获取总对数
     fn get_total_pairs(&self) -> usize {
-        match self.difficulty_selection.selected_difficulty {
-            Some(GameDifficulty::Easy) => 8,    // 8对
-            Some(GameDifficulty::Medium) => 12, // 12对
-            Some(GameDifficulty::Hard) => 18,   // 18对
-            None => 8,
-        }
+        self.profile().pairs
     }
 
     /// 显示游戏进行界面
@@ -201,26 +280,28 @@ impl MemoryGame {
                             ui.add_space(10.0);
                             
                             if let Some(difficulty) = self.difficulty_selection.selected_difficulty {
-                                let (difficulty_text, total_pairs) = match difficulty {
-                                    GameDifficulty::Easy => ("简单难度", 8),    // 8对
-                                    GameDifficulty::Medium => ("中等难度", 12), // 12对
-                                    GameDifficulty::Hard => ("困难难度", 18),   // 18对
-                                };
-                                ui.colored_label(egui::Color32::LIGHT_BLUE, difficulty_text);
+                                let total_pairs = self.get_total_pairs();
+                                ui.colored_label(egui::Color32::LIGHT_BLUE, difficulty.label());
                                 ui.label(format!("进度: {}/{} 对", self.matched_pairs, total_pairs));
                                 ui.label(format!("移动次数: {}", self.moves_count));
                                 ui.label(format!("剩余卡片: {} 张", self.get_remaining_cards_count()));
                                 
                                 // 显示倒计时
-                                let seconds_remaining = self.time_remaining.as_secs();
-                                let color = if seconds_remaining <= 10 {
-                                    egui::Color32::RED
-                                } else if seconds_remaining <= 30 {
-                                    egui::Color32::YELLOW
-                                } else {
-                                    egui::Color32::BLACK
-                                };
-                                ui.colored_label(color, format!("剩余时间: {}秒", seconds_remaining));
+                                if let Some(timer) = &mut self.game_timer {
+                                    ui.horizontal(|ui| {
+                                        ui.colored_label(
+                                            timer.color(),
+                                            format!("剩余时间: {}秒", timer.remaining().as_secs()),
+                                        );
+                                        if ui.button("暂停 (Esc)").clicked() {
+                                            timer.pause();
+                                        }
+                                    });
+                                }
+
+                                if let Some(message) = &self.power_up_message {
+                                    ui.colored_label(egui::Color32::DARK_GREEN, message);
+                                }
                             }
                             
                             ui.add_space(10.0);
@@ -230,13 +311,14 @@ impl MemoryGame {
                     ui.add_space(20.0);
 
                     // 游戏卡片网格
+                    let mut triggered_special = None;
                     ui.horizontal(|ui| {
-                        let grid_size = match self.difficulty_selection.selected_difficulty {
-                            Some(GameDifficulty::Easy) => (4, 4),   // 4×4网格
-                            Some(GameDifficulty::Medium) => (4, 6), // 4×6网格
-                            Some(GameDifficulty::Hard) => (4, 9),   // 困难难度改为4×9网格
-                            None => (4, 4),
-                        };
+                        let columns = self.profile().columns;
+                        // 特殊卡牌会占用额外的行
+                        let grid_size = (self.game_cards.len().div_ceil(columns).max(4), columns);
+                        let peeking = self.peek_timer.is_some();
+                        // 等待配对结果或动画播放时锁定输入
+                        let input_locked = self.check_timer.is_some() || self.any_card_animating();
 
                         let grid_width = (grid_size.1 as f32) * 78.0;
                         ui.add_space((ui.available_width() - grid_width) / 2.0);
@@ -248,27 +330,44 @@ impl MemoryGame {
                                     for j in 0..grid_size.1 {
                                         let index = i * grid_size.1 + j;
                                         if index < self.game_cards.len() {
-                                            if let Some(card) = &mut self.game_cards[index] {
+                                            if peeking && let Some(card) = &self.game_cards[index] {
+                                                // 透视期间只显示正面，不响应点击
+                                                let mut temp_card = card.clone();
+                                                temp_card.is_face_up = true;
+                                                temp_card.animation = None;
+                                                let response = temp_card.render(ui, egui::vec2(70.0, 100.0), egui::Sense::hover());
+                                                if let Some(&special) = self.special_cards.get(&card.id) {
+                                                    special.paint_label(ui, response.rect);
+                                                }
+                                            } else if let Some(card) = &mut self.game_cards[index] {
                                                 let is_hovered = self.hovered_card == Some(index);
+                                                let can_flip = !input_locked && !card.is_face_up && self.flipped_cards.len() < 2;
                                                 
                                                 let base_size = egui::vec2(70.0, 100.0);
-                                                let display_size = if is_hovered && !card.is_face_up && self.flipped_cards.len() < 2 && self.check_timer.is_none() {
+                                                let display_size = if is_hovered && can_flip {
                                                     base_size * 1.05
                                                 } else {
                                                     base_size
                                                 };
                                                 
-                                                let response = card.show(ui, display_size);
+                                                let response = if input_locked {
+                                                    card.render(ui, display_size, egui::Sense::hover())
+                                                } else {
+                                                    card.show(ui, display_size)
+                                                };
+                                                if card.is_face_up && !card.is_animating() && let Some(&special) = self.special_cards.get(&card.id) {
+                                                    special.paint_label(ui, response.rect);
+                                                }
                                                 
-                                                if response.hovered() && !card.is_face_up && self.flipped_cards.len() < 2 && self.check_timer.is_none() {
+                                                if response.hovered() && can_flip {
                                                     self.hovered_card = Some(index);
                                                 } else if self.hovered_card == Some(index) && !response.hovered() {
                                                     self.hovered_card = None;
                                                 }
                                                 
-                                                if response.clicked() {
-                                                    if self.check_timer.is_some() && !self.flipped_cards.contains(&index)  {
-                                                        card.is_face_up = false;
+                                                if response.clicked() && !input_locked {
+                                                    if let Some(&special) = self.special_cards.get(&card.id) {
+                                                        triggered_special = Some((index, special));
                                                     } else if self.flipped_cards.len() < 2 
                                                         && card.is_face_up 
                                                         && !self.flipped_cards.contains(&index) 
@@ -293,6 +392,10 @@ impl MemoryGame {
                             });
                     });
 
+                    if let Some((index, special)) = triggered_special {
+                        self.trigger_special_card(index, special);
+                    }
+
                     ui.add_space(20.0);
 
                     ui.horizontal(|ui| {
@@ -314,16 +417,15 @@ impl MemoryGame {
                         
                         if self.centered_button(ui, "返回主菜单", 120.0, 40.0).clicked() {
                             return_to_menu = true;
+                            self.suspended_session = self.snapshot().map(SavedSession::Memory);
                             self.reset_game_state();
                             self.state = MemoryGameState::DifficultySelection;
                         }
                     });
 
                     let total_pairs = self.get_total_pairs();
-                    if self.matched_pairs >= total_pairs {
-                        self.game_won = true;
-                        self.state = MemoryGameState::GameOver;
-                        self.game_timer = None;
+                    if self.matched_pairs >= total_pairs && !self.any_card_animating() {
+                        self.finish_game(true);
                     }
 
                     ui.add_space(20.0);
@@ -333,6 +435,84 @@ impl MemoryGame {
         return_to_menu
     }
 
+    /// 结束游戏并记录结果
+    fn finish_game(&mut self, won: bool) {
+        self.game_won = won;
+        self.state = MemoryGameState::GameOver;
+        self.difficulty_selection.record_result(won);
+
+        let difficulty = self.difficulty_selection.selected_difficulty.unwrap_or(GameDifficulty::Easy);
+        let mut result = GameResult::new(GameKind::Memory, difficulty, won);
+        result.time_secs = self.game_timer.as_ref().map(|timer| timer.elapsed().as_secs());
+        result.moves = Some(self.moves_count);
+        self.score_submission.offer(&result);
+        self.finished_result = Some(result);
+        if won {
+            self.events.push(GameEvent::MemoryCleared { difficulty, mismatches: self.mismatches });
+        }
+        self.game_timer = None;
+    }
+
+    /// 保存进行中的一局，没有进行中的游戏时返回 None
+    fn snapshot(&self) -> Option<MemorySave> {
+        if self.state != MemoryGameState::GamePlaying || self.game_cards.is_empty() {
+            return None;
+        }
+        Some(MemorySave {
+            difficulty: self.difficulty_selection.selected_difficulty?,
+            adaptive_level: self.difficulty_selection.adaptive.level,
+            custom_profile: self.custom_profile,
+            cards: self
+                .game_cards
+                .iter()
+                .map(|slot| slot.as_ref().and_then(SavedCard::from_card))
+                .collect(),
+            flipped_cards: self.flipped_cards.clone(),
+            matched_pairs: self.matched_pairs,
+            moves_count: self.moves_count,
+            mismatches: self.mismatches,
+            power_ups_enabled: self.power_ups_enabled,
+            special_cards: self.special_cards.clone(),
+            power_up_message: self.power_up_message.clone(),
+            timer: self.game_timer.as_ref().map(GameTimer::snapshot),
+        })
+    }
+
+    /// 从存档恢复一局，计时器处于暂停状态
+    fn restore(&mut self, ctx: &egui::Context, save: MemorySave) {
+        self.reset_game_state();
+        self.difficulty_selection.restore(save.difficulty, save.adaptive_level);
+        self.custom_profile = save.custom_profile;
+        self.power_ups_enabled = save.power_ups_enabled;
+
+        self.game_cards = save
+            .cards
+            .iter()
+            .map(|slot| {
+                let saved = slot.as_ref()?;
+                let face_path = match save.special_cards.get(&saved.id) {
+                    Some(special) => special.face_path().to_string(),
+                    None => util::get_card_image_path(saved.rank, saved.suit),
+                };
+                saved.restore(ctx, &face_path)
+            })
+            .collect();
+        self.special_cards = save.special_cards;
+        self.flipped_cards = save.flipped_cards;
+        // 存档时有两张待判定的卡片，恢复后重新判定
+        if self.flipped_cards.len() == 2 {
+            self.check_timer = Some(Instant::now());
+        }
+        self.matched_pairs = save.matched_pairs;
+        self.moves_count = save.moves_count;
+        self.mismatches = save.mismatches;
+        self.power_up_message = save.power_up_message;
+        self.game_timer = save.timer.map(GameTimer::restore);
+        self.paused_since = None;
+        self.game_started = true;
+        self.state = MemoryGameState::GamePlaying;
+    }
+
     /// 获取剩余卡片数量
     fn get_remaining_cards_count(&self) -> usize {
         self.game_cards.iter().filter(|card| card.is_some()).count()
@@ -352,35 +532,71 @@ impl MemoryGame {
 
     /// 处理匹配结果
     fn process_match_result(&mut self) {
-        if self.flipped_cards.len() == 2 {
-            if let (Some(Some(card1)), Some(Some(card2))) = (
+        if self.flipped_cards.len() == 2
+            && let (Some(Some(card1)), Some(Some(card2))) = (
                 self.game_cards.get(self.flipped_cards[0]),
                 self.game_cards.get(self.flipped_cards[1])
-            ) {
-                if card1.rank == card2.rank && Self::is_same_color(card1.suit, card2.suit) {
-                    self.matched_pairs += 1;
-                    
-                    for &index in &self.flipped_cards {
-                        if index < self.game_cards.len() {
-                            self.game_cards[index] = None;
-                        }
+            )
+        {
+            if card1.rank == card2.rank && Self::is_same_color(card1.suit, card2.suit) {
+                self.matched_pairs += 1;
+                
+                for &index in &self.flipped_cards {
+                    if let Some(Some(card)) = self.game_cards.get_mut(index) {
+                        card.fly_away();
                     }
-                    
-                    self.flipped_cards.clear();
-                } else {
-                    let flipped_copy = self.flipped_cards.clone();
-                    self.flipped_cards.clear();
-                    
-                    for &index in &flipped_copy {
-                        if let Some(Some(card)) = self.game_cards.get_mut(index) {
-                            card.is_face_up = false;
-                        }
+                }
+                
+                self.flipped_cards.clear();
+            } else {
+                self.mismatches += 1;
+                let flipped_copy = self.flipped_cards.clone();
+                self.flipped_cards.clear();
+                
+                for &index in &flipped_copy {
+                    if let Some(Some(card)) = self.game_cards.get_mut(index) {
+                        card.flip_to(false);
                     }
                 }
             }
         }
     }
 
+    /// 是否有卡片正在播放动画
+    fn any_card_animating(&self) -> bool {
+        card::any_animating(self.game_cards.iter().flatten())
+    }
+
+    /// 触发特殊卡牌效果
+    fn trigger_special_card(&mut self, index: usize, special: SpecialCard) {
+        // 特殊卡牌翻开后即飞离牌桌
+        if let Some(Some(card)) = self.game_cards.get_mut(index) {
+            card.fly_away();
+        }
+        self.hovered_card = None;
+
+        match special {
+            SpecialCard::Peek => {
+                self.peek_timer = Some(Instant::now());
+            }
+            SpecialCard::ShuffleTrap => {
+                self.shuffle_cards();
+            }
+            SpecialCard::TimeBonus => {
+                if let Some(timer) = &mut self.game_timer {
+                    timer.add_time(TIME_BONUS);
+                }
+            }
+        }
+
+        let detail = match special {
+            SpecialCard::Peek => "所有卡片翻开1秒".to_string(),
+            SpecialCard::ShuffleTrap => "剩余卡片已被重新洗乱".to_string(),
+            SpecialCard::TimeBonus => format!("剩余时间增加{}秒", TIME_BONUS.as_secs()),
+        };
+        self.power_up_message = Some(format!("{}牌: {}", special.label(), detail));
+    }
+
     /// 检查两张卡片是否同颜色
     fn is_same_color(suit1: u8, suit2: u8) -> bool {
         let is_red1 = suit1 == 2 || suit1 == 3;
@@ -388,6 +604,22 @@ impl MemoryGame {
         is_red1 == is_red2
     }
 
+    /// 跟随游戏计时器暂停：继续时把透视和配对检查的起点顺延暂停的时长
+    fn sync_pause(&mut self) {
+        let paused = self.game_timer.as_ref().is_some_and(GameTimer::is_paused);
+        match self.paused_since {
+            None if paused => self.paused_since = Some(Instant::now()),
+            Some(since) if !paused => {
+                let pause = since.elapsed();
+                for timer in [&mut self.check_timer, &mut self.peek_timer].into_iter().flatten() {
+                    *timer += pause;
+                }
+                self.paused_since = None;
+            }
+            _ => {}
+        }
+    }
+
     /// 初始化游戏
     fn initialize_game(&mut self, ctx: &egui::Context) {
         self.game_cards.clear();
@@ -397,13 +629,12 @@ impl MemoryGame {
         self.check_timer = None;
         self.hovered_card = None;
         self.game_won = false;
+        self.special_cards.clear();
+        self.peek_timer = None;
+        self.paused_since = None;
+        self.power_up_message = None;
         
-        let pairs_count = match self.difficulty_selection.selected_difficulty {
-            Some(GameDifficulty::Easy) => 8,    // 8对
-            Some(GameDifficulty::Medium) => 12, // 12对
-            Some(GameDifficulty::Hard) => 18,   // 18对
-            None => 8,
-        };
+        let pairs_count = self.get_total_pairs();
         
         let total_cards = pairs_count * 2;
         self.game_cards = vec![None; total_cards];
@@ -411,14 +642,14 @@ impl MemoryGame {
         let mut all_possible_pairs = Vec::new();
         
         for rank in 1..=13 {
-            let red_suits = vec![2, 3];
+            let red_suits = [2, 3];
             for i in 0..red_suits.len() {
                 for j in (i + 1)..red_suits.len() {
                     all_possible_pairs.push((rank, red_suits[i], red_suits[j]));
                 }
             }
             
-            let black_suits = vec![1, 4];
+            let black_suits = [1, 4];
             for i in 0..black_suits.len() {
                 for j in (i + 1)..black_suits.len() {
                     all_possible_pairs.push((rank, black_suits[i], black_suits[j]));
@@ -442,27 +673,44 @@ impl MemoryGame {
             }
         }
         
+        // 加入特殊卡牌
+        if self.power_ups_enabled {
+            let specials = [SpecialCard::Peek, SpecialCard::ShuffleTrap, SpecialCard::TimeBonus];
+            for (offset, special) in specials.into_iter().enumerate() {
+                let id = total_cards + offset;
+                if let Ok(card) = Card::new(ctx, id, 1, 1, "assets/card_back/default.png", special.face_path()) {
+                    self.game_cards.push(Some(card));
+                    self.special_cards.insert(id, special);
+                }
+            }
+        }
+        
         self.shuffle_cards();
         
         // 启动游戏计时器
-        self.game_timer = Some(Instant::now());
-        self.time_remaining = self.get_total_time();
+        self.game_timer = Some(GameTimer::start(self.get_total_time()));
     }
 
-    /// 洗牌
+    /// 洗牌（只打乱背面朝上的卡片，已翻开的卡片和空位保持不动）
     fn shuffle_cards(&mut self) {
         let mut rng = rand::rng();
         
-        let mut cards: Vec<Card> = self.game_cards
-            .iter_mut()
-            .filter_map(|card| card.take())
+        let slots: Vec<usize> = self.game_cards
+            .iter()
+            .enumerate()
+            .filter(|(_, card)| matches!(card, Some(card) if !card.is_face_up))
+            .map(|(index, _)| index)
+            .collect();
+        
+        let mut cards: Vec<Card> = slots
+            .iter()
+            .filter_map(|&index| self.game_cards[index].take())
             .collect();
         
         cards.shuffle(&mut rng);
         
-        let mut card_iter = cards.into_iter();
-        for slot in &mut self.game_cards {
-            *slot = card_iter.next();
+        for (&index, card) in slots.iter().zip(cards) {
+            self.game_cards[index] = Some(card);
         }
     }
 
@@ -472,13 +720,180 @@ impl MemoryGame {
         self.flipped_cards.clear();
         self.matched_pairs = 0;
         self.moves_count = 0;
+        self.mismatches = 0;
         self.game_started = false;
         self.difficulty_selection.reset();
         self.check_timer = None;
         self.hovered_card = None;
         self.game_timer = None;
-        self.time_remaining = Duration::from_secs(0);
         self.game_won = false;
+        self.special_cards.clear();
+        self.peek_timer = None;
+        self.paused_since = None;
+        self.power_up_message = None;
+        self.score_submission.clear();
+    }
+}
+
+impl CardGame for MemoryGame {
+    fn kind(&self) -> GameKind {
+        GameKind::Memory
+    }
+
+    fn name(&self) -> &'static str {
+        "神经衰弱游戏"
+    }
+
+    fn rules(&self) -> Vec<String> {
+        [
+            "匹配相同数字且同颜色的牌对",
+            "红桃和方片为红色，黑桃和梅花为黑色",
+            "简单难度: 8对牌，60秒时间",
+            "中等难度: 12对牌，90秒时间",
+            "困难难度: 18对牌，120秒时间",
+            "自定义难度: 自由设置牌对数量、每行卡片数和时间限制",
+            "自适应难度: 根据最近战绩自动调整牌对数量和时间，使胜率保持在60%左右",
+            "在时间内匹配所有牌对即可获胜",
+            "特殊卡牌（可选）: 透视牌翻开所有卡片1秒，洗牌陷阱打乱剩余卡片，加时牌增加15秒",
+            "可调整未配对卡片翻回前的展示时间",
+            "按 Esc 或点击暂停按钮可以暂停计时",
+        ]
+        .map(String::from)
+        .to_vec()
+    }
+
+    fn difficulty(&mut self) -> (&mut DifficultySelection, &mut dyn DifficultyProfile) {
+        (&mut self.difficulty_selection, &mut self.custom_profile)
+    }
+
+    /// 设置主菜单卡片
+    fn set_main_menu_cards(&mut self, cards: &[Card]) {
+        self.main_menu_cards = cards.to_vec();
+    }
+
+    /// 显示游戏界面，返回是否要返回主菜单
+    fn show(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) -> bool {
+        let mut return_to_menu = false;
+
+        // 处理过渡动画
+        if self.difficulty_selection.is_in_transition() {
+            if self.difficulty_selection.show_transition_animation(ui) {
+                self.state = MemoryGameState::GamePlaying;
+                self.initialize_game(ctx);
+            }
+            return return_to_menu;
+        }
+
+        // 暂停期间透视和配对检查都不计时
+        self.sync_pause();
+        let paused = self.paused_since.is_some();
+
+        // 检查是否需要处理匹配结果（等待翻牌动画结束后再展示一段时间）
+        if !paused && let Some(timer) = self.check_timer {
+            if timer.elapsed() >= FLIP_DURATION + Duration::from_millis(self.mismatch_delay_ms) {
+                self.process_match_result();
+                self.check_timer = None;
+            } else {
+                ctx.request_repaint();
+            }
+        }
+
+        // 移除已经飞走的卡片
+        for slot in &mut self.game_cards {
+            if slot.as_ref().is_some_and(|card| card.has_flown_away()) {
+                *slot = None;
+            }
+        }
+
+        // 透视结束
+        if !paused && let Some(timer) = self.peek_timer {
+            if timer.elapsed() >= PEEK_DURATION {
+                self.peek_timer = None;
+            } else {
+                ctx.request_repaint();
+            }
+        }
+
+        // 更新游戏计时器
+        if let Some(timer) = &mut self.game_timer {
+            timer.handle_pause_input(ctx);
+            if timer.is_expired() {
+                // 时间到，游戏结束
+                self.finish_game(false);
+            }
+        }
+
+        // 暂停时显示遮罩
+        if let Some(timer) = &mut self.game_timer
+            && timer.is_paused()
+            && timer::show_pause_overlay(ctx)
+        {
+            timer.resume();
+        }
+
+        match self.state {
+            MemoryGameState::DifficultySelection => {
+                ui.horizontal(|ui| {
+                    ui.add_space(ui.available_width() / 2.0 - 60.0);
+                    ui.checkbox(&mut self.power_ups_enabled, "启用特殊卡牌");
+                });
+                ui.horizontal(|ui| {
+                    ui.add_space(ui.available_width() / 2.0 - 150.0);
+                    ui.add(
+                        egui::Slider::new(&mut self.mismatch_delay_ms, 300..=3000)
+                            .suffix("毫秒")
+                            .text("未配对翻回延迟"),
+                    );
+                });
+                let (menu_return, _) = 
+                    self.show_difficulty_selection(ui);
+                return_to_menu = menu_return;
+            }
+            MemoryGameState::GamePlaying => {
+                return_to_menu = self.show_game_playing(ui, ctx);
+            }
+            MemoryGameState::GameOver => {
+                return_to_menu = self.show_game_over(ui);
+            }
+        }
+
+        return_to_menu
+    }
+
+    /// 取出刚结束的一局结果
+    fn take_result(&mut self) -> Option<GameResult> {
+        self.finished_result.take()
+    }
+
+    /// 取出本帧产生的游戏事件（用于判定成就）
+    fn take_events(&mut self) -> Vec<GameEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// 取出玩家提交的排行榜成绩
+    fn take_leaderboard_entry(&mut self) -> Option<(GameKind, LeaderboardEntry)> {
+        self.score_submission.take_entry()
+    }
+
+    /// 取出中途返回主菜单时保存的存档
+    fn take_suspended_session(&mut self) -> Option<SavedSession> {
+        self.suspended_session.take()
+    }
+
+    /// 重置到难度选择界面
+    fn reset(&mut self) {
+        self.reset_game_state();
+        self.state = MemoryGameState::DifficultySelection;
+    }
+
+    fn save_state(&self) -> Option<SavedSession> {
+        self.snapshot().map(SavedSession::Memory)
+    }
+
+    fn restore_state(&mut self, ctx: &egui::Context, session: SavedSession) {
+        if let SavedSession::Memory(save) = session {
+            self.restore(ctx, save);
+        }
     }
 }
 
@@ -0,0 +1,786 @@
+// game10.rs - 红心大战，玩家对三家 AI，每局先传牌再打 13 墩，红桃每张 1 分、黑桃Q 13 分，有人达到结束分数时得分最低者获胜
+use eframe::egui;
+use crate::achievements::GameEvent;
+use crate::card::Card;
+use crate::card_game::CardGame;
+use crate::difficulty::{self, DifficultyProfile, DifficultySelection, GameDifficulty};
+use crate::hearts::{self, AiView, PassDirection, TrickRules};
+use crate::leaderboard::{LeaderboardEntry, ScoreSubmission};
+use crate::player_profile::{GameKind, GameResult};
+use crate::save_game::SavedSession;
+use crate::solitaire::{self, card_name, suit_of};
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// AI 每一步行动（以及收走一墩牌）的间隔
+const AI_STEP: Duration = Duration::from_millis(700);
+/// 卡片大小
+const CARD_W: f32 = 70.0;
+const CARD_H: f32 = 98.0;
+/// 玩家手牌的叠放间距
+const HAND_SPACING: f32 = 40.0;
+/// 选中的牌向上抬起的高度
+const SELECTED_LIFT: f32 = 18.0;
+/// 四家的称呼（0 为玩家，1 为左家，2 为对家，3 为右家，按 0→1→2→3 的顺序出牌）
+const SEAT_NAMES: [&str; 4] = ["你", "左家", "对家", "右家"];
+
+/// 红心大战难度参数
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct HeartsProfile {
+    pub ai_skill: f32,     // AI 水平（0~1：越高越会传牌、躲分、记牌和阻止全收）
+    pub target_score: u32, // 结束分数：有人达到时本场结束
+}
+
+impl DifficultyProfile for HeartsProfile {
+    fn preset(difficulty: GameDifficulty) -> Self {
+        let ai_skill = match difficulty {
+            GameDifficulty::Easy => 0.2,
+            GameDifficulty::Medium | GameDifficulty::Custom | GameDifficulty::Adaptive => 0.6,
+            GameDifficulty::Hard => 1.0,
+        };
+        Self { ai_skill, target_score: 100 }
+    }
+
+    fn adaptive(level: f32) -> Self {
+        // 强度越高 AI 水平越高
+        Self {
+            ai_skill: difficulty::lerp(0.2, 1.0, level),
+            ..Self::preset(GameDifficulty::Medium)
+        }
+    }
+
+    fn load_preset(&mut self, difficulty: GameDifficulty) {
+        *self = Self::preset(difficulty);
+    }
+
+    fn show_editor(&mut self, ui: &mut egui::Ui) {
+        ui.add(egui::Slider::new(&mut self.ai_skill, 0.0..=1.0).text("AI水平"));
+        ui.add(egui::Slider::new(&mut self.target_score, 50..=150).step_by(10.0).text("结束分数"));
+    }
+}
+
+/// 一局的阶段
+#[derive(PartialEq, Clone, Copy, Serialize, Deserialize)]
+enum RoundPhase {
+    Passing,
+    Playing,
+    Settled,
+}
+
+/// 一局的牌局（存档时整体保存）
+#[derive(Clone, Serialize, Deserialize)]
+struct HeartsRound {
+    hands: [Vec<usize>; 4],      // 四家手牌（按花色分组排列）
+    direction: PassDirection,    // 本局的传牌方向
+    phase: RoundPhase,           // 当前阶段
+    received: Vec<usize>,        // 玩家传牌收到的牌（出第一张牌前抬起显示）
+    turn: usize,                 // 当前出牌的一家
+    trick: Vec<(usize, usize)>,  // 本墩已出的牌：(座位, 牌)
+    tricks_played: usize,        // 已打完的墩数
+    hearts_broken: bool,         // 红桃是否已经破了
+    taken: [u32; 4],             // 本局各家吃到的分
+    played: Vec<usize>,          // 之前各墩出过的牌
+    voids: [[bool; 4]; 4],       // 各家已经缺的花色（没有跟出首出花色时记下）
+}
+
+impl HeartsRound {
+    /// 洗牌，每家发 13 张；第 hand_number 局的传牌方向按左、右、对家、不传轮换
+    fn deal(hand_number: usize) -> Self {
+        let mut deck: Vec<usize> = (0..52).collect();
+        deck.shuffle(&mut rand::rng());
+        let mut hands: [Vec<usize>; 4] = Default::default();
+        for hand in &mut hands {
+            hand.extend(deck.drain(..13));
+            hearts::sort_hand(hand);
+        }
+        let mut round = Self {
+            hands,
+            direction: PassDirection::for_hand(hand_number),
+            phase: RoundPhase::Passing,
+            received: Vec::new(),
+            turn: 0,
+            trick: Vec::new(),
+            tricks_played: 0,
+            hearts_broken: false,
+            taken: [0; 4],
+            played: Vec::new(),
+            voids: [[false; 4]; 4],
+        };
+        if round.direction == PassDirection::Hold {
+            round.start_play();
+        }
+        round
+    }
+
+    /// 传牌结束，由持有梅花2的一家首出
+    fn start_play(&mut self) {
+        self.phase = RoundPhase::Playing;
+        self.turn = self.hands.iter().position(|hand| hand.contains(&hearts::two_of_clubs())).unwrap_or(0);
+    }
+
+    /// 某家当前的出牌限制
+    fn rules(&self) -> TrickRules {
+        TrickRules {
+            lead_suit: self.trick.first().map(|&(_, card)| suit_of(card)),
+            first_trick: self.tricks_played == 0,
+            hearts_broken: self.hearts_broken,
+        }
+    }
+}
+
+/// 红心大战未完成一场的存档
+#[derive(Serialize, Deserialize)]
+pub struct HeartsSave {
+    difficulty: GameDifficulty,
+    adaptive_level: f32,
+    custom_profile: HeartsProfile,
+    round: HeartsRound,
+    hand_number: usize,
+    scores: [u32; 4],
+    moon_shots: u32,
+    message: String,
+}
+
+/// 红心大战游戏状态
+#[derive(PartialEq, Clone, Copy)]
+enum HeartsState {
+    DifficultySelection,
+    Playing,
+}
+
+/// 红心大战游戏
+pub struct HeartsGame {
+    difficulty_selection: DifficultySelection,
+    state: HeartsState,
+
+    // 52张卡片，下标即卡片编号
+    cards: Vec<Card>,
+    // 当前这一局
+    round: HeartsRound,
+    // 当前是第几局（从 0 开始，决定传牌方向）
+    hand_number: usize,
+    // 玩家选中要传走的牌
+    selected: Vec<usize>,
+    // 四家的累计得分（越少越好）
+    scores: [u32; 4],
+    // 玩家全收的次数
+    moon_shots: u32,
+    // AI 下一步行动的计时器
+    ai_timer: Option<Instant>,
+    // 显示给玩家的消息
+    message: String,
+    // 本场是否已结束
+    session_over: bool,
+
+    // 自定义难度参数
+    custom_profile: HeartsProfile,
+    // 刚结束的一场结果，等待主程序记录
+    finished_result: Option<GameResult>,
+    // 等待主程序处理的游戏事件
+    events: Vec<GameEvent>,
+    // 结束界面的排行榜成绩提交
+    score_submission: ScoreSubmission,
+    // 中途返回主菜单时保存的存档
+    suspended_session: Option<SavedSession>,
+}
+
+impl HeartsGame {
+    pub fn new() -> Self {
+        Self {
+            difficulty_selection: DifficultySelection::new(GameKind::Hearts),
+            state: HeartsState::DifficultySelection,
+            cards: Vec::new(),
+            round: HeartsRound::deal(0),
+            hand_number: 0,
+            selected: Vec::new(),
+            scores: [0; 4],
+            moon_shots: 0,
+            ai_timer: None,
+            message: String::new(),
+            session_over: false,
+            custom_profile: HeartsProfile::preset(GameDifficulty::Custom),
+            finished_result: None,
+            events: Vec::new(),
+            score_submission: ScoreSubmission::new(),
+            suspended_session: None,
+        }
+    }
+
+    /// 当前难度的参数
+    fn profile(&self) -> HeartsProfile {
+        self.difficulty_selection.profile(&self.custom_profile)
+    }
+
+    /// 开始新的一场
+    fn start_session(&mut self, ctx: &egui::Context) {
+        self.reset_game_state();
+        if self.cards.len() != 52 {
+            self.cards = solitaire::load_cards(ctx, 0..52);
+        }
+        self.state = HeartsState::Playing;
+        self.start_round();
+    }
+
+    /// 发牌开始新的一局
+    fn start_round(&mut self) {
+        self.round = HeartsRound::deal(self.hand_number);
+        self.selected.clear();
+        self.ai_timer = None;
+        for card in &mut self.cards {
+            card.is_face_up = false;
+            card.animation = None;
+        }
+        self.message = match self.round.direction {
+            PassDirection::Hold => format!("第 {} 局不传牌，{}有梅花2，由{}首出", self.hand_number + 1, SEAT_NAMES[self.round.turn], SEAT_NAMES[self.round.turn]),
+            direction => format!("第 {} 局，选择 3 张牌{}", self.hand_number + 1, direction.label()),
+        };
+    }
+
+    /// 按局面翻转卡片的正反面：AI 的手牌背面朝上
+    fn sync_faces(&mut self, animate: bool) {
+        let mut faces = vec![true; 52];
+        for &card in self.round.hands[1..].iter().flatten() {
+            faces[card] = false;
+        }
+        for (card, face_up) in self.cards.iter_mut().zip(faces) {
+            if animate {
+                card.flip_to(face_up);
+            } else {
+                card.is_face_up = face_up;
+            }
+        }
+    }
+
+    /// 玩家选好 3 张牌后，四家同时传牌
+    fn pass_cards(&mut self) {
+        let skill = self.profile().ai_skill;
+        let round = &mut self.round;
+        let mut passes: [Vec<usize>; 4] = Default::default();
+        passes[0] = std::mem::take(&mut self.selected);
+        for (seat, pass) in passes.iter_mut().enumerate().skip(1) {
+            *pass = hearts::choose_pass(&round.hands[seat], skill);
+        }
+        for (seat, pass) in passes.iter().enumerate() {
+            let Some(target) = round.direction.target(seat) else {
+                continue;
+            };
+            round.hands[seat].retain(|card| !pass.contains(card));
+            round.hands[target].extend(pass.iter().copied());
+            if target == 0 {
+                round.received = pass.clone();
+            }
+        }
+        for hand in &mut round.hands {
+            hearts::sort_hand(hand);
+        }
+        round.start_play();
+
+        let received: Vec<String> = round.received.iter().map(|&card| card_name(card)).collect();
+        self.message = format!("你收到了{}，由{}首出梅花2", received.join("、"), SEAT_NAMES[round.turn]);
+    }
+
+    /// 一家出一张牌（调用前已检查合法）
+    fn play_card(&mut self, seat: usize, card: usize) {
+        let round = &mut self.round;
+        if let Some(lead_suit) = round.rules().lead_suit
+            && suit_of(card) != lead_suit
+        {
+            round.voids[seat][lead_suit as usize - 1] = true;
+        }
+        if hearts::points(card) > 0 && !round.hearts_broken {
+            round.hearts_broken = true;
+            self.message = format!("{}垫出{}，红桃破了！", SEAT_NAMES[seat], card_name(card));
+        } else {
+            self.message = format!("{}出了{}", SEAT_NAMES[seat], card_name(card));
+        }
+        round.hands[seat].retain(|&held| held != card);
+        round.trick.push((seat, card));
+        if round.trick.len() < 4 {
+            round.turn = (round.turn + 1) % 4;
+        }
+        if seat == 0 {
+            round.received.clear();
+        }
+    }
+
+    /// 四家都出完后收走这一墩，吃下的一家首出下一墩
+    fn collect_trick(&mut self) {
+        let round = &mut self.round;
+        let winner = hearts::trick_winner(&round.trick);
+        let points = hearts::trick_points(&round.trick);
+        round.taken[winner] += points;
+        round.played.extend(round.trick.drain(..).map(|(_, card)| card));
+        round.tricks_played += 1;
+        round.turn = winner;
+        self.message = if points > 0 {
+            format!("{}吃下这一墩，得 {} 分", SEAT_NAMES[winner], points)
+        } else {
+            format!("{}吃下这一墩", SEAT_NAMES[winner])
+        };
+        if round.tricks_played == 13 {
+            self.settle_round();
+        }
+    }
+
+    /// 13 墩打完后结算本局，有人达到结束分数时结束本场
+    fn settle_round(&mut self) {
+        let round = &mut self.round;
+        round.phase = RoundPhase::Settled;
+        let (added, shooter) = hearts::hand_scores(round.taken);
+        for (score, points) in self.scores.iter_mut().zip(added) {
+            *score += points;
+        }
+        self.hand_number += 1;
+        // 这一局加分最少（可以并列）算赢下一局
+        if added.iter().all(|&points| points >= added[0]) {
+            self.events.push(GameEvent::RoundWon { game: GameKind::Hearts, spring: false });
+        }
+
+        self.message = match shooter {
+            Some(0) => {
+                self.moon_shots += 1;
+                self.events.push(GameEvent::MoonShot);
+                "你全收了！其余三家各加 26 分".to_string()
+            }
+            Some(seat) => format!("{}全收了！其余三家各加 26 分", SEAT_NAMES[seat]),
+            None => {
+                let details: Vec<String> = (0..4).map(|seat| format!("{} +{}", SEAT_NAMES[seat], added[seat])).collect();
+                format!("本局结束：{}", details.join("，"))
+            }
+        };
+        if self.scores.iter().any(|&score| score >= self.profile().target_score) {
+            self.end_session();
+        }
+    }
+
+    /// 结束本场并记录战绩：得分最低（可以并列）为胜
+    fn end_session(&mut self) {
+        if self.session_over {
+            return;
+        }
+        self.session_over = true;
+        let lowest = self.scores.iter().copied().min().unwrap_or(0);
+        let won = self.scores[0] == lowest;
+        self.difficulty_selection.record_result(won);
+        self.message.push_str(if won { "\n本场结束，你的得分最低，你赢了！" } else { "\n本场结束，你输了。" });
+
+        let difficulty = self.difficulty_selection.selected_difficulty.unwrap_or(GameDifficulty::Easy);
+        let mut result = GameResult::new(GameKind::Hearts, difficulty, won);
+        result.final_score = Some(self.scores[0] as i64);
+        result.moon_shots = self.moon_shots;
+        self.score_submission.offer(&result);
+        self.finished_result = Some(result);
+    }
+
+    /// AI 行动一步，或收走出完的一墩（每步之间有间隔，便于看清出牌）
+    fn update_ai(&mut self, ctx: &egui::Context) {
+        let round = &self.round;
+        let trick_done = round.trick.len() == 4;
+        if self.session_over || round.phase != RoundPhase::Playing || (round.turn == 0 && !trick_done) {
+            self.ai_timer = None;
+            return;
+        }
+        let timer = *self.ai_timer.get_or_insert_with(Instant::now);
+        if timer.elapsed() < AI_STEP {
+            ctx.request_repaint_after(AI_STEP - timer.elapsed());
+            return;
+        }
+        self.ai_timer = None;
+
+        if trick_done {
+            self.collect_trick();
+        } else {
+            let seat = round.turn;
+            let view = AiView {
+                hand: &round.hands[seat],
+                seat,
+                trick: &round.trick,
+                rules: round.rules(),
+                played: &round.played,
+                voids: &round.voids,
+                taken: round.taken,
+                skill: self.profile().ai_skill,
+            };
+            if let Some(card) = hearts::choose_play(&view) {
+                self.play_card(seat, card);
+            }
+        }
+        ctx.request_repaint();
+    }
+
+    /// 玩家手牌中第 index 张的位置（选中或刚收到的牌抬起）
+    fn hand_card_rect(&self, area: egui::Rect, index: usize) -> egui::Rect {
+        let hand = &self.round.hands[0];
+        let width = CARD_W + hand.len().saturating_sub(1) as f32 * HAND_SPACING;
+        let x = area.center().x - width / 2.0 + index as f32 * HAND_SPACING;
+        let card = hand[index];
+        let lifted = self.selected.contains(&card) || self.round.received.contains(&card);
+        let lift = if lifted { SELECTED_LIFT } else { 0.0 };
+        egui::Rect::from_min_size(egui::pos2(x, area.max.y - CARD_H - 10.0 - lift), egui::vec2(CARD_W, CARD_H))
+    }
+
+    /// 点击手牌：传牌阶段切换选中，出牌阶段直接出这张牌
+    fn handle_hand_click(&mut self, area: egui::Rect, response: &egui::Response) {
+        if !response.clicked() {
+            return;
+        }
+        let Some(pointer) = response.interact_pointer_pos() else {
+            return;
+        };
+        let hand = &self.round.hands[0];
+        let Some(index) = (0..hand.len()).rev().find(|&index| self.hand_card_rect(area, index).contains(pointer)) else {
+            return;
+        };
+        let card = hand[index];
+        match self.round.phase {
+            RoundPhase::Passing => {
+                if let Some(position) = self.selected.iter().position(|&selected| selected == card) {
+                    self.selected.remove(position);
+                } else if self.selected.len() < 3 {
+                    self.selected.push(card);
+                }
+            }
+            RoundPhase::Playing if self.round.turn == 0 && self.round.trick.len() < 4 => {
+                match self.round.rules().check(&self.round.hands[0], card) {
+                    Ok(()) => self.play_card(0, card),
+                    Err(reason) => self.message = reason.to_string(),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// 绘制牌桌：左、上、右三家 AI，中间本墩的出牌，下方玩家的手牌
+    fn paint_table(&self, painter: &egui::Painter, area: egui::Rect) {
+        let round = &self.round;
+        let font = egui::FontId::proportional(16.0);
+        let label_color = |seat: usize| {
+            if round.phase == RoundPhase::Playing && round.turn == seat && round.trick.len() < 4 {
+                egui::Color32::GOLD
+            } else {
+                egui::Color32::BLACK
+            }
+        };
+        let label = |seat: usize| {
+            format!("{} 剩 {} 张  总分 {}  本局 {}", SEAT_NAMES[seat], round.hands[seat].len(), self.scores[seat], round.taken[seat])
+        };
+
+        // 三家 AI：背面一张加剩余张数；(座位, 背面牌的左上角, 名字的位置和对齐方式)
+        let hand_top = area.max.y - CARD_H - 10.0 - SELECTED_LIFT;
+        let side_y = area.min.y + CARD_H + 60.0;
+        let seats = [
+            (1, egui::pos2(area.min.x + 20.0, side_y), egui::pos2(area.min.x + 20.0, side_y - 6.0), egui::Align2::LEFT_BOTTOM),
+            (
+                2,
+                egui::pos2(area.center().x - CARD_W / 2.0, area.min.y + 20.0),
+                egui::pos2(area.center().x + CARD_W / 2.0 + 12.0, area.min.y + 20.0 + CARD_H / 2.0),
+                egui::Align2::LEFT_CENTER,
+            ),
+            (3, egui::pos2(area.max.x - 20.0 - CARD_W, side_y), egui::pos2(area.max.x - 20.0, side_y - 6.0), egui::Align2::RIGHT_BOTTOM),
+        ];
+        for (seat, back_min, label_pos, label_align) in seats {
+            painter.text(label_pos, label_align, label(seat), font.clone(), label_color(seat));
+            let back_rect = egui::Rect::from_min_size(back_min, egui::vec2(CARD_W, CARD_H));
+            match round.hands[seat].first() {
+                Some(&card) => self.cards[card].paint(painter, back_rect),
+                None => solitaire::paint_slot(painter, back_rect, ""),
+            }
+        }
+
+        // 本墩的出牌：每家的牌偏向自己的方向
+        let center = egui::pos2(area.center().x, (area.min.y + CARD_H + 20.0 + hand_top) / 2.0);
+        let offsets = [egui::vec2(0.0, 50.0), egui::vec2(-80.0, 0.0), egui::vec2(0.0, -50.0), egui::vec2(80.0, 0.0)];
+        for &(seat, card) in &round.trick {
+            let rect = egui::Rect::from_center_size(center + offsets[seat], egui::vec2(CARD_W, CARD_H));
+            self.cards[card].paint(painter, rect);
+        }
+
+        // 玩家的手牌：轮到玩家出牌时，不能出的牌变暗
+        painter.text(egui::pos2(area.center().x, hand_top - 8.0), egui::Align2::CENTER_BOTTOM, label(0), font, label_color(0));
+        let my_turn = round.phase == RoundPhase::Playing && round.turn == 0 && round.trick.len() < 4;
+        let legal = if my_turn { round.rules().legal_plays(&round.hands[0]) } else { round.hands[0].clone() };
+        for (index, &card) in round.hands[0].iter().enumerate() {
+            let rect = self.hand_card_rect(area, index);
+            self.cards[card].paint(painter, rect);
+            if !legal.contains(&card) {
+                painter.rect_filled(rect, 4.0, egui::Color32::from_black_alpha(90));
+            }
+        }
+    }
+
+    /// 显示游戏进行界面，返回是否要返回主菜单
+    fn show_game_playing(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) -> bool {
+        let mut return_to_menu = false;
+
+        self.update_ai(ctx);
+        self.show_top_info(ui);
+        ui.vertical_centered(|ui| {
+            ui.colored_label(egui::Color32::BLACK, &self.message);
+            ui.add_space(6.0);
+            if self.session_over {
+                self.show_session_over(ui);
+            } else {
+                self.show_action_buttons(ui);
+            }
+        });
+        ui.add_space(6.0);
+
+        if !self.session_over {
+            ui.horizontal(|ui| {
+                ui.add_space(ui.available_width() - 130.0);
+                if ui.button("返回主菜单").clicked() {
+                    return_to_menu = true;
+                    self.suspended_session = self.snapshot().map(SavedSession::Hearts);
+                    self.reset_to_difficulty_selection();
+                }
+            });
+        }
+        if return_to_menu || self.state != HeartsState::Playing {
+            return return_to_menu;
+        }
+        if self.cards.len() < 52 {
+            ui.colored_label(egui::Color32::RED, "卡片图片加载失败");
+            return return_to_menu;
+        }
+
+        // 牌桌
+        let (area, response) = ui.allocate_exact_size(ui.available_size(), egui::Sense::click());
+        self.handle_hand_click(area, &response);
+        self.sync_faces(true);
+        self.paint_table(ui.painter(), area);
+
+        return_to_menu
+    }
+
+    /// 显示顶部信息：第几局、传牌方向、红桃是否已破和结束分数
+    fn show_top_info(&self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.vertical(|ui| {
+                ui.colored_label(egui::Color32::LIGHT_BLUE, format!("第 {} 局", self.hand_number + usize::from(self.round.phase != RoundPhase::Settled)));
+                ui.colored_label(egui::Color32::DARK_GRAY, format!("传牌: {}", self.round.direction.label()));
+            });
+            ui.add_space(30.0);
+            ui.vertical(|ui| {
+                let (text, color) = if self.round.hearts_broken {
+                    ("红桃已破", egui::Color32::RED)
+                } else {
+                    ("红桃未破", egui::Color32::DARK_GRAY)
+                };
+                ui.colored_label(color, text);
+                ui.colored_label(egui::Color32::GOLD, format!("结束分数: {}", self.profile().target_score));
+            });
+            if let Some(difficulty) = self.difficulty_selection.selected_difficulty {
+                ui.add_space(30.0);
+                ui.colored_label(egui::Color32::DARK_GRAY, difficulty.label());
+            }
+        });
+    }
+
+    /// 操作按钮：传牌，或结算后开始下一局
+    fn show_action_buttons(&mut self, ui: &mut egui::Ui) {
+        match self.round.phase {
+            RoundPhase::Passing => {
+                let text = format!("{}（已选 {}/3）", self.round.direction.label(), self.selected.len());
+                ui.horizontal(|ui| {
+                    ui.add_space(ui.available_width() / 2.0 - 90.0);
+                    let button = egui::Button::new(text).min_size(egui::vec2(180.0, 36.0));
+                    if ui.add_enabled(self.selected.len() == 3, button).clicked() {
+                        self.pass_cards();
+                    }
+                });
+            }
+            RoundPhase::Playing if self.round.trick.len() == 4 => {}
+            RoundPhase::Playing if self.round.turn == 0 => {
+                ui.colored_label(egui::Color32::BLUE, "轮到你出牌，点击一张能出的牌");
+            }
+            RoundPhase::Playing => {
+                ui.colored_label(egui::Color32::BLUE, format!("{}思考中...", SEAT_NAMES[self.round.turn]));
+            }
+            RoundPhase::Settled => {
+                ui.horizontal(|ui| {
+                    ui.add_space(ui.available_width() / 2.0 - 60.0);
+                    if self.centered_button(ui, "下一局", 120.0, 40.0).clicked() {
+                        self.start_round();
+                    }
+                });
+            }
+        }
+    }
+
+    /// 本场结束：显示最终得分、成绩提交和选择难度按钮
+    fn show_session_over(&mut self, ui: &mut egui::Ui) {
+        let scores: Vec<String> = (0..4).map(|seat| format!("{} {}", SEAT_NAMES[seat], self.scores[seat])).collect();
+        ui.colored_label(egui::Color32::BLUE, format!("共 {} 局，最终得分：{}", self.hand_number, scores.join("  ")));
+        ui.add_space(10.0);
+        self.score_submission.show(ui);
+        ui.add_space(10.0);
+        ui.horizontal(|ui| {
+            ui.add_space(ui.available_width() / 2.0 - 75.0);
+            if self.centered_button(ui, "选择难度", 150.0, 40.0).clicked() {
+                self.reset_to_difficulty_selection();
+            }
+        });
+    }
+
+    /// 创建居中的按钮
+    fn centered_button(&self, ui: &mut egui::Ui, text: &str, width: f32, height: f32) -> egui::Response {
+        ui.add_sized(
+            egui::vec2(width, height),
+            egui::Button::new(
+                egui::RichText::new(text)
+                    .text_style(egui::TextStyle::Button)
+                    .color(egui::Color32::BLACK),
+            ),
+        )
+    }
+
+    /// 保存进行中的一场，没有进行中的游戏时返回 None
+    fn snapshot(&self) -> Option<HeartsSave> {
+        if self.state != HeartsState::Playing || self.session_over {
+            return None;
+        }
+        Some(HeartsSave {
+            difficulty: self.difficulty_selection.selected_difficulty?,
+            adaptive_level: self.difficulty_selection.adaptive.level,
+            custom_profile: self.custom_profile,
+            round: self.round.clone(),
+            hand_number: self.hand_number,
+            scores: self.scores,
+            moon_shots: self.moon_shots,
+            message: self.message.clone(),
+        })
+    }
+
+    /// 从存档恢复一场
+    fn restore(&mut self, ctx: &egui::Context, save: HeartsSave) {
+        self.reset_game_state();
+        self.difficulty_selection.restore(save.difficulty, save.adaptive_level);
+        self.custom_profile = save.custom_profile;
+        if self.cards.len() != 52 {
+            self.cards = solitaire::load_cards(ctx, 0..52);
+        }
+        self.round = save.round;
+        self.hand_number = save.hand_number;
+        self.scores = save.scores;
+        self.moon_shots = save.moon_shots;
+        self.message = save.message;
+        self.sync_faces(false);
+        self.state = HeartsState::Playing;
+    }
+
+    /// 重置到难度选择界面
+    fn reset_to_difficulty_selection(&mut self) {
+        self.difficulty_selection.reset();
+        self.state = HeartsState::DifficultySelection;
+        self.reset_game_state();
+    }
+
+    /// 重置游戏状态
+    fn reset_game_state(&mut self) {
+        self.hand_number = 0;
+        self.selected.clear();
+        self.scores = [0; 4];
+        self.moon_shots = 0;
+        self.ai_timer = None;
+        self.message.clear();
+        self.session_over = false;
+        self.score_submission.clear();
+    }
+}
+
+impl CardGame for HeartsGame {
+    fn kind(&self) -> GameKind {
+        GameKind::Hearts
+    }
+
+    fn rules(&self) -> Vec<String> {
+        [
+            "四人使用一副 52 张牌，每人 13 张；你的左家、对家和右家由 AI 控制，按你→左家→对家→右家的顺序出牌",
+            "传牌：每局开始前选 3 张牌传出，按局轮换传给左家、右家、对家，第四局不传牌",
+            "持有梅花2的一家首出第一墩；必须跟出首出花色，没有时可以垫任意牌，出首出花色最大牌的一家吃下这一墩并首出下一墩",
+            "第一墩不能垫红桃和黑桃Q；红桃破之前（有人垫过红桃或黑桃Q）不能首出红桃，除非手里只剩红桃",
+            "罚分：每张红桃 1 分，黑桃Q 13 分；一家吃到全部 26 分（全收）时自己不加分，其余三家各加 26 分",
+            "有人的总分达到结束分数（默认 100 分）时本场结束，总分最低（可以并列）为胜",
+            "传牌阶段点击手牌选中或取消；出牌时点击一张能出的牌（不能出的牌变暗），刚收到的牌在出第一张牌前抬起显示",
+            "简单难度：AI 随便传牌和出牌；中等难度：AI 传走危险的大牌，跟牌尽量出压不住的最大一张，缺门时垫黑桃Q和大红桃；困难难度：AI 还会记牌、记住各家缺的花色、逼出黑桃Q，并阻止别人全收",
+            "自定义难度：自由设置 AI 水平和结束分数；自适应难度：根据最近战绩自动调整 AI 水平",
+        ]
+        .map(String::from)
+        .to_vec()
+    }
+
+    fn difficulty(&mut self) -> (&mut DifficultySelection, &mut dyn DifficultyProfile) {
+        (&mut self.difficulty_selection, &mut self.custom_profile)
+    }
+
+    fn menu_face(&self) -> &'static str {
+        "assets/card_face/SpadeQ.png"
+    }
+
+    /// 显示游戏界面，返回是否要返回主菜单
+    fn show(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) -> bool {
+        let mut return_to_menu = false;
+
+        // 处理过渡动画
+        if self.difficulty_selection.is_in_transition() {
+            if self.difficulty_selection.show_transition_animation(ui) {
+                self.start_session(ctx);
+            }
+            return return_to_menu;
+        }
+
+        match self.state {
+            HeartsState::DifficultySelection => {
+                let (menu_return, _) = self.show_difficulty_selection(ui);
+                return_to_menu = menu_return;
+            }
+            HeartsState::Playing => {
+                return_to_menu = self.show_game_playing(ui, ctx);
+            }
+        }
+
+        return_to_menu
+    }
+
+    /// 取出刚结束的一场结果
+    fn take_result(&mut self) -> Option<GameResult> {
+        self.finished_result.take()
+    }
+
+    /// 取出本局产生的游戏事件（用于判定成就）
+    fn take_events(&mut self) -> Vec<GameEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// 取出玩家提交的排行榜成绩
+    fn take_leaderboard_entry(&mut self) -> Option<(GameKind, LeaderboardEntry)> {
+        self.score_submission.take_entry()
+    }
+
+    /// 取出中途返回主菜单时保存的存档
+    fn take_suspended_session(&mut self) -> Option<SavedSession> {
+        self.suspended_session.take()
+    }
+
+    /// 重置到难度选择界面
+    fn reset(&mut self) {
+        self.reset_to_difficulty_selection();
+    }
+
+    fn save_state(&self) -> Option<SavedSession> {
+        self.snapshot().map(SavedSession::Hearts)
+    }
+
+    fn restore_state(&mut self, ctx: &egui::Context, session: SavedSession) {
+        if let SavedSession::Hearts(save) = session {
+            self.restore(ctx, save);
+        }
+    }
+}
+
+impl Default for HeartsGame {
+    fn default() -> Self {
+        Self::new()
+    }
+}
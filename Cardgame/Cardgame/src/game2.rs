@@ -1,10 +1,25 @@
 // game2.rs
-use crate::card::Card;
-use crate::difficulty::{DifficultySelection, GameDifficulty};
+use crate::achievements::GameEvent;
+use crate::card::{self, Card};
+use crate::card_game::CardGame;
+use crate::code_solver::{self, CodeSolver, Feedback};
+use crate::difficulty::{self, DifficultyProfile, DifficultySelection, GameDifficulty};
+use crate::leaderboard::{LeaderboardEntry, ScoreSubmission};
+use crate::notepad::DeductionNotepad;
+use crate::player_profile::{GameKind, GameResult};
+use crate::save_game::{self, SavedCard, SavedSession};
+use crate::timer::{self, GameTimer, TimerSnapshot};
 use crate::util;
 use eframe::egui;
 use rand::Rng;
-use std::time::{Duration, Instant};
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// 使用提示扣除的时间
+const HINT_PENALTY: Duration = Duration::from_secs(10);
+/// 检查求解器后台搜索结果的间隔
+const SOLVER_POLL: Duration = Duration::from_millis(100);
 
 /// 猜数字游戏状态
 #[derive(PartialEq)]
@@ -14,21 +29,381 @@ enum GuessNumberState {
     GameOver,           // 游戏结束
 }
 
+/// 猜数字游戏模式
+#[derive(PartialEq, Clone, Copy, Serialize, Deserialize)]
+enum GuessMode {
+    Classic, // 玩家猜电脑的密码
+    Reverse, // 电脑猜玩家心中的密码
+    Mastermind, // 彩色珠子版（Mastermind）
+    Duel,    // 双人对战：互相猜对方的密码
+}
+
+/// 对战对手类型
+#[derive(PartialEq, Clone, Copy, Serialize, Deserialize)]
+enum DuelOpponent {
+    HotSeat, // 本地双人轮流操作
+    Ai,      // 玩家对战电脑
+}
+
+/// 对战阶段
+#[derive(PartialEq, Clone, Copy, Serialize, Deserialize)]
+enum DuelPhase {
+    SetSecret(usize), // 第N位玩家设置密码
+    Handover(usize),  // 交给第N位玩家（避免看到对方的密码）
+    Guessing,         // 轮流猜测
+}
+
+/// 对战中一方的状态
+struct DuelSide {
+    name: &'static str,
+    secret: Vec<u8>,                       // 自己设置的密码
+    guesses: Vec<Feedback>,                // 自己对对方密码的猜测记录
+}
+
+impl DuelSide {
+    fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            secret: Vec::new(),
+            guesses: Vec::new(),
+        }
+    }
+}
+
+/// Mastermind 珠子颜色
+const PEG_COLORS: [(egui::Color32, &str); 10] = [
+    (egui::Color32::from_rgb(220, 40, 40), "红"),
+    (egui::Color32::from_rgb(40, 170, 60), "绿"),
+    (egui::Color32::from_rgb(40, 90, 220), "蓝"),
+    (egui::Color32::from_rgb(240, 210, 40), "黄"),
+    (egui::Color32::from_rgb(245, 140, 30), "橙"),
+    (egui::Color32::from_rgb(140, 60, 190), "紫"),
+    (egui::Color32::from_rgb(40, 200, 210), "青"),
+    (egui::Color32::from_rgb(240, 110, 180), "粉"),
+    (egui::Color32::from_rgb(130, 80, 40), "棕"),
+    (egui::Color32::from_rgb(150, 150, 150), "灰"),
+];
+
+/// 绘制一颗珠子
+fn draw_peg(ui: &mut egui::Ui, color: Option<egui::Color32>, radius: f32, sense: egui::Sense) -> egui::Response {
+    let (rect, response) = ui.allocate_exact_size(egui::vec2(radius * 2.0, radius * 2.0), sense);
+    let painter = ui.painter();
+    match color {
+        Some(color) => {
+            painter.circle_filled(rect.center(), radius, color);
+            painter.circle_stroke(rect.center(), radius, egui::Stroke::new(1.0, egui::Color32::BLACK));
+        }
+        None => {
+            painter.circle_stroke(rect.center(), radius - 1.0, egui::Stroke::new(1.0, egui::Color32::DARK_GRAY));
+        }
+    }
+    if response.hovered() && sense.senses_click() {
+        painter.circle_stroke(rect.center(), radius + 2.0, egui::Stroke::new(2.0, egui::Color32::GOLD));
+    }
+    response
+}
+
+/// 绘制黑白反馈小珠：黑色表示颜色和位置都正确（A），白色表示颜色正确位置错误（B）
+fn draw_feedback_pegs(ui: &mut egui::Ui, (a_count, b_count): (usize, usize), length: usize) {
+    let columns = length.div_ceil(2);
+    egui::Grid::new(ui.next_auto_id())
+        .spacing(egui::vec2(2.0, 2.0))
+        .show(ui, |ui| {
+            for index in 0..length {
+                let color = if index < a_count {
+                    Some(egui::Color32::BLACK)
+                } else if index < a_count + b_count {
+                    Some(egui::Color32::WHITE)
+                } else {
+                    None
+                };
+                draw_peg(ui, color, 5.0, egui::Sense::hover());
+                if (index + 1) % columns == 0 {
+                    ui.end_row();
+                }
+            }
+        });
+}
+
+/// 显示 Mastermind 棋盘式猜测记录
+fn show_peg_board(ui: &mut egui::Ui, guesses: &[Feedback], length: usize) {
+    egui::ScrollArea::vertical()
+        .id_salt("mastermind_board")
+        .max_height(300.0)
+        .show(ui, |ui| {
+            for (index, (guess, result)) in guesses.iter().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.add_space(ui.available_width() / 2.0 - (length as f32 * 36.0 + 80.0) / 2.0);
+                    ui.label(format!("{:>2}", index + 1));
+                    ui.add_space(10.0);
+                    for &peg in guess {
+                        draw_peg(ui, Some(PEG_COLORS[peg as usize].0), 14.0, egui::Sense::hover());
+                        ui.add_space(4.0);
+                    }
+                    ui.add_space(10.0);
+                    draw_feedback_pegs(ui, *result, length);
+                });
+                ui.add_space(4.0);
+            }
+        });
+}
+
+/// 密码规则
+#[derive(PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub struct CodeRules {
+    pub length: usize,     // 密码位数（3~8）
+    pub alphabet_size: u8, // 可用数字个数，使用 0 ~ alphabet_size-1
+    pub allow_repeats: bool, // 是否允许数字重复
+}
+
+impl CodeRules {
+    pub const MIN_LENGTH: usize = 3;
+    pub const MAX_LENGTH: usize = 8;
+    pub const MIN_ALPHABET: u8 = 2;
+    pub const MAX_ALPHABET: u8 = 10;
+
+    /// 修正不合法的组合：不允许重复时可用数字个数不能少于位数
+    pub fn normalize(&mut self) {
+        self.length = self.length.clamp(Self::MIN_LENGTH, Self::MAX_LENGTH);
+        self.alphabet_size = self.alphabet_size.clamp(Self::MIN_ALPHABET, Self::MAX_ALPHABET);
+        if !self.allow_repeats && (self.alphabet_size as usize) < self.length {
+            self.alphabet_size = self.length as u8;
+        }
+    }
+
+    /// 规则描述
+    pub fn describe(&self) -> String {
+        format!(
+            "{}位数字（0-{}，{}）",
+            self.length,
+            self.alphabet_size - 1,
+            if self.allow_repeats { "数字可以重复" } else { "数字不能重复" }
+        )
+    }
+
+    /// 随机生成一个符合规则的密码
+    pub fn random_code(&self) -> Vec<u8> {
+        let mut rng = rand::rng();
+        if self.allow_repeats {
+            (0..self.length)
+                .map(|_| rng.random_range(0..self.alphabet_size))
+                .collect()
+        } else {
+            let mut digits: Vec<u8> = (0..self.alphabet_size).collect();
+            digits.shuffle(&mut rng);
+            digits.truncate(self.length);
+            digits
+        }
+    }
+
+    /// 检查玩家给出的A/B反馈本身是否可能出现
+    pub fn validate_feedback(&self, (a_count, b_count): (usize, usize)) -> Result<(), String> {
+        if a_count + b_count > self.length {
+            return Err(format!("A和B的总数不能超过{}", self.length));
+        }
+        if a_count + 1 == self.length && b_count == 1 {
+            return Err(format!("{}A1B 不可能出现", a_count));
+        }
+        Ok(())
+    }
+
+    /// 检查猜测是否符合规则，不符合时返回错误提示
+    pub fn validate(&self, guess: &[u8]) -> Result<(), String> {
+        if guess.len() != self.length {
+            return Err(format!("请输入{}位数字", self.length));
+        }
+        if guess.iter().any(|&d| d >= self.alphabet_size) {
+            return Err(format!("只能使用数字0-{}", self.alphabet_size - 1));
+        }
+        if !self.allow_repeats {
+            for (i, d) in guess.iter().enumerate() {
+                if guess[..i].contains(d) {
+                    return Err("当前规则下数字不能重复".to_string());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for CodeRules {
+    fn default() -> Self {
+        Self {
+            length: 4,
+            alphabet_size: 10,
+            allow_repeats: true,
+        }
+    }
+}
+
+/// 计算猜测结果：(A的数量, B的数量)
+/// A表示数字和位置都正确，B表示数字正确但位置错误
+pub fn score_guess(guess: &[u8], target: &[u8]) -> (usize, usize) {
+    let mut a_count = 0;
+    let mut guess_counts = [0usize; CodeRules::MAX_ALPHABET as usize];
+    let mut target_counts = [0usize; CodeRules::MAX_ALPHABET as usize];
+
+    // 先计算A，位置不对的数字分别计数
+    for (&g, &t) in guess.iter().zip(target) {
+        if g == t {
+            a_count += 1;
+        } else {
+            guess_counts[g as usize] += 1;
+            target_counts[t as usize] += 1;
+        }
+    }
+
+    // 再计算B（数字正确但位置错误）
+    let b_count = guess_counts
+        .iter()
+        .zip(&target_counts)
+        .map(|(g, t)| (*g).min(*t))
+        .sum();
+
+    (a_count, b_count)
+}
+
+/// 结果显示文字，如 "1A2B"
+pub fn format_score((a_count, b_count): (usize, usize)) -> String {
+    format!("{}A{}B", a_count, b_count)
+}
+
+/// 显示猜测记录表格
+fn show_guess_history(ui: &mut egui::Ui, title: &str, guesses: &[Feedback]) {
+    ui.vertical_centered(|ui| {
+        ui.heading(title);
+        ui.add_space(10.0);
+        
+        // 使用可滚动区域显示猜测记录
+        egui::ScrollArea::vertical()
+            .id_salt(title)
+            .max_height(200.0) 
+            .show(ui, |ui| {
+         
+                ui.horizontal(|ui| {
+                    ui.label("次数");
+                    ui.add_space(40.0);
+                    ui.label("猜测数字");
+                    ui.add_space(40.0);
+                    ui.label("结果");
+                });
+                
+                ui.separator();
+                
+                // 猜测记录
+                for (index, (guess, result)) in guesses.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.label(format!("{}", index + 1));
+                        ui.add_space(30.0);
+                        ui.label(guess.iter().map(|d| d.to_string()).collect::<String>());
+                        ui.add_space(30.0);
+                        ui.colored_label(egui::Color32::LIGHT_BLUE, format_score(*result));
+                    });
+                }
+            });
+    });
+}
+
+/// 猜数字难度参数
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct GuessProfile {
+    pub time_limit_secs: u64, // 时间限制（秒）
+    pub max_attempts: usize,  // 最大尝试次数
+    pub hints: usize,         // 提示次数
+}
+
+impl DifficultyProfile for GuessProfile {
+    fn preset(difficulty: GameDifficulty) -> Self {
+        match difficulty {
+            // 简单：180秒，20次尝试，3次提示
+            GameDifficulty::Easy => Self { time_limit_secs: 180, max_attempts: 20, hints: 3 },
+            // 中等：120秒，15次尝试，2次提示
+            GameDifficulty::Medium | GameDifficulty::Custom | GameDifficulty::Adaptive => Self { time_limit_secs: 120, max_attempts: 15, hints: 2 },
+            // 困难：90秒，10次尝试，1次提示
+            GameDifficulty::Hard => Self { time_limit_secs: 90, max_attempts: 10, hints: 1 },
+        }
+    }
+
+    fn adaptive(level: f32) -> Self {
+        Self {
+            time_limit_secs: difficulty::lerp(180.0, 90.0, level).round() as u64,
+            max_attempts: difficulty::lerp(20.0, 10.0, level).round() as usize,
+            hints: difficulty::lerp(3.0, 1.0, level).round() as usize,
+        }
+    }
+
+    fn load_preset(&mut self, difficulty: GameDifficulty) {
+        *self = Self::preset(difficulty);
+    }
+
+    fn show_editor(&mut self, ui: &mut egui::Ui) {
+        ui.add(egui::Slider::new(&mut self.time_limit_secs, 30..=600).suffix("秒").text("时间限制"));
+        ui.add(egui::Slider::new(&mut self.max_attempts, 1..=50).suffix("次").text("最大尝试次数"));
+        ui.add(egui::Slider::new(&mut self.hints, 0..=10).suffix("次").text("提示次数"));
+    }
+}
+
+/// 猜数字未完成一局的存档（求解器在恢复时根据猜测记录重建）
+#[derive(Serialize, Deserialize)]
+pub struct GuessNumberSave {
+    difficulty: GameDifficulty,
+    adaptive_level: f32,
+    custom_profile: GuessProfile,
+    guess_mode: GuessMode,
+    code_rules: CodeRules,
+    target_number: Vec<u8>,
+    guesses: Vec<Feedback>,
+    attempts: usize,
+    max_attempts: usize,
+    hints_remaining: usize,
+    notepad: DeductionNotepad,
+    cards: Vec<SavedCard>,
+    flipped_cards: Vec<usize>,
+    timer: Option<TimerSnapshot>,
+    computer_guess: Vec<u8>,
+    duel_opponent: DuelOpponent,
+    duel_sides: Vec<(Vec<u8>, Vec<Feedback>)>, // 对战双方的 (密码, 猜测记录)
+    duel_phase: DuelPhase,
+    duel_turn: usize,
+}
+
 /// 猜数字游戏结构体
 pub struct GuessNumberGame {
     state: GuessNumberState,
     difficulty_selection: DifficultySelection,
     target_number: Vec<u8>,           // 目标数字
-    guesses: Vec<(Vec<u8>, String)>,  // 猜测记录 (猜测数字, 结果)
-    current_guess: Vec<u8>,           // 当前猜测数字
+    guesses: Vec<Feedback>,           // 猜测记录 (猜测数字, (A, B))
+    current_guess: Vec<Option<u8>>,   // 当前猜测数字（None 表示尚未填写）
+    input_cursor: usize,              // 当前输入位置
+    highlight_incomplete: bool,       // 是否高亮未填写的位置
     max_attempts: usize,              // 最大尝试次数
     attempts: usize,                  // 当前尝试次数
     game_won: bool,                   // 是否获胜
-    digit_count: usize,               // 数字位数
+    code_rules: CodeRules,            // 密码规则（位数、可用数字、是否可重复）
+    input_error: Option<String>,      // 猜测输入错误提示
+    solver: Option<CodeSolver>,       // 求解器（维护剩余可能的密码）
+    hints_remaining: usize,           // 剩余提示次数
+    hint_message: Option<String>,     // 提示信息
+    notepad: DeductionNotepad,        // 推理笔记
+    guess_mode: GuessMode,            // 游戏模式
+    computer_guess: Vec<u8>,          // 反向模式下电脑当前的猜测
+    feedback: (usize, usize),         // 反向模式下玩家输入的A/B反馈
+    feedback_error: Option<String>,   // 反馈错误提示
+    pending_feedback: Option<CodeSolver>, // 反向模式下等待后台枚举确认的反馈（保存加入反馈前的求解器）
+    duel_opponent: DuelOpponent,      // 对战对手类型
+    duel_sides: Vec<DuelSide>,        // 对战双方
+    duel_phase: DuelPhase,            // 对战阶段
+    duel_turn: usize,                 // 当前轮到的玩家
+    duel_winner: Option<usize>,       // 对战获胜方（None为平局）
+    duel_ai_solver: Option<CodeSolver>, // 电脑一方的求解器
     game_cards: Vec<Card>,            // 游戏卡片
     flipped_cards: Vec<usize>,        // 已翻开的卡片
-    game_timer: Option<Instant>,      // 游戏计时器
-    time_remaining: Duration,         // 剩余时间
+    game_timer: Option<GameTimer>,    // 游戏计时器
+    custom_profile: GuessProfile,     // 自定义难度参数
+    finished_result: Option<GameResult>, // 刚结束的一局结果，等待主程序记录
+    score_submission: ScoreSubmission, // 结束界面的排行榜成绩提交
+    events: Vec<GameEvent>,           // 等待主程序处理的游戏事件
+    suspended_session: Option<SavedSession>, // 中途返回主菜单时保存的存档
 }
 
 impl GuessNumberGame {
@@ -36,98 +411,166 @@ impl GuessNumberGame {
     pub fn new() -> Self {
         Self {
             state: GuessNumberState::DifficultySelection,
-            difficulty_selection: DifficultySelection::new(),
+            difficulty_selection: DifficultySelection::new(GameKind::GuessNumber),
             target_number: Vec::new(),
             guesses: Vec::new(),
             current_guess: Vec::new(),
+            input_cursor: 0,
+            highlight_incomplete: false,
             max_attempts: 20,
             attempts: 0,
             game_won: false,
-            digit_count: 4,
+            code_rules: CodeRules::default(),
+            input_error: None,
+            solver: None,
+            hints_remaining: 0,
+            hint_message: None,
+            notepad: DeductionNotepad::new(0, 0),
+            guess_mode: GuessMode::Classic,
+            computer_guess: Vec::new(),
+            feedback: (0, 0),
+            feedback_error: None,
+            pending_feedback: None,
+            duel_opponent: DuelOpponent::HotSeat,
+            duel_sides: Vec::new(),
+            duel_phase: DuelPhase::SetSecret(0),
+            duel_turn: 0,
+            duel_winner: None,
+            duel_ai_solver: None,
             game_cards: Vec::new(),
             flipped_cards: Vec::new(),
             game_timer: None,
-            time_remaining: Duration::from_secs(0),
+            custom_profile: GuessProfile::preset(GameDifficulty::Custom),
+            finished_result: None,
+            score_submission: ScoreSubmission::new(),
+            events: Vec::new(),
+            suspended_session: None,
         }
     }
 
-    /// 显示游戏界面，返回是否要返回主菜单
-    pub fn show(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) -> bool {
-        let mut return_to_menu = false;
-
-        // 处理过渡动画
-        if self.difficulty_selection.is_in_transition() {
-            if self.difficulty_selection.show_transition_animation(ui) {
-                self.state = GuessNumberState::GamePlaying;
-                self.initialize_game(ctx);
-            }
-            return return_to_menu;
-        }
-
-        // 更新游戏计时器
-        if let Some(timer) = self.game_timer {
-            let elapsed = timer.elapsed();
-            let total_time = self.get_total_time();
-            
-            if elapsed < total_time {
-                self.time_remaining = total_time - elapsed;
-            } else {
-                // 时间到，游戏结束
-                self.time_remaining = Duration::from_secs(0);
-                self.state = GuessNumberState::GameOver;
-                self.game_won = false;
-                self.game_timer = None;
-                // 翻开所有卡片
-                for card in &mut self.game_cards {
-                    card.is_face_up = true;
-                }
-            }
-        }
-
-        // 使用 CentralPanel 确保内容始终居中
-        egui::CentralPanel::default().show(ui.ctx(), |ui| {
-            match self.state {
-                GuessNumberState::DifficultySelection => {
-                    let rules = vec![
-                        "根据提示猜测4位数字（0-9，数字可以重复）",
-                        "A表示数字和位置都正确，B表示数字正确但位置错误",
-                        "简单难度: 20次尝试，180秒时间",
-                        "中等难度: 15次尝试，120秒时间",
-                        "困难难度: 10次尝试，90秒时间",
-                    ];
-                    let (menu_return, _) = 
-                        self.difficulty_selection.show(ui, "猜数字游戏", &rules);
-                    return_to_menu = menu_return;
-                }
-                GuessNumberState::GamePlaying => {
-                    return_to_menu = self.show_game_playing(ui, ctx);
-                }
-                GuessNumberState::GameOver => {
-                    return_to_menu = self.show_game_over(ui);
+    /// 显示密码规则设置
+    fn show_code_rules_setup(&mut self, ui: &mut egui::Ui) {
+        ui.add_space(10.0);
+        ui.horizontal(|ui| {
+            ui.add_space(ui.available_width() / 2.0 - 200.0);
+            ui.vertical(|ui| {
+                ui.colored_label(egui::Color32::LIGHT_BLUE, "游戏模式:");
+                ui.horizontal(|ui| {
+                    ui.radio_value(&mut self.guess_mode, GuessMode::Classic, "经典模式（你来猜）");
+                    ui.radio_value(&mut self.guess_mode, GuessMode::Reverse, "反向模式（电脑来猜）");
+                    ui.radio_value(&mut self.guess_mode, GuessMode::Mastermind, "彩珠模式（Mastermind）");
+                    ui.radio_value(&mut self.guess_mode, GuessMode::Duel, "对战模式");
+                });
+                if self.guess_mode == GuessMode::Duel {
+                    ui.horizontal(|ui| {
+                        ui.label("对手:");
+                        ui.radio_value(&mut self.duel_opponent, DuelOpponent::HotSeat, "本地双人");
+                        ui.radio_value(&mut self.duel_opponent, DuelOpponent::Ai, "电脑");
+                    });
                 }
-            }
+                ui.add_space(5.0);
+                ui.colored_label(egui::Color32::LIGHT_BLUE, "密码规则设置:");
+                ui.add(
+                    egui::Slider::new(&mut self.code_rules.length, CodeRules::MIN_LENGTH..=CodeRules::MAX_LENGTH)
+                        .text("密码位数"),
+                );
+                let (alphabet_text, repeats_text) = if self.guess_mode == GuessMode::Mastermind {
+                    ("颜色数量", "允许颜色重复")
+                } else {
+                    ("可用数字个数", "允许数字重复（关闭即经典公牛母牛规则）")
+                };
+                ui.add(
+                    egui::Slider::new(&mut self.code_rules.alphabet_size, CodeRules::MIN_ALPHABET..=CodeRules::MAX_ALPHABET)
+                        .text(alphabet_text),
+                );
+                ui.checkbox(&mut self.code_rules.allow_repeats, repeats_text);
+            });
         });
-
-        return_to_menu
+        self.code_rules.normalize();
     }
 
     /// 获取总游戏时间（根据难度）
     fn get_total_time(&self) -> Duration {
-        match self.difficulty_selection.selected_difficulty {
-            Some(GameDifficulty::Easy) => Duration::from_secs(180),   // 简单180秒
-            Some(GameDifficulty::Medium) => Duration::from_secs(120), // 中等120秒
-            Some(GameDifficulty::Hard) => Duration::from_secs(90),    // 困难90秒
-            None => Duration::from_secs(180),
-        }
+        Duration::from_secs(self.profile().time_limit_secs)
     }
 
     /// 获取最大尝试次数（根据难度）
     fn get_max_attempts(&self) -> usize {
-        match self.difficulty_selection.selected_difficulty {
-            Some(GameDifficulty::Easy) => 20,    // 简单20次
-            Some(GameDifficulty::Medium) => 15,  // 中等15次
-            Some(GameDifficulty::Hard) => 10,    // 困难10次
-            None => 20,
+        self.profile().max_attempts
+    }
+
+    /// 获取提示次数（根据难度）
+    fn get_max_hints(&self) -> usize {
+        self.profile().hints
+    }
+
+    /// 当前难度的参数
+    fn profile(&self) -> GuessProfile {
+        self.difficulty_selection.profile(&self.custom_profile)
+    }
+
+    /// 取出求解器后台枚举的结果，枚举完成后重新自动推理，反向模式下确认等待中的反馈
+    fn poll_solvers(&mut self, ctx: &egui::Context) {
+        let mut searching = false;
+        if let Some(solver) = &mut self.solver {
+            let finished = solver.poll();
+            if finished && self.notepad.auto_fill {
+                self.notepad.apply_deductions(Some(solver), &self.guesses);
+            }
+            searching |= solver.is_searching();
+            if finished && self.guess_mode == GuessMode::Reverse && self.state == GuessNumberState::GamePlaying {
+                self.resolve_feedback();
+            }
+        }
+        if let Some(solver) = &mut self.duel_ai_solver {
+            solver.poll();
+            searching |= solver.is_searching();
+        }
+        if searching {
+            ctx.request_repaint_after(SOLVER_POLL);
+        }
+    }
+
+    /// 使用提示：由求解器给出下一次的最佳猜测
+    fn use_hint(&mut self) {
+        if self.hints_remaining == 0 {
+            return;
+        }
+        match self.solver.as_ref().and_then(|solver| solver.suggest()) {
+            Some(suggestion) => {
+                self.hint_message = Some(format!("提示: 建议猜测 {}", self.format_code(&suggestion)));
+                self.current_guess = suggestion.into_iter().map(Some).collect();
+                self.highlight_incomplete = false;
+                self.hints_remaining -= 1;
+                if let Some(timer) = &mut self.game_timer {
+                    timer.subtract_time(HINT_PENALTY);
+                }
+            }
+            None if self.solver.as_ref().is_some_and(CodeSolver::is_searching) => {
+                self.hint_message = Some("正在计算剩余可能性，请稍候再使用提示".to_string());
+            }
+            None => {
+                // 可能性过多时不消耗提示次数
+                self.hint_message = Some(format!("提示{}，请先多猜几次再使用", code_solver::TOO_MANY_NOTICE));
+            }
+        }
+    }
+
+    /// 提示按钮：候选太多时禁用并说明原因，而不是点了没有反应
+    fn show_hint_button(&mut self, ui: &mut egui::Ui, button_width: f32, button_height: f32) {
+        if self.hints_remaining == 0 {
+            return;
+        }
+        let too_many = self.solver.as_ref().is_some_and(CodeSolver::is_too_many);
+        let hint_text = format!("提示 (剩余{}次)", self.hints_remaining);
+        let hint = ui.add_enabled_ui(!too_many, |ui| {
+            self.centered_button(ui, &hint_text, button_width, button_height)
+        });
+        if hint.inner.clicked() {
+            self.use_hint();
+        }
+        if too_many {
+            ui.colored_label(egui::Color32::GRAY, format!("提示：{}", code_solver::TOO_MANY_NOTICE));
         }
     }
 
@@ -148,36 +591,24 @@ impl GuessNumberGame {
             ui.add_space(10.0);
             
             if let Some(difficulty) = self.difficulty_selection.selected_difficulty {
-                let difficulty_text = match difficulty {
-                    GameDifficulty::Easy => "简单难度",
-                    GameDifficulty::Medium => "中等难度",
-                    GameDifficulty::Hard => "困难难度",
-                };
-                ui.colored_label(egui::Color32::LIGHT_BLUE, difficulty_text);
+                ui.colored_label(egui::Color32::LIGHT_BLUE, difficulty.label());
                 
                 // 游戏状态信息 - 确保倒计时显示
                 ui.horizontal(|ui| {
                     ui.label(format!("剩余尝试次数: {}/{}", self.max_attempts - self.attempts, self.max_attempts));
                     
                     // 显示倒计时 - 确保正确显示
-                    let seconds_remaining = self.time_remaining.as_secs();
-                    let minutes = seconds_remaining / 60;
-                    let seconds = seconds_remaining % 60;
-                    let time_text = if minutes > 0 {
-                        format!("{}分{}秒", minutes, seconds)
-                    } else {
-                        format!("{}秒", seconds)
-                    };
-                    
-                    let color = if seconds_remaining <= 10 {
-                        egui::Color32::RED
-                    } else if seconds_remaining <= 30 {
-                        egui::Color32::YELLOW
-                    } else {
-                        egui::Color32::BLACK
-                    };
-                    ui.colored_label(color, format!("剩余时间: {}", time_text));
+                    if let Some(timer) = &mut self.game_timer {
+                        ui.colored_label(timer.color(), format!("剩余时间: {}", timer.remaining_text()));
+                        if ui.button("暂停 (Esc)").clicked() {
+                            timer.pause();
+                        }
+                    }
                 });
+
+                if let Some(solver) = &self.solver {
+                    ui.label(format!("剩余可能: {}", solver.remaining_text()));
+                }
             }
             
             ui.add_space(30.0);
@@ -186,119 +617,813 @@ impl GuessNumberGame {
             ui.label("目标数字卡片:");
             ui.add_space(10.0);
             ui.horizontal(|ui| {
-                let total_width = self.digit_count as f32 * 80.0 + (self.digit_count - 1) as f32 * 10.0;
+                let total_width = self.code_rules.length as f32 * 80.0 + (self.code_rules.length - 1) as f32 * 10.0;
                 ui.add_space(ui.available_width() / 2.0 - total_width / 2.0);
                 
-                for i in 0..self.digit_count {
+                for i in 0..self.code_rules.length {
                     if i < self.game_cards.len() {
-                        let card = &mut self.game_cards[i];
-                        let response = card.show(ui, egui::vec2(70.0, 100.0));
-                        
-                        if response.clicked() && card.is_face_up {
-                            card.is_face_up = false;
-                        }
+                        // 游戏进行中目标卡片保持背面朝上
+                        self.game_cards[i].render(ui, egui::vec2(70.0, 100.0), egui::Sense::hover());
                     }
                     
-                    if i < self.digit_count - 1 {
+                    if i < self.code_rules.length - 1 {
                         ui.add_space(10.0);
                     }
                 }
             });
 
-            ui.add_space(30.0);
+            ui.add_space(30.0);
+
+            
+            // 数字输入区域，居中显示
+            ui.label(format!("请输入您的猜测: {}", self.code_rules.describe()));
+            ui.add_space(10.0);
+            let enter_pressed = self.show_code_input(ui);
+
+            ui.add_space(15.0);
+            self.show_notepad(ui);
+            ui.add_space(15.0);
+
+            // 控制按钮
+            let button_width = 200.0;
+            let button_height = 40.0;
+            
+            if let Some(error) = &self.input_error {
+                ui.colored_label(egui::Color32::RED, error);
+                ui.add_space(10.0);
+            }
+
+            if let Some(message) = &self.hint_message {
+                ui.colored_label(egui::Color32::DARK_GREEN, message);
+                ui.add_space(10.0);
+            }
+
+            self.show_hint_button(ui, button_width, button_height);
+            ui.add_space(15.0);
+
+            if self.centered_button(ui, "提交猜测", button_width, button_height).clicked() || enter_pressed {
+                self.submit_guess();
+            }
+            ui.add_space(15.0);
+
+            if self.centered_button(ui, "重新开始", button_width, button_height).clicked() {
+                self.reset_game_state();
+                self.state = GuessNumberState::DifficultySelection;
+            }
+            ui.add_space(15.0);
+
+            if self.centered_button(ui, "返回主菜单", button_width, button_height).clicked() {
+                return_to_menu = true;
+                self.suspended_session = self.snapshot().map(SavedSession::GuessNumber);
+                self.reset_game_state();
+                self.state = GuessNumberState::DifficultySelection;
+            }
+
+            ui.add_space(30.0);
+
+            // 猜测历史记录 - 使用可滚动区域
+            if !self.guesses.is_empty() {
+                show_guess_history(ui, "猜测记录", &self.guesses);
+            }
+
+            ui.add_space(20.0);
+        });
+
+        return_to_menu
+    }
+
+    /// 显示可折叠的推理笔记
+    fn show_notepad(&mut self, ui: &mut egui::Ui) {
+        let labels: Vec<String> = (0..self.code_rules.alphabet_size)
+            .map(|digit| self.format_code(&[digit]))
+            .collect();
+        egui::CollapsingHeader::new("推理笔记").show(ui, |ui| {
+            ui.horizontal(|ui| {
+                if ui.checkbox(&mut self.notepad.auto_fill, "根据猜测记录自动推理").changed()
+                    && self.notepad.auto_fill
+                {
+                    self.notepad.apply_deductions(self.solver.as_ref(), &self.guesses);
+                }
+                if ui.button("清空笔记").clicked() {
+                    self.notepad.clear();
+                }
+            });
+            ui.add_space(5.0);
+            self.notepad.show(ui, &labels);
+        });
+    }
+
+    /// 密码的显示文字：数字模式为数字串，彩珠模式为颜色名
+    fn format_code(&self, code: &[u8]) -> String {
+        if self.guess_mode == GuessMode::Mastermind {
+            code.iter()
+                .map(|&peg| PEG_COLORS[peg as usize].1)
+                .collect::<Vec<_>>()
+                .join(" ")
+        } else {
+            code.iter().map(|d| d.to_string()).collect()
+        }
+    }
+
+    /// 显示彩珠模式游戏界面
+    fn show_mastermind_playing(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut return_to_menu = false;
+
+        ui.vertical_centered(|ui| {
+            ui.add_space(20.0);
+
+            ui.heading("猜数字游戏 - 彩珠模式");
+            ui.add_space(10.0);
+            ui.horizontal(|ui| {
+                ui.add_space(ui.available_width() / 2.0 - 150.0);
+                ui.label(format!("剩余尝试次数: {}/{}", self.max_attempts - self.attempts, self.max_attempts));
+                if let Some(timer) = &mut self.game_timer {
+                    ui.colored_label(timer.color(), format!("剩余时间: {}", timer.remaining_text()));
+                    if ui.button("暂停 (Esc)").clicked() {
+                        timer.pause();
+                    }
+                }
+            });
+            if let Some(solver) = &self.solver {
+                ui.label(format!("剩余可能: {}", solver.remaining_text()));
+            }
+
+            ui.add_space(20.0);
+
+            // 隐藏的密码
+            ui.horizontal(|ui| {
+                let length = self.code_rules.length;
+                ui.add_space(ui.available_width() / 2.0 - length as f32 * 18.0);
+                for _ in 0..length {
+                    draw_peg(ui, Some(egui::Color32::DARK_GRAY), 14.0, egui::Sense::hover());
+                    ui.add_space(4.0);
+                }
+            });
+
+            ui.add_space(10.0);
+            ui.separator();
+
+            // 棋盘式猜测记录
+            show_peg_board(ui, &self.guesses, self.code_rules.length);
+
+            ui.add_space(10.0);
+            ui.separator();
+            ui.add_space(10.0);
+
+            // 当前猜测：左键切换到下一种颜色，右键切换到上一种颜色
+            ui.label("点击珠子切换颜色（右键反向切换），也可以用数字键输入颜色编号:");
+            ui.add_space(10.0);
+            let enter_pressed = self.handle_code_keys(ui);
+            ui.horizontal(|ui| {
+                let length = self.code_rules.length;
+                ui.add_space(ui.available_width() / 2.0 - length as f32 * 18.0);
+                for i in 0..length {
+                    let color = self.current_guess[i].map(|peg| PEG_COLORS[peg as usize].0);
+                    let response = draw_peg(ui, color, 14.0, egui::Sense::click());
+                    let center = response.rect.center();
+                    if self.highlight_incomplete && self.current_guess[i].is_none() {
+                        ui.painter().circle_stroke(center, 17.0, egui::Stroke::new(2.0, egui::Color32::RED));
+                    } else if i == self.input_cursor {
+                        ui.painter().circle_stroke(center, 17.0, egui::Stroke::new(2.0, egui::Color32::BLUE));
+                    }
+                    if response.clicked() {
+                        self.cycle_input(i, true);
+                    } else if response.secondary_clicked() {
+                        self.cycle_input(i, false);
+                    }
+                    ui.add_space(4.0);
+                }
+            });
+
+            ui.add_space(10.0);
+
+            // 可用颜色图例
+            ui.horizontal(|ui| {
+                let alphabet = self.code_rules.alphabet_size as usize;
+                ui.add_space(ui.available_width() / 2.0 - alphabet as f32 * 20.0);
+                for (index, (color, name)) in PEG_COLORS.iter().take(alphabet).enumerate() {
+                    draw_peg(ui, Some(*color), 6.0, egui::Sense::hover());
+                    ui.label(format!("{}{}", index, name));
+                }
+            });
+
+            ui.add_space(10.0);
+            self.show_notepad(ui);
+            ui.add_space(20.0);
+
+            if let Some(error) = &self.input_error {
+                ui.colored_label(egui::Color32::RED, error);
+                ui.add_space(10.0);
+            }
+            if let Some(message) = &self.hint_message {
+                ui.colored_label(egui::Color32::DARK_GREEN, message);
+                ui.add_space(10.0);
+            }
+
+            let button_width = 200.0;
+            let button_height = 40.0;
+
+            self.show_hint_button(ui, button_width, button_height);
+            ui.add_space(15.0);
+
+            if self.centered_button(ui, "提交猜测", button_width, button_height).clicked() || enter_pressed {
+                self.submit_guess();
+            }
+            ui.add_space(15.0);
+
+            if self.centered_button(ui, "重新开始", button_width, button_height).clicked() {
+                self.reset_game_state();
+                self.state = GuessNumberState::DifficultySelection;
+            }
+            ui.add_space(15.0);
+
+            if self.centered_button(ui, "返回主菜单", button_width, button_height).clicked() {
+                return_to_menu = true;
+                self.suspended_session = self.snapshot().map(SavedSession::GuessNumber);
+                self.reset_game_state();
+                self.state = GuessNumberState::DifficultySelection;
+            }
+
+            ui.add_space(20.0);
+        });
+
+        return_to_menu
+    }
+
+    /// 显示彩珠模式结束界面
+    fn show_mastermind_game_over(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut return_to_menu = false;
+
+        ui.vertical_centered(|ui| {
+            ui.add_space(20.0);
+
+            if self.game_won {
+                ui.colored_label(egui::Color32::GOLD, "🎉 恭喜你猜对了！ 🎉");
+                ui.label(format!("总共尝试次数: {}", self.attempts));
+            } else {
+                ui.colored_label(egui::Color32::RED, "💀 游戏结束！ 💀");
+                if self.attempts >= self.max_attempts {
+                    ui.label("尝试次数用完了！");
+                } else {
+                    ui.label("时间到了！");
+                }
+            }
+
+            ui.add_space(20.0);
+
+            // 揭晓密码
+            ui.label("密码:");
+            ui.add_space(10.0);
+            ui.horizontal(|ui| {
+                let length = self.target_number.len();
+                ui.add_space(ui.available_width() / 2.0 - length as f32 * 18.0);
+                for &peg in &self.target_number {
+                    draw_peg(ui, Some(PEG_COLORS[peg as usize].0), 14.0, egui::Sense::hover());
+                    ui.add_space(4.0);
+                }
+            });
+            ui.label(self.format_code(&self.target_number));
+
+            ui.add_space(20.0);
+            show_peg_board(ui, &self.guesses, self.code_rules.length);
+            ui.add_space(20.0);
+            self.score_submission.show(ui);
+            ui.add_space(10.0);
+
+            let button_width = 200.0;
+            let button_height = 40.0;
+
+            if self.centered_button(ui, "再玩一次", button_width, button_height).clicked() {
+                self.reset_game_state();
+                self.state = GuessNumberState::DifficultySelection;
+            }
+            ui.add_space(15.0);
+
+            if self.centered_button(ui, "返回主菜单", button_width, button_height).clicked() {
+                return_to_menu = true;
+                self.reset_game_state();
+                self.state = GuessNumberState::DifficultySelection;
+            }
+
+            ui.add_space(20.0);
+        });
+
+        return_to_menu
+    }
+
+    /// 显示反向模式游戏界面：电脑猜测，玩家给出反馈
+    fn show_reverse_playing(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut return_to_menu = false;
+
+        ui.vertical_centered(|ui| {
+            ui.add_space(20.0);
+
+            ui.heading("猜数字游戏 - 反向模式");
+            ui.add_space(10.0);
+            ui.label(format!("请在心中想好一个{}", self.code_rules.describe()));
+            ui.label(format!(
+                "电脑剩余尝试次数: {}/{}",
+                self.max_attempts - self.attempts,
+                self.max_attempts
+            ));
+            if let Some(solver) = &self.solver {
+                ui.label(format!("剩余可能: {}", solver.remaining_text()));
+                if solver.is_too_many() {
+                    // 候选未能枚举时无法判断反馈是否互相矛盾
+                    ui.colored_label(egui::Color32::GRAY, format!("矛盾检测：{}", code_solver::TOO_MANY_NOTICE));
+                }
+            }
+
+            ui.add_space(30.0);
+
+            // 电脑的猜测（求解器在后台枚举时等待结果）
+            let thinking = self.solver.as_ref().is_some_and(CodeSolver::is_searching);
+            ui.label("电脑猜测:");
+            ui.add_space(10.0);
+            if self.computer_guess.is_empty() || thinking {
+                ui.label(egui::RichText::new("电脑思考中…").size(24.0));
+            }
+            ui.label(
+                egui::RichText::new(
+                    self.computer_guess.iter().map(|d| d.to_string()).collect::<String>(),
+                )
+                .size(36.0)
+                .strong(),
+            );
+
+            ui.add_space(30.0);
+
+            // 反馈输入
+            ui.label("请根据你的密码给出反馈:");
+            ui.add_space(10.0);
+            ui.horizontal(|ui| {
+                ui.add_space(ui.available_width() / 2.0 - 90.0);
+                let length = self.code_rules.length;
+                egui::ComboBox::from_id_salt("feedback_a")
+                    .width(60.0)
+                    .selected_text(format!("{}A", self.feedback.0))
+                    .show_ui(ui, |ui| {
+                        for count in 0..=length {
+                            ui.selectable_value(&mut self.feedback.0, count, format!("{}A", count));
+                        }
+                    });
+                ui.add_space(10.0);
+                egui::ComboBox::from_id_salt("feedback_b")
+                    .width(60.0)
+                    .selected_text(format!("{}B", self.feedback.1))
+                    .show_ui(ui, |ui| {
+                        for count in 0..=length {
+                            ui.selectable_value(&mut self.feedback.1, count, format!("{}B", count));
+                        }
+                    });
+            });
+
+            ui.add_space(20.0);
+
+            if let Some(error) = &self.feedback_error {
+                ui.colored_label(egui::Color32::RED, error);
+                ui.add_space(10.0);
+            }
+
+            let button_width = 200.0;
+            let button_height = 40.0;
+
+            let can_submit = !thinking && !self.computer_guess.is_empty();
+            let submit = ui.add_enabled_ui(can_submit, |ui| {
+                self.centered_button(ui, "提交反馈", button_width, button_height)
+            });
+            if submit.inner.clicked() {
+                self.submit_feedback();
+            }
+            ui.add_space(15.0);
+
+            if self.centered_button(ui, "重新开始", button_width, button_height).clicked() {
+                self.reset_game_state();
+                self.state = GuessNumberState::DifficultySelection;
+            }
+            ui.add_space(15.0);
+
+            if self.centered_button(ui, "返回主菜单", button_width, button_height).clicked() {
+                return_to_menu = true;
+                self.suspended_session = self.snapshot().map(SavedSession::GuessNumber);
+                self.reset_game_state();
+                self.state = GuessNumberState::DifficultySelection;
+            }
+
+            ui.add_space(30.0);
+
+            if !self.guesses.is_empty() {
+                show_guess_history(ui, "电脑猜测记录", &self.guesses);
+            }
+
+            ui.add_space(20.0);
+        });
+
+        return_to_menu
+    }
+
+    /// 显示反向模式结束界面
+    fn show_reverse_game_over(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut return_to_menu = false;
+
+        ui.vertical_centered(|ui| {
+            ui.add_space(20.0);
+
+            if self.game_won {
+                ui.colored_label(egui::Color32::GOLD, "🎉 电脑没能猜出你的密码，你赢了！ 🎉");
+                ui.label(format!("电脑用完了全部 {} 次尝试", self.max_attempts));
+            } else {
+                ui.colored_label(egui::Color32::RED, "电脑猜中了你的密码！");
+                ui.label(format!(
+                    "电脑用了 {} 次猜中: {}",
+                    self.attempts,
+                    self.computer_guess.iter().map(|d| d.to_string()).collect::<String>()
+                ));
+            }
+
+            ui.add_space(20.0);
+
+            if !self.guesses.is_empty() {
+                show_guess_history(ui, "电脑猜测记录", &self.guesses);
+                ui.add_space(30.0);
+            }
+
+            let button_width = 200.0;
+            let button_height = 40.0;
+
+            if self.centered_button(ui, "再玩一次", button_width, button_height).clicked() {
+                self.reset_game_state();
+                self.state = GuessNumberState::DifficultySelection;
+            }
+            ui.add_space(15.0);
+
+            if self.centered_button(ui, "返回主菜单", button_width, button_height).clicked() {
+                return_to_menu = true;
+                self.reset_game_state();
+                self.state = GuessNumberState::DifficultySelection;
+            }
+
+            ui.add_space(20.0);
+        });
+
+        return_to_menu
+    }
+
+    /// 显示对战模式游戏界面
+    fn show_duel_playing(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut return_to_menu = false;
+
+        ui.vertical_centered(|ui| {
+            ui.add_space(20.0);
+            ui.heading("猜数字游戏 - 对战模式");
+            ui.add_space(10.0);
+            ui.label(format!("密码规则: {}，每人最多猜{}次", self.code_rules.describe(), self.max_attempts));
+            ui.add_space(20.0);
+
+            match self.duel_phase {
+                DuelPhase::SetSecret(side) => {
+                    let name = self.duel_sides[side].name;
+                    ui.colored_label(egui::Color32::LIGHT_BLUE, format!("{}请设置你的密码（不要让对方看到）", name));
+                    ui.add_space(10.0);
+                    let enter_pressed = self.show_code_input(ui);
+                    ui.add_space(10.0);
+                    if let Some(error) = &self.input_error {
+                        ui.colored_label(egui::Color32::RED, error);
+                        ui.add_space(10.0);
+                    }
+                    if self.centered_button(ui, "确认密码", 200.0, 40.0).clicked() || enter_pressed {
+                        self.confirm_duel_secret(side);
+                    }
+                }
+                DuelPhase::Handover(side) => {
+                    let name = self.duel_sides[side].name;
+                    ui.colored_label(egui::Color32::LIGHT_BLUE, format!("请把设备交给{}", name));
+                    ui.add_space(20.0);
+                    if self.centered_button(ui, &format!("我是{}，继续", name), 200.0, 40.0).clicked() {
+                        self.duel_phase = if self.duel_sides[side].secret.is_empty() {
+                            DuelPhase::SetSecret(side)
+                        } else {
+                            DuelPhase::Guessing
+                        };
+                    }
+                }
+                DuelPhase::Guessing => {
+                    let name = self.duel_sides[self.duel_turn].name;
+                    let opponent = self.duel_sides[1 - self.duel_turn].name;
+                    ui.colored_label(egui::Color32::LIGHT_BLUE, format!("轮到{}猜{}的密码", name, opponent));
+                    if self.duel_turn == 1 && self.duel_cracked(0) {
+                        ui.colored_label(
+                            egui::Color32::GOLD,
+                            format!("{}已经猜中，这是{}扳平的最后一次机会", opponent, name),
+                        );
+                    }
+                    ui.add_space(10.0);
+                    let enter_pressed = self.show_code_input(ui);
+                    ui.add_space(10.0);
+                    if let Some(error) = &self.input_error {
+                        ui.colored_label(egui::Color32::RED, error);
+                        ui.add_space(10.0);
+                    }
+                    if self.centered_button(ui, "提交猜测", 200.0, 40.0).clicked() || enter_pressed {
+                        self.submit_duel_guess();
+                    }
+                }
+            }
+
+            ui.add_space(15.0);
+            if self.centered_button(ui, "重新开始", 200.0, 40.0).clicked() {
+                self.reset_game_state();
+                self.state = GuessNumberState::DifficultySelection;
+            }
+            ui.add_space(15.0);
+            if self.centered_button(ui, "返回主菜单", 200.0, 40.0).clicked() {
+                return_to_menu = true;
+                self.suspended_session = self.snapshot().map(SavedSession::GuessNumber);
+                self.reset_game_state();
+                self.state = GuessNumberState::DifficultySelection;
+            }
+
+            ui.add_space(30.0);
+            self.show_duel_histories(ui);
+        });
+
+        return_to_menu
+    }
+
+    /// 并排显示双方的猜测记录
+    fn show_duel_histories(&self, ui: &mut egui::Ui) {
+        if self.duel_sides.len() < 2 {
+            return;
+        }
+        ui.columns(2, |columns| {
+            for (column, side) in columns.iter_mut().zip(&self.duel_sides) {
+                let title = format!("{}的猜测", side.name);
+                show_guess_history(column, &title, &side.guesses);
+            }
+        });
+    }
+
+    /// 确认一方设置的密码
+    fn confirm_duel_secret(&mut self, side: usize) {
+        let Some(secret) = self.take_complete_guess() else {
+            return;
+        };
+        self.duel_sides[side].secret = secret;
+
+        self.duel_phase = if side == 0 && self.duel_opponent == DuelOpponent::HotSeat {
+            DuelPhase::Handover(1)
+        } else if self.duel_opponent == DuelOpponent::HotSeat {
+            DuelPhase::Handover(0)
+        } else {
+            DuelPhase::Guessing
+        };
+    }
+
+    /// 提交当前玩家对对方密码的猜测
+    fn submit_duel_guess(&mut self) {
+        let Some(guess) = self.take_complete_guess() else {
+            return;
+        };
+        self.record_duel_guess(self.duel_turn, guess);
+
+        // 电脑对手紧接着猜一次
+        if self.duel_opponent == DuelOpponent::Ai && self.state == GuessNumberState::GamePlaying {
+            let ai_guess = self
+                .duel_ai_solver
+                .as_ref()
+                .and_then(|solver| solver.suggest())
+                .unwrap_or_else(|| self.code_rules.random_code());
+            self.record_duel_guess(1, ai_guess);
+        }
+    }
+
+    /// 某一方最近一次猜测是否已经猜中对方的密码
+    fn duel_cracked(&self, side: usize) -> bool {
+        self.duel_sides[side]
+            .guesses
+            .last()
+            .is_some_and(|(_, (a_count, _))| *a_count == self.code_rules.length)
+    }
+
+    /// 记录一次对战猜测；每轮双方各猜一次，后手猜完才判断胜负，同一轮都猜中算平局
+    fn record_duel_guess(&mut self, side: usize, guess: Vec<u8>) {
+        let score = score_guess(&guess, &self.duel_sides[1 - side].secret);
+        if side == 1
+            && let Some(solver) = &mut self.duel_ai_solver
+        {
+            solver.add_feedback(&guess, score);
+        }
+        self.duel_sides[side].guesses.push((guess, score));
+
+        if side == 0 {
+            // 先手猜中后，后手仍有这一轮的最后一次机会
+            self.duel_turn = 1;
+            return;
+        }
+
+        let cracked = [self.duel_cracked(0), self.duel_cracked(1)];
+        if cracked[0] || cracked[1] {
+            // 只有一方猜中时获胜，同一轮都猜中算平局
+            self.duel_winner = if cracked[0] && cracked[1] { None } else { Some(usize::from(cracked[1])) };
+            self.state = GuessNumberState::GameOver;
+        } else if self.duel_sides.iter().all(|s| s.guesses.len() >= self.max_attempts) {
+            // 双方都用完尝试次数，平局
+            self.duel_winner = None;
+            self.state = GuessNumberState::GameOver;
+        } else {
+            self.duel_turn = 0;
+        }
+    }
+
+    /// 显示对战模式结束界面
+    fn show_duel_game_over(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut return_to_menu = false;
 
-            
-            // 数字输入区域，居中显示
-            ui.label("请输入您的猜测 (0-9，数字可以重复):");
-            ui.add_space(10.0);
-            ui.horizontal(|ui| {
-                let total_width = self.digit_count as f32 * 80.0;
-                ui.add_space(ui.available_width() / 2.0 - total_width / 2.0);
-                
-                for i in 0..self.digit_count {
-                    ui.vertical(|ui| {
-                        ui.label(format!("第{}位", i + 1));
-                        egui::ComboBox::from_id_salt(format!("digit_{}", i))
-                            .width(60.0)
-                            .selected_text(format!("{}", self.current_guess[i]))
-                            .show_ui(ui, |ui| {
-                                for digit in 0..=9 {
-                                    ui.selectable_value(&mut self.current_guess[i], digit, format!("{}", digit));
-                                }
-                            });
-                    });
-                    
-                    if i < self.digit_count - 1 {
-                        ui.add_space(10.0);
-                    }
+        ui.vertical_centered(|ui| {
+            ui.add_space(20.0);
+
+            match self.duel_winner {
+                Some(side) => {
+                    let winner = self.duel_sides[side].name;
+                    ui.colored_label(egui::Color32::GOLD, format!("🎉 {}获胜！ 🎉", winner));
+                    ui.label(format!("用了 {} 次猜中对方的密码", self.duel_sides[side].guesses.len()));
                 }
-            });
+                None if self.duel_cracked(0) => {
+                    ui.colored_label(egui::Color32::GOLD, "双方在同一轮猜中对方的密码，平局！");
+                }
+                None => {
+                    ui.colored_label(egui::Color32::RED, "双方都没能猜中，平局！");
+                }
+            }
 
+            ui.add_space(10.0);
+            for side in &self.duel_sides {
+                ui.label(format!(
+                    "{}的密码: {}",
+                    side.name,
+                    side.secret.iter().map(|d| d.to_string()).collect::<String>()
+                ));
+            }
 
+            ui.add_space(20.0);
+            self.show_duel_histories(ui);
             ui.add_space(30.0);
 
-            // 控制按钮
-            let button_width = 200.0;
-            let button_height = 40.0;
-            
-            if self.centered_button(ui, "提交猜测", button_width, button_height).clicked() {
-                self.submit_guess();
-            }
-            ui.add_space(15.0);
-
-            if self.centered_button(ui, "重新开始", button_width, button_height).clicked() {
+            if self.centered_button(ui, "再玩一次", 200.0, 40.0).clicked() {
                 self.reset_game_state();
                 self.state = GuessNumberState::DifficultySelection;
             }
             ui.add_space(15.0);
-
-            if self.centered_button(ui, "返回主菜单", button_width, button_height).clicked() {
+            if self.centered_button(ui, "返回主菜单", 200.0, 40.0).clicked() {
                 return_to_menu = true;
                 self.reset_game_state();
                 self.state = GuessNumberState::DifficultySelection;
             }
 
-            ui.add_space(30.0);
+            ui.add_space(20.0);
+        });
 
-            // 猜测历史记录 - 使用可滚动区域
-            if !self.guesses.is_empty() {
-                ui.vertical_centered(|ui| {
-                    ui.heading("猜测记录");
+        return_to_menu
+    }
+
+    /// 显示数字输入区域：每一位是一张可点击的数字卡片，支持键盘输入
+    /// 返回是否按下了回车键
+    fn show_code_input(&mut self, ui: &mut egui::Ui) -> bool {
+        let enter_pressed = self.handle_code_keys(ui);
+
+        ui.horizontal(|ui| {
+            let length = self.code_rules.length;
+            let total_width = length as f32 * 70.0 - 10.0;
+            ui.add_space(ui.available_width() / 2.0 - total_width / 2.0);
+            
+            for i in 0..length {
+                let (rect, response) = ui.allocate_exact_size(egui::vec2(60.0, 80.0), egui::Sense::click());
+                let missing = self.highlight_incomplete && self.current_guess[i].is_none();
+                let (fill, stroke) = if missing {
+                    (egui::Color32::from_rgb(255, 220, 220), egui::Stroke::new(3.0, egui::Color32::RED))
+                } else if i == self.input_cursor {
+                    (egui::Color32::WHITE, egui::Stroke::new(3.0, egui::Color32::GOLD))
+                } else {
+                    (egui::Color32::WHITE, egui::Stroke::new(1.0, egui::Color32::DARK_GRAY))
+                };
+                let painter = ui.painter();
+                painter.rect_filled(rect, 6.0, fill);
+                painter.rect_stroke(rect, 6.0, stroke, egui::StrokeKind::Inside);
+                let text = self.current_guess[i].map_or("_".to_string(), |digit| digit.to_string());
+                painter.text(
+                    rect.center(),
+                    egui::Align2::CENTER_CENTER,
+                    text,
+                    egui::FontId::proportional(36.0),
+                    egui::Color32::BLACK,
+                );
+
+                // 左键切换到下一个数字，右键切换到上一个数字
+                if response.clicked() {
+                    self.cycle_input(i, true);
+                } else if response.secondary_clicked() {
+                    self.cycle_input(i, false);
+                }
+                
+                if i < length - 1 {
                     ui.add_space(10.0);
-                    
-                    // 使用可滚动区域显示猜测记录
-                    egui::ScrollArea::vertical()
-                        .max_height(200.0) 
-                        .show(ui, |ui| {
-                     
-                            ui.horizontal(|ui| {
-                                ui.label("次数");
-                                ui.add_space(40.0);
-                                ui.label("猜测数字");
-                                ui.add_space(40.0);
-                                ui.label("结果");
-                            });
-                            
-                            ui.separator();
-                            
-                            // 猜测记录
-                            for (index, (guess, result)) in self.guesses.iter().enumerate() {
-                                ui.horizontal(|ui| {
-                                    ui.label(format!("{}", index + 1));
-                                    ui.add_space(30.0);
-                                    ui.label(guess.iter().map(|d| d.to_string()).collect::<String>());
-                                    ui.add_space(30.0);
-                                    ui.colored_label(egui::Color32::LIGHT_BLUE, result.clone());
-                                });
-                            }
-                        });
-                });
+                }
             }
+        });
 
-            ui.add_space(20.0);
+        ui.add_space(5.0);
+        ui.label("数字键输入，退格删除，←→ 移动，回车提交；点击卡片切换数字（右键反向）");
+
+        enter_pressed
+    }
+
+    /// 处理键盘输入：数字键填入当前位置，退格删除，方向键移动，返回是否按下回车
+    fn handle_code_keys(&mut self, ui: &egui::Ui) -> bool {
+        let length = self.current_guess.len();
+        let paused = self.game_timer.as_ref().is_some_and(|timer| timer.is_paused());
+        if length == 0 || paused {
+            return false;
+        }
+
+        let (typed, backspace, left, right, enter) = ui.input(|input| {
+            let typed: Vec<u8> = input
+                .events
+                .iter()
+                .filter_map(|event| match event {
+                    egui::Event::Text(text) => Some(text),
+                    _ => None,
+                })
+                .flat_map(|text| text.chars())
+                .filter_map(|c| c.to_digit(10).map(|d| d as u8))
+                .collect();
+            (
+                typed,
+                input.key_pressed(egui::Key::Backspace),
+                input.key_pressed(egui::Key::ArrowLeft),
+                input.key_pressed(egui::Key::ArrowRight),
+                input.key_pressed(egui::Key::Enter),
+            )
         });
 
-        return_to_menu
+        for digit in typed {
+            if digit < self.code_rules.alphabet_size {
+                self.current_guess[self.input_cursor] = Some(digit);
+                self.input_cursor = (self.input_cursor + 1).min(length - 1);
+                self.highlight_incomplete = false;
+            }
+        }
+        if backspace {
+            // 当前位置已填写则清空，否则退回上一位清空
+            if self.current_guess[self.input_cursor].is_none() && self.input_cursor > 0 {
+                self.input_cursor -= 1;
+            }
+            self.current_guess[self.input_cursor] = None;
+        }
+        if left {
+            self.input_cursor = self.input_cursor.saturating_sub(1);
+        }
+        if right {
+            self.input_cursor = (self.input_cursor + 1).min(length - 1);
+        }
+
+        enter
+    }
+
+    /// 点击切换某一位的数字
+    fn cycle_input(&mut self, position: usize, forward: bool) {
+        let alphabet = self.code_rules.alphabet_size;
+        let slot = &mut self.current_guess[position];
+        *slot = Some(match (*slot, forward) {
+            (None, true) => 0,
+            (None, false) => alphabet - 1,
+            (Some(digit), true) => (digit + 1) % alphabet,
+            (Some(digit), false) => (digit + alphabet - 1) % alphabet,
+        });
+        self.input_cursor = position;
+        self.highlight_incomplete = false;
+    }
+
+    /// 取出已填写完整且符合规则的猜测并清空输入，不完整时高亮未填写的位置
+    fn take_complete_guess(&mut self) -> Option<Vec<u8>> {
+        let Some(guess) = self.current_guess.iter().copied().collect::<Option<Vec<u8>>>() else {
+            self.highlight_incomplete = true;
+            self.input_error = Some("还有位置没有填写".to_string());
+            return None;
+        };
+        if let Err(error) = self.code_rules.validate(&guess) {
+            self.input_error = Some(error);
+            return None;
+        }
+        self.input_error = None;
+        self.highlight_incomplete = false;
+        self.clear_input();
+        Some(guess)
+    }
+
+    /// 清空当前输入
+    fn clear_input(&mut self) {
+        self.current_guess = vec![None; self.code_rules.length];
+        self.input_cursor = 0;
     }
 
     /// 显示游戏结束界面
@@ -321,18 +1446,18 @@ impl GuessNumberGame {
             ui.label("目标数字:");
             ui.add_space(10.0);
             ui.horizontal(|ui| {
-                let total_width = self.digit_count as f32 * 80.0 + (self.digit_count - 1) as f32 * 10.0;
+                let total_width = self.code_rules.length as f32 * 80.0 + (self.code_rules.length - 1) as f32 * 10.0;
                 ui.add_space(ui.available_width() / 2.0 - total_width / 2.0);
                 
-                for i in 0..self.digit_count {
+                for i in 0..self.code_rules.length {
                     if i < self.game_cards.len() {
                         let card = &mut self.game_cards[i];
                         // 确保卡片是翻开状态
-                        card.is_face_up = true;
-                        card.show(ui, egui::vec2(70.0, 100.0));
+                        card.flip_to(true);
+                        card.render(ui, egui::vec2(70.0, 100.0), egui::Sense::hover());
                     }
                     
-                    if i < self.digit_count - 1 {
+                    if i < self.code_rules.length - 1 {
                         ui.add_space(10.0);
                     }
                 }
@@ -340,9 +1465,7 @@ impl GuessNumberGame {
 
             ui.add_space(10.0);
             ui.label(format!("目标数字: {}", 
-                self.target_number.iter().map(|d| {
-                    if *d == 10 { "0".to_string() } else { d.to_string() }
-                }).collect::<String>()));
+                self.target_number.iter().map(|d| d.to_string()).collect::<String>()));
             
             if self.game_won {
                 ui.label(format!("总共尝试次数: {}", self.attempts));
@@ -353,6 +1476,9 @@ impl GuessNumberGame {
                     ui.label("时间到了！");
                 }
             }
+
+            ui.add_space(10.0);
+            self.score_submission.show(ui);
             
             ui.add_space(20.0);
 
@@ -373,7 +1499,7 @@ impl GuessNumberGame {
                                         ui.label(format!("{}: ", index + 1));
                                         ui.label(guess.iter().map(|d| d.to_string()).collect::<String>());
                                         ui.label(" → ");
-                                        ui.colored_label(egui::Color32::LIGHT_BLUE, result.clone());
+                                        ui.colored_label(egui::Color32::LIGHT_BLUE, format_score(*result));
                                     });
                                 }
                             });
@@ -382,21 +1508,24 @@ impl GuessNumberGame {
                 ui.add_space(30.0);
             }
             
-            // 按钮
+            // 按钮（目标卡片翻开动画播放完之前不可点击）
             let button_width = 200.0;
             let button_height = 40.0;
-            
-            if self.centered_button(ui, "再玩一次", button_width, button_height).clicked() {
-                self.reset_game_state();
-                self.state = GuessNumberState::DifficultySelection;
-            }
-            ui.add_space(15.0);
+            let animating = card::any_animating(&self.game_cards);
 
-            if self.centered_button(ui, "返回主菜单", button_width, button_height).clicked() {
-                return_to_menu = true;
-                self.reset_game_state();
-                self.state = GuessNumberState::DifficultySelection;
-            }
+            ui.add_enabled_ui(!animating, |ui| {
+                if self.centered_button(ui, "再玩一次", button_width, button_height).clicked() {
+                    self.reset_game_state();
+                    self.state = GuessNumberState::DifficultySelection;
+                }
+                ui.add_space(15.0);
+
+                if self.centered_button(ui, "返回主菜单", button_width, button_height).clicked() {
+                    return_to_menu = true;
+                    self.reset_game_state();
+                    self.state = GuessNumberState::DifficultySelection;
+                }
+            });
 
             ui.add_space(20.0);
         });
@@ -413,93 +1542,301 @@ impl GuessNumberGame {
         self.game_cards.clear();
         self.flipped_cards.clear();
         
+        // 对战模式由双方各自设置密码，不计时
+        if self.guess_mode == GuessMode::Duel {
+            self.target_number.clear();
+            self.duel_turn = 0;
+            self.duel_winner = None;
+            self.duel_phase = DuelPhase::SetSecret(0);
+            match self.duel_opponent {
+                DuelOpponent::HotSeat => {
+                    self.duel_sides = self.duel_side_names().map(DuelSide::new).into();
+                    self.duel_ai_solver = None;
+                }
+                DuelOpponent::Ai => {
+                    let [player, ai] = self.duel_side_names();
+                    let mut ai_side = DuelSide::new(ai);
+                    ai_side.secret = self.code_rules.random_code();
+                    self.duel_sides = vec![DuelSide::new(player), ai_side];
+                    self.duel_ai_solver = Some(CodeSolver::new(self.code_rules));
+                }
+            }
+            return;
+        }
+
+        // 彩珠模式用珠子代替数字卡片
+        if self.guess_mode == GuessMode::Mastermind {
+            self.game_timer = Some(GameTimer::start(self.get_total_time()));
+            return;
+        }
+
+        // 反向模式由电脑来猜，不需要目标卡片和计时
+        if self.guess_mode == GuessMode::Reverse {
+            self.target_number.clear();
+            self.feedback = (0, 0);
+            self.feedback_error = None;
+            self.computer_guess = self.next_computer_guess().unwrap_or_default();
+            return;
+        }
+        
         // 创建数字卡片
         for (i, &digit) in self.target_number.iter().enumerate() {
             let suit = (i % 4) as u8 + 1; // 循环使用四种花色
             let card_value = if digit == 0 { 10 } else { digit }; // 0用10表示
             let face_path = util::get_card_image_path(card_value, suit);
-            if let Ok(card) = Card::new(ctx, i as usize, card_value, suit, "assets/card_back/default.png", &face_path) {
+            if let Ok(card) = Card::new(ctx, i, card_value, suit, "assets/card_back/default.png", &face_path) {
                 self.game_cards.push(card);
             }
         }
         
         // 启动游戏计时器
-        self.game_timer = Some(Instant::now());
-        self.time_remaining = self.get_total_time();
+        self.game_timer = Some(GameTimer::start(self.get_total_time()));
     }
 
     /// 生成新的目标数字
     fn generate_new_number(&mut self) {
-        let mut rng = rand::rng();
+        let rules = self.code_rules;
         
         // 生成随机数字
-        self.target_number = (0..self.digit_count)
-            .map(|_| rng.random_range(0..=9))
-            .collect();
+        self.target_number = rules.random_code();
         
         // 重置猜测状态
         self.guesses.clear();
-        self.current_guess = vec![0; self.digit_count];
+        self.clear_input();
+        self.highlight_incomplete = false;
+        self.input_error = None;
+        self.solver = Some(CodeSolver::new(rules));
+        self.hints_remaining = self.get_max_hints();
+        self.hint_message = None;
+        let auto_fill = self.notepad.auto_fill;
+        self.notepad = DeductionNotepad::new(rules.length, rules.alphabet_size);
+        self.notepad.auto_fill = auto_fill;
     }
 
     /// 提交猜测
     fn submit_guess(&mut self) {
+        // 不完整或不符合规则的猜测不计入尝试次数
+        let Some(guess) = self.take_complete_guess() else {
+            return;
+        };
         self.attempts += 1;
 
         // 计算A和B的数量
-        let mut a_count = 0;
-        let mut b_count = 0;
-        
-    
-        let mut target_used = vec![false; self.digit_count];
-        let mut guess_used = vec![false; self.digit_count];
-        
-        // 先计算A
-        for i in 0..self.digit_count {
-            if self.current_guess[i] == self.target_number[i] {
-                a_count += 1;
-                target_used[i] = true;
-                guess_used[i] = true;
+        let (a_count, b_count) = score_guess(&guess, &self.target_number);
+
+        // 保存猜测记录并缩小剩余可能
+        if let Some(solver) = &mut self.solver {
+            solver.add_feedback(&guess, (a_count, b_count));
+        }
+        self.guesses.push((guess, (a_count, b_count)));
+        self.hint_message = None;
+        if self.notepad.auto_fill {
+            self.notepad.apply_deductions(self.solver.as_ref(), &self.guesses);
+        }
+
+        // 检查是否获胜
+        if a_count == self.code_rules.length {
+            self.finish_guessing(true);
+        } else if self.attempts >= self.max_attempts {
+            self.finish_guessing(false);
+        }
+    }
+
+    /// 经典和彩珠模式结束：翻开目标卡片并记录战绩
+    ///
+    /// 反向模式和对战模式不产生 GameResult：反向模式的尝试次数是电脑的，对战模式可能是两名本地玩家，
+    /// 计入按难度统计的最少尝试、排行榜和成就会与经典模式的成绩混在一起。
+    fn finish_guessing(&mut self, won: bool) {
+        self.game_won = won;
+        self.state = GuessNumberState::GameOver;
+        // 翻开所有卡片
+        for card in &mut self.game_cards {
+            card.flip_to(true);
+        }
+        self.difficulty_selection.record_result(won);
+
+        let difficulty = self.difficulty_selection.selected_difficulty.unwrap_or(GameDifficulty::Easy);
+        let mut result = GameResult::new(GameKind::GuessNumber, difficulty, won);
+        result.time_secs = self.game_timer.as_ref().map(|timer| timer.elapsed().as_secs());
+        result.moves = Some(self.attempts);
+        result.code_rules = Some(self.code_rules);
+        self.score_submission.offer(&result);
+        self.finished_result = Some(result);
+        if won {
+            self.events.push(GameEvent::CodeCracked {
+                difficulty,
+                attempts: self.attempts,
+                hints_used: self.get_max_hints().saturating_sub(self.hints_remaining),
+            });
+        }
+        self.game_timer = None;
+    }
+
+    /// 对战双方的名字
+    fn duel_side_names(&self) -> [&'static str; 2] {
+        match self.duel_opponent {
+            DuelOpponent::HotSeat => ["玩家1", "玩家2"],
+            DuelOpponent::Ai => ["玩家", "电脑"],
+        }
+    }
+
+    /// 保存进行中的一局，没有进行中的游戏时返回 None
+    fn snapshot(&self) -> Option<GuessNumberSave> {
+        if self.state != GuessNumberState::GamePlaying {
+            return None;
+        }
+        Some(GuessNumberSave {
+            difficulty: self.difficulty_selection.selected_difficulty?,
+            adaptive_level: self.difficulty_selection.adaptive.level,
+            custom_profile: self.custom_profile,
+            guess_mode: self.guess_mode,
+            code_rules: self.code_rules,
+            target_number: self.target_number.clone(),
+            // 等待确认的反馈不存档，恢复后由玩家重新输入
+            guesses: self.guesses[..self.guesses.len() - usize::from(self.pending_feedback.is_some())].to_vec(),
+            attempts: self.attempts,
+            max_attempts: self.max_attempts,
+            hints_remaining: self.hints_remaining,
+            notepad: self.notepad.clone(),
+            cards: save_game::save_cards(&self.game_cards),
+            flipped_cards: self.flipped_cards.clone(),
+            timer: self.game_timer.as_ref().map(GameTimer::snapshot),
+            computer_guess: self.computer_guess.clone(),
+            duel_opponent: self.duel_opponent,
+            duel_sides: self
+                .duel_sides
+                .iter()
+                .map(|side| (side.secret.clone(), side.guesses.clone()))
+                .collect(),
+            duel_phase: self.duel_phase,
+            duel_turn: self.duel_turn,
+        })
+    }
+
+    /// 从存档恢复一局，计时器处于暂停状态
+    fn restore(&mut self, ctx: &egui::Context, save: GuessNumberSave) {
+        self.reset_game_state();
+        self.difficulty_selection.restore(save.difficulty, save.adaptive_level);
+        self.custom_profile = save.custom_profile;
+        self.guess_mode = save.guess_mode;
+        self.code_rules = save.code_rules;
+        self.target_number = save.target_number;
+        self.attempts = save.attempts;
+        self.max_attempts = save.max_attempts;
+        self.hints_remaining = save.hints_remaining;
+        self.notepad = save.notepad;
+        self.clear_input();
+
+        // 按猜测记录重建求解器
+        let mut solver = CodeSolver::new(self.code_rules);
+        for (guess, score) in &save.guesses {
+            solver.add_feedback(guess, *score);
+        }
+        self.solver = Some(solver);
+        self.guesses = save.guesses;
+
+        self.game_cards = save_game::restore_cards(ctx, &save.cards);
+        self.flipped_cards = save.flipped_cards;
+        self.game_timer = save.timer.map(GameTimer::restore);
+        self.computer_guess = save.computer_guess;
+
+        self.duel_opponent = save.duel_opponent;
+        self.duel_sides = self
+            .duel_side_names()
+            .into_iter()
+            .zip(save.duel_sides)
+            .map(|(name, (secret, guesses))| DuelSide { name, secret, guesses })
+            .collect();
+        if self.guess_mode == GuessMode::Duel && self.duel_opponent == DuelOpponent::Ai {
+            let mut solver = CodeSolver::new(self.code_rules);
+            for (guess, score) in self.duel_sides.get(1).map_or(&[][..], |side| &side.guesses) {
+                solver.add_feedback(guess, *score);
             }
+            self.duel_ai_solver = Some(solver);
         }
-        
-        // 再计算B（数字正确但位置错误）
-        for i in 0..self.digit_count {
-            if !guess_used[i] {
-                for j in 0..self.digit_count {
-                    if !target_used[j] && self.current_guess[i] == self.target_number[j] {
-                        b_count += 1;
-                        target_used[j] = true;
-                        break;
-                    }
-                }
+        self.duel_phase = save.duel_phase;
+        self.duel_turn = save.duel_turn;
+        self.state = GuessNumberState::GamePlaying;
+        if self.guess_mode == GuessMode::Reverse {
+            self.resolve_feedback();
+        }
+    }
+
+    /// 电脑的下一次猜测：优先使用求解器，可能性过多时随机猜测，后台仍在枚举时返回 None
+    fn next_computer_guess(&self) -> Option<Vec<u8>> {
+        match &self.solver {
+            Some(solver) if solver.is_searching() => None,
+            solver => Some(
+                solver
+                    .as_ref()
+                    .and_then(|solver| solver.suggest())
+                    .unwrap_or_else(|| self.code_rules.random_code()),
+            ),
+        }
+    }
+
+    /// 提交玩家对电脑猜测的反馈，候选需要后台枚举时等枚举完成后再确认
+    fn submit_feedback(&mut self) {
+        if self.pending_feedback.is_some()
+            || self.computer_guess.is_empty()
+            || self.solver.as_ref().is_some_and(CodeSolver::is_searching)
+        {
+            return;
+        }
+        if let Err(error) = self.code_rules.validate_feedback(self.feedback) {
+            self.feedback_error = Some(error);
+            return;
+        }
+
+        let guess = self.computer_guess.clone();
+        if let Some(solver) = &mut self.solver {
+            self.pending_feedback = Some(solver.clone());
+            solver.add_feedback(&guess, self.feedback);
+        }
+        self.guesses.push((guess, self.feedback));
+        self.resolve_feedback();
+    }
+
+    /// 确认等待中的反馈：矛盾时撤回反馈并恢复之前的求解器，否则计入尝试次数并给出下一次猜测
+    fn resolve_feedback(&mut self) {
+        if self.solver.as_ref().is_some_and(CodeSolver::is_searching) {
+            return;
+        }
+        let Some(previous_solver) = self.pending_feedback.take() else {
+            // 开局时候选还没枚举完，枚举完成后再给出第一次猜测
+            if self.computer_guess.is_empty() {
+                self.computer_guess = self.next_computer_guess().unwrap_or_default();
+            }
+            return;
+        };
+
+        if self.solver.as_ref().is_some_and(CodeSolver::is_inconsistent) {
+            self.solver = Some(previous_solver);
+            if let Some((_, score)) = self.guesses.pop() {
+                self.feedback_error = Some(format!(
+                    "反馈 {} 与之前的反馈矛盾：不存在同时符合所有反馈的密码，请检查后重新输入",
+                    format_score(score)
+                ));
             }
+            return;
         }
 
-        // 保存猜测记录
-        let result = format!("{}A{}B", a_count, b_count);
-        self.guesses.push((self.current_guess.clone(), result));
+        self.feedback_error = None;
+        self.attempts += 1;
 
-        // 检查是否获胜
-        if a_count == self.digit_count {
-            self.game_won = true;
+        let exact = self.guesses.last().map_or(0, |(_, (a_count, _))| *a_count);
+        if exact == self.code_rules.length {
+            // 电脑猜中
+            self.game_won = false;
             self.state = GuessNumberState::GameOver;
-            self.game_timer = None;
-            // 翻开所有卡片
-            for card in &mut self.game_cards {
-                card.is_face_up = true;
-            }
         } else if self.attempts >= self.max_attempts {
+            // 电脑用完尝试次数，玩家获胜
+            self.game_won = true;
             self.state = GuessNumberState::GameOver;
-            self.game_timer = None;
-            // 翻开所有卡片
-            for card in &mut self.game_cards {
-                card.is_face_up = true;
-            }
+        } else {
+            self.computer_guess = self.next_computer_guess().unwrap_or_default();
+            self.feedback = (0, 0);
         }
-
-        // 重置当前猜测
-        self.current_guess = vec![0; self.digit_count];
     }
 
     /// 重置游戏状态
@@ -507,13 +1844,28 @@ impl GuessNumberGame {
         self.target_number.clear();
         self.guesses.clear();
         self.current_guess.clear();
+        self.input_cursor = 0;
+        self.highlight_incomplete = false;
         self.attempts = 0;
         self.game_won = false;
         self.game_cards.clear();
         self.flipped_cards.clear();
         self.difficulty_selection.reset();
         self.game_timer = None;
-        self.time_remaining = Duration::from_secs(0);
+        self.input_error = None;
+        self.solver = None;
+        self.hints_remaining = 0;
+        self.hint_message = None;
+        self.computer_guess.clear();
+        self.feedback = (0, 0);
+        self.feedback_error = None;
+        self.pending_feedback = None;
+        self.duel_sides.clear();
+        self.duel_phase = DuelPhase::SetSecret(0);
+        self.duel_turn = 0;
+        self.duel_winner = None;
+        self.duel_ai_solver = None;
+        self.score_submission.clear();
     }
 
     /// 创建居中的按钮
@@ -529,8 +1881,278 @@ impl GuessNumberGame {
     }
 }
 
+impl CardGame for GuessNumberGame {
+    fn kind(&self) -> GameKind {
+        GameKind::GuessNumber
+    }
+
+    fn name(&self) -> &'static str {
+        "猜数字游戏"
+    }
+
+    fn rules(&self) -> Vec<String> {
+        let mut rules = vec![format!("根据提示猜测{}", self.code_rules.describe())];
+        rules.extend(
+            [
+                "A表示数字和位置都正确，B表示数字正确但位置错误",
+                "简单难度: 20次尝试，180秒时间",
+                "中等难度: 15次尝试，120秒时间",
+                "困难难度: 10次尝试，90秒时间",
+                "自定义难度: 自由设置时间限制、尝试次数和提示次数",
+                "自适应难度: 根据经典和彩珠模式的最近战绩自动调整时间、尝试次数和提示次数",
+                "提示按钮会给出最佳猜测（简单3次，中等2次，困难1次），每次扣除10秒",
+                "按 Esc 或点击暂停按钮可以暂停计时",
+                "反向模式: 你想一个密码，电脑来猜，你输入每次的A/B反馈",
+                "彩珠模式: 用彩色珠子代替数字，黑珠=颜色位置都对，白珠=颜色对位置错",
+                "对战模式: 双方各设一个密码，轮流猜对方的密码，先猜中者获胜",
+            ]
+            .map(String::from),
+        );
+        rules
+    }
+
+    fn difficulty(&mut self) -> (&mut DifficultySelection, &mut dyn DifficultyProfile) {
+        (&mut self.difficulty_selection, &mut self.custom_profile)
+    }
+
+    fn menu_face(&self) -> &'static str {
+        "assets/card_face/JOKER-B.png"
+    }
+
+    /// 显示游戏界面，返回是否要返回主菜单
+    fn show(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) -> bool {
+        let mut return_to_menu = false;
+
+        // 处理过渡动画
+        if self.difficulty_selection.is_in_transition() {
+            if self.difficulty_selection.show_transition_animation(ui) {
+                self.state = GuessNumberState::GamePlaying;
+                self.initialize_game(ctx);
+            }
+            return return_to_menu;
+        }
+
+        // 更新游戏计时器
+        if let Some(timer) = &mut self.game_timer {
+            timer.handle_pause_input(ctx);
+            if timer.is_expired() {
+                // 时间到，游戏结束
+                self.finish_guessing(false);
+            }
+        }
+
+        // 暂停时显示遮罩
+        if let Some(timer) = &mut self.game_timer
+            && timer.is_paused()
+            && timer::show_pause_overlay(ctx)
+        {
+            timer.resume();
+        }
+
+        self.poll_solvers(ctx);
+
+        // 使用 CentralPanel 确保内容始终居中
+        egui::CentralPanel::default().show(ui.ctx(), |ui| {
+            match self.state {
+                GuessNumberState::DifficultySelection => {
+                    self.show_code_rules_setup(ui);
+
+                    let (menu_return, _) = 
+                        self.show_difficulty_selection(ui);
+                    return_to_menu = menu_return;
+                }
+                GuessNumberState::GamePlaying => {
+                    return_to_menu = match self.guess_mode {
+                        GuessMode::Classic => self.show_game_playing(ui, ctx),
+                        GuessMode::Reverse => self.show_reverse_playing(ui),
+                        GuessMode::Mastermind => self.show_mastermind_playing(ui),
+                        GuessMode::Duel => self.show_duel_playing(ui),
+                    };
+                }
+                GuessNumberState::GameOver => {
+                    return_to_menu = match self.guess_mode {
+                        GuessMode::Classic => self.show_game_over(ui),
+                        GuessMode::Reverse => self.show_reverse_game_over(ui),
+                        GuessMode::Mastermind => self.show_mastermind_game_over(ui),
+                        GuessMode::Duel => self.show_duel_game_over(ui),
+                    };
+                }
+            }
+        });
+
+        return_to_menu
+    }
+
+    /// 取出刚结束的一局结果
+    fn take_result(&mut self) -> Option<GameResult> {
+        self.finished_result.take()
+    }
+
+    /// 取出本帧产生的游戏事件（用于判定成就）
+    fn take_events(&mut self) -> Vec<GameEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// 取出玩家提交的排行榜成绩
+    fn take_leaderboard_entry(&mut self) -> Option<(GameKind, LeaderboardEntry)> {
+        self.score_submission.take_entry()
+    }
+
+    /// 取出中途返回主菜单时保存的存档
+    fn take_suspended_session(&mut self) -> Option<SavedSession> {
+        self.suspended_session.take()
+    }
+
+    /// 重置到难度选择界面
+    fn reset(&mut self) {
+        self.reset_game_state();
+        self.state = GuessNumberState::DifficultySelection;
+    }
+
+    fn save_state(&self) -> Option<SavedSession> {
+        self.snapshot().map(SavedSession::GuessNumber)
+    }
+
+    fn restore_state(&mut self, ctx: &egui::Context, session: SavedSession) {
+        if let SavedSession::GuessNumber(save) = session {
+            self.restore(ctx, save);
+        }
+    }
+}
+
 impl Default for GuessNumberGame {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 反复取出后台枚举结果，直到没有等待确认的反馈且求解器空闲
+    fn wait(game: &mut GuessNumberGame, ctx: &egui::Context) {
+        let start = std::time::Instant::now();
+        while game.pending_feedback.is_some() || game.solver.as_ref().is_some_and(CodeSolver::is_searching) {
+            assert!(start.elapsed().as_secs() < 60, "后台枚举超时");
+            game.poll_solvers(ctx);
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn reverse_mode_rejects_contradictory_feedback_after_background_search() {
+        let ctx = egui::Context::default();
+        let rules = CodeRules { length: 6, alphabet_size: 10, allow_repeats: true };
+        let mut game = GuessNumberGame::new();
+        game.guess_mode = GuessMode::Reverse;
+        game.state = GuessNumberState::GamePlaying;
+        game.code_rules = rules;
+        game.max_attempts = 10;
+        game.solver = Some(CodeSolver::new(rules));
+
+        // 候选需要后台枚举，枚举完成前电脑不猜、反馈也不能提交
+        assert!(game.solver.as_ref().unwrap().is_searching());
+        assert_eq!(game.next_computer_guess(), None);
+        game.computer_guess = vec![0, 1, 2, 3, 4, 5];
+        game.submit_feedback();
+        assert!(game.guesses.is_empty());
+        wait(&mut game, &ctx);
+
+        // 六位都猜 0 时不可能只有 B：后台枚举完成后发现矛盾，撤回反馈并恢复之前的求解器
+        game.computer_guess = vec![0; 6];
+        game.feedback = (0, 1);
+        game.submit_feedback();
+        assert!(game.solver.as_ref().unwrap().is_searching());
+        assert_eq!(game.guesses.len(), 1);
+        game.submit_feedback();
+        assert_eq!(game.guesses.len(), 1);
+        wait(&mut game, &ctx);
+        assert_eq!(game.attempts, 0);
+        assert!(game.guesses.is_empty());
+        assert!(game.feedback_error.is_some());
+        assert!(!game.solver.as_ref().unwrap().is_inconsistent());
+        assert_eq!(game.solver.as_ref().unwrap().remaining(), None);
+
+        // 0-5 都不在密码中：只剩 6-9 组成的密码
+        game.computer_guess = vec![0, 1, 2, 3, 4, 5];
+        game.feedback = (0, 0);
+        game.submit_feedback();
+        assert!(game.solver.as_ref().unwrap().is_searching());
+        wait(&mut game, &ctx);
+        assert_eq!(game.attempts, 1);
+        assert_eq!(game.guesses.len(), 1);
+        assert!(game.feedback_error.is_none());
+        assert_eq!(game.solver.as_ref().unwrap().remaining(), Some(4096));
+
+        // 又说 6-9 也都不在，同样撤回
+        game.computer_guess = vec![6, 7, 8, 9, 6, 7];
+        game.feedback = (0, 0);
+        game.submit_feedback();
+        assert_eq!(game.attempts, 1);
+        assert_eq!(game.guesses.len(), 1);
+        assert!(game.feedback_error.is_some());
+        assert_eq!(game.solver.as_ref().unwrap().remaining(), Some(4096));
+        assert_eq!(game.computer_guess, vec![6, 7, 8, 9, 6, 7]);
+    }
+
+    /// 双方密码分别为 1234 和 5678 的双人对战
+    fn duel_game() -> GuessNumberGame {
+        let mut game = GuessNumberGame::new();
+        game.guess_mode = GuessMode::Duel;
+        game.state = GuessNumberState::GamePlaying;
+        game.code_rules = CodeRules { length: 4, alphabet_size: 10, allow_repeats: false };
+        game.max_attempts = 10;
+        game.duel_sides = vec![DuelSide::new("玩家1"), DuelSide::new("玩家2")];
+        game.duel_sides[0].secret = vec![1, 2, 3, 4];
+        game.duel_sides[1].secret = vec![5, 6, 7, 8];
+        game.duel_phase = DuelPhase::Guessing;
+        game
+    }
+
+    #[test]
+    fn duel_second_player_gets_a_final_guess() {
+        let mut game = duel_game();
+        game.record_duel_guess(0, vec![5, 6, 7, 8]);
+        assert!(game.state == GuessNumberState::GamePlaying);
+        assert_eq!(game.duel_turn, 1);
+
+        game.record_duel_guess(1, vec![4, 3, 2, 1]);
+        assert!(game.state == GuessNumberState::GameOver);
+        assert_eq!(game.duel_winner, Some(0));
+    }
+
+    #[test]
+    fn duel_is_a_draw_when_both_crack_in_the_same_round() {
+        let mut game = duel_game();
+        game.record_duel_guess(0, vec![5, 6, 7, 8]);
+        game.record_duel_guess(1, vec![1, 2, 3, 4]);
+        assert!(game.state == GuessNumberState::GameOver);
+        assert_eq!(game.duel_winner, None);
+        assert!(game.duel_cracked(0) && game.duel_cracked(1));
+    }
+
+    #[test]
+    fn duel_second_player_wins_when_only_they_crack() {
+        let mut game = duel_game();
+        game.record_duel_guess(0, vec![1, 2, 3, 4]);
+        game.record_duel_guess(1, vec![1, 2, 3, 4]);
+        assert!(game.state == GuessNumberState::GameOver);
+        assert_eq!(game.duel_winner, Some(1));
+    }
+
+    #[test]
+    fn hint_reports_too_many_candidates_without_spending_a_hint() {
+        let ctx = egui::Context::default();
+        let rules = CodeRules { length: 8, alphabet_size: 10, allow_repeats: true };
+        let mut game = GuessNumberGame::new();
+        game.code_rules = rules;
+        game.hints_remaining = 2;
+        game.solver = Some(CodeSolver::new(rules));
+        wait(&mut game, &ctx);
+        assert!(game.solver.as_ref().unwrap().is_too_many());
+
+        game.use_hint();
+        assert_eq!(game.hints_remaining, 2);
+        assert!(game.hint_message.as_ref().unwrap().contains(code_solver::TOO_MANY_NOTICE));
+    }
+}
@@ -1,12 +1,19 @@
 // game3.rs - 德州扑克游戏（标准52张牌版）
 use eframe::egui;
-use crate::card::Card;
-use crate::difficulty::{DifficultySelection, GameDifficulty};
+use crate::achievements::GameEvent;
+use crate::card::{self, Card};
+use crate::card_game::CardGame;
+use crate::difficulty::{self, DifficultyProfile, DifficultySelection, GameDifficulty};
+use crate::leaderboard::{LeaderboardEntry, ScoreSubmission};
+use crate::player_profile::{GameKind, GameResult};
+use crate::poker_hand::{self, HandResult, HandStrength};
+use crate::save_game::{self, SavedCard, SavedSession};
+use crate::timer::{self, GameTimer, TimerSnapshot};
 use crate::util::get_card_image_path;
 use std::collections::VecDeque;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use rand::seq::SliceRandom;
-use rand::thread_rng;
+use serde::{Deserialize, Serialize};
 
 /// 德州扑克游戏状态
 pub struct TexasHoldemGame {
@@ -81,10 +88,149 @@ pub struct TexasHoldemGame {
     
     // 标记玩家是否已经使用过特殊行动
     has_used_special_action: bool,
+
+    // 玩家行动倒计时（超时自动行动）
+    shot_clock: Option<GameTimer>,
+
+    // 自定义难度参数
+    custom_profile: HoldemProfile,
+
+    // 本场开始时的玩家筹码和最大底池（用于统计）
+    session_start_chips: i32,
+    biggest_pot: u32,
+
+    // 刚结束的一场结果，等待主程序记录
+    finished_result: Option<GameResult>,
+    // 结束界面的排行榜成绩提交
+    score_submission: ScoreSubmission,
+    // 等待主程序处理的游戏事件
+    events: Vec<GameEvent>,
+    // 中途返回主菜单时保存的存档
+    suspended_session: Option<SavedSession>,
+}
+
+/// 德州扑克未完成一场的存档
+#[derive(Serialize, Deserialize)]
+pub struct TexasHoldemSave {
+    difficulty: GameDifficulty,
+    adaptive_level: f32,
+    custom_profile: HoldemProfile,
+    player_hand: Vec<SavedCard>,
+    ai_hand: Vec<SavedCard>,
+    community_cards: Vec<SavedCard>,
+    deck: Vec<SavedCard>,
+    player_chips: i32,
+    ai_chips: i32,
+    pot: u32,
+    current_bet: u32,
+    game_phase: GamePhase,
+    message: String,
+    show_ai_cards: bool,
+    waiting_for_ai: bool,
+    player_acted: bool,
+    ai_acted: bool,
+    both_checked: bool,
+    first_round: bool,
+    has_used_special_action: bool,
+    shot_clock: Option<TimerSnapshot>,
+    session_start_chips: i32,
+    biggest_pot: u32,
+}
+
+/// AI风格
+#[derive(PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub enum AiStyle {
+    Reckless, // 无脑下注
+    Steady,   // 稳健：总是下注，不会同意过牌
+    Bluffer,  // 诈唬：有一半概率同意过牌
+}
+
+impl AiStyle {
+    /// 风格的显示名称
+    fn label(self) -> &'static str {
+        match self {
+            AiStyle::Reckless => "无脑下注",
+            AiStyle::Steady => "稳健",
+            AiStyle::Bluffer => "诈唬",
+        }
+    }
+}
+
+/// 德州扑克难度参数
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct HoldemProfile {
+    pub ai_style: AiStyle,     // AI风格
+    pub player_chips: i32,     // 玩家初始筹码
+    pub ai_chips: i32,         // AI初始筹码
+    pub small_blind: u32,      // 小盲注（每局开始时玩家下）
+    pub big_blind: u32,        // 大盲注（每局开始时AI下）
+    pub base_bet: u32,         // 基础下注额（翻牌后每阶段的下注额为基础额的2、3、4倍）
+    pub shot_clock_secs: u64,  // 行动倒计时（秒）
+}
+
+impl DifficultyProfile for HoldemProfile {
+    fn preset(difficulty: GameDifficulty) -> Self {
+        let ai_style = match difficulty {
+            GameDifficulty::Easy => AiStyle::Reckless,
+            GameDifficulty::Medium | GameDifficulty::Custom | GameDifficulty::Adaptive => AiStyle::Steady,
+            GameDifficulty::Hard => AiStyle::Bluffer,
+        };
+        let shot_clock_secs = match difficulty {
+            GameDifficulty::Easy => 30,                             // 简单30秒
+            GameDifficulty::Medium | GameDifficulty::Custom | GameDifficulty::Adaptive => 20, // 中等20秒
+            GameDifficulty::Hard => 15,                             // 困难15秒
+        };
+        Self {
+            ai_style,
+            player_chips: 200,
+            ai_chips: 100,
+            small_blind: 5,
+            big_blind: 10,
+            base_bet: 10,
+            shot_clock_secs,
+        }
+    }
+
+    fn adaptive(level: f32) -> Self {
+        // 强度越高AI越激进，行动倒计时越短
+        let ai_style = if level < 0.34 {
+            AiStyle::Reckless
+        } else if level < 0.67 {
+            AiStyle::Steady
+        } else {
+            AiStyle::Bluffer
+        };
+        Self {
+            ai_style,
+            shot_clock_secs: difficulty::lerp(30.0, 15.0, level).round() as u64,
+            ..Self::preset(GameDifficulty::Medium)
+        }
+    }
+
+    fn load_preset(&mut self, difficulty: GameDifficulty) {
+        *self = Self::preset(difficulty);
+    }
+
+    fn show_editor(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("AI风格:");
+            for style in [AiStyle::Reckless, AiStyle::Steady, AiStyle::Bluffer] {
+                ui.radio_value(&mut self.ai_style, style, style.label());
+            }
+        });
+        ui.add(egui::Slider::new(&mut self.player_chips, 20..=1000).text("玩家初始筹码"));
+        ui.add(egui::Slider::new(&mut self.ai_chips, 20..=1000).text("AI初始筹码"));
+        ui.add(egui::Slider::new(&mut self.small_blind, 1..=50).text("小盲注"));
+        ui.add(egui::Slider::new(&mut self.big_blind, 2..=100).text("大盲注"));
+        // 大盲注不能小于小盲注
+        self.big_blind = self.big_blind.max(self.small_blind);
+        ui.add(egui::Slider::new(&mut self.base_bet, 5..=100).text("基础下注额"));
+        ui.add(egui::Slider::new(&mut self.shot_clock_secs, 5..=120).suffix("秒").text("行动倒计时"));
+    }
 }
 
 /// 德州扑克游戏阶段
-#[derive(PartialEq, Clone, Copy)]
+#[derive(PartialEq, Clone, Copy, Serialize, Deserialize)]
 enum GamePhase {
     PreFlop,
     Flop,
@@ -111,7 +257,7 @@ struct CardId {
 impl TexasHoldemGame {
     pub fn new() -> Self {
         Self {
-            difficulty_selection: DifficultySelection::new(),
+            difficulty_selection: DifficultySelection::new(GameKind::TexasHoldem),
             game_state: TexasHoldemState::DifficultySelection,
             player_hand: Vec::new(),
             ai_hand: Vec::new(),
@@ -135,77 +281,17 @@ impl TexasHoldemGame {
             both_checked: false,
             first_round: true,
             has_used_special_action: false,
+            shot_clock: None,
+            custom_profile: HoldemProfile::preset(GameDifficulty::Custom),
+            session_start_chips: 200,
+            biggest_pot: 0,
+            finished_result: None,
+            score_submission: ScoreSubmission::new(),
+            events: Vec::new(),
+            suspended_session: None,
         }
     }
 
-    /// 设置主菜单卡片（只用于获取背面纹理）
-    pub fn set_main_menu_cards(&mut self, cards: Vec<Card>) {
-        if let Some(card) = cards.first() {
-            self.back_card_texture = Some(card.back_tex.clone());
-        }
-    }
-
-    /// 显示游戏界面
-    pub fn show(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) -> bool {
-        if self.ctx.is_none() {
-            self.ctx = Some(ctx.clone());
-        }
-
-        if self.waiting_for_ai {
-            if let Some(timer) = self.ai_thinking_timer {
-                if timer.elapsed().as_millis() > 500 {
-                    self.waiting_for_ai = false;
-                    self.ai_thinking_timer = None;
-                    self.perform_ai_action();
-                }
-            }
-        }
-
-        let mut return_to_menu = false;
-
-        match self.game_state {
-            TexasHoldemState::DifficultySelection => {
-                let rules = vec![
-                    "每位玩家发2张底牌，然后依次进行5张公共牌的发牌",
-                    "游戏分为四个下注回合：翻牌前、翻牌、转牌、河牌",
-                    "通过组合7张牌（2张底牌+5张公共牌）形成最好的5张牌组合",
-                    "牌型大小：同花顺 > 四条 > 葫芦 > 同花 > 顺子 > 三条 > 两对 > 一对 > 高牌",
-                    "固定下注规则：每阶段固定下注额，逐阶段递增",
-                    "支持负数筹码，结算后筹码≤0判负",
-                    "每小局游戏中可以使用一次过牌或弃牌",
-                    "使用标准52张扑克牌（无鬼牌）",
-                    "简单难度：AI无脑下注，中等难度：AI根据手牌决定，困难难度：AI会诈唬",
-                ];
-
-                let (menu_return, game_start) = 
-                    self.difficulty_selection.show(ui, "德州扑克", &rules);
-
-                if menu_return {
-                    return_to_menu = true;
-                    self.reset_to_main_menu();
-                }
-
-                if game_start {
-                    self.game_state = TexasHoldemState::Initializing;
-                    self.game_initializing = true;
-                    self.difficulty_selection.transition_timer = Some(Instant::now());
-                }
-            }
-            TexasHoldemState::Initializing => {
-                if self.difficulty_selection.show_transition_animation(ui) {
-                    self.start_game_fast();
-                    self.game_state = TexasHoldemState::Playing;
-                    self.game_initializing = false;
-                }
-            }
-            TexasHoldemState::Playing => {
-                self.show_game_ui(ui);
-            }
-        }
-
-        return_to_menu
-    }
-
     /// 显示游戏主界面
     fn show_game_ui(&mut self, ui: &mut egui::Ui) {
         ui.vertical_centered(|ui| {
@@ -232,19 +318,23 @@ impl TexasHoldemGame {
             
             ui.add_space(20.0);
 
-            // 操作按钮区域 ，根据游戏状态显示不同的按钮
-            self.show_action_buttons(ui);
-
-            // 游戏进行中始终显示选择难度按钮
-            if !self.game_over {
-                ui.add_space(10.0);
-                ui.horizontal(|ui| {
-                    ui.add_space(ui.available_width() / 2.0 - 75.0);
-                    if self.centered_button(ui, "选择难度", 150.0, 35.0).clicked() {
-                        self.reset_to_difficulty_selection();
-                    }
-                });
-            }
+            // 翻牌动画播放期间暂停接受操作
+            let animating = card::any_animating(self.ai_hand.iter().chain(&self.community_cards));
+            ui.add_enabled_ui(!animating, |ui| {
+                // 操作按钮区域 ，根据游戏状态显示不同的按钮
+                self.show_action_buttons(ui);
+
+                // 游戏进行中始终显示选择难度按钮
+                if !self.game_over {
+                    ui.add_space(10.0);
+                    ui.horizontal(|ui| {
+                        ui.add_space(ui.available_width() / 2.0 - 75.0);
+                        if self.centered_button(ui, "选择难度", 150.0, 35.0).clicked() {
+                            self.reset_to_difficulty_selection();
+                        }
+                    });
+                }
+            });
         });
     }
 
@@ -291,12 +381,16 @@ impl TexasHoldemGame {
                 ui.colored_label(egui::Color32::BLACK, format!("阶段: {}", phase_text));
 
                 if let Some(difficulty) = self.difficulty_selection.selected_difficulty {
-                    let difficulty_text = match difficulty {
-                        GameDifficulty::Easy => "简单难度",
-                        GameDifficulty::Medium => "中等难度",
-                        GameDifficulty::Hard => "困难难度",
-                    };
-                    ui.colored_label(egui::Color32::DARK_GRAY, difficulty_text);
+                    ui.colored_label(egui::Color32::DARK_GRAY, difficulty.label());
+                }
+
+                if let Some(clock) = &mut self.shot_clock {
+                    ui.horizontal(|ui| {
+                        ui.colored_label(clock.color(), format!("行动倒计时: {}秒", clock.remaining().as_secs()));
+                        if ui.button("暂停 (Esc)").clicked() {
+                            clock.pause();
+                        }
+                    });
                 }
             });
         });
@@ -324,9 +418,9 @@ impl TexasHoldemGame {
         ui.horizontal(|ui| {
             ui.add_space(ui.available_width() / 2.0 - 100.0);
             for card in &mut self.ai_hand {
-                // 摊牌阶段或游戏结束时显示AI手牌
-                card.is_face_up = self.show_ai_cards || self.game_phase == GamePhase::Showdown || self.game_over;
-                card.show(ui, egui::vec2(80.0, 120.0));
+                // 摊牌阶段或游戏结束时翻开AI手牌
+                card.flip_to(self.show_ai_cards || self.game_phase == GamePhase::Showdown || self.game_over);
+                card.render(ui, egui::vec2(80.0, 120.0), egui::Sense::hover());
             }
         });
     }
@@ -351,15 +445,12 @@ impl TexasHoldemGame {
 
             for i in 0..5 {
                 if i < self.community_cards.len() {
-                    let mut temp_card = self.community_cards[i].clone();
-                    temp_card.is_face_up = i < cards_to_reveal;
-                    temp_card.show(ui, egui::vec2(80.0, 120.0));
-                } else {
-                    if let Some(back_card) = self.create_back_card_fast() {
-                        let mut temp_card = back_card;
-                        temp_card.is_face_up = false;
-                        temp_card.show(ui, egui::vec2(80.0, 120.0));
-                    }
+                    // 进入新阶段时翻开对应的公共牌
+                    let card = &mut self.community_cards[i];
+                    card.flip_to(i < cards_to_reveal);
+                    card.render(ui, egui::vec2(80.0, 120.0), egui::Sense::hover());
+                } else if let Some(back_card) = self.create_back_card_fast() {
+                    back_card.render(ui, egui::vec2(80.0, 120.0), egui::Sense::hover());
                 }
             }
         });
@@ -374,8 +465,8 @@ impl TexasHoldemGame {
         ui.horizontal(|ui| {
             ui.add_space(ui.available_width() / 2.0 - 110.0);
             for card in &mut self.player_hand {
-                card.is_face_up = true;
-                card.show(ui, egui::vec2(100.0, 150.0));
+                card.flip_to(true);
+                card.render(ui, egui::vec2(100.0, 150.0), egui::Sense::hover());
             }
         });
     }
@@ -399,7 +490,9 @@ impl TexasHoldemGame {
     fn show_action_buttons(&mut self, ui: &mut egui::Ui) {
         // 根据游戏状态决定显示什么按钮
         if self.game_over {
-            // 游戏结束状态：显示选择难度按钮
+            // 游戏结束状态：显示成绩提交和选择难度按钮
+            self.score_submission.show(ui);
+            ui.add_space(10.0);
             ui.horizontal(|ui| {
                 ui.add_space(ui.available_width() / 2.0 - 75.0);
                 if self.centered_button(ui, "选择难度", 150.0, 40.0).clicked() {
@@ -447,9 +540,7 @@ impl TexasHoldemGame {
 
                 // 下注按钮
                 if self.centered_button(ui, &format!("下注 ({})", fixed_bet), 120.0, 40.0).clicked() {
-                    self.place_bet(fixed_bet);
-                    self.player_acted = true;
-                    self.start_ai_thinking();
+                    self.player_bet_action();
                 }
 
                 // 只有在不是第一回合且未使用过特殊行动时才显示过牌和弃牌
@@ -458,10 +549,7 @@ impl TexasHoldemGame {
                     
                     // 过牌按钮
                     if self.centered_button(ui, "过牌", 120.0, 40.0).clicked() {
-                        self.player_check();
-                        self.player_acted = true;
-                        self.has_used_special_action = true;
-                        self.start_ai_thinking();
+                        self.player_check_action();
                     }
                     
                     ui.add_space(20.0);
@@ -476,17 +564,73 @@ impl TexasHoldemGame {
         }
     }
 
-    /// 获取固定下注金额
+    /// 玩家选择下注
+    fn player_bet_action(&mut self) {
+        self.place_bet(self.get_fixed_bet_amount());
+        self.player_acted = true;
+        self.start_ai_thinking();
+    }
+
+    /// 玩家选择过牌（消耗本局的特殊行动）
+    fn player_check_action(&mut self) {
+        self.player_check();
+        self.player_acted = true;
+        self.has_used_special_action = true;
+        self.start_ai_thinking();
+    }
+
+    /// 行动倒计时时长（根据难度）
+    fn get_shot_clock_time(&self) -> Duration {
+        Duration::from_secs(self.profile().shot_clock_secs)
+    }
+
+    /// 更新行动倒计时：轮到玩家行动时计时，超时自动过牌，不能过牌时自动下注
+    fn update_shot_clock(&mut self, ctx: &egui::Context) {
+        let needs_action = !self.game_over
+            && self.game_phase != GamePhase::Showdown
+            && !self.waiting_for_ai
+            && !self.player_acted;
+        if !needs_action {
+            self.shot_clock = None;
+            return;
+        }
+
+        let total = self.get_shot_clock_time();
+        let clock = self.shot_clock.get_or_insert_with(|| {
+            GameTimer::start(total).with_thresholds(Duration::from_secs(10), Duration::from_secs(5))
+        });
+        clock.handle_pause_input(ctx);
+        if !clock.is_expired() {
+            return;
+        }
+
+        self.shot_clock = None;
+        if !self.first_round && !self.has_used_special_action {
+            self.player_check_action();
+            self.message = "时间到，自动过牌".to_string();
+        } else {
+            self.player_bet_action();
+            self.message.insert_str(0, "时间到，自动下注。");
+        }
+    }
+
+    /// 获取固定下注金额（翻牌前只有盲注）
     fn get_fixed_bet_amount(&self) -> u32 {
+        let base_bet = self.profile().base_bet;
         match self.game_phase {
-            GamePhase::PreFlop => 10,
-            GamePhase::Flop => 20,
-            GamePhase::Turn => 30,
-            GamePhase::River => 40,
+            GamePhase::PreFlop => 0,
+            GamePhase::Flop => base_bet * 2,
+            GamePhase::Turn => base_bet * 3,
+            GamePhase::River => base_bet * 4,
             GamePhase::Showdown => 0,
         }
     }
 
+    /// 当前难度的参数
+    fn profile(&self) -> HoldemProfile {
+        self.difficulty_selection.profile(&self.custom_profile)
+    }
+
     /// 创建居中的按钮
     fn centered_button(&self, ui: &mut egui::Ui, text: &str, width: f32, height: f32) -> egui::Response {
         ui.add_sized(
@@ -502,18 +646,15 @@ impl TexasHoldemGame {
 
     /// 快速创建背面卡片
     fn create_back_card_fast(&self) -> Option<Card> {
-        if let Some(back_tex) = &self.back_card_texture {
-            Some(Card {
-                id: 999,
-                is_face_up: false,
-                rank: 1,
-                suit: 1,
-                back_tex: back_tex.clone(),
-                face_tex: back_tex.clone(),
-            })
-        } else {
-            None
-        }
+        self.back_card_texture.as_ref().map(|back_tex| Card {
+            id: 999,
+            is_face_up: false,
+            rank: 1,
+            suit: 1,
+            back_tex: back_tex.clone(),
+            face_tex: back_tex.clone(),
+            animation: None,
+        })
     }
 
     /// 开始AI思考
@@ -535,6 +676,7 @@ impl TexasHoldemGame {
                 self.message.push_str("\nAI选择弃牌");
                 self.show_ai_cards = true;
                 self.player_chips += self.pot as i32;
+                self.events.push(GameEvent::HandWon { strength: None, pot: self.pot });
                 self.pot = 0;
                 self.ai_acted = true;
                 self.check_round_end();
@@ -549,6 +691,7 @@ impl TexasHoldemGame {
                 // AI也允许负数下注
                 self.ai_chips -= amount as i32;
                 self.pot += amount;
+                self.biggest_pot = self.biggest_pot.max(self.pot);
                 self.current_bet = amount;
                 self.message.push_str(&format!("\nAI下注 {} 筹码", amount));
                 self.ai_acted = true;
@@ -564,28 +707,23 @@ impl TexasHoldemGame {
         
         let fixed_bet = self.get_fixed_bet_amount();
         
-        if let Some(difficulty) = self.difficulty_selection.selected_difficulty {
-            match difficulty {
-                GameDifficulty::Easy => {
-                    // 简单AI：总是下注
-                    AiAction::Bet(fixed_bet)
-                }
-                GameDifficulty::Medium => {
-                    // 中等AI：总是下注，不会同意过牌
+        match self.profile().ai_style {
+            AiStyle::Reckless => {
+                // 无脑AI：总是下注
+                AiAction::Bet(fixed_bet)
+            }
+            AiStyle::Steady => {
+                // 稳健AI：总是下注，不会同意过牌
+                AiAction::Bet(fixed_bet)
+            }
+            AiStyle::Bluffer => {
+                // 诈唬AI：50%概率同意过牌，否则下注
+                if self.player_acted && rng.random_bool(0.5) {
+                    AiAction::Check
+                } else {
                     AiAction::Bet(fixed_bet)
                 }
-                GameDifficulty::Hard => {
-                    // 困难AI：50%概率同意过牌，否则下注
-                    if self.player_acted && rng.random_bool(0.5) {
-                        AiAction::Check
-                    } else {
-                        AiAction::Bet(fixed_bet)
-                    }
-                }
             }
-        } else {
-            // 默认总是下注
-            AiAction::Bet(fixed_bet)
         }
     }
 
@@ -594,13 +732,14 @@ impl TexasHoldemGame {
 
         self.player_chips -= amount as i32;
         self.pot += amount;
+        self.biggest_pot = self.biggest_pot.max(self.pot);
         self.current_bet = amount;
         self.message = format!("你下注了 {} 筹码", amount);
         
         // 检查是否立即输掉游戏
         if self.player_chips <= 0 {
             self.message.push_str(&format!("\n你的筹码为{}，游戏结束！", self.player_chips));
-            self.game_over = true;
+            self.end_game();
         }
     }
 
@@ -633,10 +772,15 @@ impl TexasHoldemGame {
 
     /// 快速启动游戏
     fn start_game_fast(&mut self) {
+        let profile = self.profile();
+        self.player_chips = profile.player_chips;
+        self.ai_chips = profile.ai_chips;
+        self.session_start_chips = profile.player_chips;
+        self.biggest_pot = 0;
         self.initialize_deck_fast();
         self.deal_cards();
         
-        self.message = "游戏开始！第一回合由你先下注。".to_string();
+        self.message = "游戏开始！".to_string();
         self.game_over = false;
         self.show_ai_cards = false;
         self.waiting_for_ai = false;
@@ -644,6 +788,26 @@ impl TexasHoldemGame {
         self.ai_acted = false;
         self.first_round = true;
         self.has_used_special_action = false;
+        self.post_blinds();
+    }
+
+    /// 每局开始时下盲注：玩家下小盲注，AI下大盲注，代替翻牌前的固定下注，随后直接翻牌
+    fn post_blinds(&mut self) {
+        let profile = self.profile();
+        self.player_chips -= profile.small_blind as i32;
+        self.ai_chips -= profile.big_blind as i32;
+        self.pot += profile.small_blind + profile.big_blind;
+        self.biggest_pot = self.biggest_pot.max(self.pot);
+        self.message.push_str(&format!(
+            "\n你下小盲注 {} 筹码，AI下大盲注 {} 筹码",
+            profile.small_blind, profile.big_blind
+        ));
+
+        // 盲注就可能让一方筹码见底
+        self.check_game_end();
+        if !self.game_over {
+            self.advance_phase();
+        }
     }
 
     /// 快速初始化牌堆 - 只生成9张牌
@@ -686,8 +850,7 @@ impl TexasHoldemGame {
             
             Card::new(ctx, id, rank, suit, back_path, &face_path)
         } else {
-            Err(image::ImageError::IoError(std::io::Error::new(
-                std::io::ErrorKind::Other,
+            Err(image::ImageError::IoError(std::io::Error::other(
                 "Context not available",
             )))
         }
@@ -759,19 +922,23 @@ impl TexasHoldemGame {
         match comparison {
             std::cmp::Ordering::Greater => {
                 self.message.push_str(&format!("\n你赢了！{} > {}", 
-                    player_result.hand_strength.to_string(), 
-                    ai_result.hand_strength.to_string()));
+                    player_result.hand_strength.name(), 
+                    ai_result.hand_strength.name()));
                 self.player_chips += self.pot as i32;
+                self.events.push(GameEvent::HandWon {
+                    strength: Some(player_result.hand_strength),
+                    pot: self.pot,
+                });
             }
             std::cmp::Ordering::Less => {
                 self.message.push_str(&format!("\nAI赢了！{} > {}", 
-                    ai_result.hand_strength.to_string(), 
-                    player_result.hand_strength.to_string()));
+                    ai_result.hand_strength.name(), 
+                    player_result.hand_strength.name()));
                 self.ai_chips += self.pot as i32;
             }
             std::cmp::Ordering::Equal => {
                 self.message.push_str(&format!("\n平局！双方都是{}", 
-                    player_result.hand_strength.to_string()));
+                    player_result.hand_strength.name()));
                 let half_pot = self.pot / 2;
                 self.player_chips += half_pot as i32;
                 self.ai_chips += half_pot as i32;
@@ -785,10 +952,92 @@ impl TexasHoldemGame {
     /// 检查游戏是否结束 
     fn check_game_end(&mut self) {
         if self.player_chips <= 0 || self.ai_chips <= 0 {
+            self.end_game();
+        }
+    }
+
+    /// 结束整场游戏并记录战绩
+    fn end_game(&mut self) {
+        if !self.game_over {
             self.game_over = true;
+            let won = self.player_chips > 0;
+            self.difficulty_selection.record_result(won);
+
+            let difficulty = self.difficulty_selection.selected_difficulty.unwrap_or(GameDifficulty::Easy);
+            let mut result = GameResult::new(GameKind::TexasHoldem, difficulty, won);
+            result.net_chips = (self.player_chips - self.session_start_chips) as i64;
+            result.biggest_pot = self.biggest_pot;
+            self.score_submission.offer(&result);
+            self.finished_result = Some(result);
         }
     }
 
+    /// 保存进行中的一场，没有进行中的游戏时返回 None
+    fn snapshot(&self) -> Option<TexasHoldemSave> {
+        if self.game_state != TexasHoldemState::Playing || self.game_over {
+            return None;
+        }
+        Some(TexasHoldemSave {
+            difficulty: self.difficulty_selection.selected_difficulty?,
+            adaptive_level: self.difficulty_selection.adaptive.level,
+            custom_profile: self.custom_profile,
+            player_hand: save_game::save_cards(&self.player_hand),
+            ai_hand: save_game::save_cards(&self.ai_hand),
+            community_cards: save_game::save_cards(&self.community_cards),
+            deck: save_game::save_cards(&self.deck),
+            player_chips: self.player_chips,
+            ai_chips: self.ai_chips,
+            pot: self.pot,
+            current_bet: self.current_bet,
+            game_phase: self.game_phase,
+            message: self.message.clone(),
+            show_ai_cards: self.show_ai_cards,
+            waiting_for_ai: self.waiting_for_ai,
+            player_acted: self.player_acted,
+            ai_acted: self.ai_acted,
+            both_checked: self.both_checked,
+            first_round: self.first_round,
+            has_used_special_action: self.has_used_special_action,
+            shot_clock: self.shot_clock.as_ref().map(GameTimer::snapshot),
+            session_start_chips: self.session_start_chips,
+            biggest_pot: self.biggest_pot,
+        })
+    }
+
+    /// 从存档恢复一场，行动倒计时处于暂停状态
+    fn restore(&mut self, ctx: &egui::Context, save: TexasHoldemSave) {
+        self.reset_game_state();
+        self.ctx = Some(ctx.clone());
+        self.difficulty_selection.restore(save.difficulty, save.adaptive_level);
+        self.custom_profile = save.custom_profile;
+
+        self.player_hand = save_game::restore_cards(ctx, &save.player_hand);
+        self.ai_hand = save_game::restore_cards(ctx, &save.ai_hand);
+        self.community_cards = save_game::restore_cards(ctx, &save.community_cards);
+        self.deck = save_game::restore_cards(ctx, &save.deck).into();
+
+        self.player_chips = save.player_chips;
+        self.ai_chips = save.ai_chips;
+        self.pot = save.pot;
+        self.current_bet = save.current_bet;
+        self.game_phase = save.game_phase;
+        self.message = save.message;
+        self.show_ai_cards = save.show_ai_cards;
+        self.waiting_for_ai = save.waiting_for_ai;
+        // 存档时AI正在思考，恢复后重新计时
+        self.ai_thinking_timer = save.waiting_for_ai.then(Instant::now);
+        self.player_acted = save.player_acted;
+        self.ai_acted = save.ai_acted;
+        self.both_checked = save.both_checked;
+        self.first_round = save.first_round;
+        self.has_used_special_action = save.has_used_special_action;
+        self.shot_clock = save.shot_clock.map(GameTimer::restore);
+        self.session_start_chips = save.session_start_chips;
+        self.biggest_pot = save.biggest_pot;
+        self.game_initializing = false;
+        self.game_state = TexasHoldemState::Playing;
+    }
+
     /// 开始下一局游戏
     fn start_next_round(&mut self) {
         self.player_hand.clear();
@@ -808,11 +1057,13 @@ impl TexasHoldemGame {
         // 重置特殊行动使用状态 - 每一小局都可以重新使用
         self.has_used_special_action = false;
         self.first_round = true;
+        self.shot_clock = None;
         
         self.initialize_deck_fast();
         self.deal_cards();
         
         self.message = format!("新一局开始！你的筹码: {}, AI筹码: {}", self.player_chips, self.ai_chips);
+        self.post_blinds();
     }
 
     /// 重置到难度选择界面
@@ -852,6 +1103,8 @@ impl TexasHoldemGame {
         self.both_checked = false;
         self.first_round = true;
         self.has_used_special_action = false;
+        self.shot_clock = None;
+        self.score_submission.clear();
     }
 
     /// 评估最佳手牌
@@ -878,8 +1131,9 @@ impl TexasHoldemGame {
         let combinations = self.generate_combinations(cards, 5);
         
         for combo in combinations {
-            let result = self.evaluate_five_card_hand(&combo);
-            if self.compare_hand_results(&result, &best_result) == std::cmp::Ordering::Greater {
+            let ranks_suits: Vec<(u8, u8)> = combo.iter().map(|card| (card.rank, card.suit)).collect();
+            let result = poker_hand::evaluate_five_card_hand(&ranks_suits);
+            if poker_hand::compare_hand_results(&result, &best_result) == std::cmp::Ordering::Greater {
                 best_result = result;
             }
         }
@@ -917,166 +1171,6 @@ impl TexasHoldemGame {
         }
     }
 
-    /// 评估5张牌的手牌
-    fn evaluate_five_card_hand(&self, cards: &[Card]) -> HandResult {
-        let mut ranks: Vec<u8> = cards.iter().map(|c| c.rank).collect();
-        let suits: Vec<u8> = cards.iter().map(|c| c.suit).collect();
-        
-        ranks.sort_by(|a, b| b.cmp(a)); // 降序排列
-        
-        let is_flush = suits.iter().all(|&s| s == suits[0]);
-        let is_straight = self.is_straight(&ranks);
-        
-        // 检查同花顺
-        if is_flush && is_straight {
-            return HandResult {
-                hand_strength: HandStrength::StraightFlush,
-                high_cards: vec![ranks[0]],
-            };
-        }
-        
-        // 检查四条
-        if let Some(quad_rank) = self.has_n_of_a_kind(&ranks, 4) {
-            let kicker = *ranks.iter().find(|&&r| r != quad_rank).unwrap_or(&1);
-            return HandResult {
-                hand_strength: HandStrength::FourOfAKind,
-                high_cards: vec![quad_rank, kicker],
-            };
-        }
-        
-        // 检查葫芦
-        if let (Some(three_rank), Some(two_rank)) = (self.has_n_of_a_kind(&ranks, 3), self.has_n_of_a_kind(&ranks, 2)) {
-            if three_rank != two_rank {
-                return HandResult {
-                    hand_strength: HandStrength::FullHouse,
-                    high_cards: vec![three_rank, two_rank],
-                };
-            }
-        }
-        
-        // 检查同花
-        if is_flush {
-            return HandResult {
-                hand_strength: HandStrength::Flush,
-                high_cards: ranks,
-            };
-        }
-        
-        // 检查顺子
-        if is_straight {
-            return HandResult {
-                hand_strength: HandStrength::Straight,
-                high_cards: vec![ranks[0]],
-            };
-        }
-        
-        // 检查三条
-        if let Some(three_rank) = self.has_n_of_a_kind(&ranks, 3) {
-            let mut kickers: Vec<u8> = ranks.iter().filter(|&&r| r != three_rank).cloned().collect();
-            kickers.sort_by(|a, b| b.cmp(a));
-            kickers.truncate(2);
-            kickers.insert(0, three_rank);
-            return HandResult {
-                hand_strength: HandStrength::ThreeOfAKind,
-                high_cards: kickers,
-            };
-        }
-        
-        // 检查两对
-        if let Some(pairs) = self.get_pairs(&ranks) {
-            if pairs.len() >= 2 {
-                let mut high_cards = vec![pairs[0], pairs[1]];
-                let kicker = *ranks.iter().find(|&&r| r != pairs[0] && r != pairs[1]).unwrap_or(&1);
-                high_cards.push(kicker);
-                return HandResult {
-                    hand_strength: HandStrength::TwoPair,
-                    high_cards,
-                };
-            }
-        }
-        
-        // 检查一对
-        if let Some(pair_rank) = self.has_n_of_a_kind(&ranks, 2) {
-            let mut kickers: Vec<u8> = ranks.iter().filter(|&&r| r != pair_rank).cloned().collect();
-            kickers.sort_by(|a, b| b.cmp(a));
-            kickers.truncate(3);
-            kickers.insert(0, pair_rank);
-            return HandResult {
-                hand_strength: HandStrength::OnePair,
-                high_cards: kickers,
-            };
-        }
-        
-        // 高牌
-        HandResult {
-            hand_strength: HandStrength::HighCard,
-            high_cards: ranks,
-        }
-    }
-
-    /// 检查是否是顺子
-    fn is_straight(&self, ranks: &[u8]) -> bool {
-        let mut sorted_ranks = ranks.to_vec();
-        sorted_ranks.sort();
-        sorted_ranks.dedup();
-        
-        if sorted_ranks.len() < 5 {
-            return false;
-        }
-        
-        // 检查普通顺子
-        for i in 0..=sorted_ranks.len() - 5 {
-            if sorted_ranks[i + 4] - sorted_ranks[i] == 4 {
-                return true;
-            }
-        }
-        
-        // 检查A-2-3-4-5顺子
-        if sorted_ranks.contains(&1) && sorted_ranks.contains(&2) && sorted_ranks.contains(&3) && 
-           sorted_ranks.contains(&4) && sorted_ranks.contains(&5) {
-            return true;
-        }
-        
-        false
-    }
-
-    /// 检查是否有N张相同点数的牌
-    fn has_n_of_a_kind(&self, ranks: &[u8], n: usize) -> Option<u8> {
-        let mut count_map = std::collections::HashMap::new();
-        for &rank in ranks {
-            *count_map.entry(rank).or_insert(0) += 1;
-        }
-        
-        for (&rank, &count) in &count_map {
-            if count == n {
-                return Some(rank);
-            }
-        }
-        None
-    }
-
-    /// 获取所有对子
-    fn get_pairs(&self, ranks: &[u8]) -> Option<Vec<u8>> {
-        let mut count_map = std::collections::HashMap::new();
-        for &rank in ranks {
-            *count_map.entry(rank).or_insert(0) += 1;
-        }
-        
-        let mut pairs: Vec<u8> = count_map
-            .iter()
-            .filter(|&(_, &count)| count >= 2)
-            .map(|(&rank, _)| rank)
-            .collect();
-        
-        pairs.sort_by(|a, b| b.cmp(a));
-        
-        if pairs.is_empty() {
-            None
-        } else {
-            Some(pairs)
-        }
-    }
-
     /// 获取排序后的点数
     fn get_sorted_ranks(&self, cards: &[Card]) -> Vec<u8> {
         let mut ranks: Vec<u8> = cards.iter().map(|c| c.rank).collect();
@@ -1084,91 +1178,162 @@ impl TexasHoldemGame {
         ranks
     }
 
-    /// 比较两手牌的结果
-    fn compare_hand_results(&self, hand1: &HandResult, hand2: &HandResult) -> std::cmp::Ordering {
-        // 先比较手牌强度
-        let strength_cmp = hand1.hand_strength.to_u8().cmp(&hand2.hand_strength.to_u8());
-        if strength_cmp != std::cmp::Ordering::Equal {
-            return strength_cmp;
-        }
-        
-        // 相同手牌强度时，比较关键牌
-        for (h1, h2) in hand1.high_cards.iter().zip(hand2.high_cards.iter()) {
-            let card_cmp = h1.cmp(h2);
-            if card_cmp != std::cmp::Ordering::Equal {
-                return card_cmp;
-            }
-        }
-        
-        // 如果所有关键牌都相同，比较剩余牌
-        let min_len = hand1.high_cards.len().min(hand2.high_cards.len());
-        if hand1.high_cards.len() > min_len || hand2.high_cards.len() > min_len {
-          
-            return std::cmp::Ordering::Equal;
-        }
-        
-        std::cmp::Ordering::Equal
-    }
-
     /// 比较两手牌
     fn compare_hands(&self, hand1: &HandResult, hand2: &HandResult) -> std::cmp::Ordering {
-        self.compare_hand_results(hand1, hand2)
+        poker_hand::compare_hand_results(hand1, hand2)
     }
 }
 
 /// AI行动枚举
+#[allow(dead_code)]
 enum AiAction {
     Fold,
     Check,
     Bet(u32),
 }
 
-/// 手牌结果
-struct HandResult {
-    hand_strength: HandStrength,
-    high_cards: Vec<u8>,
-}
+impl CardGame for TexasHoldemGame {
+    fn kind(&self) -> GameKind {
+        GameKind::TexasHoldem
+    }
 
-/// 手牌强度枚举
-#[derive(PartialEq, Clone, Copy, Debug)]
-enum HandStrength {
-    HighCard,
-    OnePair,
-    TwoPair,
-    ThreeOfAKind,
-    Straight,
-    Flush,
-    FullHouse,
-    FourOfAKind,
-    StraightFlush,
-}
+    fn name(&self) -> &'static str {
+        "德州扑克"
+    }
 
-impl HandStrength {
-    fn to_u8(&self) -> u8 {
-        match self {
-            HandStrength::HighCard => 0,
-            HandStrength::OnePair => 1,
-            HandStrength::TwoPair => 2,
-            HandStrength::ThreeOfAKind => 3,
-            HandStrength::Straight => 4,
-            HandStrength::Flush => 5,
-            HandStrength::FullHouse => 6,
-            HandStrength::FourOfAKind => 7,
-            HandStrength::StraightFlush => 8,
+    fn rules(&self) -> Vec<String> {
+        [
+            "每位玩家发2张底牌，然后依次进行5张公共牌的发牌",
+            "游戏分为四个下注回合：翻牌前、翻牌、转牌、河牌",
+            "通过组合7张牌（2张底牌+5张公共牌）形成最好的5张牌组合",
+            "牌型大小：同花顺 > 四条 > 葫芦 > 同花 > 顺子 > 三条 > 两对 > 一对 > 高牌",
+            "固定下注规则：每阶段固定下注额，逐阶段递增",
+            "支持负数筹码，结算后筹码≤0判负",
+            "每小局游戏中可以使用一次过牌或弃牌",
+            "使用标准52张扑克牌（无鬼牌）",
+            "简单难度：AI无脑下注，中等难度：AI根据手牌决定，困难难度：AI会诈唬",
+            "自定义难度：自由设置AI风格、双方初始筹码、基础下注额和行动倒计时",
+            "自适应难度：根据最近战绩自动调整AI风格和行动倒计时",
+            "行动倒计时：简单30秒，中等20秒，困难15秒，超时自动过牌（不能过牌时自动下注）",
+            "按 Esc 可以暂停行动倒计时",
+        ]
+        .map(String::from)
+        .to_vec()
+    }
+
+    fn difficulty(&mut self) -> (&mut DifficultySelection, &mut dyn DifficultyProfile) {
+        (&mut self.difficulty_selection, &mut self.custom_profile)
+    }
+
+    /// 设置主菜单卡片（只用于获取背面纹理）
+    fn set_main_menu_cards(&mut self, cards: &[Card]) {
+        if let Some(card) = cards.first() {
+            self.back_card_texture = Some(card.back_tex.clone());
         }
     }
-    
-    fn to_string(&self) -> String {
-        match self {
-            HandStrength::HighCard => "高牌".to_string(),
-            HandStrength::OnePair => "一对".to_string(),
-            HandStrength::TwoPair => "两对".to_string(),
-            HandStrength::ThreeOfAKind => "三条".to_string(),
-            HandStrength::Straight => "顺子".to_string(),
-            HandStrength::Flush => "同花".to_string(),
-            HandStrength::FullHouse => "葫芦".to_string(),
-            HandStrength::FourOfAKind => "四条".to_string(),
-            HandStrength::StraightFlush => "同花顺".to_string(),
+
+    /// 显示游戏界面
+    fn show(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) -> bool {
+        if self.ctx.is_none() {
+            self.ctx = Some(ctx.clone());
+        }
+
+        if self.waiting_for_ai
+            && let Some(timer) = self.ai_thinking_timer
+            && timer.elapsed().as_millis() > 500
+        {
+            self.waiting_for_ai = false;
+            self.ai_thinking_timer = None;
+            self.perform_ai_action();
+        }
+
+        let mut return_to_menu = false;
+
+        match self.game_state {
+            TexasHoldemState::DifficultySelection => {
+                let (menu_return, game_start) = 
+                    self.show_difficulty_selection(ui);
+
+                if menu_return {
+                    return_to_menu = true;
+                    self.reset_to_main_menu();
+                }
+
+                if game_start {
+                    self.game_state = TexasHoldemState::Initializing;
+                    self.game_initializing = true;
+                    self.difficulty_selection.transition_timer = Some(Instant::now());
+                }
+            }
+            TexasHoldemState::Initializing => {
+                if self.difficulty_selection.show_transition_animation(ui) {
+                    self.start_game_fast();
+                    self.game_state = TexasHoldemState::Playing;
+                    self.game_initializing = false;
+                }
+            }
+            TexasHoldemState::Playing => {
+                self.update_shot_clock(ctx);
+                self.show_game_ui(ui);
+
+                // 中途返回主菜单时保存本场进度
+                if !self.game_over {
+                    ui.add_space(10.0);
+                    ui.horizontal(|ui| {
+                        ui.add_space(ui.available_width() / 2.0 - 75.0);
+                        if self.centered_button(ui, "返回主菜单", 150.0, 40.0).clicked() {
+                            return_to_menu = true;
+                            self.suspended_session = self.snapshot().map(SavedSession::TexasHoldem);
+                            self.reset_to_main_menu();
+                        }
+                    });
+                }
+
+                // 暂停时显示遮罩
+                if let Some(clock) = &mut self.shot_clock
+                    && clock.is_paused()
+                    && timer::show_pause_overlay(ctx)
+                {
+                    clock.resume();
+                }
+            }
+        }
+
+        return_to_menu
+    }
+
+    /// 取出刚结束的一场结果
+    fn take_result(&mut self) -> Option<GameResult> {
+        self.finished_result.take()
+    }
+
+    /// 取出本帧产生的游戏事件（用于判定成就）
+    fn take_events(&mut self) -> Vec<GameEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// 取出玩家提交的排行榜成绩
+    fn take_leaderboard_entry(&mut self) -> Option<(GameKind, LeaderboardEntry)> {
+        self.score_submission.take_entry()
+    }
+
+    /// 取出中途返回主菜单时保存的存档
+    fn take_suspended_session(&mut self) -> Option<SavedSession> {
+        self.suspended_session.take()
+    }
+
+    /// 重置到难度选择界面
+    fn reset(&mut self) {
+        self.reset_to_main_menu();
+    }
+
+    fn save_state(&self) -> Option<SavedSession> {
+        self.snapshot().map(SavedSession::TexasHoldem)
+    }
+
+    fn restore_state(&mut self, ctx: &egui::Context, session: SavedSession) {
+        if let SavedSession::TexasHoldem(save) = session {
+            self.restore(ctx, save);
         }
     }
 }
@@ -0,0 +1,1221 @@
+// game4.rs - 21点游戏（多副牌牌靴，筹码在整场内累计）
+use eframe::egui;
+use crate::achievements::GameEvent;
+use crate::card::Card;
+use crate::card_game::CardGame;
+use crate::difficulty::{self, DifficultyProfile, DifficultySelection, GameDifficulty};
+use crate::leaderboard::{LeaderboardEntry, ScoreSubmission};
+use crate::player_profile::{GameKind, GameResult};
+use crate::save_game::{self, SavedCard, SavedSession};
+use crate::util::get_card_image_path;
+use std::time::{Duration, Instant};
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+
+/// 卡片背面图片
+const CARD_BACK_PATH: &str = "assets/card_back/default.png";
+/// 庄家每一步行动的间隔
+const DEALER_STEP: Duration = Duration::from_millis(600);
+/// 分牌后最多的手数
+const MAX_HANDS: usize = 4;
+/// 卡片显示大小
+const CARD_SIZE: egui::Vec2 = egui::vec2(80.0, 120.0);
+
+/// 一张牌的点数（A 记为 1，J/Q/K 记为 10）
+pub fn card_value(rank: u8) -> u32 {
+    rank.min(10) as u32
+}
+
+/// 计算一手牌的点数，返回 (点数, 是否为软牌)
+/// 软牌：A 按 11 点计算且不爆牌
+pub fn hand_total(ranks: &[u8]) -> (u32, bool) {
+    let total: u32 = ranks.iter().map(|&rank| card_value(rank)).sum();
+    if ranks.contains(&1) && total + 10 <= 21 {
+        (total + 10, true)
+    } else {
+        (total, false)
+    }
+}
+
+/// 黑杰克按 3:2 赔付的赢额（奇数下注额的零头舍去）
+pub fn blackjack_winnings(bet: u32) -> u32 {
+    bet * 3 / 2
+}
+
+/// 玩家黑杰克时退还的总额（含本金）：庄家也是黑杰克时平局只退回下注
+pub fn blackjack_payout(bet: u32, dealer_blackjack: bool) -> u32 {
+    if dealer_blackjack {
+        bet
+    } else {
+        bet + blackjack_winnings(bet)
+    }
+}
+
+/// 生成洗好的牌靴（每张牌用 (点数, 花色) 表示，发牌时才加载纹理）
+pub fn build_shoe(decks: u32) -> Vec<(u8, u8)> {
+    let mut shoe = Vec::new();
+    for _ in 0..decks {
+        for suit in 1..=4 {
+            for rank in 1..=13 {
+                shoe.push((rank, suit));
+            }
+        }
+    }
+    shoe.shuffle(&mut rand::rng());
+    shoe
+}
+
+/// 21点难度参数
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct BlackjackProfile {
+    pub decks: u32,                // 牌靴中的牌副数
+    pub penetration: f32,          // 切牌位置（发出这一比例的牌后重新洗牌）
+    pub dealer_hits_soft_17: bool, // 庄家软17是否要牌
+    pub surrender_allowed: bool,   // 是否允许投降
+    pub starting_bankroll: i32,    // 初始筹码
+    pub min_bet: u32,              // 最小下注额（偶数，下注额为它的整数倍，黑杰克 3:2 赔付不会有零头）
+}
+
+impl DifficultyProfile for BlackjackProfile {
+    fn preset(difficulty: GameDifficulty) -> Self {
+        match difficulty {
+            GameDifficulty::Easy => Self {
+                decks: 1,
+                penetration: 0.75,
+                dealer_hits_soft_17: false,
+                surrender_allowed: true,
+                starting_bankroll: 1000,
+                min_bet: 10,
+            },
+            GameDifficulty::Medium | GameDifficulty::Custom | GameDifficulty::Adaptive => Self {
+                decks: 6,
+                penetration: 0.75,
+                dealer_hits_soft_17: false,
+                surrender_allowed: true,
+                starting_bankroll: 1000,
+                min_bet: 10,
+            },
+            GameDifficulty::Hard => Self {
+                decks: 8,
+                penetration: 0.65,
+                dealer_hits_soft_17: true,
+                surrender_allowed: false,
+                starting_bankroll: 1000,
+                min_bet: 20,
+            },
+        }
+    }
+
+    fn adaptive(level: f32) -> Self {
+        // 强度越高牌副数越多，切牌越早，庄家规则越不利于玩家
+        Self {
+            decks: difficulty::lerp(1.0, 8.0, level).round() as u32,
+            penetration: difficulty::lerp(0.75, 0.65, level),
+            dealer_hits_soft_17: level >= 0.5,
+            surrender_allowed: level < 0.67,
+            ..Self::preset(GameDifficulty::Medium)
+        }
+    }
+
+    fn load_preset(&mut self, difficulty: GameDifficulty) {
+        *self = Self::preset(difficulty);
+    }
+
+    fn show_editor(&mut self, ui: &mut egui::Ui) {
+        ui.add(egui::Slider::new(&mut self.decks, 1..=8).text("牌副数"));
+        ui.add(egui::Slider::new(&mut self.penetration, 0.5..=0.9).text("切牌位置"));
+        ui.checkbox(&mut self.dealer_hits_soft_17, "庄家软17要牌");
+        ui.checkbox(&mut self.surrender_allowed, "允许投降");
+        ui.add(egui::Slider::new(&mut self.starting_bankroll, 100..=10000).text("初始筹码"));
+        ui.add(egui::Slider::new(&mut self.min_bet, 2..=100).step_by(2.0).text("最小下注额"));
+    }
+}
+
+/// 21点游戏状态
+#[derive(PartialEq, Clone, Copy)]
+enum BlackjackState {
+    DifficultySelection,
+    Initializing,
+    Playing,
+}
+
+/// 一局中的阶段
+#[derive(PartialEq, Clone, Copy, Serialize, Deserialize)]
+enum RoundPhase {
+    Betting,    // 下注
+    Insurance,  // 庄家明牌为A，询问是否买保险
+    PlayerTurn, // 玩家行动
+    DealerTurn, // 庄家补牌
+    Settled,    // 本局已结算
+}
+
+/// 玩家的一手牌（分牌后可以有多手）
+struct PlayerHand {
+    cards: Vec<Card>,
+    bet: u32,
+    doubled: bool,      // 已加倍
+    finished: bool,     // 已结束行动（停牌、爆牌、加倍、投降）
+    split_aces: bool,   // 由分A得到（只能再拿一张牌）
+    surrendered: bool,  // 已投降
+    from_split: bool,   // 由分牌得到（21点不算黑杰克）
+}
+
+impl PlayerHand {
+    fn new(bet: u32) -> Self {
+        Self {
+            cards: Vec::new(),
+            bet,
+            doubled: false,
+            finished: false,
+            split_aces: false,
+            surrendered: false,
+            from_split: false,
+        }
+    }
+
+    fn ranks(&self) -> Vec<u8> {
+        self.cards.iter().map(|card| card.rank).collect()
+    }
+
+    fn total(&self) -> (u32, bool) {
+        hand_total(&self.ranks())
+    }
+
+    fn is_bust(&self) -> bool {
+        self.total().0 > 21
+    }
+
+    /// 是否为黑杰克（前两张牌21点，分牌得到的不算）
+    fn is_blackjack(&self) -> bool {
+        !self.from_split && self.cards.len() == 2 && self.total().0 == 21
+    }
+}
+
+/// 一手牌的存档
+#[derive(Serialize, Deserialize)]
+struct SavedHand {
+    cards: Vec<SavedCard>,
+    bet: u32,
+    doubled: bool,
+    finished: bool,
+    split_aces: bool,
+    surrendered: bool,
+    from_split: bool,
+}
+
+/// 21点未完成一场的存档
+#[derive(Serialize, Deserialize)]
+pub struct BlackjackSave {
+    difficulty: GameDifficulty,
+    adaptive_level: f32,
+    custom_profile: BlackjackProfile,
+    shoe: Vec<(u8, u8)>,
+    cut_card: usize,
+    cut_card_reached: bool,
+    dealer_hand: Vec<SavedCard>,
+    hands: Vec<SavedHand>,
+    active_hand: usize,
+    phase: RoundPhase,
+    bet: u32,
+    insurance: u32,
+    bankroll: i32,
+    round_start_bankroll: i32,
+    session_start_bankroll: i32,
+    biggest_win: u32,
+    rounds_played: usize,
+    message: String,
+    next_card_id: usize,
+}
+
+/// 21点游戏
+pub struct BlackjackGame {
+    // 游戏难度设置
+    difficulty_selection: DifficultySelection,
+    game_state: BlackjackState,
+
+    // 牌靴（末尾为下一张要发的牌）
+    shoe: Vec<(u8, u8)>,
+    // 切牌位置：牌靴剩余张数不超过它时，本局结束后重新洗牌
+    cut_card: usize,
+    cut_card_reached: bool,
+
+    // 庄家手牌（第二张为暗牌）
+    dealer_hand: Vec<Card>,
+    // 玩家手牌（分牌后有多手）
+    hands: Vec<PlayerHand>,
+    // 当前行动的那一手
+    active_hand: usize,
+    phase: RoundPhase,
+
+    // 下一局的下注额
+    bet: u32,
+    // 本局的保险金额
+    insurance: u32,
+    // 玩家筹码（整场累计）
+    bankroll: i32,
+    // 本局开始前和本场开始时的筹码
+    round_start_bankroll: i32,
+    session_start_bankroll: i32,
+    // 单局最大赢额
+    biggest_win: u32,
+    // 已玩的局数
+    rounds_played: usize,
+
+    // 显示给玩家的消息
+    message: String,
+    // 本场是否已结束
+    session_over: bool,
+    // 庄家下一步行动的计时器
+    dealer_timer: Option<Instant>,
+    // 下一张牌的编号
+    next_card_id: usize,
+
+    ctx: Option<egui::Context>,
+
+    // 自定义难度参数
+    custom_profile: BlackjackProfile,
+    // 刚结束的一场结果，等待主程序记录
+    finished_result: Option<GameResult>,
+    // 等待主程序处理的游戏事件
+    events: Vec<GameEvent>,
+    // 结束界面的排行榜成绩提交
+    score_submission: ScoreSubmission,
+    // 中途返回主菜单时保存的存档
+    suspended_session: Option<SavedSession>,
+}
+
+impl BlackjackGame {
+    pub fn new() -> Self {
+        let profile = BlackjackProfile::preset(GameDifficulty::Medium);
+        Self {
+            difficulty_selection: DifficultySelection::new(GameKind::Blackjack),
+            game_state: BlackjackState::DifficultySelection,
+            shoe: Vec::new(),
+            cut_card: 0,
+            cut_card_reached: false,
+            dealer_hand: Vec::new(),
+            hands: Vec::new(),
+            active_hand: 0,
+            phase: RoundPhase::Betting,
+            bet: profile.min_bet,
+            insurance: 0,
+            bankroll: profile.starting_bankroll,
+            round_start_bankroll: profile.starting_bankroll,
+            session_start_bankroll: profile.starting_bankroll,
+            biggest_win: 0,
+            rounds_played: 0,
+            message: "欢迎来到21点！".to_string(),
+            session_over: false,
+            dealer_timer: None,
+            next_card_id: 0,
+            ctx: None,
+            custom_profile: BlackjackProfile::preset(GameDifficulty::Custom),
+            finished_result: None,
+            events: Vec::new(),
+            score_submission: ScoreSubmission::new(),
+            suspended_session: None,
+        }
+    }
+
+    /// 当前难度的参数
+    fn profile(&self) -> BlackjackProfile {
+        self.difficulty_selection.profile(&self.custom_profile)
+    }
+
+    /// 开始新的一场
+    fn start_session(&mut self) {
+        let profile = self.profile();
+        self.reset_game_state();
+        self.bankroll = profile.starting_bankroll;
+        self.round_start_bankroll = profile.starting_bankroll;
+        self.session_start_bankroll = profile.starting_bankroll;
+        self.bet = profile.min_bet;
+        self.reshuffle();
+        self.message = format!("新的一场开始！{}副牌，请下注。", profile.decks);
+    }
+
+    /// 重新洗牌并放置切牌
+    fn reshuffle(&mut self) {
+        let profile = self.profile();
+        self.shoe = build_shoe(profile.decks);
+        self.cut_card = (self.shoe.len() as f32 * (1.0 - profile.penetration)).round() as usize;
+        self.cut_card_reached = false;
+    }
+
+    /// 从牌靴发一张牌，牌靴发空时立即重新洗牌
+    fn draw_card(&mut self, face_up: bool) -> Option<Card> {
+        if self.shoe.is_empty() {
+            self.reshuffle();
+            self.message.push_str("\n牌靴已发完，重新洗牌");
+        }
+        let (rank, suit) = self.shoe.pop()?;
+        if !self.cut_card_reached && self.shoe.len() <= self.cut_card {
+            self.cut_card_reached = true;
+            self.message.push_str("\n切牌出现，本局结束后重新洗牌");
+        }
+
+        let ctx = self.ctx.as_ref()?;
+        let mut card = Card::new(ctx, self.next_card_id, rank, suit, CARD_BACK_PATH, &get_card_image_path(rank, suit)).ok()?;
+        self.next_card_id += 1;
+        if face_up {
+            card.flip_to(true);
+        }
+        Some(card)
+    }
+
+    /// 给指定的一手牌发一张明牌
+    fn deal_to_hand(&mut self, index: usize) {
+        if let Some(card) = self.draw_card(true) {
+            self.hands[index].cards.push(card);
+        }
+    }
+
+    /// 给庄家发一张牌
+    fn deal_to_dealer(&mut self, face_up: bool) {
+        if let Some(card) = self.draw_card(face_up) {
+            self.dealer_hand.push(card);
+        }
+    }
+
+    /// 庄家手牌的点数（包括暗牌）
+    fn dealer_total(&self) -> (u32, bool) {
+        let ranks: Vec<u8> = self.dealer_hand.iter().map(|card| card.rank).collect();
+        hand_total(&ranks)
+    }
+
+    /// 庄家是否为黑杰克
+    fn dealer_has_blackjack(&self) -> bool {
+        self.dealer_hand.len() == 2 && self.dealer_total().0 == 21
+    }
+
+    /// 翻开庄家的暗牌
+    fn reveal_hole_card(&mut self) {
+        for card in &mut self.dealer_hand {
+            card.flip_to(true);
+        }
+    }
+
+    /// 下注并发牌
+    fn deal_round(&mut self) {
+        if self.cut_card_reached {
+            self.reshuffle();
+            self.message = "重新洗牌。".to_string();
+        } else {
+            self.message.clear();
+        }
+
+        self.round_start_bankroll = self.bankroll;
+        self.bankroll -= self.bet as i32;
+        self.insurance = 0;
+        self.dealer_hand.clear();
+        self.hands = vec![PlayerHand::new(self.bet)];
+        self.active_hand = 0;
+
+        self.deal_to_hand(0);
+        self.deal_to_dealer(true);
+        self.deal_to_hand(0);
+        self.deal_to_dealer(false);
+
+        if self.dealer_hand.first().is_some_and(|card| card.rank == 1) {
+            // 庄家明牌为A：先询问保险
+            self.phase = RoundPhase::Insurance;
+            self.message.push_str("\n庄家明牌为A，是否购买保险？");
+            self.message = self.message.trim_start().to_string();
+        } else {
+            self.check_blackjacks();
+        }
+    }
+
+    /// 庄家检查暗牌和双方的黑杰克，没有黑杰克时轮到玩家行动
+    fn check_blackjacks(&mut self) {
+        if self.dealer_has_blackjack() || self.hands[0].is_blackjack() {
+            self.hands[0].finished = true;
+            self.reveal_hole_card();
+            self.settle_round();
+        } else {
+            if self.insurance > 0 {
+                self.message.push_str(&format!("\n庄家不是黑杰克，输掉保险 {}", self.insurance));
+            }
+            self.message.push_str("\n请选择：要牌、停牌、加倍、分牌或投降");
+            self.message = self.message.trim_start().to_string();
+            self.phase = RoundPhase::PlayerTurn;
+        }
+    }
+
+    /// 玩家决定是否购买保险（保险金额为下注额的一半）
+    fn decide_insurance(&mut self, buy: bool) {
+        if buy {
+            self.insurance = self.bet / 2;
+            self.bankroll -= self.insurance as i32;
+        }
+        self.check_blackjacks();
+    }
+
+    /// 当前行动的那一手
+    fn active(&self) -> Option<&PlayerHand> {
+        self.hands.get(self.active_hand)
+    }
+
+    /// 当前这一手能否加倍
+    fn can_double(&self) -> bool {
+        self.active().is_some_and(|hand| {
+            hand.cards.len() == 2 && !hand.split_aces && self.bankroll >= hand.bet as i32
+        })
+    }
+
+    /// 当前这一手能否分牌（两张点数相同的牌）
+    fn can_split(&self) -> bool {
+        self.hands.len() < MAX_HANDS
+            && self.active().is_some_and(|hand| {
+                hand.cards.len() == 2
+                    && !hand.split_aces
+                    && card_value(hand.cards[0].rank) == card_value(hand.cards[1].rank)
+                    && self.bankroll >= hand.bet as i32
+            })
+    }
+
+    /// 能否投降（只能在前两张牌、没有分牌时）
+    fn can_surrender(&self) -> bool {
+        self.profile().surrender_allowed && self.hands.len() == 1 && self.hands[0].cards.len() == 2
+    }
+
+    /// 要牌
+    fn hit(&mut self) {
+        let index = self.active_hand;
+        self.deal_to_hand(index);
+        let (total, _) = self.hands[index].total();
+        if total >= 21 {
+            // 爆牌或到21点自动停牌
+            self.hands[index].finished = true;
+            self.advance_hand();
+        }
+    }
+
+    /// 停牌
+    fn stand(&mut self) {
+        self.hands[self.active_hand].finished = true;
+        self.advance_hand();
+    }
+
+    /// 加倍：下注额翻倍，只再拿一张牌
+    fn double_down(&mut self) {
+        let index = self.active_hand;
+        let bet = self.hands[index].bet;
+        self.bankroll -= bet as i32;
+        self.hands[index].bet += bet;
+        self.hands[index].doubled = true;
+        self.deal_to_hand(index);
+        self.hands[index].finished = true;
+        self.advance_hand();
+    }
+
+    /// 分牌：拆成两手，各补一张牌
+    fn split(&mut self) {
+        let index = self.active_hand;
+        let bet = self.hands[index].bet;
+        self.bankroll -= bet as i32;
+
+        let Some(moved) = self.hands[index].cards.pop() else {
+            return;
+        };
+        let split_aces = moved.rank == 1;
+        let mut new_hand = PlayerHand::new(bet);
+        new_hand.cards.push(moved);
+        self.hands.insert(index + 1, new_hand);
+
+        for hand_index in [index, index + 1] {
+            self.hands[hand_index].from_split = true;
+            self.hands[hand_index].split_aces = split_aces;
+            self.deal_to_hand(hand_index);
+            // 分A只能各拿一张牌
+            if split_aces || self.hands[hand_index].total().0 == 21 {
+                self.hands[hand_index].finished = true;
+            }
+        }
+        self.advance_hand();
+    }
+
+    /// 投降：收回一半下注额
+    fn surrender(&mut self) {
+        let hand = &mut self.hands[0];
+        hand.surrendered = true;
+        hand.finished = true;
+        self.bankroll += (hand.bet / 2) as i32;
+        self.advance_hand();
+    }
+
+    /// 转到下一手未结束的牌，全部结束后轮到庄家
+    fn advance_hand(&mut self) {
+        if let Some(next) = self.hands.iter().position(|hand| !hand.finished) {
+            self.active_hand = next;
+            return;
+        }
+
+        self.reveal_hole_card();
+        let all_out = self.hands.iter().all(|hand| hand.is_bust() || hand.surrendered);
+        if all_out {
+            // 玩家的牌全部爆牌或投降，庄家无需补牌
+            self.settle_round();
+        } else {
+            self.phase = RoundPhase::DealerTurn;
+            self.dealer_timer = Some(Instant::now());
+        }
+    }
+
+    /// 庄家是否继续要牌：不足17点要牌，软17按规则决定
+    fn dealer_should_hit(&self) -> bool {
+        let (total, soft) = self.dealer_total();
+        total < 17 || (total == 17 && soft && self.profile().dealer_hits_soft_17)
+    }
+
+    /// 庄家行动一步（每步之间有间隔，便于看清补牌）
+    fn update_dealer(&mut self, ctx: &egui::Context) {
+        if self.phase != RoundPhase::DealerTurn {
+            return;
+        }
+        let Some(timer) = self.dealer_timer else {
+            return;
+        };
+        if timer.elapsed() < DEALER_STEP {
+            ctx.request_repaint_after(DEALER_STEP - timer.elapsed());
+            return;
+        }
+
+        if self.dealer_should_hit() {
+            self.deal_to_dealer(true);
+            self.dealer_timer = Some(Instant::now());
+        } else {
+            self.dealer_timer = None;
+            self.settle_round();
+        }
+    }
+
+    /// 结算本局
+    fn settle_round(&mut self) {
+        let (dealer_total, _) = self.dealer_total();
+        let dealer_blackjack = self.dealer_has_blackjack();
+        let dealer_bust = dealer_total > 21;
+        let mut lines = Vec::new();
+
+        if self.insurance > 0 && dealer_blackjack {
+            self.bankroll += (self.insurance * 3) as i32;
+            lines.push(format!("保险赔付 {}", self.insurance * 2));
+        }
+
+        let many_hands = self.hands.len() > 1;
+        for (index, hand) in self.hands.iter().enumerate() {
+            let (total, _) = hand.total();
+            let (payout, outcome) = if hand.surrendered {
+                (0, "投降，收回一半下注".to_string())
+            } else if total > 21 {
+                (0, format!("{}点爆牌，输掉 {}", total, hand.bet))
+            } else if hand.is_blackjack() {
+                // 黑杰克按3:2赔付，庄家也是黑杰克时平局
+                let payout = blackjack_payout(hand.bet, dealer_blackjack);
+                if dealer_blackjack {
+                    (payout, "双方都是黑杰克，平局，退回下注".to_string())
+                } else {
+                    (payout, format!("黑杰克！赢得 {}", payout - hand.bet))
+                }
+            } else if dealer_blackjack {
+                (0, format!("庄家黑杰克，输掉 {}", hand.bet))
+            } else if total == dealer_total {
+                (hand.bet, "平局，退回下注".to_string())
+            } else if dealer_bust || total > dealer_total {
+                (hand.bet * 2, format!("{}点胜，赢得 {}", total, hand.bet))
+            } else {
+                (0, format!("{}点负，输掉 {}", total, hand.bet))
+            };
+            self.bankroll += payout as i32;
+            if payout > hand.bet {
+                self.events.push(GameEvent::BlackjackHandWon { blackjack: hand.is_blackjack() && !dealer_blackjack });
+            }
+            if many_hands {
+                lines.push(format!("第{}手：{}", index + 1, outcome));
+            } else {
+                lines.push(outcome);
+            }
+        }
+
+        let net = self.bankroll - self.round_start_bankroll;
+        if net > 0 {
+            self.biggest_win = self.biggest_win.max(net as u32);
+        }
+        self.rounds_played += 1;
+        self.phase = RoundPhase::Settled;
+
+        let dealer_text = if dealer_blackjack {
+            "庄家: 黑杰克".to_string()
+        } else if dealer_bust {
+            format!("庄家: {}点爆牌", dealer_total)
+        } else {
+            format!("庄家: {}点", dealer_total)
+        };
+        let mut message = format!("{}\n{}\n本局净{} {}", dealer_text, lines.join("\n"), if net >= 0 { "赢" } else { "输" }, net.abs());
+        if self.cut_card_reached {
+            message.push_str("\n已到切牌，下一局重新洗牌");
+        }
+        self.message = message;
+
+        let min_bet = self.profile().min_bet;
+        if self.bankroll < min_bet as i32 {
+            self.message.push_str("\n筹码不足最小下注额，本场结束！");
+            self.end_session();
+        } else {
+            // 下一局的下注额不超过剩余筹码
+            self.bet = self.bet.min(self.max_bet());
+        }
+    }
+
+    /// 当前允许的最大下注额（最小下注额的整数倍）
+    fn max_bet(&self) -> u32 {
+        let min_bet = self.profile().min_bet;
+        (self.bankroll.max(0) as u32 / min_bet * min_bet).max(min_bet)
+    }
+
+    /// 结束本场并记录战绩：筹码多于开始时算赢
+    fn end_session(&mut self) {
+        if self.session_over {
+            return;
+        }
+        // 一局都没玩就结束时不记录战绩，直接回到难度选择
+        if self.rounds_played == 0 {
+            self.reset_to_difficulty_selection();
+            return;
+        }
+        self.session_over = true;
+        let won = self.bankroll > self.session_start_bankroll;
+        self.difficulty_selection.record_result(won);
+
+        let difficulty = self.difficulty_selection.selected_difficulty.unwrap_or(GameDifficulty::Easy);
+        let mut result = GameResult::new(GameKind::Blackjack, difficulty, won);
+        result.moves = Some(self.rounds_played);
+        result.net_chips = (self.bankroll - self.session_start_bankroll) as i64;
+        result.biggest_pot = self.biggest_win;
+        self.score_submission.offer(&result);
+        self.finished_result = Some(result);
+    }
+
+    /// 显示游戏主界面
+    fn show_game_ui(&mut self, ui: &mut egui::Ui) {
+        ui.vertical_centered(|ui| {
+            self.show_top_info(ui);
+            ui.add_space(10.0);
+
+            self.show_dealer_hand(ui);
+            ui.add_space(20.0);
+
+            self.show_player_hands(ui);
+            ui.add_space(20.0);
+
+            ui.colored_label(egui::Color32::BLACK, &self.message);
+            ui.add_space(20.0);
+
+            self.show_action_buttons(ui);
+        });
+    }
+
+    /// 显示顶部信息
+    fn show_top_info(&self, ui: &mut egui::Ui) {
+        let profile = self.profile();
+        ui.horizontal(|ui| {
+            ui.vertical(|ui| {
+                let net = self.bankroll - self.session_start_bankroll;
+                let net_color = if net >= 0 { egui::Color32::GREEN } else { egui::Color32::RED };
+                ui.colored_label(egui::Color32::GOLD, format!("你的筹码: {}", self.bankroll));
+                ui.colored_label(net_color, format!("本场净赢: {}", net));
+                ui.colored_label(egui::Color32::LIGHT_BLUE, format!("已玩局数: {}", self.rounds_played));
+            });
+
+            ui.add_space(ui.available_width() - 300.0);
+            ui.vertical(|ui| {
+                if let Some(difficulty) = self.difficulty_selection.selected_difficulty {
+                    ui.colored_label(egui::Color32::DARK_GRAY, difficulty.label());
+                }
+                ui.colored_label(egui::Color32::BLACK, format!("牌靴: {}副，剩余 {} 张", profile.decks, self.shoe.len()));
+                let soft_17 = if profile.dealer_hits_soft_17 { "庄家软17要牌" } else { "庄家软17停牌" };
+                ui.colored_label(egui::Color32::BLACK, soft_17);
+                if self.cut_card_reached {
+                    ui.colored_label(egui::Color32::ORANGE, "已到切牌，本局后重新洗牌");
+                }
+            });
+        });
+    }
+
+    /// 显示庄家手牌
+    fn show_dealer_hand(&self, ui: &mut egui::Ui) {
+        let hole_hidden = self.dealer_hand.iter().any(|card| !card.is_face_up);
+        let label = if self.dealer_hand.is_empty() {
+            "庄家".to_string()
+        } else if hole_hidden {
+            format!("庄家: 明牌 {}", Self::total_text(hand_total(&[self.dealer_hand[0].rank])))
+        } else {
+            format!("庄家: {}", Self::total_text(self.dealer_total()))
+        };
+        ui.label(label);
+
+        ui.horizontal(|ui| {
+            let width = self.dealer_hand.len() as f32 * (CARD_SIZE.x + 8.0);
+            ui.add_space((ui.available_width() - width) / 2.0);
+            for card in &self.dealer_hand {
+                card.render(ui, CARD_SIZE, egui::Sense::hover());
+            }
+        });
+    }
+
+    /// 显示玩家的每一手牌，当前行动的那一手高亮
+    fn show_player_hands(&self, ui: &mut egui::Ui) {
+        if self.hands.is_empty() {
+            return;
+        }
+        let playing = self.phase == RoundPhase::PlayerTurn;
+        let hand_widths: f32 = self
+            .hands
+            .iter()
+            .map(|hand| hand.cards.len().max(2) as f32 * (CARD_SIZE.x + 8.0) + 30.0)
+            .sum();
+
+        ui.horizontal(|ui| {
+            ui.add_space(((ui.available_width() - hand_widths) / 2.0).max(0.0));
+            for (index, hand) in self.hands.iter().enumerate() {
+                let active = playing && index == self.active_hand;
+                let stroke = if active {
+                    egui::Stroke::new(3.0, egui::Color32::GOLD)
+                } else {
+                    egui::Stroke::new(1.0, egui::Color32::GRAY)
+                };
+                egui::Frame::group(ui.style()).stroke(stroke).show(ui, |ui| {
+                    ui.vertical(|ui| {
+                        let mut status = format!("下注 {}  {}", hand.bet, Self::total_text(hand.total()));
+                        if hand.is_blackjack() {
+                            status = format!("下注 {}  黑杰克", hand.bet);
+                        } else if hand.surrendered {
+                            status.push_str("（投降）");
+                        } else if hand.is_bust() {
+                            status.push_str("（爆牌）");
+                        } else if hand.doubled {
+                            status.push_str("（加倍）");
+                        }
+                        ui.label(status);
+                        ui.horizontal(|ui| {
+                            for card in &hand.cards {
+                                card.render(ui, CARD_SIZE, egui::Sense::hover());
+                            }
+                        });
+                    });
+                });
+            }
+        });
+    }
+
+    /// 点数的显示文字
+    fn total_text((total, soft): (u32, bool)) -> String {
+        if soft {
+            format!("{}点（软）", total)
+        } else {
+            format!("{}点", total)
+        }
+    }
+
+    /// 显示操作按钮
+    fn show_action_buttons(&mut self, ui: &mut egui::Ui) {
+        if self.session_over {
+            // 本场结束：显示成绩提交和选择难度按钮
+            ui.colored_label(
+                egui::Color32::BLUE,
+                format!("本场结束！共 {} 局，净赢 {}", self.rounds_played, self.bankroll - self.session_start_bankroll),
+            );
+            ui.add_space(10.0);
+            self.score_submission.show(ui);
+            ui.add_space(10.0);
+            ui.horizontal(|ui| {
+                ui.add_space(ui.available_width() / 2.0 - 75.0);
+                if self.centered_button(ui, "选择难度", 150.0, 40.0).clicked() {
+                    self.reset_to_difficulty_selection();
+                }
+            });
+            return;
+        }
+
+        match self.phase {
+            RoundPhase::Betting | RoundPhase::Settled => self.show_betting_buttons(ui),
+            RoundPhase::Insurance => {
+                let cost = self.bet / 2;
+                ui.horizontal(|ui| {
+                    ui.add_space(ui.available_width() / 2.0 - 130.0);
+                    let can_buy = cost > 0 && self.bankroll >= cost as i32;
+                    if ui
+                        .add_enabled(can_buy, egui::Button::new(format!("买保险 ({})", cost)).min_size(egui::vec2(120.0, 40.0)))
+                        .clicked()
+                    {
+                        self.decide_insurance(true);
+                    }
+                    ui.add_space(20.0);
+                    if self.centered_button(ui, "不买保险", 120.0, 40.0).clicked() {
+                        self.decide_insurance(false);
+                    }
+                });
+            }
+            RoundPhase::PlayerTurn => self.show_player_buttons(ui),
+            RoundPhase::DealerTurn => {
+                ui.colored_label(egui::Color32::BLUE, "庄家补牌中...");
+            }
+        }
+    }
+
+    /// 下注界面：调整下注额后发牌，也可以结束本场
+    fn show_betting_buttons(&mut self, ui: &mut egui::Ui) {
+        let min_bet = self.profile().min_bet;
+        let max_bet = self.max_bet();
+
+        ui.horizontal(|ui| {
+            ui.add_space(ui.available_width() / 2.0 - 160.0);
+            if ui.add_enabled(self.bet > min_bet, egui::Button::new(format!("-{}", min_bet))).clicked() {
+                self.bet -= min_bet;
+            }
+            ui.colored_label(egui::Color32::GOLD, format!("下注额: {}", self.bet));
+            if ui.add_enabled(self.bet + min_bet <= max_bet, egui::Button::new(format!("+{}", min_bet))).clicked() {
+                self.bet += min_bet;
+            }
+            if ui.add_enabled(self.bet * 2 <= max_bet, egui::Button::new("×2")).clicked() {
+                self.bet *= 2;
+            }
+            if ui.button("最小").clicked() {
+                self.bet = min_bet;
+            }
+            if ui.button("全下").clicked() {
+                self.bet = max_bet;
+            }
+        });
+        ui.add_space(10.0);
+
+        ui.horizontal(|ui| {
+            ui.add_space(ui.available_width() / 2.0 - 130.0);
+            let deal_text = if self.phase == RoundPhase::Settled { "下一局" } else { "发牌" };
+            if self.centered_button(ui, deal_text, 120.0, 40.0).clicked() {
+                self.deal_round();
+            }
+            ui.add_space(20.0);
+            if self.centered_button(ui, "结束本场", 120.0, 40.0).clicked() {
+                self.message = "你结束了本场。".to_string();
+                self.end_session();
+            }
+        });
+    }
+
+    /// 玩家行动按钮：要牌、停牌、加倍、分牌、投降
+    fn show_player_buttons(&mut self, ui: &mut egui::Ui) {
+        let can_double = self.can_double();
+        let can_split = self.can_split();
+        let can_surrender = self.can_surrender();
+
+        if self.hands.len() > 1 {
+            ui.label(format!("正在行动: 第{}手", self.active_hand + 1));
+        }
+        ui.horizontal(|ui| {
+            let total_width = 100.0 * 5.0 + 10.0 * 4.0;
+            ui.add_space((ui.available_width() - total_width) / 2.0);
+            let button = |text: &str| egui::Button::new(text).min_size(egui::vec2(100.0, 40.0));
+
+            if ui.add(button("要牌")).clicked() {
+                self.hit();
+            }
+            ui.add_space(10.0);
+            if ui.add(button("停牌")).clicked() {
+                self.stand();
+            }
+            ui.add_space(10.0);
+            if ui.add_enabled(can_double, button("加倍")).clicked() {
+                self.double_down();
+            }
+            ui.add_space(10.0);
+            if ui.add_enabled(can_split, button("分牌")).clicked() {
+                self.split();
+            }
+            ui.add_space(10.0);
+            if ui.add_enabled(can_surrender, button("投降")).clicked() {
+                self.surrender();
+            }
+        });
+    }
+
+    /// 创建居中的按钮
+    fn centered_button(&self, ui: &mut egui::Ui, text: &str, width: f32, height: f32) -> egui::Response {
+        ui.add_sized(
+            egui::vec2(width, height),
+            egui::Button::new(
+                egui::RichText::new(text)
+                    .text_style(egui::TextStyle::Button)
+                    .color(egui::Color32::BLACK)
+                    .size(14.0)
+            )
+        )
+    }
+
+    /// 保存进行中的一场，没有进行中的游戏时返回 None
+    fn snapshot(&self) -> Option<BlackjackSave> {
+        if self.game_state != BlackjackState::Playing || self.session_over {
+            return None;
+        }
+        Some(BlackjackSave {
+            difficulty: self.difficulty_selection.selected_difficulty?,
+            adaptive_level: self.difficulty_selection.adaptive.level,
+            custom_profile: self.custom_profile,
+            shoe: self.shoe.clone(),
+            cut_card: self.cut_card,
+            cut_card_reached: self.cut_card_reached,
+            dealer_hand: save_game::save_cards(&self.dealer_hand),
+            hands: self
+                .hands
+                .iter()
+                .map(|hand| SavedHand {
+                    cards: save_game::save_cards(&hand.cards),
+                    bet: hand.bet,
+                    doubled: hand.doubled,
+                    finished: hand.finished,
+                    split_aces: hand.split_aces,
+                    surrendered: hand.surrendered,
+                    from_split: hand.from_split,
+                })
+                .collect(),
+            active_hand: self.active_hand,
+            phase: self.phase,
+            bet: self.bet,
+            insurance: self.insurance,
+            bankroll: self.bankroll,
+            round_start_bankroll: self.round_start_bankroll,
+            session_start_bankroll: self.session_start_bankroll,
+            biggest_win: self.biggest_win,
+            rounds_played: self.rounds_played,
+            message: self.message.clone(),
+            next_card_id: self.next_card_id,
+        })
+    }
+
+    /// 从存档恢复一场
+    fn restore(&mut self, ctx: &egui::Context, save: BlackjackSave) {
+        self.reset_game_state();
+        self.ctx = Some(ctx.clone());
+        self.difficulty_selection.restore(save.difficulty, save.adaptive_level);
+        self.custom_profile = save.custom_profile;
+
+        self.shoe = save.shoe;
+        self.cut_card = save.cut_card;
+        self.cut_card_reached = save.cut_card_reached;
+        self.dealer_hand = save_game::restore_cards(ctx, &save.dealer_hand);
+        self.hands = save
+            .hands
+            .into_iter()
+            .map(|hand| PlayerHand {
+                cards: save_game::restore_cards(ctx, &hand.cards),
+                bet: hand.bet,
+                doubled: hand.doubled,
+                finished: hand.finished,
+                split_aces: hand.split_aces,
+                surrendered: hand.surrendered,
+                from_split: hand.from_split,
+            })
+            .collect();
+        self.active_hand = save.active_hand;
+        self.phase = save.phase;
+        self.bet = save.bet;
+        self.insurance = save.insurance;
+        self.bankroll = save.bankroll;
+        self.round_start_bankroll = save.round_start_bankroll;
+        self.session_start_bankroll = save.session_start_bankroll;
+        self.biggest_win = save.biggest_win;
+        self.rounds_played = save.rounds_played;
+        self.message = save.message;
+        self.next_card_id = save.next_card_id;
+        // 存档时庄家正在补牌，恢复后重新计时
+        self.dealer_timer = (self.phase == RoundPhase::DealerTurn).then(Instant::now);
+        self.game_state = BlackjackState::Playing;
+    }
+
+    /// 重置到难度选择界面
+    fn reset_to_difficulty_selection(&mut self) {
+        self.difficulty_selection.reset();
+        self.game_state = BlackjackState::DifficultySelection;
+        self.reset_game_state();
+    }
+
+    /// 重置游戏状态
+    fn reset_game_state(&mut self) {
+        self.shoe.clear();
+        self.cut_card = 0;
+        self.cut_card_reached = false;
+        self.dealer_hand.clear();
+        self.hands.clear();
+        self.active_hand = 0;
+        self.phase = RoundPhase::Betting;
+        self.insurance = 0;
+        self.biggest_win = 0;
+        self.rounds_played = 0;
+        self.message = "欢迎来到21点！".to_string();
+        self.session_over = false;
+        self.dealer_timer = None;
+        self.next_card_id = 0;
+        self.score_submission.clear();
+    }
+}
+
+impl CardGame for BlackjackGame {
+    fn kind(&self) -> GameKind {
+        GameKind::Blackjack
+    }
+
+    fn rules(&self) -> Vec<String> {
+        [
+            "与庄家比点数，手牌越接近21点越好，超过21点即爆牌",
+            "A 算 1 点或 11 点，J/Q/K 算 10 点",
+            "前两张牌为 A 和 10 点牌即黑杰克，按 3:2 赔付，其余获胜按 1:1 赔付",
+            "操作：要牌、停牌、加倍（下注翻倍只再拿一张牌）、分牌（两张点数相同的牌拆成两手，最多四手，分A只各拿一张牌）、投降（收回一半下注）",
+            "庄家明牌为A时可以购买保险（下注额的一半），庄家黑杰克时保险按 2:1 赔付",
+            "庄家不足17点必须要牌，软17（含按11点计算的A）是否要牌由规则决定",
+            "多副牌牌靴，发到切牌位置后，本局结束时重新洗牌",
+            "筹码在整场内累计，筹码不足最小下注额或选择结束本场时结算，筹码多于开始时为胜",
+            "简单难度：1副牌，庄家软17停牌，可投降；中等难度：6副牌，庄家软17停牌，可投降；困难难度：8副牌，庄家软17要牌，不可投降",
+            "自定义难度：自由设置牌副数、切牌位置、庄家软17规则、是否允许投降、初始筹码和最小下注额",
+            "自适应难度：根据最近战绩自动调整牌副数、切牌位置和庄家规则",
+        ]
+        .map(String::from)
+        .to_vec()
+    }
+
+    fn difficulty(&mut self) -> (&mut DifficultySelection, &mut dyn DifficultyProfile) {
+        (&mut self.difficulty_selection, &mut self.custom_profile)
+    }
+
+    fn menu_face(&self) -> &'static str {
+        "assets/card_face/SpadeA.png"
+    }
+
+    /// 显示游戏界面
+    fn show(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) -> bool {
+        if self.ctx.is_none() {
+            self.ctx = Some(ctx.clone());
+        }
+
+        let mut return_to_menu = false;
+
+        match self.game_state {
+            BlackjackState::DifficultySelection => {
+                let (menu_return, game_start) = self.show_difficulty_selection(ui);
+
+                if menu_return {
+                    return_to_menu = true;
+                    self.reset_to_difficulty_selection();
+                }
+
+                if game_start {
+                    self.game_state = BlackjackState::Initializing;
+                    self.difficulty_selection.transition_timer = Some(Instant::now());
+                }
+            }
+            BlackjackState::Initializing => {
+                if self.difficulty_selection.show_transition_animation(ui) {
+                    self.start_session();
+                    self.game_state = BlackjackState::Playing;
+                }
+            }
+            BlackjackState::Playing => {
+                self.update_dealer(ctx);
+                self.show_game_ui(ui);
+
+                // 中途返回主菜单时保存本场进度
+                if !self.session_over {
+                    ui.add_space(20.0);
+                    ui.horizontal(|ui| {
+                        ui.add_space(ui.available_width() / 2.0 - 75.0);
+                        if self.centered_button(ui, "返回主菜单", 150.0, 40.0).clicked() {
+                            return_to_menu = true;
+                            self.suspended_session = self.snapshot().map(SavedSession::Blackjack);
+                            self.reset_to_difficulty_selection();
+                        }
+                    });
+                }
+            }
+        }
+
+        return_to_menu
+    }
+
+    /// 取出刚结束的一场结果
+    fn take_result(&mut self) -> Option<GameResult> {
+        self.finished_result.take()
+    }
+
+    /// 取出本局产生的游戏事件（用于判定成就）
+    fn take_events(&mut self) -> Vec<GameEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// 取出玩家提交的排行榜成绩
+    fn take_leaderboard_entry(&mut self) -> Option<(GameKind, LeaderboardEntry)> {
+        self.score_submission.take_entry()
+    }
+
+    /// 取出中途返回主菜单时保存的存档
+    fn take_suspended_session(&mut self) -> Option<SavedSession> {
+        self.suspended_session.take()
+    }
+
+    /// 重置到难度选择界面
+    fn reset(&mut self) {
+        self.reset_to_difficulty_selection();
+    }
+
+    fn save_state(&self) -> Option<SavedSession> {
+        self.snapshot().map(SavedSession::Blackjack)
+    }
+
+    fn restore_state(&mut self, ctx: &egui::Context, session: SavedSession) {
+        if let SavedSession::Blackjack(save) = session {
+            self.restore(ctx, save);
+        }
+    }
+}
+
+impl Default for BlackjackGame {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hand_totals_count_one_ace_as_eleven_when_safe() {
+        let cases: [(&[u8], (u32, bool)); 12] = [
+            (&[1, 6], (17, true)),
+            (&[1, 1], (12, true)),
+            (&[1, 10], (21, true)),
+            (&[1, 13], (21, true)),
+            (&[1, 1, 9], (21, true)),
+            (&[1, 5, 5], (21, true)),
+            (&[1, 1, 1, 1], (14, true)),
+            (&[1, 6, 10], (17, false)),
+            (&[12, 11, 1], (21, false)),
+            (&[10, 5, 1], (16, false)),
+            (&[10, 13], (20, false)),
+            (&[9, 8, 7], (24, false)),
+        ];
+        for (ranks, expected) in cases {
+            assert_eq!(hand_total(ranks), expected, "手牌 {:?}", ranks);
+        }
+    }
+
+    #[test]
+    fn blackjack_pays_three_to_two() {
+        // 下注额是偶数的最小下注额的整数倍
+        let cases = [(2, 3), (4, 6), (10, 15), (20, 30), (50, 75), (100, 150)];
+        for (bet, winnings) in cases {
+            assert_eq!(blackjack_winnings(bet), winnings, "下注 {}", bet);
+            assert_eq!(blackjack_payout(bet, false), bet + winnings, "下注 {}", bet);
+            // 庄家也是黑杰克：平局，只退回下注
+            assert_eq!(blackjack_payout(bet, true), bet, "下注 {}", bet);
+        }
+    }
+}
@@ -0,0 +1,1069 @@
+// game5.rs - 克朗代克纸牌（经典接龙），支持拖放、翻一张/翻三张、自动完成、无限撤销和两种计分方式
+use eframe::egui;
+use crate::achievements::GameEvent;
+use crate::card::Card;
+use crate::card_game::CardGame;
+use crate::difficulty::{DifficultyProfile, DifficultySelection, GameDifficulty};
+use crate::leaderboard::{LeaderboardEntry, ScoreSubmission};
+use crate::player_profile::{GameKind, GameResult};
+use crate::save_game::SavedSession;
+use crate::solitaire::{self, DragState, CARD_H, CARD_W, is_red, rank_of, suit_of};
+use crate::timer::{self, GameTimer, TimerSnapshot};
+use std::time::{Duration, Instant};
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+
+/// 牌列之间的间距
+const COLUMN_GAP: f32 = 20.0;
+/// 上排（牌堆、废牌堆、收牌区）与牌列之间的间距
+const ROW_GAP: f32 = 24.0;
+/// 翻三张时废牌堆展开的间距
+const WASTE_FAN: f32 = 22.0;
+/// 自动完成时每张牌的间隔
+const AUTO_COMPLETE_STEP: Duration = Duration::from_millis(80);
+
+/// 计分方式
+#[derive(PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub enum ScoringMode {
+    Standard, // 标准计分
+    Vegas,    // 拉斯维加斯计分（买入52分，每张收牌+5分，限制翻牌次数）
+}
+
+impl ScoringMode {
+    /// 计分方式的显示名称
+    fn label(self) -> &'static str {
+        match self {
+            ScoringMode::Standard => "标准计分",
+            ScoringMode::Vegas => "拉斯维加斯计分",
+        }
+    }
+}
+
+/// 克朗代克难度参数
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct KlondikeProfile {
+    pub draw_count: usize,    // 每次从牌堆翻出的张数（1 或 3）
+    pub scoring: ScoringMode, // 计分方式
+}
+
+impl DifficultyProfile for KlondikeProfile {
+    fn preset(difficulty: GameDifficulty) -> Self {
+        match difficulty {
+            GameDifficulty::Easy => Self { draw_count: 1, scoring: ScoringMode::Standard },
+            GameDifficulty::Medium | GameDifficulty::Custom | GameDifficulty::Adaptive => {
+                Self { draw_count: 3, scoring: ScoringMode::Standard }
+            }
+            GameDifficulty::Hard => Self { draw_count: 3, scoring: ScoringMode::Vegas },
+        }
+    }
+
+    fn adaptive(level: f32) -> Self {
+        // 强度较低时翻一张，较高时翻三张，最高时使用拉斯维加斯计分（限制翻牌次数）
+        if level < 0.4 {
+            Self::preset(GameDifficulty::Easy)
+        } else if level < 0.8 {
+            Self::preset(GameDifficulty::Medium)
+        } else {
+            Self::preset(GameDifficulty::Hard)
+        }
+    }
+
+    fn load_preset(&mut self, difficulty: GameDifficulty) {
+        *self = Self::preset(difficulty);
+    }
+
+    fn show_editor(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("翻牌张数:");
+            ui.radio_value(&mut self.draw_count, 1, "翻一张");
+            ui.radio_value(&mut self.draw_count, 3, "翻三张");
+        });
+        ui.horizontal(|ui| {
+            ui.label("计分方式:");
+            for scoring in [ScoringMode::Standard, ScoringMode::Vegas] {
+                ui.radio_value(&mut self.scoring, scoring, scoring.label());
+            }
+        });
+    }
+}
+
+impl KlondikeProfile {
+    /// 允许把废牌堆翻回牌堆的次数（None 为不限）
+    fn max_recycles(&self) -> Option<u32> {
+        match self.scoring {
+            ScoringMode::Standard => None,
+            // 拉斯维加斯规则：翻一张只能过一遍牌堆，翻三张可以过三遍
+            ScoringMode::Vegas => Some(if self.draw_count == 1 { 0 } else { 2 }),
+        }
+    }
+}
+
+/// 牌桌上的一叠牌
+#[derive(PartialEq, Clone, Copy)]
+enum Pile {
+    Stock,             // 牌堆
+    Waste,             // 废牌堆
+    Foundation(usize), // 收牌区（4个）
+    Tableau(usize),    // 牌列（7列）
+}
+
+/// 牌桌局面（撤销时整体恢复）
+#[derive(Clone, Serialize, Deserialize)]
+struct KlondikeBoard {
+    stock: Vec<usize>,            // 牌堆（末尾为顶部）
+    waste: Vec<usize>,            // 废牌堆（末尾为顶部）
+    foundations: [Vec<usize>; 4], // 收牌区
+    tableau: [Vec<usize>; 7],     // 牌列
+    hidden: [usize; 7],           // 每列底部背面朝上的张数
+    score: i32,                   // 分数
+    recycles: u32,                // 已把废牌堆翻回牌堆的次数
+}
+
+impl KlondikeBoard {
+    /// 洗牌并发牌：第 n 列发 n 张，只有最上面一张正面朝上
+    fn deal(scoring: ScoringMode) -> Self {
+        let mut deck: Vec<usize> = (0..52).collect();
+        deck.shuffle(&mut rand::rng());
+
+        let mut tableau: [Vec<usize>; 7] = Default::default();
+        let mut hidden = [0; 7];
+        for (column, cards) in tableau.iter_mut().enumerate() {
+            cards.extend(deck.drain(..column + 1));
+            hidden[column] = column;
+        }
+
+        Self {
+            stock: deck,
+            waste: Vec::new(),
+            foundations: Default::default(),
+            tableau,
+            hidden,
+            score: if scoring == ScoringMode::Vegas { -52 } else { 0 },
+            recycles: 0,
+        }
+    }
+
+    fn pile(&self, pile: Pile) -> &Vec<usize> {
+        match pile {
+            Pile::Stock => &self.stock,
+            Pile::Waste => &self.waste,
+            Pile::Foundation(index) => &self.foundations[index],
+            Pile::Tableau(index) => &self.tableau[index],
+        }
+    }
+
+    fn pile_mut(&mut self, pile: Pile) -> &mut Vec<usize> {
+        match pile {
+            Pile::Stock => &mut self.stock,
+            Pile::Waste => &mut self.waste,
+            Pile::Foundation(index) => &mut self.foundations[index],
+            Pile::Tableau(index) => &mut self.tableau[index],
+        }
+    }
+
+    /// 从某叠牌的第 index 张开始能否拿起（废牌堆和收牌区只能拿最上面一张，牌列只能拿正面朝上的牌）
+    fn can_pick(&self, pile: Pile, index: usize) -> bool {
+        let len = self.pile(pile).len();
+        match pile {
+            Pile::Stock => false,
+            Pile::Waste | Pile::Foundation(_) => index + 1 == len,
+            Pile::Tableau(column) => index >= self.hidden[column] && index < len,
+        }
+    }
+
+    /// 一组牌（第一张在最下面）能否放到目标位置
+    fn can_place(&self, cards: &[usize], to: Pile) -> bool {
+        let Some(&first) = cards.first() else {
+            return false;
+        };
+        match to {
+            Pile::Foundation(index) => {
+                // 收牌区：同花色从 A 到 K 依次放
+                cards.len() == 1
+                    && match self.foundations[index].last() {
+                        None => rank_of(first) == 1,
+                        Some(&top) => suit_of(top) == suit_of(first) && rank_of(first) == rank_of(top) + 1,
+                    }
+            }
+            Pile::Tableau(index) => {
+                // 牌列：红黑相间、点数递减，空列只能放 K
+                match self.tableau[index].last() {
+                    None => rank_of(first) == 13,
+                    Some(&top) => is_red(top) != is_red(first) && rank_of(first) + 1 == rank_of(top),
+                }
+            }
+            Pile::Stock | Pile::Waste => false,
+        }
+    }
+
+    /// 能否把某叠牌从第 index 张开始移到目标位置（收牌区之间不能互相移动）
+    fn can_move(&self, from: Pile, index: usize, to: Pile) -> bool {
+        if from == to || matches!((from, to), (Pile::Foundation(_), Pile::Foundation(_))) {
+            return false;
+        }
+        self.can_pick(from, index) && self.can_place(&self.pile(from)[index..], to)
+    }
+
+    /// 牌列最上面的牌被移走后翻开新的顶牌，返回是否翻开了
+    fn reveal_top(&mut self, column: usize) -> bool {
+        let len = self.tableau[column].len();
+        if len > 0 && self.hidden[column] >= len {
+            self.hidden[column] = len - 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// 某张牌能放进的收牌区
+    fn foundation_for(&self, id: usize) -> Option<usize> {
+        (0..4).find(|&index| self.can_place(&[id], Pile::Foundation(index)))
+    }
+
+    /// 是否已全部收齐
+    fn is_won(&self) -> bool {
+        self.foundations.iter().all(|foundation| foundation.len() == 13)
+    }
+
+    /// 是否可以自动完成：牌堆和废牌堆已空，牌列全部翻开
+    fn can_auto_complete(&self) -> bool {
+        self.stock.is_empty()
+            && self.waste.is_empty()
+            && self.tableau.iter().any(|column| !column.is_empty())
+            && self.hidden.iter().zip(&self.tableau).all(|(&hidden, column)| hidden == 0 || column.is_empty())
+    }
+}
+
+/// 一步移动的得分：标准计分废牌到牌列 +5、收牌 +10、从收牌区移回 -15；拉斯维加斯计分收牌 +5、移回 -5
+fn move_score(scoring: ScoringMode, from: Pile, to: Pile) -> i32 {
+    match (scoring, from, to) {
+        // 收牌区之间的移动不计分（也不允许）
+        (_, Pile::Foundation(_), Pile::Foundation(_)) => 0,
+        (ScoringMode::Standard, Pile::Waste, Pile::Tableau(_)) => 5,
+        (ScoringMode::Standard, _, Pile::Foundation(_)) => 10,
+        (ScoringMode::Standard, Pile::Foundation(_), _) => -15,
+        (ScoringMode::Vegas, _, Pile::Foundation(_)) => 5,
+        (ScoringMode::Vegas, Pile::Foundation(_), _) => -5,
+        _ => 0,
+    }
+}
+
+/// 克朗代克未完成一局的存档
+#[derive(Serialize, Deserialize)]
+pub struct KlondikeSave {
+    difficulty: GameDifficulty,
+    adaptive_level: f32,
+    custom_profile: KlondikeProfile,
+    board: KlondikeBoard,
+    history: Vec<KlondikeBoard>,
+    moves: usize,
+    timer: Option<TimerSnapshot>,
+}
+
+/// 牌桌布局（每帧根据可用区域计算）
+struct TableLayout {
+    origin: egui::Pos2, // 牌桌左上角
+    bottom: f32,        // 牌桌底边
+    draw_count: usize,  // 翻牌张数（决定废牌堆展开几张）
+}
+
+impl TableLayout {
+    /// 某叠牌的底座位置
+    fn slot(&self, pile: Pile) -> egui::Rect {
+        let (column, y) = match pile {
+            Pile::Stock => (0, self.origin.y),
+            Pile::Waste => (1, self.origin.y),
+            Pile::Foundation(index) => (3 + index, self.origin.y),
+            Pile::Tableau(index) => (index, self.origin.y + CARD_H + ROW_GAP),
+        };
+        let x = self.origin.x + column as f32 * (CARD_W + COLUMN_GAP);
+        egui::Rect::from_min_size(egui::pos2(x, y), egui::vec2(CARD_W, CARD_H))
+    }
+
+    /// 牌列中背面 / 正面卡片的间距，列太长放不下时压缩
+    fn tableau_offsets(&self, len: usize, hidden: usize) -> (f32, f32) {
+        let available = self.bottom - self.slot(Pile::Tableau(0)).min.y - CARD_H;
+        solitaire::column_offsets(len, hidden, available)
+    }
+
+    /// 某叠牌中第 index 张牌的位置
+    fn card_rect(&self, board: &KlondikeBoard, pile: Pile, index: usize) -> egui::Rect {
+        let slot = self.slot(pile);
+        match pile {
+            Pile::Waste => {
+                // 翻三张时展开最上面的三张
+                let fan_start = board.waste.len().saturating_sub(self.draw_count);
+                slot.translate(egui::vec2(index.saturating_sub(fan_start) as f32 * WASTE_FAN, 0.0))
+            }
+            Pile::Tableau(column) => {
+                let (hidden_offset, shown_offset) = self.tableau_offsets(board.tableau[column].len(), board.hidden[column]);
+                let hidden = board.hidden[column].min(index) as f32;
+                let shown = index as f32 - hidden;
+                slot.translate(egui::vec2(0.0, hidden * hidden_offset + shown * shown_offset))
+            }
+            Pile::Stock | Pile::Foundation(_) => slot,
+        }
+    }
+
+    /// 牌桌总宽度
+    fn width() -> f32 {
+        7.0 * CARD_W + 6.0 * COLUMN_GAP
+    }
+}
+
+/// 克朗代克游戏状态
+#[derive(PartialEq, Clone, Copy)]
+enum KlondikeState {
+    DifficultySelection,
+    Playing,
+    GameOver,
+}
+
+/// 克朗代克纸牌游戏
+pub struct KlondikeGame {
+    difficulty_selection: DifficultySelection,
+    state: KlondikeState,
+
+    // 52张卡片，下标即卡片编号
+    cards: Vec<Card>,
+    // 当前局面
+    board: KlondikeBoard,
+    // 历史局面（用于无限撤销）
+    history: Vec<KlondikeBoard>,
+    // 移动次数
+    moves: usize,
+    // 正计时
+    game_timer: Option<GameTimer>,
+    // 正在拖动的牌
+    drag: Option<DragState<Pile>>,
+    // 自动完成中，上一步的时间
+    auto_complete: Option<Instant>,
+    // 是否获胜
+    game_won: bool,
+    // 提示消息
+    message: String,
+
+    // 自定义难度参数
+    custom_profile: KlondikeProfile,
+    // 刚结束的一局结果，等待主程序记录
+    finished_result: Option<GameResult>,
+    // 等待主程序处理的游戏事件
+    events: Vec<GameEvent>,
+    // 结束界面的排行榜成绩提交
+    score_submission: ScoreSubmission,
+    // 中途返回主菜单时保存的存档
+    suspended_session: Option<SavedSession>,
+}
+
+impl KlondikeGame {
+    pub fn new() -> Self {
+        Self {
+            difficulty_selection: DifficultySelection::new(GameKind::Klondike),
+            state: KlondikeState::DifficultySelection,
+            cards: Vec::new(),
+            board: KlondikeBoard::deal(ScoringMode::Standard),
+            history: Vec::new(),
+            moves: 0,
+            game_timer: None,
+            drag: None,
+            auto_complete: None,
+            game_won: false,
+            message: String::new(),
+            custom_profile: KlondikeProfile::preset(GameDifficulty::Custom),
+            finished_result: None,
+            events: Vec::new(),
+            score_submission: ScoreSubmission::new(),
+            suspended_session: None,
+        }
+    }
+
+    /// 当前难度的参数
+    fn profile(&self) -> KlondikeProfile {
+        self.difficulty_selection.profile(&self.custom_profile)
+    }
+
+    /// 加载52张卡片的纹理（下标即卡片编号）
+    fn load_cards(&mut self, ctx: &egui::Context) {
+        if self.cards.len() == 52 {
+            return;
+        }
+        self.cards = solitaire::load_cards(ctx, 0..52);
+    }
+
+    /// 开始新的一局
+    fn start_game(&mut self, ctx: &egui::Context) {
+        self.load_cards(ctx);
+        for card in &mut self.cards {
+            card.is_face_up = false;
+            card.animation = None;
+        }
+        self.board = KlondikeBoard::deal(self.profile().scoring);
+        self.history.clear();
+        self.moves = 0;
+        self.drag = None;
+        self.auto_complete = None;
+        self.game_won = false;
+        self.message = "拖动卡片移动，双击卡片直接收牌，点击牌堆翻牌".to_string();
+        self.game_timer = Some(GameTimer::stopwatch());
+        self.state = KlondikeState::Playing;
+    }
+
+    /// 按局面翻转卡片的正反面（animate 为 false 时直接翻转，用于恢复存档）
+    fn sync_faces(&mut self, animate: bool) {
+        let board = &self.board;
+        let mut faces = vec![false; 52];
+        for &id in board.waste.iter().chain(board.foundations.iter().flatten()) {
+            faces[id] = true;
+        }
+        for (column, cards) in board.tableau.iter().enumerate() {
+            for &id in &cards[board.hidden[column].min(cards.len())..] {
+                faces[id] = true;
+            }
+        }
+        for (card, face_up) in self.cards.iter_mut().zip(faces) {
+            if animate {
+                card.flip_to(face_up);
+            } else {
+                card.is_face_up = face_up;
+            }
+        }
+    }
+
+    /// 记录当前局面以便撤销
+    fn push_history(&mut self) {
+        self.history.push(self.board.clone());
+    }
+
+    /// 撤销上一步
+    fn undo(&mut self) {
+        if let Some(board) = self.history.pop() {
+            self.board = board;
+            self.auto_complete = None;
+            self.message = "已撤销".to_string();
+        }
+    }
+
+    /// 调整分数（标准计分不低于0）
+    fn add_score(&mut self, delta: i32) {
+        self.board.score += delta;
+        if self.profile().scoring == ScoringMode::Standard {
+            self.board.score = self.board.score.max(0);
+        }
+    }
+
+    /// 点击牌堆：翻牌到废牌堆，牌堆为空时把废牌堆翻回（受计分方式限制）
+    fn click_stock(&mut self) {
+        let profile = self.profile();
+        if !self.board.stock.is_empty() {
+            self.push_history();
+            for _ in 0..profile.draw_count {
+                if let Some(id) = self.board.stock.pop() {
+                    self.board.waste.push(id);
+                }
+            }
+            self.moves += 1;
+        } else if !self.board.waste.is_empty() {
+            if profile.max_recycles().is_some_and(|max| self.board.recycles >= max) {
+                self.message = "拉斯维加斯计分：已经不能再翻牌了".to_string();
+                return;
+            }
+            self.push_history();
+            let waste = std::mem::take(&mut self.board.waste);
+            self.board.stock = waste.into_iter().rev().collect();
+            self.board.recycles += 1;
+            self.moves += 1;
+            if profile.scoring == ScoringMode::Standard {
+                // 标准计分：翻一张每次重新翻牌扣100分，翻三张扣20分
+                self.add_score(if profile.draw_count == 1 { -100 } else { -20 });
+            }
+        }
+    }
+
+    /// 移动卡片（调用前已检查合法），并按计分方式加减分
+    fn move_cards(&mut self, from: Pile, index: usize, to: Pile) {
+        self.push_history();
+        let moving = self.board.pile_mut(from).split_off(index);
+        self.board.pile_mut(to).extend(moving);
+        self.moves += 1;
+
+        let scoring = self.profile().scoring;
+        self.add_score(move_score(scoring, from, to));
+
+        if let Pile::Tableau(column) = from
+            && self.board.reveal_top(column)
+            && scoring == ScoringMode::Standard
+        {
+            // 标准计分：翻开牌列中的牌加5分
+            self.add_score(5);
+        }
+
+        if self.board.is_won() {
+            self.finish_game(true);
+        }
+    }
+
+    /// 尝试把某叠牌最上面的一张放进收牌区，返回是否成功
+    fn move_to_foundation(&mut self, pile: Pile) -> bool {
+        let Some(&top) = self.board.pile(pile).last() else {
+            return false;
+        };
+        if !self.board.can_pick(pile, self.board.pile(pile).len() - 1) || matches!(pile, Pile::Foundation(_)) {
+            return false;
+        }
+        let Some(foundation) = self.board.foundation_for(top) else {
+            return false;
+        };
+        let index = self.board.pile(pile).len() - 1;
+        self.move_cards(pile, index, Pile::Foundation(foundation));
+        true
+    }
+
+    /// 自动完成：每隔一小段时间把一张牌收进收牌区（优先点数小的）
+    fn update_auto_complete(&mut self, ctx: &egui::Context) {
+        let Some(last_step) = self.auto_complete else {
+            return;
+        };
+        if last_step.elapsed() < AUTO_COMPLETE_STEP {
+            ctx.request_repaint_after(AUTO_COMPLETE_STEP - last_step.elapsed());
+            return;
+        }
+
+        let next = (0..7)
+            .map(Pile::Tableau)
+            .chain([Pile::Waste])
+            .filter_map(|pile| {
+                let &top = self.board.pile(pile).last()?;
+                self.board.foundation_for(top).map(|_| (rank_of(top), pile))
+            })
+            .min_by_key(|&(rank, _)| rank);
+
+        match next {
+            Some((_, pile)) => {
+                self.move_to_foundation(pile);
+                self.auto_complete = (self.state == KlondikeState::Playing).then(Instant::now);
+            }
+            None => self.auto_complete = None,
+        }
+    }
+
+    /// 结束本局并记录结果
+    fn finish_game(&mut self, won: bool) {
+        self.game_won = won;
+        self.state = KlondikeState::GameOver;
+        self.auto_complete = None;
+        self.drag = None;
+        self.difficulty_selection.record_result(won);
+
+        let difficulty = self.difficulty_selection.selected_difficulty.unwrap_or(GameDifficulty::Easy);
+        let mut result = GameResult::new(GameKind::Klondike, difficulty, won);
+        result.time_secs = self.game_timer.as_ref().map(|timer| timer.elapsed().as_secs());
+        result.moves = Some(self.moves);
+        result.final_score = Some(self.board.score as i64);
+        if won {
+            self.events.push(GameEvent::SolitaireCleared { time_secs: result.time_secs });
+        }
+        self.score_submission.offer(&result);
+        self.finished_result = Some(result);
+        if let Some(timer) = &mut self.game_timer {
+            timer.pause();
+        }
+    }
+
+    /// 指针下最上面的一张牌
+    fn card_at(&self, layout: &TableLayout, pos: egui::Pos2) -> Option<(Pile, usize)> {
+        for column in 0..7 {
+            let pile = Pile::Tableau(column);
+            for index in (0..self.board.tableau[column].len()).rev() {
+                if layout.card_rect(&self.board, pile, index).contains(pos) {
+                    return Some((pile, index));
+                }
+            }
+        }
+        for pile in [Pile::Waste, Pile::Foundation(0), Pile::Foundation(1), Pile::Foundation(2), Pile::Foundation(3)] {
+            let len = self.board.pile(pile).len();
+            if len > 0 && layout.card_rect(&self.board, pile, len - 1).contains(pos) {
+                return Some((pile, len - 1));
+            }
+        }
+        None
+    }
+
+    /// 松开拖动的牌：放到与它重叠最多的合法位置
+    fn drop_cards(&mut self, layout: &TableLayout, drag: DragState<Pile>, pointer: egui::Pos2) {
+        let dragged = drag.rect(pointer);
+        let target = (0..4)
+            .map(Pile::Foundation)
+            .chain((0..7).map(Pile::Tableau))
+            .filter(|&pile| self.board.can_move(drag.from, drag.index, pile))
+            .map(|pile| {
+                let len = self.board.pile(pile).len();
+                let rect = if len == 0 {
+                    layout.slot(pile)
+                } else {
+                    layout.card_rect(&self.board, pile, len - 1)
+                };
+                (pile, rect.intersect(dragged).area())
+            })
+            .filter(|&(_, area)| area > 0.0)
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+
+        if let Some((to, _)) = target {
+            self.move_cards(drag.from, drag.index, to);
+        }
+    }
+
+    /// 处理牌桌上的点击、双击和拖放
+    fn handle_input(&mut self, layout: &TableLayout, response: &egui::Response) {
+        let press_origin = response.ctx.input(|input| input.pointer.press_origin());
+
+        if response.drag_started()
+            && let Some(origin) = press_origin
+            && let Some((pile, index)) = self.card_at(layout, origin)
+            && self.board.can_pick(pile, index)
+        {
+            let rect = layout.card_rect(&self.board, pile, index);
+            self.drag = Some(DragState { from: pile, index, grab_offset: origin - rect.min });
+        }
+
+        if response.drag_stopped()
+            && let Some(drag) = self.drag.take()
+            && let Some(pointer) = response.interact_pointer_pos()
+        {
+            self.drop_cards(layout, drag, pointer);
+            return;
+        }
+
+        let Some(pointer) = response.interact_pointer_pos() else {
+            return;
+        };
+        if response.clicked() && layout.slot(Pile::Stock).contains(pointer) {
+            self.click_stock();
+        } else if response.double_clicked()
+            && let Some((pile, index)) = self.card_at(layout, pointer)
+            && index + 1 == self.board.pile(pile).len()
+        {
+            // 只有双击一叠牌最上面的那张才自动收牌
+            self.move_to_foundation(pile);
+        }
+    }
+
+    /// 绘制牌桌
+    fn paint_table(&self, painter: &egui::Painter, layout: &TableLayout, pointer: Option<egui::Pos2>) {
+        let dragging = |pile: Pile, index: usize| DragState::contains(&self.drag, pile, index);
+
+        // 牌堆：显示背面和剩余张数，空时显示能否重新翻牌
+        let stock_slot = layout.slot(Pile::Stock);
+        if let Some(&top) = self.board.stock.last() {
+            self.cards[top].paint(painter, stock_slot);
+            painter.text(
+                stock_slot.center_bottom() + egui::vec2(0.0, 10.0),
+                egui::Align2::CENTER_CENTER,
+                format!("{}", self.board.stock.len()),
+                egui::FontId::proportional(13.0),
+                egui::Color32::WHITE,
+            );
+        } else {
+            let can_recycle = self.profile().max_recycles().is_none_or(|max| self.board.recycles < max);
+            solitaire::paint_slot(painter, stock_slot, if can_recycle { "↻" } else { "✖" });
+        }
+
+        // 废牌堆：只画最上面展开的几张和它下面的一张
+        solitaire::paint_slot(painter, layout.slot(Pile::Waste), "");
+        let waste_len = self.board.waste.len();
+        let first_visible = waste_len.saturating_sub(layout.draw_count + 1);
+        for index in first_visible..waste_len {
+            if !dragging(Pile::Waste, index) {
+                self.cards[self.board.waste[index]].paint(painter, layout.card_rect(&self.board, Pile::Waste, index));
+            }
+        }
+
+        // 收牌区
+        for foundation in 0..4 {
+            let pile = Pile::Foundation(foundation);
+            let slot = layout.slot(pile);
+            solitaire::paint_slot(painter, slot, "A");
+            let cards = &self.board.foundations[foundation];
+            let visible = cards.len().saturating_sub(2);
+            for (index, &id) in cards.iter().enumerate().skip(visible) {
+                if !dragging(pile, index) {
+                    self.cards[id].paint(painter, slot);
+                }
+            }
+        }
+
+        // 牌列
+        for column in 0..7 {
+            let pile = Pile::Tableau(column);
+            solitaire::paint_slot(painter, layout.slot(pile), "");
+            for (index, &id) in self.board.tableau[column].iter().enumerate() {
+                if !dragging(pile, index) {
+                    self.cards[id].paint(painter, layout.card_rect(&self.board, pile, index));
+                }
+            }
+        }
+
+        // 拖动中的牌画在最上层，跟随指针
+        if let Some(drag) = &self.drag
+            && let Some(pointer) = pointer
+        {
+            let drag_painter = solitaire::drag_painter(painter.ctx());
+            let base = drag.rect(pointer);
+            let first_rect = layout.card_rect(&self.board, drag.from, drag.index);
+            for (index, &id) in self.board.pile(drag.from).iter().enumerate().skip(drag.index) {
+                let offset = layout.card_rect(&self.board, drag.from, index).min - first_rect.min;
+                self.cards[id].paint(&drag_painter, base.translate(offset));
+            }
+        }
+    }
+
+    /// 显示游戏进行界面，返回是否要返回主菜单
+    fn show_game_playing(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) -> bool {
+        let mut return_to_menu = false;
+        let profile = self.profile();
+
+        // 顶部信息和操作按钮
+        ui.horizontal(|ui| {
+            ui.add_space((ui.available_width() - TableLayout::width()) / 2.0);
+            ui.vertical(|ui| {
+                ui.colored_label(egui::Color32::GOLD, format!("分数: {}", self.board.score));
+                ui.label(format!("移动次数: {}", self.moves));
+            });
+            ui.add_space(20.0);
+            ui.vertical(|ui| {
+                if let Some(timer) = &self.game_timer {
+                    ui.label(format!("用时: {}", timer.elapsed_text()));
+                }
+                let recycles = match profile.max_recycles() {
+                    Some(max) => format!("，剩余重新翻牌 {} 次", max.saturating_sub(self.board.recycles)),
+                    None => String::new(),
+                };
+                ui.label(format!(
+                    "{}，{}{}",
+                    if profile.draw_count == 1 { "翻一张" } else { "翻三张" },
+                    profile.scoring.label(),
+                    recycles
+                ));
+            });
+            ui.add_space(20.0);
+
+            let undo_key = ui.input(|input| input.modifiers.command && input.key_pressed(egui::Key::Z));
+            if ui.add_enabled(!self.history.is_empty(), egui::Button::new("撤销 (Ctrl+Z)")).clicked() || undo_key {
+                self.undo();
+            }
+            if ui.add_enabled(self.board.can_auto_complete() && self.auto_complete.is_none(), egui::Button::new("自动完成")).clicked() {
+                self.auto_complete = Some(Instant::now());
+            }
+            if ui.button("暂停 (Esc)").clicked()
+                && let Some(timer) = &mut self.game_timer
+            {
+                timer.pause();
+            }
+            if ui.button("放弃本局").clicked() {
+                self.message = "你放弃了本局".to_string();
+                self.finish_game(false);
+            }
+            if ui.button("返回主菜单").clicked() {
+                return_to_menu = true;
+                self.suspended_session = self.snapshot().map(SavedSession::Klondike);
+                self.reset_game_state();
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.add_space((ui.available_width() - TableLayout::width()) / 2.0);
+            ui.colored_label(egui::Color32::BLACK, &self.message);
+        });
+        ui.add_space(10.0);
+
+        if self.state != KlondikeState::Playing {
+            return return_to_menu;
+        }
+        if self.cards.len() < 52 {
+            ui.colored_label(egui::Color32::RED, "卡片图片加载失败");
+            return return_to_menu;
+        }
+
+        // 牌桌
+        let available = ui.available_size();
+        let (rect, response) = ui.allocate_exact_size(available, egui::Sense::click_and_drag());
+        let layout = TableLayout {
+            origin: egui::pos2(rect.center().x - TableLayout::width() / 2.0, rect.min.y),
+            bottom: rect.max.y - 10.0,
+            draw_count: profile.draw_count,
+        };
+
+        if self.auto_complete.is_none() {
+            self.handle_input(&layout, &response);
+        }
+        self.update_auto_complete(ctx);
+        self.sync_faces(true);
+
+        let pointer = ctx.input(|input| input.pointer.interact_pos());
+        self.paint_table(ui.painter(), &layout, pointer);
+
+        return_to_menu
+    }
+
+    /// 显示游戏结束界面
+    fn show_game_over(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) -> bool {
+        let mut return_to_menu = false;
+
+        ui.vertical_centered(|ui| {
+            ui.add_space(ui.available_height() / 2.0 - 120.0);
+            if self.game_won {
+                ui.colored_label(egui::Color32::GOLD, "恭喜你完成了克朗代克！");
+            } else {
+                ui.colored_label(egui::Color32::RED, "本局结束");
+            }
+            let foundation_cards: usize = self.board.foundations.iter().map(Vec::len).sum();
+            ui.label(format!("收牌 {}/52 张，分数 {}", foundation_cards, self.board.score));
+            ui.label(format!("移动次数: {}", self.moves));
+            if let Some(timer) = &self.game_timer {
+                ui.label(format!("用时: {}", timer.elapsed_text()));
+            }
+
+            ui.add_space(10.0);
+            self.score_submission.show(ui);
+            ui.add_space(20.0);
+
+            ui.horizontal(|ui| {
+                ui.add_space(ui.available_width() / 2.0 - 200.0);
+                if self.centered_button(ui, "再来一局", 120.0, 40.0).clicked() {
+                    self.start_game(ctx);
+                }
+                ui.add_space(20.0);
+                if self.centered_button(ui, "选择难度", 120.0, 40.0).clicked() {
+                    self.reset_game_state();
+                }
+                ui.add_space(20.0);
+                if self.centered_button(ui, "返回主菜单", 120.0, 40.0).clicked() {
+                    return_to_menu = true;
+                    self.reset_game_state();
+                }
+            });
+        });
+
+        return_to_menu
+    }
+
+    /// 创建居中的按钮
+    fn centered_button(&self, ui: &mut egui::Ui, text: &str, width: f32, height: f32) -> egui::Response {
+        ui.add_sized(
+            egui::vec2(width, height),
+            egui::Button::new(
+                egui::RichText::new(text)
+                    .text_style(egui::TextStyle::Button)
+                    .color(egui::Color32::BLACK),
+            ),
+        )
+    }
+
+    /// 保存进行中的一局，没有进行中的游戏时返回 None
+    fn snapshot(&self) -> Option<KlondikeSave> {
+        if self.state != KlondikeState::Playing {
+            return None;
+        }
+        Some(KlondikeSave {
+            difficulty: self.difficulty_selection.selected_difficulty?,
+            adaptive_level: self.difficulty_selection.adaptive.level,
+            custom_profile: self.custom_profile,
+            board: self.board.clone(),
+            history: self.history.clone(),
+            moves: self.moves,
+            timer: self.game_timer.as_ref().map(GameTimer::snapshot),
+        })
+    }
+
+    /// 从存档恢复一局，计时器处于暂停状态
+    fn restore(&mut self, ctx: &egui::Context, save: KlondikeSave) {
+        self.reset_game_state();
+        self.difficulty_selection.restore(save.difficulty, save.adaptive_level);
+        self.custom_profile = save.custom_profile;
+        self.load_cards(ctx);
+        self.board = save.board;
+        self.history = save.history;
+        self.moves = save.moves;
+        self.game_timer = save.timer.map(GameTimer::restore);
+        self.sync_faces(false);
+        self.message = "已恢复上次的进度".to_string();
+        self.state = KlondikeState::Playing;
+    }
+
+    /// 重置到难度选择界面
+    fn reset_game_state(&mut self) {
+        self.difficulty_selection.reset();
+        self.state = KlondikeState::DifficultySelection;
+        self.history.clear();
+        self.moves = 0;
+        self.game_timer = None;
+        self.drag = None;
+        self.auto_complete = None;
+        self.game_won = false;
+        self.message.clear();
+        self.score_submission.clear();
+    }
+}
+
+impl CardGame for KlondikeGame {
+    fn kind(&self) -> GameKind {
+        GameKind::Klondike
+    }
+
+    fn name(&self) -> &'static str {
+        "克朗代克纸牌"
+    }
+
+    fn rules(&self) -> Vec<String> {
+        [
+            "目标：把52张牌按花色从 A 到 K 依次收进右上方的4个收牌区",
+            "牌列中的牌按红黑相间、点数递减叠放，可以整叠移动正面朝上的牌，空列只能放 K",
+            "点击左上角的牌堆翻牌到废牌堆，废牌堆最上面的牌可以移到牌列或收牌区",
+            "拖动卡片移动，双击卡片直接放进收牌区；牌列中的背面牌露出时自动翻开",
+            "撤销次数不限（Ctrl+Z），牌全部翻开后可以自动完成",
+            "标准计分：废牌到牌列+5，收牌+10，翻开牌列中的牌+5，从收牌区移回-15，重新翻牌扣分（翻一张-100，翻三张-20）",
+            "拉斯维加斯计分：开局-52分，每收一张牌+5分，移回-5分；翻一张只能过一遍牌堆，翻三张可以过三遍",
+            "简单难度：翻一张、标准计分；中等难度：翻三张、标准计分；困难难度：翻三张、拉斯维加斯计分",
+            "自定义难度：自由选择翻牌张数和计分方式；自适应难度：根据最近战绩自动调整",
+            "按 Esc 可以暂停计时",
+        ]
+        .map(String::from)
+        .to_vec()
+    }
+
+    fn difficulty(&mut self) -> (&mut DifficultySelection, &mut dyn DifficultyProfile) {
+        (&mut self.difficulty_selection, &mut self.custom_profile)
+    }
+
+    fn menu_face(&self) -> &'static str {
+        "assets/card_face/HeartK.png"
+    }
+
+    /// 显示游戏界面，返回是否要返回主菜单
+    fn show(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) -> bool {
+        let mut return_to_menu = false;
+
+        // 处理过渡动画
+        if self.difficulty_selection.is_in_transition() {
+            if self.difficulty_selection.show_transition_animation(ui) {
+                self.start_game(ctx);
+            }
+            return return_to_menu;
+        }
+
+        // 更新计时器
+        if self.state == KlondikeState::Playing
+            && let Some(timer) = &mut self.game_timer
+        {
+            timer.handle_pause_input(ctx);
+        }
+
+        match self.state {
+            KlondikeState::DifficultySelection => {
+                let (menu_return, _) = self.show_difficulty_selection(ui);
+                return_to_menu = menu_return;
+            }
+            KlondikeState::Playing => {
+                return_to_menu = self.show_game_playing(ui, ctx);
+            }
+            KlondikeState::GameOver => {
+                return_to_menu = self.show_game_over(ui, ctx);
+            }
+        }
+
+        // 暂停时显示遮罩
+        if self.state == KlondikeState::Playing
+            && let Some(timer) = &mut self.game_timer
+            && timer.is_paused()
+            && timer::show_pause_overlay(ctx)
+        {
+            timer.resume();
+        }
+
+        return_to_menu
+    }
+
+    /// 取出刚结束的一局结果
+    fn take_result(&mut self) -> Option<GameResult> {
+        self.finished_result.take()
+    }
+
+    /// 取出本局产生的游戏事件（用于判定成就）
+    fn take_events(&mut self) -> Vec<GameEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// 取出玩家提交的排行榜成绩
+    fn take_leaderboard_entry(&mut self) -> Option<(GameKind, LeaderboardEntry)> {
+        self.score_submission.take_entry()
+    }
+
+    /// 取出中途返回主菜单时保存的存档
+    fn take_suspended_session(&mut self) -> Option<SavedSession> {
+        self.suspended_session.take()
+    }
+
+    /// 重置到难度选择界面
+    fn reset(&mut self) {
+        self.reset_game_state();
+    }
+
+    fn save_state(&self) -> Option<SavedSession> {
+        self.snapshot().map(SavedSession::Klondike)
+    }
+
+    fn restore_state(&mut self, ctx: &egui::Context, session: SavedSession) {
+        if let SavedSession::Klondike(save) = session {
+            self.restore(ctx, save);
+        }
+    }
+}
+
+impl Default for KlondikeGame {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solitaire::card_id;
+
+    /// 空牌桌，收牌区 0 放着黑桃 A
+    fn board_with_ace() -> KlondikeBoard {
+        let mut board = KlondikeBoard::deal(ScoringMode::Standard);
+        board.stock.clear();
+        board.waste.clear();
+        board.tableau = Default::default();
+        board.hidden = [0; 7];
+        board.foundations[0].push(card_id(1, 1));
+        board
+    }
+
+    #[test]
+    fn ace_cannot_hop_between_foundations() {
+        let board = board_with_ace();
+        for to in 1..4 {
+            assert!(board.can_place(&[card_id(1, 1)], Pile::Foundation(to)));
+            assert!(!board.can_move(Pile::Foundation(0), 0, Pile::Foundation(to)));
+        }
+        assert!(!board.can_move(Pile::Foundation(0), 0, Pile::Foundation(0)));
+        for scoring in [ScoringMode::Standard, ScoringMode::Vegas] {
+            assert_eq!(move_score(scoring, Pile::Foundation(0), Pile::Foundation(1)), 0);
+        }
+    }
+
+    #[test]
+    fn legal_moves_are_scored() {
+        let mut board = board_with_ace();
+        board.waste.push(card_id(2, 1));
+        board.tableau[0].push(card_id(3, 2));
+        assert!(board.can_move(Pile::Waste, 0, Pile::Foundation(0)));
+        assert!(board.can_move(Pile::Waste, 0, Pile::Tableau(0)));
+        assert!(!board.can_move(Pile::Waste, 0, Pile::Foundation(1)));
+        assert_eq!(move_score(ScoringMode::Standard, Pile::Waste, Pile::Foundation(0)), 10);
+        assert_eq!(move_score(ScoringMode::Standard, Pile::Waste, Pile::Tableau(0)), 5);
+        assert_eq!(move_score(ScoringMode::Standard, Pile::Foundation(0), Pile::Tableau(0)), -15);
+        assert_eq!(move_score(ScoringMode::Vegas, Pile::Tableau(0), Pile::Foundation(0)), 5);
+        assert_eq!(move_score(ScoringMode::Vegas, Pile::Foundation(0), Pile::Tableau(0)), -5);
+    }
+}
@@ -0,0 +1,1044 @@
+// game6.rs - 空当接龙，使用与 Windows 空当接龙相同的编号发牌，支持超级移动、无限撤销和求解器提示
+use eframe::egui;
+use crate::achievements::GameEvent;
+use crate::card::Card;
+use crate::card_game::CardGame;
+use crate::difficulty::{DifficultyProfile, DifficultySelection, GameDifficulty};
+use crate::freecell_solver::{self, Hint, SolverJob, SolverResult};
+use crate::leaderboard::{LeaderboardEntry, ScoreSubmission};
+use crate::player_profile::{GameKind, GameResult};
+use crate::save_game::SavedSession;
+use crate::solitaire::{self, DragState, CARD_H, CARD_W, card_id, card_name, is_red, rank_of, suit_of};
+use crate::timer::{self, GameTimer, TimerSnapshot};
+use std::time::Duration;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// 牌列之间的间距
+const COLUMN_GAP: f32 = 14.0;
+/// 上排（空当、收牌区）与牌列之间的间距
+const ROW_GAP: f32 = 24.0;
+/// 经典牌局编号的范围
+const MAX_DEAL_NUMBER: u32 = 1_000_000;
+/// 等待求解结果时的刷新间隔
+const SOLVER_POLL: Duration = Duration::from_millis(100);
+
+/// 按 Windows 空当接龙的编号发牌（线性同余随机数），8 列依次发 52 张
+fn ms_deal(deal_number: u32) -> [Vec<usize>; 8] {
+    // 原版的牌序为 A♣ A♦ A♥ A♠ 2♣ …，花色对应本项目的编号（1 黑桃、2 红桃、3 方片、4 梅花）
+    const SUITS: [u8; 4] = [4, 3, 2, 1];
+    let mut deck: Vec<usize> = (0..52).map(|index| card_id(index as u8 / 4 + 1, SUITS[index % 4])).collect();
+
+    let mut seed = deal_number;
+    let mut columns: [Vec<usize>; 8] = Default::default();
+    for dealt in 0..52 {
+        seed = seed.wrapping_mul(214013).wrapping_add(2531011) & 0x7fff_ffff;
+        let left = 52 - dealt;
+        let index = (seed >> 16) as usize % left;
+        columns[dealt % 8].push(deck[index]);
+        deck[index] = deck[left - 1];
+    }
+    columns
+}
+
+/// 空当接龙难度参数
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct FreeCellProfile {
+    pub free_cells: usize,       // 空当数量
+    pub max_hints: Option<u32>,  // 每局可用的提示次数（None 为不限）
+}
+
+impl DifficultyProfile for FreeCellProfile {
+    fn preset(difficulty: GameDifficulty) -> Self {
+        match difficulty {
+            GameDifficulty::Easy => Self { free_cells: 4, max_hints: None },
+            GameDifficulty::Medium | GameDifficulty::Custom | GameDifficulty::Adaptive => {
+                Self { free_cells: 4, max_hints: Some(3) }
+            }
+            GameDifficulty::Hard => Self { free_cells: 3, max_hints: Some(0) },
+        }
+    }
+
+    fn adaptive(level: f32) -> Self {
+        // 强度越高提示越少，最高时只剩 3 个空当
+        Self {
+            free_cells: if level >= 0.8 { 3 } else { 4 },
+            max_hints: Some(crate::difficulty::lerp(6.0, 0.0, level).round() as u32),
+        }
+    }
+
+    fn load_preset(&mut self, difficulty: GameDifficulty) {
+        *self = Self::preset(difficulty);
+    }
+
+    fn show_editor(&mut self, ui: &mut egui::Ui) {
+        ui.add(egui::Slider::new(&mut self.free_cells, 1..=4).text("空当数量"));
+        ui.horizontal(|ui| {
+            let mut unlimited = self.max_hints.is_none();
+            if ui.checkbox(&mut unlimited, "不限提示次数").changed() {
+                self.max_hints = if unlimited { None } else { Some(3) };
+            }
+            if let Some(hints) = &mut self.max_hints {
+                ui.add(egui::DragValue::new(hints).range(0..=20).suffix(" 次"));
+            }
+        });
+    }
+}
+
+/// 牌桌上的一叠牌
+#[derive(PartialEq, Clone, Copy)]
+pub enum Pile {
+    Cell(usize),       // 空当
+    Foundation(usize), // 收牌区（4个）
+    Column(usize),     // 牌列（8列）
+}
+
+/// 牌桌局面（撤销时整体恢复）
+#[derive(Clone, Serialize, Deserialize)]
+pub struct FreeCellBoard {
+    pub cells: Vec<Option<usize>>,    // 空当
+    pub foundations: [Vec<usize>; 4], // 收牌区
+    pub columns: [Vec<usize>; 8],     // 牌列
+}
+
+impl FreeCellBoard {
+    /// 按编号发牌
+    pub fn deal(deal_number: u32, free_cells: usize) -> Self {
+        Self {
+            cells: vec![None; free_cells],
+            foundations: Default::default(),
+            columns: ms_deal(deal_number),
+        }
+    }
+
+    /// 某叠牌中的卡片（空当最多一张）
+    fn cards(&self, pile: Pile) -> Vec<usize> {
+        match pile {
+            Pile::Cell(index) => self.cells[index].into_iter().collect(),
+            Pile::Foundation(index) => self.foundations[index].clone(),
+            Pile::Column(index) => self.columns[index].clone(),
+        }
+    }
+
+    fn len(&self, pile: Pile) -> usize {
+        match pile {
+            Pile::Cell(index) => usize::from(self.cells[index].is_some()),
+            Pile::Foundation(index) => self.foundations[index].len(),
+            Pile::Column(index) => self.columns[index].len(),
+        }
+    }
+
+    /// 一次最多能移动的张数：(空闲空当 + 1) × 2^(空列数)，移到空列时那一列不算
+    fn move_capacity(&self, to: Pile) -> usize {
+        let free_cells = self.cells.iter().filter(|cell| cell.is_none()).count();
+        let empty_columns = (0..8)
+            .filter(|&column| self.columns[column].is_empty() && to != Pile::Column(column))
+            .count();
+        (free_cells + 1) << empty_columns
+    }
+
+    /// 从某叠牌的第 index 张开始能否拿起（牌列只能拿顶部有序的一段）
+    fn can_pick(&self, pile: Pile, index: usize) -> bool {
+        match pile {
+            Pile::Cell(cell) => index == 0 && self.cells[cell].is_some(),
+            Pile::Foundation(_) => false,
+            Pile::Column(column) => {
+                let len = self.columns[column].len();
+                index < len && len - index <= freecell_solver::run_length(&self.columns[column])
+            }
+        }
+    }
+
+    /// 一组牌（第一张在最下面）能否放到目标位置
+    fn can_place(&self, cards: &[usize], to: Pile) -> bool {
+        let Some(&first) = cards.first() else {
+            return false;
+        };
+        match to {
+            Pile::Cell(cell) => cards.len() == 1 && self.cells.get(cell).is_some_and(Option::is_none),
+            Pile::Foundation(index) => {
+                // 收牌区：同花色从 A 到 K 依次放
+                cards.len() == 1
+                    && match self.foundations[index].last() {
+                        None => rank_of(first) == 1,
+                        Some(&top) => suit_of(top) == suit_of(first) && rank_of(first) == rank_of(top) + 1,
+                    }
+            }
+            Pile::Column(index) => {
+                // 牌列：红黑相间、点数递减，空列可以放任何牌，张数受空当和空列限制
+                cards.len() <= self.move_capacity(to)
+                    && match self.columns[index].last() {
+                        None => true,
+                        Some(&top) => is_red(top) != is_red(first) && rank_of(first) + 1 == rank_of(top),
+                    }
+            }
+        }
+    }
+
+    /// 移动卡片（调用前已检查合法）
+    fn move_cards(&mut self, from: Pile, index: usize, to: Pile) {
+        let moving = match from {
+            Pile::Cell(cell) => self.cells[cell].take().into_iter().collect(),
+            Pile::Foundation(foundation) => self.foundations[foundation].split_off(index),
+            Pile::Column(column) => self.columns[column].split_off(index),
+        };
+        match to {
+            Pile::Cell(cell) => self.cells[cell] = moving.first().copied(),
+            Pile::Foundation(foundation) => self.foundations[foundation].extend(moving),
+            Pile::Column(column) => self.columns[column].extend(moving),
+        }
+    }
+
+    /// 某张牌能放进的收牌区
+    pub fn foundation_for(&self, id: usize) -> Option<usize> {
+        (0..4).find(|&index| self.can_place(&[id], Pile::Foundation(index)))
+    }
+
+    /// 每个花色已收的张数
+    fn collected_by_suit(&self) -> [u8; 4] {
+        let mut counts = [0; 4];
+        for &id in self.foundations.iter().flatten() {
+            counts[suit_of(id) as usize - 1] += 1;
+        }
+        counts
+    }
+
+    /// 自动收掉所有安全的牌（不会再被用来接牌的牌）
+    fn auto_play(&mut self) {
+        loop {
+            let counts = self.collected_by_suit();
+            let sources = (0..self.cells.len()).map(Pile::Cell).chain((0..8).map(Pile::Column));
+            let next = sources
+                .filter_map(|pile| {
+                    let len = self.len(pile);
+                    let &card = self.cards(pile).last()?;
+                    let foundation = self.foundation_for(card)?;
+                    freecell_solver::is_safe_to_collect(card, &counts).then_some((pile, len - 1, foundation))
+                })
+                .next();
+            let Some((pile, index, foundation)) = next else {
+                return;
+            };
+            self.move_cards(pile, index, Pile::Foundation(foundation));
+        }
+    }
+
+    /// 是否已全部收齐
+    fn is_won(&self) -> bool {
+        self.foundations.iter().all(|foundation| foundation.len() == 13)
+    }
+}
+
+/// 空当接龙未完成一局的存档
+#[derive(Serialize, Deserialize)]
+pub struct FreeCellSave {
+    difficulty: GameDifficulty,
+    adaptive_level: f32,
+    custom_profile: FreeCellProfile,
+    deal_number: u32,
+    board: FreeCellBoard,
+    history: Vec<FreeCellBoard>,
+    moves: usize,
+    hints_used: u32,
+    timer: Option<TimerSnapshot>,
+}
+
+/// 牌桌布局（每帧根据可用区域计算）
+struct TableLayout {
+    origin: egui::Pos2, // 牌桌左上角
+    bottom: f32,        // 牌桌底边
+}
+
+impl TableLayout {
+    /// 某叠牌的底座位置（空当在左上，收牌区在右上）
+    fn slot(&self, pile: Pile) -> egui::Rect {
+        let (column, y) = match pile {
+            Pile::Cell(index) => (index, self.origin.y),
+            Pile::Foundation(index) => (4 + index, self.origin.y),
+            Pile::Column(index) => (index, self.origin.y + CARD_H + ROW_GAP),
+        };
+        let x = self.origin.x + column as f32 * (CARD_W + COLUMN_GAP);
+        egui::Rect::from_min_size(egui::pos2(x, y), egui::vec2(CARD_W, CARD_H))
+    }
+
+    /// 某叠牌中第 index 张牌的位置
+    fn card_rect(&self, board: &FreeCellBoard, pile: Pile, index: usize) -> egui::Rect {
+        let slot = self.slot(pile);
+        match pile {
+            Pile::Column(column) => {
+                let available = self.bottom - slot.min.y - CARD_H;
+                let (_, offset) = solitaire::column_offsets(board.columns[column].len(), 0, available);
+                slot.translate(egui::vec2(0.0, index as f32 * offset))
+            }
+            Pile::Cell(_) | Pile::Foundation(_) => slot,
+        }
+    }
+
+    /// 牌桌总宽度
+    fn width() -> f32 {
+        8.0 * CARD_W + 7.0 * COLUMN_GAP
+    }
+}
+
+/// 空当接龙游戏状态
+#[derive(PartialEq, Clone, Copy)]
+enum FreeCellState {
+    DifficultySelection,
+    Playing,
+    GameOver,
+}
+
+/// 空当接龙游戏
+pub struct FreeCellGame {
+    difficulty_selection: DifficultySelection,
+    state: FreeCellState,
+
+    // 牌局编号（与 Windows 空当接龙相同）
+    deal_number: u32,
+    // 开始时是否随机选择牌局编号
+    random_deal: bool,
+    // 52张卡片，下标即卡片编号
+    cards: Vec<Card>,
+    // 当前局面
+    board: FreeCellBoard,
+    // 历史局面（用于无限撤销）
+    history: Vec<FreeCellBoard>,
+    // 移动次数
+    moves: usize,
+    // 已用提示次数
+    hints_used: u32,
+    // 正计时
+    game_timer: Option<GameTimer>,
+    // 正在拖动的牌
+    drag: Option<DragState<Pile>>,
+    // 后台求解任务
+    solver: Option<SolverJob>,
+    // 当前局面的求解结果
+    analysis: Option<SolverResult>,
+    // 求解完成后是否显示提示
+    hint_requested: bool,
+    // 正在显示的提示
+    hint: Option<Hint>,
+    // 是否获胜
+    game_won: bool,
+    // 提示消息
+    message: String,
+
+    // 自定义难度参数
+    custom_profile: FreeCellProfile,
+    // 刚结束的一局结果，等待主程序记录
+    finished_result: Option<GameResult>,
+    // 等待主程序处理的游戏事件
+    events: Vec<GameEvent>,
+    // 结束界面的排行榜成绩提交
+    score_submission: ScoreSubmission,
+    // 中途返回主菜单时保存的存档
+    suspended_session: Option<SavedSession>,
+}
+
+impl FreeCellGame {
+    pub fn new() -> Self {
+        Self {
+            difficulty_selection: DifficultySelection::new(GameKind::FreeCell),
+            state: FreeCellState::DifficultySelection,
+            deal_number: 1,
+            random_deal: true,
+            cards: Vec::new(),
+            board: FreeCellBoard::deal(1, 4),
+            history: Vec::new(),
+            moves: 0,
+            hints_used: 0,
+            game_timer: None,
+            drag: None,
+            solver: None,
+            analysis: None,
+            hint_requested: false,
+            hint: None,
+            game_won: false,
+            message: String::new(),
+            custom_profile: FreeCellProfile::preset(GameDifficulty::Custom),
+            finished_result: None,
+            events: Vec::new(),
+            score_submission: ScoreSubmission::new(),
+            suspended_session: None,
+        }
+    }
+
+    /// 当前难度的参数
+    fn profile(&self) -> FreeCellProfile {
+        self.difficulty_selection.profile(&self.custom_profile)
+    }
+
+    /// 加载52张卡片的纹理（正面朝上）
+    fn load_cards(&mut self, ctx: &egui::Context) {
+        if self.cards.len() != 52 {
+            self.cards = solitaire::load_cards(ctx, 0..52);
+        }
+        for card in &mut self.cards {
+            card.is_face_up = true;
+            card.animation = None;
+        }
+    }
+
+    /// 开始新的一局
+    fn start_game(&mut self, ctx: &egui::Context) {
+        self.load_cards(ctx);
+        self.board = FreeCellBoard::deal(self.deal_number, self.profile().free_cells);
+        self.history.clear();
+        self.moves = 0;
+        self.hints_used = 0;
+        self.drag = None;
+        self.position_changed();
+        self.game_won = false;
+        self.message = "拖动卡片移动，双击卡片收牌或放进空当".to_string();
+        self.game_timer = Some(GameTimer::stopwatch());
+        self.state = FreeCellState::Playing;
+    }
+
+    /// 局面改变后丢弃过期的求解结果和提示
+    fn position_changed(&mut self) {
+        self.solver = None;
+        self.analysis = None;
+        self.hint_requested = false;
+        self.hint = None;
+    }
+
+    /// 撤销上一步
+    fn undo(&mut self) {
+        if let Some(board) = self.history.pop() {
+            self.board = board;
+            self.position_changed();
+            self.message = "已撤销".to_string();
+        }
+    }
+
+    /// 移动卡片（调用前已检查合法），然后自动收掉安全的牌
+    fn move_cards(&mut self, from: Pile, index: usize, to: Pile) {
+        self.history.push(self.board.clone());
+        self.board.move_cards(from, index, to);
+        self.board.auto_play();
+        self.moves += 1;
+        self.position_changed();
+        self.message.clear();
+
+        if self.board.is_won() {
+            self.finish_game(true);
+        }
+    }
+
+    /// 双击：优先收牌，否则放进空当
+    fn quick_move(&mut self, pile: Pile) {
+        let len = self.board.len(pile);
+        let Some(&card) = self.board.cards(pile).last() else {
+            return;
+        };
+        if matches!(pile, Pile::Foundation(_)) {
+            return;
+        }
+        if let Some(foundation) = self.board.foundation_for(card) {
+            self.move_cards(pile, len - 1, Pile::Foundation(foundation));
+        } else if matches!(pile, Pile::Column(_))
+            && let Some(cell) = self.board.cells.iter().position(Option::is_none)
+        {
+            self.move_cards(pile, len - 1, Pile::Cell(cell));
+        }
+    }
+
+    /// 开始分析当前局面（已有结果或正在分析时不重复开始）
+    fn analyze(&mut self) {
+        if self.analysis.is_none() && self.solver.is_none() {
+            self.solver = Some(SolverJob::spawn(&self.board));
+            self.message = "正在分析局面…".to_string();
+        }
+        self.show_analysis();
+    }
+
+    /// 请求提示
+    fn request_hint(&mut self) {
+        if self.hint.is_some() || self.hint_requested {
+            return;
+        }
+        self.hint_requested = true;
+        self.analyze();
+    }
+
+    /// 取出后台求解结果
+    fn poll_solver(&mut self, ctx: &egui::Context) {
+        let Some(job) = &self.solver else {
+            return;
+        };
+        match job.poll() {
+            Some(result) => {
+                self.solver = None;
+                self.analysis = Some(result);
+                self.show_analysis();
+            }
+            None => ctx.request_repaint_after(SOLVER_POLL),
+        }
+    }
+
+    /// 显示求解结果（请求了提示时同时高亮提示的一步）
+    fn show_analysis(&mut self) {
+        let Some(result) = self.analysis else {
+            return;
+        };
+        self.message = match result {
+            SolverResult::Solvable { hint: None, .. } => "所有牌都已收齐".to_string(),
+            SolverResult::Solvable { hint: Some(hint), steps } => {
+                if self.hint_requested {
+                    // 只有真正给出了一步才算用掉一次提示
+                    self.hints_used += 1;
+                    self.hint = Some(hint);
+                    let card = self.board.cards(hint.from)[hint.index];
+                    format!("提示：把{}移到{}（已找到 {} 步的解法）", card_name(card), pile_name(hint.to), steps)
+                } else {
+                    format!("当前局面有解，已找到 {} 步的解法", steps)
+                }
+            }
+            SolverResult::Unsolvable => "当前局面已经无解，可以撤销几步再试".to_string(),
+            SolverResult::Unknown => "局面太复杂，无法判断是否有解".to_string(),
+        };
+        self.hint_requested = false;
+    }
+
+    /// 结束本局并记录结果
+    fn finish_game(&mut self, won: bool) {
+        self.game_won = won;
+        self.state = FreeCellState::GameOver;
+        self.drag = None;
+        self.position_changed();
+        self.difficulty_selection.record_result(won);
+
+        let difficulty = self.difficulty_selection.selected_difficulty.unwrap_or(GameDifficulty::Easy);
+        let mut result = GameResult::new(GameKind::FreeCell, difficulty, won);
+        result.time_secs = self.game_timer.as_ref().map(|timer| timer.elapsed().as_secs());
+        result.moves = Some(self.moves);
+        if won {
+            self.events.push(GameEvent::SolitaireCleared { time_secs: result.time_secs });
+        }
+        self.score_submission.offer(&result);
+        self.finished_result = Some(result);
+        if let Some(timer) = &mut self.game_timer {
+            timer.pause();
+        }
+    }
+
+    /// 指针下最上面的一张牌
+    fn card_at(&self, layout: &TableLayout, pos: egui::Pos2) -> Option<(Pile, usize)> {
+        let piles = (0..8)
+            .map(Pile::Column)
+            .chain((0..self.board.cells.len()).map(Pile::Cell))
+            .chain((0..4).map(Pile::Foundation));
+        for pile in piles {
+            for index in (0..self.board.len(pile)).rev() {
+                if layout.card_rect(&self.board, pile, index).contains(pos) {
+                    return Some((pile, index));
+                }
+            }
+        }
+        None
+    }
+
+    /// 松开拖动的牌：放到与它重叠最多的合法位置
+    fn drop_cards(&mut self, layout: &TableLayout, drag: DragState<Pile>, pointer: egui::Pos2) {
+        let dragged = drag.rect(pointer);
+        let cards = self.board.cards(drag.from)[drag.index..].to_vec();
+
+        let overlaps: Vec<(Pile, f32)> = (0..self.board.cells.len())
+            .map(Pile::Cell)
+            .chain((0..4).map(Pile::Foundation))
+            .chain((0..8).map(Pile::Column))
+            .filter(|&pile| pile != drag.from)
+            .map(|pile| {
+                let len = self.board.len(pile);
+                let rect = if len == 0 {
+                    layout.slot(pile)
+                } else {
+                    layout.card_rect(&self.board, pile, len - 1)
+                };
+                (pile, rect.intersect(dragged).area())
+            })
+            .filter(|&(_, area)| area > 0.0)
+            .collect();
+        let best = |legal_only: bool| {
+            overlaps
+                .iter()
+                .filter(|&&(pile, _)| !legal_only || self.board.can_place(&cards, pile))
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|&(pile, _)| pile)
+        };
+
+        if let Some(to) = best(true) {
+            self.move_cards(drag.from, drag.index, to);
+        } else if let Some(to @ Pile::Column(_)) = best(false)
+            && cards.len() > self.board.move_capacity(to)
+        {
+            self.message = format!("空当和空列不够，最多只能移动 {} 张", self.board.move_capacity(to));
+        }
+    }
+
+    /// 处理牌桌上的双击和拖放
+    fn handle_input(&mut self, layout: &TableLayout, response: &egui::Response) {
+        let press_origin = response.ctx.input(|input| input.pointer.press_origin());
+
+        if response.drag_started()
+            && let Some(origin) = press_origin
+            && let Some((pile, index)) = self.card_at(layout, origin)
+            && self.board.can_pick(pile, index)
+        {
+            let rect = layout.card_rect(&self.board, pile, index);
+            self.drag = Some(DragState { from: pile, index, grab_offset: origin - rect.min });
+        }
+
+        if response.drag_stopped()
+            && let Some(drag) = self.drag.take()
+            && let Some(pointer) = response.interact_pointer_pos()
+        {
+            self.drop_cards(layout, drag, pointer);
+            return;
+        }
+
+        if response.double_clicked()
+            && let Some(pointer) = response.interact_pointer_pos()
+            && let Some((pile, _)) = self.card_at(layout, pointer)
+        {
+            self.quick_move(pile);
+        }
+    }
+
+    /// 绘制牌桌
+    fn paint_table(&self, painter: &egui::Painter, layout: &TableLayout, pointer: Option<egui::Pos2>) {
+        let dragging = |pile: Pile, index: usize| DragState::contains(&self.drag, pile, index);
+
+        // 空当
+        for (cell, card) in self.board.cells.iter().enumerate() {
+            let slot = layout.slot(Pile::Cell(cell));
+            solitaire::paint_slot(painter, slot, "");
+            if let Some(id) = card
+                && !dragging(Pile::Cell(cell), 0)
+            {
+                self.cards[*id].paint(painter, slot);
+            }
+        }
+
+        // 收牌区
+        for foundation in 0..4 {
+            let pile = Pile::Foundation(foundation);
+            let slot = layout.slot(pile);
+            solitaire::paint_slot(painter, slot, "A");
+            if let Some(&id) = self.board.foundations[foundation].last() {
+                self.cards[id].paint(painter, slot);
+            }
+        }
+
+        // 牌列
+        for column in 0..8 {
+            let pile = Pile::Column(column);
+            solitaire::paint_slot(painter, layout.slot(pile), "");
+            for (index, &id) in self.board.columns[column].iter().enumerate() {
+                if !dragging(pile, index) {
+                    self.cards[id].paint(painter, layout.card_rect(&self.board, pile, index));
+                }
+            }
+        }
+
+        // 提示：高亮要移动的牌和目标位置
+        if let Some(hint) = self.hint {
+            solitaire::paint_highlight(painter, layout.card_rect(&self.board, hint.from, hint.index));
+            let len = self.board.len(hint.to);
+            let target = if len == 0 {
+                layout.slot(hint.to)
+            } else {
+                layout.card_rect(&self.board, hint.to, len - 1)
+            };
+            solitaire::paint_highlight(painter, target);
+        }
+
+        // 拖动中的牌画在最上层，跟随指针
+        if let Some(drag) = &self.drag
+            && let Some(pointer) = pointer
+        {
+            let drag_painter = solitaire::drag_painter(painter.ctx());
+            let base = drag.rect(pointer);
+            let first_rect = layout.card_rect(&self.board, drag.from, drag.index);
+            for (index, &id) in self.board.cards(drag.from).iter().enumerate().skip(drag.index) {
+                let offset = layout.card_rect(&self.board, drag.from, index).min - first_rect.min;
+                self.cards[id].paint(&drag_painter, base.translate(offset));
+            }
+        }
+    }
+
+    /// 显示游戏进行界面，返回是否要返回主菜单
+    fn show_game_playing(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) -> bool {
+        let mut return_to_menu = false;
+        let profile = self.profile();
+        let hints_left = profile.max_hints.map(|max| max.saturating_sub(self.hints_used));
+
+        // 顶部信息和操作按钮
+        ui.horizontal(|ui| {
+            ui.add_space((ui.available_width() - TableLayout::width()) / 2.0);
+            ui.vertical(|ui| {
+                ui.colored_label(egui::Color32::GOLD, format!("牌局 #{}", self.deal_number));
+                ui.label(format!("移动次数: {}", self.moves));
+            });
+            ui.add_space(20.0);
+            ui.vertical(|ui| {
+                if let Some(timer) = &self.game_timer {
+                    ui.label(format!("用时: {}", timer.elapsed_text()));
+                }
+                let capacity = self.board.move_capacity(Pile::Column(usize::MAX));
+                ui.label(format!("{} 个空当，当前最多移动 {} 张", profile.free_cells, capacity));
+            });
+            ui.add_space(20.0);
+
+            let undo_key = ui.input(|input| input.modifiers.command && input.key_pressed(egui::Key::Z));
+            if ui.add_enabled(!self.history.is_empty(), egui::Button::new("撤销 (Ctrl+Z)")).clicked() || undo_key {
+                self.undo();
+            }
+            if ui.add_enabled(self.solver.is_none(), egui::Button::new("是否有解")).clicked() {
+                self.analyze();
+            }
+            let hint_text = match hints_left {
+                Some(left) => format!("提示（剩余 {} 次）", left),
+                None => "提示".to_string(),
+            };
+            if ui.add_enabled(hints_left != Some(0), egui::Button::new(hint_text)).clicked() {
+                self.request_hint();
+            }
+            if ui.button("暂停 (Esc)").clicked()
+                && let Some(timer) = &mut self.game_timer
+            {
+                timer.pause();
+            }
+            if ui.button("放弃本局").clicked() {
+                self.message = "你放弃了本局".to_string();
+                self.finish_game(false);
+            }
+            if ui.button("返回主菜单").clicked() {
+                return_to_menu = true;
+                self.suspended_session = self.snapshot().map(SavedSession::FreeCell);
+                self.reset_game_state();
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.add_space((ui.available_width() - TableLayout::width()) / 2.0);
+            ui.colored_label(egui::Color32::BLACK, &self.message);
+        });
+        ui.add_space(10.0);
+
+        if self.state != FreeCellState::Playing {
+            return return_to_menu;
+        }
+        if self.cards.len() < 52 {
+            ui.colored_label(egui::Color32::RED, "卡片图片加载失败");
+            return return_to_menu;
+        }
+
+        // 牌桌
+        let available = ui.available_size();
+        let (rect, response) = ui.allocate_exact_size(available, egui::Sense::click_and_drag());
+        let layout = TableLayout {
+            origin: egui::pos2(rect.center().x - TableLayout::width() / 2.0, rect.min.y),
+            bottom: rect.max.y - 10.0,
+        };
+
+        self.handle_input(&layout, &response);
+        self.poll_solver(ctx);
+
+        let pointer = ctx.input(|input| input.pointer.interact_pos());
+        self.paint_table(ui.painter(), &layout, pointer);
+
+        return_to_menu
+    }
+
+    /// 显示游戏结束界面
+    fn show_game_over(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) -> bool {
+        let mut return_to_menu = false;
+
+        ui.vertical_centered(|ui| {
+            ui.add_space(ui.available_height() / 2.0 - 120.0);
+            if self.game_won {
+                ui.colored_label(egui::Color32::GOLD, format!("恭喜你完成了牌局 #{}！", self.deal_number));
+            } else {
+                ui.colored_label(egui::Color32::RED, format!("牌局 #{} 结束", self.deal_number));
+            }
+            let collected: usize = self.board.foundations.iter().map(Vec::len).sum();
+            ui.label(format!("收牌 {}/52 张", collected));
+            ui.label(format!("移动次数: {}", self.moves));
+            if let Some(timer) = &self.game_timer {
+                ui.label(format!("用时: {}", timer.elapsed_text()));
+            }
+
+            ui.add_space(10.0);
+            self.score_submission.show(ui);
+            ui.add_space(20.0);
+
+            ui.horizontal(|ui| {
+                ui.add_space(ui.available_width() / 2.0 - 270.0);
+                if self.centered_button(ui, "重玩本局", 120.0, 40.0).clicked() {
+                    self.start_game(ctx);
+                }
+                ui.add_space(20.0);
+                if self.centered_button(ui, "下一局", 120.0, 40.0).clicked() {
+                    self.deal_number = self.deal_number % MAX_DEAL_NUMBER + 1;
+                    self.start_game(ctx);
+                }
+                ui.add_space(20.0);
+                if self.centered_button(ui, "选择难度", 120.0, 40.0).clicked() {
+                    self.reset_game_state();
+                }
+                ui.add_space(20.0);
+                if self.centered_button(ui, "返回主菜单", 120.0, 40.0).clicked() {
+                    return_to_menu = true;
+                    self.reset_game_state();
+                }
+            });
+        });
+
+        return_to_menu
+    }
+
+    /// 创建居中的按钮
+    fn centered_button(&self, ui: &mut egui::Ui, text: &str, width: f32, height: f32) -> egui::Response {
+        ui.add_sized(
+            egui::vec2(width, height),
+            egui::Button::new(
+                egui::RichText::new(text)
+                    .text_style(egui::TextStyle::Button)
+                    .color(egui::Color32::BLACK),
+            ),
+        )
+    }
+
+    /// 保存进行中的一局，没有进行中的游戏时返回 None
+    fn snapshot(&self) -> Option<FreeCellSave> {
+        if self.state != FreeCellState::Playing {
+            return None;
+        }
+        Some(FreeCellSave {
+            difficulty: self.difficulty_selection.selected_difficulty?,
+            adaptive_level: self.difficulty_selection.adaptive.level,
+            custom_profile: self.custom_profile,
+            deal_number: self.deal_number,
+            board: self.board.clone(),
+            history: self.history.clone(),
+            moves: self.moves,
+            hints_used: self.hints_used,
+            timer: self.game_timer.as_ref().map(GameTimer::snapshot),
+        })
+    }
+
+    /// 从存档恢复一局，计时器处于暂停状态
+    fn restore(&mut self, ctx: &egui::Context, save: FreeCellSave) {
+        self.reset_game_state();
+        self.difficulty_selection.restore(save.difficulty, save.adaptive_level);
+        self.custom_profile = save.custom_profile;
+        self.load_cards(ctx);
+        self.deal_number = save.deal_number;
+        self.board = save.board;
+        self.history = save.history;
+        self.moves = save.moves;
+        self.hints_used = save.hints_used;
+        self.game_timer = save.timer.map(GameTimer::restore);
+        self.message = "已恢复上次的进度".to_string();
+        self.state = FreeCellState::Playing;
+    }
+
+    /// 重置到难度选择界面
+    fn reset_game_state(&mut self) {
+        self.difficulty_selection.reset();
+        self.state = FreeCellState::DifficultySelection;
+        self.history.clear();
+        self.moves = 0;
+        self.hints_used = 0;
+        self.game_timer = None;
+        self.drag = None;
+        self.position_changed();
+        self.game_won = false;
+        self.message.clear();
+        self.score_submission.clear();
+    }
+}
+
+/// 牌桌位置的中文名称
+fn pile_name(pile: Pile) -> String {
+    match pile {
+        Pile::Cell(_) => "空当".to_string(),
+        Pile::Foundation(_) => "收牌区".to_string(),
+        Pile::Column(column) => format!("第 {} 列", column + 1),
+    }
+}
+
+impl CardGame for FreeCellGame {
+    fn kind(&self) -> GameKind {
+        GameKind::FreeCell
+    }
+
+    fn rules(&self) -> Vec<String> {
+        [
+            "目标：把52张牌按花色从 A 到 K 依次收进右上方的4个收牌区",
+            "牌局按编号发牌，与 Windows 空当接龙相同（例如 #11982 是著名的无解牌局）",
+            "牌列中的牌按红黑相间、点数递减叠放，空列可以放任何牌，左上方的空当每格可以暂放一张牌",
+            "可以一次移动一整段有序的牌，最多 (空闲空当数 + 1) × 2^空列数 张",
+            "拖动卡片移动，双击卡片收牌或放进空当；不会再被用到的牌自动收进收牌区",
+            "撤销次数不限（Ctrl+Z），“是否有解”在后台分析当前局面，“提示”给出下一步",
+            "简单难度：4 个空当，不限提示；中等难度：4 个空当，3 次提示；困难难度：3 个空当，没有提示",
+            "自定义难度：自由设置空当数量和提示次数；自适应难度：根据最近战绩自动调整",
+            "按 Esc 可以暂停计时",
+        ]
+        .map(String::from)
+        .to_vec()
+    }
+
+    fn difficulty(&mut self) -> (&mut DifficultySelection, &mut dyn DifficultyProfile) {
+        (&mut self.difficulty_selection, &mut self.custom_profile)
+    }
+
+    fn menu_face(&self) -> &'static str {
+        "assets/card_face/ClubJ.png"
+    }
+
+    /// 显示游戏界面，返回是否要返回主菜单
+    fn show(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) -> bool {
+        let mut return_to_menu = false;
+
+        // 处理过渡动画
+        if self.difficulty_selection.is_in_transition() {
+            if self.difficulty_selection.show_transition_animation(ui) {
+                if self.random_deal {
+                    self.deal_number = rand::rng().random_range(1..=32000);
+                }
+                self.start_game(ctx);
+            }
+            return return_to_menu;
+        }
+
+        // 更新计时器
+        if self.state == FreeCellState::Playing
+            && let Some(timer) = &mut self.game_timer
+        {
+            timer.handle_pause_input(ctx);
+        }
+
+        match self.state {
+            FreeCellState::DifficultySelection => {
+                ui.horizontal(|ui| {
+                    ui.add_space(ui.available_width() / 2.0 - 120.0);
+                    ui.checkbox(&mut self.random_deal, "随机牌局");
+                    ui.add_enabled(
+                        !self.random_deal,
+                        egui::DragValue::new(&mut self.deal_number).range(1..=MAX_DEAL_NUMBER).prefix("牌局 #"),
+                    );
+                });
+                let (menu_return, _) = self.show_difficulty_selection(ui);
+                return_to_menu = menu_return;
+            }
+            FreeCellState::Playing => {
+                return_to_menu = self.show_game_playing(ui, ctx);
+            }
+            FreeCellState::GameOver => {
+                return_to_menu = self.show_game_over(ui, ctx);
+            }
+        }
+
+        // 暂停时显示遮罩
+        if self.state == FreeCellState::Playing
+            && let Some(timer) = &mut self.game_timer
+            && timer.is_paused()
+            && timer::show_pause_overlay(ctx)
+        {
+            timer.resume();
+        }
+
+        return_to_menu
+    }
+
+    /// 取出刚结束的一局结果
+    fn take_result(&mut self) -> Option<GameResult> {
+        self.finished_result.take()
+    }
+
+    /// 取出本局产生的游戏事件（用于判定成就）
+    fn take_events(&mut self) -> Vec<GameEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// 取出玩家提交的排行榜成绩
+    fn take_leaderboard_entry(&mut self) -> Option<(GameKind, LeaderboardEntry)> {
+        self.score_submission.take_entry()
+    }
+
+    /// 取出中途返回主菜单时保存的存档
+    fn take_suspended_session(&mut self) -> Option<SavedSession> {
+        self.suspended_session.take()
+    }
+
+    /// 重置到难度选择界面
+    fn reset(&mut self) {
+        self.reset_game_state();
+    }
+
+    fn save_state(&self) -> Option<SavedSession> {
+        self.snapshot().map(SavedSession::FreeCell)
+    }
+
+    fn restore_state(&mut self, ctx: &egui::Context, session: SavedSession) {
+        if let SavedSession::FreeCell(save) = session {
+            self.restore(ctx, save);
+        }
+    }
+}
+
+impl Default for FreeCellGame {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 把 "JD"、"TC" 之类的牌名换算成卡牌编号
+    fn parse_card(name: &str) -> usize {
+        let mut chars = name.chars();
+        let rank = match chars.next() {
+            Some('A') => 1,
+            Some('T') => 10,
+            Some('J') => 11,
+            Some('Q') => 12,
+            Some('K') => 13,
+            Some(digit) => digit.to_digit(10).expect("点数无效") as u8,
+            None => panic!("牌名为空"),
+        };
+        let suit = match chars.next() {
+            Some('S') => 1,
+            Some('H') => 2,
+            Some('D') => 3,
+            Some('C') => 4,
+            _ => panic!("花色无效"),
+        };
+        card_id(rank, suit)
+    }
+
+    #[test]
+    fn ms_deal_1_matches_windows_layout() {
+        // Windows 空当接龙 1 号牌局，按行列出（每行从左到右依次是第 1~8 列）
+        let rows = [
+            "JD 2D 9H JC 5D 7H 7C 5H",
+            "KD KC 9S 5S AD QC KH 3H",
+            "2S KS 9D QD JS AS AH 3C",
+            "4C 5C TS QH 4H AC 4D 7S",
+            "3S TD 4S TH 8H 2C JH 7D",
+            "6D 8S 8D QS 6C 3D 8C TC",
+            "6S 9C 2H 6H",
+        ];
+        let mut expected: [Vec<usize>; 8] = Default::default();
+        for row in rows {
+            for (column, name) in row.split_whitespace().enumerate() {
+                expected[column].push(parse_card(name));
+            }
+        }
+        assert_eq!(ms_deal(1), expected);
+    }
+}
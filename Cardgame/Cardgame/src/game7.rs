@@ -0,0 +1,1042 @@
+// game7.rs - 蜘蛛纸牌，两副牌、单色/双色/四色三种难度，支持发牌、整套收牌、无限撤销和移动提示
+use eframe::egui;
+use crate::achievements::GameEvent;
+use crate::card::Card;
+use crate::card_game::CardGame;
+use crate::difficulty::{DifficultyProfile, DifficultySelection, GameDifficulty};
+use crate::leaderboard::{LeaderboardEntry, ScoreSubmission};
+use crate::player_profile::{GameKind, GameResult};
+use crate::save_game::SavedSession;
+use crate::solitaire::{self, DragState, CARD_H, CARD_W, card_id, card_name};
+use crate::timer::{self, GameTimer, TimerSnapshot};
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+
+/// 牌列之间的间距
+const COLUMN_GAP: f32 = 12.0;
+/// 牌列与下方牌堆、已完成区之间的间距
+const ROW_GAP: f32 = 20.0;
+/// 牌堆中每一轮发牌、已完成区中每一套之间的错开距离
+const STACK_OFFSET: f32 = 14.0;
+/// 每种花色数对应使用的花色（1 黑桃、2 红桃、3 方片、4 梅花）
+const SUIT_SETS: [&[u8]; 3] = [&[1], &[1, 2], &[1, 2, 3, 4]];
+
+/// 蜘蛛纸牌难度参数
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct SpiderProfile {
+    pub suits: usize, // 花色数量（1、2 或 4）
+}
+
+impl DifficultyProfile for SpiderProfile {
+    fn preset(difficulty: GameDifficulty) -> Self {
+        match difficulty {
+            GameDifficulty::Easy => Self { suits: 1 },
+            GameDifficulty::Medium | GameDifficulty::Custom | GameDifficulty::Adaptive => Self { suits: 2 },
+            GameDifficulty::Hard => Self { suits: 4 },
+        }
+    }
+
+    fn adaptive(level: f32) -> Self {
+        // 花色越多越难，强度较低时单色，中等时双色，最高时四色
+        if level < 0.4 {
+            Self::preset(GameDifficulty::Easy)
+        } else if level < 0.8 {
+            Self::preset(GameDifficulty::Medium)
+        } else {
+            Self::preset(GameDifficulty::Hard)
+        }
+    }
+
+    fn load_preset(&mut self, difficulty: GameDifficulty) {
+        *self = Self::preset(difficulty);
+    }
+
+    fn show_editor(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("花色数量:");
+            ui.radio_value(&mut self.suits, 1, "单色");
+            ui.radio_value(&mut self.suits, 2, "双色");
+            ui.radio_value(&mut self.suits, 4, "四色");
+        });
+    }
+}
+
+/// 牌桌上的一叠牌（拖动只会从牌列开始）
+#[derive(PartialEq, Clone, Copy)]
+struct Column(usize);
+
+/// 一步可行的移动：把第 from 列从第 index 张开始的牌移到第 to 列
+#[derive(PartialEq, Clone, Copy)]
+struct Hint {
+    from: usize,
+    index: usize,
+    to: usize,
+}
+
+/// 牌桌局面（撤销时整体恢复）
+///
+/// 104 张牌用 0..104 编号，第 n 张的点数为 n%13+1，花色由它属于第几套 13 张和花色数量决定。
+#[derive(Clone, Serialize, Deserialize)]
+struct SpiderBoard {
+    suits: usize,             // 花色数量
+    stock: Vec<usize>,        // 牌堆（每次发 10 张）
+    columns: [Vec<usize>; 10], // 牌列
+    hidden: [usize; 10],      // 每列底部背面朝上的张数
+    completed: Vec<usize>,    // 已收走的整套（记录每套的 K）
+    score: i32,               // 分数
+}
+
+impl SpiderBoard {
+    /// 洗牌并发牌：前 4 列 6 张、后 6 列 5 张，只有最上面一张正面朝上，剩下 50 张作为牌堆
+    fn deal(suits: usize) -> Self {
+        let mut deck: Vec<usize> = (0..104).collect();
+        deck.shuffle(&mut rand::rng());
+
+        let mut columns: [Vec<usize>; 10] = Default::default();
+        let mut hidden = [0; 10];
+        for (column, cards) in columns.iter_mut().enumerate() {
+            let count = if column < 4 { 6 } else { 5 };
+            cards.extend(deck.drain(..count));
+            hidden[column] = count - 1;
+        }
+
+        Self {
+            suits,
+            stock: deck,
+            columns,
+            hidden,
+            completed: Vec::new(),
+            score: 500,
+        }
+    }
+
+    /// 卡片的点数
+    fn rank(&self, card: usize) -> u8 {
+        (card % 13) as u8 + 1
+    }
+
+    /// 卡片的花色
+    fn suit(&self, card: usize) -> u8 {
+        let set = SUIT_SETS.iter().find(|set| set.len() == self.suits).unwrap_or(&SUIT_SETS[2]);
+        set[card / 13 % set.len()]
+    }
+
+    /// 卡片对应的牌面编号（用于加载纹理和显示名称）
+    fn face(&self, card: usize) -> usize {
+        card_id(self.rank(card), self.suit(card))
+    }
+
+    /// 牌列顶部同花色、点数连续递减的一段从第几张开始（只算正面朝上的牌）
+    fn run_start(&self, column: usize) -> usize {
+        let cards = &self.columns[column];
+        let mut start = cards.len().saturating_sub(1);
+        while start > self.hidden[column] {
+            let (below, above) = (cards[start - 1], cards[start]);
+            if self.suit(below) != self.suit(above) || self.rank(below) != self.rank(above) + 1 {
+                break;
+            }
+            start -= 1;
+        }
+        start
+    }
+
+    /// 从第 index 张开始能否拿起（必须是顶部同花色的有序一段）
+    fn can_pick(&self, column: usize, index: usize) -> bool {
+        index < self.columns[column].len() && index >= self.run_start(column)
+    }
+
+    /// 某张牌能否放到某列上：空列任何牌都可以，否则接在大 1 点的牌上（不论花色）
+    fn can_place(&self, card: usize, column: usize) -> bool {
+        match self.columns[column].last() {
+            None => true,
+            Some(&top) => self.rank(top) == self.rank(card) + 1,
+        }
+    }
+
+    /// 能否从牌堆发牌（有空列时不能发）
+    fn can_deal(&self) -> bool {
+        !self.stock.is_empty() && self.columns.iter().all(|column| !column.is_empty())
+    }
+
+    /// 牌列最上面的牌被移走后翻开新的顶牌
+    fn reveal_top(&mut self, column: usize) {
+        let len = self.columns[column].len();
+        if len > 0 && self.hidden[column] >= len {
+            self.hidden[column] = len - 1;
+        }
+    }
+
+    /// 牌列顶部凑齐 K 到 A 的同花色整套时收走，返回是否收走了
+    fn collect_run(&mut self, column: usize) -> bool {
+        let len = self.columns[column].len();
+        if len < 13 || len - self.run_start(column) < 13 {
+            return false;
+        }
+        let run = self.columns[column].split_off(len - 13);
+        self.completed.push(run[0]);
+        self.score += 100;
+        self.reveal_top(column);
+        true
+    }
+
+    /// 所有有意义的移动，按优先级从高到低排序
+    fn hints(&self) -> Vec<Hint> {
+        let mut hints: Vec<(u8, Hint)> = Vec::new();
+        for from in 0..10 {
+            let cards = &self.columns[from];
+            for index in self.run_start(from)..cards.len() {
+                let card = cards[index];
+                // 已经接在同花色的大 1 点牌上时，拆开没有意义
+                let below = index.checked_sub(1).filter(|&below| below >= self.hidden[from]).map(|below| cards[below]);
+                let sits_on_rank = below.is_some_and(|below| self.rank(below) == self.rank(card) + 1);
+                let sits_on_suit = sits_on_rank && below.is_some_and(|below| self.suit(below) == self.suit(card));
+                let frees_card = index == 0 || index == self.hidden[from];
+
+                for to in (0..10).filter(|&to| to != from) {
+                    let Some(&top) = self.columns[to].last() else {
+                        // 空列：只在能翻开背面牌或拆开混色牌时才值得移过去
+                        if index > 0 && (index == self.hidden[from] || !sits_on_rank) && index == self.run_start(from) {
+                            hints.push((0, Hint { from, index, to }));
+                        }
+                        continue;
+                    };
+                    if self.rank(top) != self.rank(card) + 1 || sits_on_suit {
+                        continue;
+                    }
+                    let same_suit = self.suit(top) == self.suit(card);
+                    if sits_on_rank && !same_suit {
+                        continue;
+                    }
+                    let priority = 1 + u8::from(same_suit) * 2 + u8::from(frees_card) * 2;
+                    hints.push((priority, Hint { from, index, to }));
+                }
+            }
+        }
+        // 多个空列只提示第一个
+        let first_empty = self.columns.iter().position(Vec::is_empty);
+        hints.retain(|(_, hint)| !self.columns[hint.to].is_empty() || Some(hint.to) == first_empty);
+        hints.sort_by_key(|(priority, _)| std::cmp::Reverse(*priority));
+        hints.into_iter().map(|(_, hint)| hint).collect()
+    }
+}
+
+/// 蜘蛛纸牌未完成一局的存档
+#[derive(Serialize, Deserialize)]
+pub struct SpiderSave {
+    difficulty: GameDifficulty,
+    adaptive_level: f32,
+    custom_profile: SpiderProfile,
+    board: SpiderBoard,
+    history: Vec<SpiderBoard>,
+    moves: usize,
+    timer: Option<TimerSnapshot>,
+}
+
+/// 牌桌布局（每帧根据可用区域计算）
+struct TableLayout {
+    origin: egui::Pos2, // 牌桌左上角
+    bottom: f32,        // 牌桌底边
+}
+
+impl TableLayout {
+    /// 牌列底座位置
+    fn slot(&self, column: usize) -> egui::Rect {
+        let x = self.origin.x + column as f32 * (CARD_W + COLUMN_GAP);
+        egui::Rect::from_min_size(egui::pos2(x, self.origin.y), egui::vec2(CARD_W, CARD_H))
+    }
+
+    /// 牌列中第 index 张牌的位置
+    fn card_rect(&self, board: &SpiderBoard, column: usize, index: usize) -> egui::Rect {
+        let slot = self.slot(column);
+        let available = self.bottom - CARD_H - ROW_GAP - slot.min.y - CARD_H;
+        let (hidden_offset, shown_offset) =
+            solitaire::column_offsets(board.columns[column].len(), board.hidden[column], available);
+        let hidden = board.hidden[column].min(index) as f32;
+        let shown = index as f32 - hidden;
+        slot.translate(egui::vec2(0.0, hidden * hidden_offset + shown * shown_offset))
+    }
+
+    /// 牌堆位置（右下角，剩余几轮就错开几张）
+    fn stock_rect(&self, board: &SpiderBoard) -> egui::Rect {
+        let deals = board.stock.len().div_ceil(10).max(1) as f32;
+        let right = self.origin.x + Self::width();
+        egui::Rect::from_min_max(
+            egui::pos2(right - CARD_W - (deals - 1.0) * STACK_OFFSET, self.bottom - CARD_H),
+            egui::pos2(right, self.bottom),
+        )
+    }
+
+    /// 已完成区第 index 套的位置（左下角）
+    fn completed_rect(&self, index: usize) -> egui::Rect {
+        let x = self.origin.x + index as f32 * STACK_OFFSET * 2.0;
+        egui::Rect::from_min_size(egui::pos2(x, self.bottom - CARD_H), egui::vec2(CARD_W, CARD_H))
+    }
+
+    /// 牌桌总宽度
+    fn width() -> f32 {
+        10.0 * CARD_W + 9.0 * COLUMN_GAP
+    }
+}
+
+/// 蜘蛛纸牌游戏状态
+#[derive(PartialEq, Clone, Copy)]
+enum SpiderState {
+    DifficultySelection,
+    Playing,
+    GameOver,
+}
+
+/// 蜘蛛纸牌游戏
+pub struct SpiderGame {
+    difficulty_selection: DifficultySelection,
+    state: SpiderState,
+
+    // 104张卡片，下标即卡片编号
+    cards: Vec<Card>,
+    // 卡片纹理对应的花色数量（花色数量变化时重新加载）
+    loaded_suits: usize,
+    // 当前局面
+    board: SpiderBoard,
+    // 历史局面（用于无限撤销）
+    history: Vec<SpiderBoard>,
+    // 移动次数
+    moves: usize,
+    // 正计时
+    game_timer: Option<GameTimer>,
+    // 正在拖动的牌
+    drag: Option<DragState<Column>>,
+    // 正在显示的提示，以及下次点击“提示”时显示第几个
+    hint: Option<Hint>,
+    next_hint: usize,
+    // 是否获胜
+    game_won: bool,
+    // 提示消息
+    message: String,
+
+    // 自定义难度参数
+    custom_profile: SpiderProfile,
+    // 刚结束的一局结果，等待主程序记录
+    finished_result: Option<GameResult>,
+    // 等待主程序处理的游戏事件
+    events: Vec<GameEvent>,
+    // 结束界面的排行榜成绩提交
+    score_submission: ScoreSubmission,
+    // 中途返回主菜单时保存的存档
+    suspended_session: Option<SavedSession>,
+}
+
+impl SpiderGame {
+    pub fn new() -> Self {
+        Self {
+            difficulty_selection: DifficultySelection::new(GameKind::Spider),
+            state: SpiderState::DifficultySelection,
+            cards: Vec::new(),
+            loaded_suits: 0,
+            board: SpiderBoard::deal(1),
+            history: Vec::new(),
+            moves: 0,
+            game_timer: None,
+            drag: None,
+            hint: None,
+            next_hint: 0,
+            game_won: false,
+            message: String::new(),
+            custom_profile: SpiderProfile::preset(GameDifficulty::Custom),
+            finished_result: None,
+            events: Vec::new(),
+            score_submission: ScoreSubmission::new(),
+            suspended_session: None,
+        }
+    }
+
+    /// 当前难度的参数
+    fn profile(&self) -> SpiderProfile {
+        self.difficulty_selection.profile(&self.custom_profile)
+    }
+
+    /// 按当前局面的花色数量加载104张卡片的纹理
+    fn load_cards(&mut self, ctx: &egui::Context) {
+        if self.cards.len() != 104 || self.loaded_suits != self.board.suits {
+            let board = &self.board;
+            self.cards = solitaire::load_cards(ctx, (0..104).map(|card| board.face(card)));
+            self.loaded_suits = board.suits;
+        }
+    }
+
+    /// 开始新的一局
+    fn start_game(&mut self, ctx: &egui::Context) {
+        self.board = SpiderBoard::deal(self.profile().suits);
+        self.load_cards(ctx);
+        for card in &mut self.cards {
+            card.is_face_up = false;
+            card.animation = None;
+        }
+        self.history.clear();
+        self.moves = 0;
+        self.drag = None;
+        self.clear_hint();
+        self.game_won = false;
+        self.message = "拖动同花色的有序牌移动，双击卡片自动移动，点击右下角牌堆发牌".to_string();
+        self.game_timer = Some(GameTimer::stopwatch());
+        self.state = SpiderState::Playing;
+    }
+
+    /// 按局面翻转卡片的正反面（animate 为 false 时直接翻转，用于恢复存档）
+    fn sync_faces(&mut self, animate: bool) {
+        let board = &self.board;
+        let mut faces = vec![true; 104];
+        for &card in &board.stock {
+            faces[card] = false;
+        }
+        for (column, cards) in board.columns.iter().enumerate() {
+            for &card in &cards[..board.hidden[column].min(cards.len())] {
+                faces[card] = false;
+            }
+        }
+        for (card, face_up) in self.cards.iter_mut().zip(faces) {
+            if animate {
+                card.flip_to(face_up);
+            } else {
+                card.is_face_up = face_up;
+            }
+        }
+    }
+
+    /// 清除正在显示的提示
+    fn clear_hint(&mut self) {
+        self.hint = None;
+        self.next_hint = 0;
+    }
+
+    /// 撤销上一步
+    fn undo(&mut self) {
+        if let Some(board) = self.history.pop() {
+            self.board = board;
+            self.clear_hint();
+            self.message = "已撤销".to_string();
+        }
+    }
+
+    /// 一步操作之后：扣分、清除提示、检查是否获胜
+    fn after_move(&mut self) {
+        self.moves += 1;
+        self.board.score -= 1;
+        self.clear_hint();
+        if self.board.completed.len() == 8 {
+            self.finish_game(true);
+        }
+    }
+
+    /// 移动卡片（调用前已检查合法），凑齐整套时收走
+    fn move_cards(&mut self, from: usize, index: usize, to: usize) {
+        self.history.push(self.board.clone());
+        let moving = self.board.columns[from].split_off(index);
+        self.board.columns[to].extend(moving);
+        self.board.reveal_top(from);
+        self.message.clear();
+        if self.board.collect_run(to) {
+            self.message = "凑齐了一整套，收走！".to_string();
+        }
+        self.after_move();
+    }
+
+    /// 从牌堆给每列发一张牌
+    fn deal_row(&mut self) {
+        if self.board.stock.is_empty() {
+            return;
+        }
+        if !self.board.can_deal() {
+            self.message = "有空列时不能发牌".to_string();
+            return;
+        }
+        self.history.push(self.board.clone());
+        for column in 0..10 {
+            if let Some(card) = self.board.stock.pop() {
+                self.board.columns[column].push(card);
+            }
+        }
+        self.message.clear();
+        for column in 0..10 {
+            if self.board.collect_run(column) {
+                self.message = "凑齐了一整套，收走！".to_string();
+            }
+        }
+        self.after_move();
+    }
+
+    /// 依次显示可行的移动
+    fn show_next_hint(&mut self) {
+        let hints = self.board.hints();
+        if hints.is_empty() {
+            self.hint = None;
+            self.message = if self.board.stock.is_empty() {
+                "已经没有可以移动的牌了，可以撤销几步或放弃本局".to_string()
+            } else {
+                "没有可以移动的牌了，请从牌堆发牌".to_string()
+            };
+            return;
+        }
+        let shown = self.next_hint % hints.len();
+        let hint = hints[shown];
+        self.next_hint = shown + 1;
+        self.hint = Some(hint);
+        let card = self.board.columns[hint.from][hint.index];
+        self.message = format!(
+            "提示 {}/{}：把第 {} 列的{}移到第 {} 列",
+            shown + 1,
+            hints.len(),
+            hint.from + 1,
+            card_name(self.board.face(card)),
+            hint.to + 1
+        );
+    }
+
+    /// 双击：把这张牌开始的一段移到最合适的位置
+    fn quick_move(&mut self, column: usize, index: usize) {
+        let best = self
+            .board
+            .hints()
+            .into_iter()
+            .find(|hint| hint.from == column && hint.index == index);
+        match best {
+            Some(hint) => self.move_cards(hint.from, hint.index, hint.to),
+            None => self.message = "这些牌没有合适的位置".to_string(),
+        }
+    }
+
+    /// 结束本局并记录结果
+    fn finish_game(&mut self, won: bool) {
+        self.game_won = won;
+        self.state = SpiderState::GameOver;
+        self.drag = None;
+        self.clear_hint();
+        self.difficulty_selection.record_result(won);
+
+        let difficulty = self.difficulty_selection.selected_difficulty.unwrap_or(GameDifficulty::Easy);
+        let mut result = GameResult::new(GameKind::Spider, difficulty, won);
+        result.time_secs = self.game_timer.as_ref().map(|timer| timer.elapsed().as_secs());
+        result.moves = Some(self.moves);
+        if won {
+            self.events.push(GameEvent::SolitaireCleared { time_secs: result.time_secs });
+        }
+        self.score_submission.offer(&result);
+        self.finished_result = Some(result);
+        if let Some(timer) = &mut self.game_timer {
+            timer.pause();
+        }
+    }
+
+    /// 指针下最上面的一张牌
+    fn card_at(&self, layout: &TableLayout, pos: egui::Pos2) -> Option<(usize, usize)> {
+        (0..10).find_map(|column| {
+            (0..self.board.columns[column].len())
+                .rev()
+                .find(|&index| layout.card_rect(&self.board, column, index).contains(pos))
+                .map(|index| (column, index))
+        })
+    }
+
+    /// 松开拖动的牌：放到与它重叠最多的合法牌列
+    fn drop_cards(&mut self, layout: &TableLayout, drag: DragState<Column>, pointer: egui::Pos2) {
+        let dragged = drag.rect(pointer);
+        let Column(from) = drag.from;
+        let card = self.board.columns[from][drag.index];
+
+        let target = (0..10)
+            .filter(|&to| to != from && self.board.can_place(card, to))
+            .map(|to| {
+                let len = self.board.columns[to].len();
+                let rect = if len == 0 {
+                    layout.slot(to)
+                } else {
+                    layout.card_rect(&self.board, to, len - 1)
+                };
+                (to, rect.intersect(dragged).area())
+            })
+            .filter(|&(_, area)| area > 0.0)
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+
+        if let Some((to, _)) = target {
+            self.move_cards(from, drag.index, to);
+        }
+    }
+
+    /// 处理牌桌上的点击、双击和拖放
+    fn handle_input(&mut self, layout: &TableLayout, response: &egui::Response) {
+        let press_origin = response.ctx.input(|input| input.pointer.press_origin());
+
+        if response.drag_started()
+            && let Some(origin) = press_origin
+            && let Some((column, index)) = self.card_at(layout, origin)
+            && self.board.can_pick(column, index)
+        {
+            let rect = layout.card_rect(&self.board, column, index);
+            self.drag = Some(DragState { from: Column(column), index, grab_offset: origin - rect.min });
+        }
+
+        if response.drag_stopped()
+            && let Some(drag) = self.drag.take()
+            && let Some(pointer) = response.interact_pointer_pos()
+        {
+            self.drop_cards(layout, drag, pointer);
+            return;
+        }
+
+        let Some(pointer) = response.interact_pointer_pos() else {
+            return;
+        };
+        if response.clicked() && layout.stock_rect(&self.board).contains(pointer) {
+            self.deal_row();
+        } else if response.double_clicked()
+            && let Some((column, index)) = self.card_at(layout, pointer)
+            && self.board.can_pick(column, index)
+        {
+            self.quick_move(column, index);
+        }
+    }
+
+    /// 绘制牌桌
+    fn paint_table(&self, painter: &egui::Painter, layout: &TableLayout, pointer: Option<egui::Pos2>) {
+        // 牌列
+        for column in 0..10 {
+            solitaire::paint_slot(painter, layout.slot(column), "");
+            for (index, &card) in self.board.columns[column].iter().enumerate() {
+                if !DragState::contains(&self.drag, Column(column), index) {
+                    self.cards[card].paint(painter, layout.card_rect(&self.board, column, index));
+                }
+            }
+        }
+
+        // 已完成的整套
+        for (index, &king) in self.board.completed.iter().enumerate() {
+            self.cards[king].paint(painter, layout.completed_rect(index));
+        }
+
+        // 牌堆：每剩一轮错开一张背面
+        let stock_rect = layout.stock_rect(&self.board);
+        for deal in 0..self.board.stock.len().div_ceil(10) {
+            let card = self.board.stock[deal * 10];
+            let x = stock_rect.min.x + deal as f32 * STACK_OFFSET;
+            let rect = egui::Rect::from_min_size(egui::pos2(x, stock_rect.min.y), egui::vec2(CARD_W, CARD_H));
+            self.cards[card].paint(painter, rect);
+        }
+        if self.board.stock.is_empty() {
+            solitaire::paint_slot(painter, stock_rect, "✖");
+        }
+
+        // 提示：高亮要移动的牌和目标位置
+        if let Some(hint) = self.hint {
+            solitaire::paint_highlight(painter, layout.card_rect(&self.board, hint.from, hint.index));
+            let len = self.board.columns[hint.to].len();
+            let target = if len == 0 {
+                layout.slot(hint.to)
+            } else {
+                layout.card_rect(&self.board, hint.to, len - 1)
+            };
+            solitaire::paint_highlight(painter, target);
+        }
+
+        // 拖动中的牌画在最上层，跟随指针
+        if let Some(drag) = &self.drag
+            && let Some(pointer) = pointer
+        {
+            let drag_painter = solitaire::drag_painter(painter.ctx());
+            let base = drag.rect(pointer);
+            let Column(from) = drag.from;
+            let first_rect = layout.card_rect(&self.board, from, drag.index);
+            for (index, &card) in self.board.columns[from].iter().enumerate().skip(drag.index) {
+                let offset = layout.card_rect(&self.board, from, index).min - first_rect.min;
+                self.cards[card].paint(&drag_painter, base.translate(offset));
+            }
+        }
+    }
+
+    /// 显示游戏进行界面，返回是否要返回主菜单
+    fn show_game_playing(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) -> bool {
+        let mut return_to_menu = false;
+
+        // 顶部信息和操作按钮
+        ui.horizontal(|ui| {
+            ui.add_space((ui.available_width() - TableLayout::width()) / 2.0);
+            ui.vertical(|ui| {
+                ui.colored_label(egui::Color32::GOLD, format!("分数: {}", self.board.score));
+                ui.label(format!("移动次数: {}", self.moves));
+            });
+            ui.add_space(20.0);
+            ui.vertical(|ui| {
+                if let Some(timer) = &self.game_timer {
+                    ui.label(format!("用时: {}", timer.elapsed_text()));
+                }
+                ui.label(format!(
+                    "{}，已完成 {}/8 套，牌堆剩余 {} 轮",
+                    match self.board.suits {
+                        1 => "单色",
+                        2 => "双色",
+                        _ => "四色",
+                    },
+                    self.board.completed.len(),
+                    self.board.stock.len().div_ceil(10)
+                ));
+            });
+            ui.add_space(20.0);
+
+            let undo_key = ui.input(|input| input.modifiers.command && input.key_pressed(egui::Key::Z));
+            if ui.add_enabled(!self.history.is_empty(), egui::Button::new("撤销 (Ctrl+Z)")).clicked() || undo_key {
+                self.undo();
+            }
+            if ui.button("提示 (H)").clicked() || ui.input(|input| input.key_pressed(egui::Key::H)) {
+                self.show_next_hint();
+            }
+            if ui.add_enabled(self.board.can_deal(), egui::Button::new("发牌")).clicked() {
+                self.deal_row();
+            }
+            if ui.button("暂停 (Esc)").clicked()
+                && let Some(timer) = &mut self.game_timer
+            {
+                timer.pause();
+            }
+            if ui.button("放弃本局").clicked() {
+                self.message = "你放弃了本局".to_string();
+                self.finish_game(false);
+            }
+            if ui.button("返回主菜单").clicked() {
+                return_to_menu = true;
+                self.suspended_session = self.snapshot().map(SavedSession::Spider);
+                self.reset_game_state();
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.add_space((ui.available_width() - TableLayout::width()) / 2.0);
+            ui.colored_label(egui::Color32::BLACK, &self.message);
+        });
+        ui.add_space(10.0);
+
+        if self.state != SpiderState::Playing {
+            return return_to_menu;
+        }
+        if self.cards.len() < 104 {
+            ui.colored_label(egui::Color32::RED, "卡片图片加载失败");
+            return return_to_menu;
+        }
+
+        // 牌桌
+        let available = ui.available_size();
+        let (rect, response) = ui.allocate_exact_size(available, egui::Sense::click_and_drag());
+        let layout = TableLayout {
+            origin: egui::pos2(rect.center().x - TableLayout::width() / 2.0, rect.min.y),
+            bottom: rect.max.y - 10.0,
+        };
+
+        self.handle_input(&layout, &response);
+        self.sync_faces(true);
+
+        let pointer = ctx.input(|input| input.pointer.interact_pos());
+        self.paint_table(ui.painter(), &layout, pointer);
+
+        return_to_menu
+    }
+
+    /// 显示游戏结束界面
+    fn show_game_over(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) -> bool {
+        let mut return_to_menu = false;
+
+        ui.vertical_centered(|ui| {
+            ui.add_space(ui.available_height() / 2.0 - 120.0);
+            if self.game_won {
+                ui.colored_label(egui::Color32::GOLD, "恭喜你完成了蜘蛛纸牌！");
+            } else {
+                ui.colored_label(egui::Color32::RED, "本局结束");
+            }
+            ui.label(format!("完成 {}/8 套，分数 {}", self.board.completed.len(), self.board.score));
+            ui.label(format!("移动次数: {}", self.moves));
+            if let Some(timer) = &self.game_timer {
+                ui.label(format!("用时: {}", timer.elapsed_text()));
+            }
+
+            ui.add_space(10.0);
+            self.score_submission.show(ui);
+            ui.add_space(20.0);
+
+            ui.horizontal(|ui| {
+                ui.add_space(ui.available_width() / 2.0 - 200.0);
+                if self.centered_button(ui, "再来一局", 120.0, 40.0).clicked() {
+                    self.start_game(ctx);
+                }
+                ui.add_space(20.0);
+                if self.centered_button(ui, "选择难度", 120.0, 40.0).clicked() {
+                    self.reset_game_state();
+                }
+                ui.add_space(20.0);
+                if self.centered_button(ui, "返回主菜单", 120.0, 40.0).clicked() {
+                    return_to_menu = true;
+                    self.reset_game_state();
+                }
+            });
+        });
+
+        return_to_menu
+    }
+
+    /// 创建居中的按钮
+    fn centered_button(&self, ui: &mut egui::Ui, text: &str, width: f32, height: f32) -> egui::Response {
+        ui.add_sized(
+            egui::vec2(width, height),
+            egui::Button::new(
+                egui::RichText::new(text)
+                    .text_style(egui::TextStyle::Button)
+                    .color(egui::Color32::BLACK),
+            ),
+        )
+    }
+
+    /// 保存进行中的一局，没有进行中的游戏时返回 None
+    fn snapshot(&self) -> Option<SpiderSave> {
+        if self.state != SpiderState::Playing {
+            return None;
+        }
+        Some(SpiderSave {
+            difficulty: self.difficulty_selection.selected_difficulty?,
+            adaptive_level: self.difficulty_selection.adaptive.level,
+            custom_profile: self.custom_profile,
+            board: self.board.clone(),
+            history: self.history.clone(),
+            moves: self.moves,
+            timer: self.game_timer.as_ref().map(GameTimer::snapshot),
+        })
+    }
+
+    /// 从存档恢复一局，计时器处于暂停状态
+    fn restore(&mut self, ctx: &egui::Context, save: SpiderSave) {
+        self.reset_game_state();
+        self.difficulty_selection.restore(save.difficulty, save.adaptive_level);
+        self.custom_profile = save.custom_profile;
+        self.board = save.board;
+        self.load_cards(ctx);
+        self.history = save.history;
+        self.moves = save.moves;
+        self.game_timer = save.timer.map(GameTimer::restore);
+        self.sync_faces(false);
+        self.message = "已恢复上次的进度".to_string();
+        self.state = SpiderState::Playing;
+    }
+
+    /// 重置到难度选择界面
+    fn reset_game_state(&mut self) {
+        self.difficulty_selection.reset();
+        self.state = SpiderState::DifficultySelection;
+        self.history.clear();
+        self.moves = 0;
+        self.game_timer = None;
+        self.drag = None;
+        self.clear_hint();
+        self.game_won = false;
+        self.message.clear();
+        self.score_submission.clear();
+    }
+}
+
+impl CardGame for SpiderGame {
+    fn kind(&self) -> GameKind {
+        GameKind::Spider
+    }
+
+    fn rules(&self) -> Vec<String> {
+        [
+            "目标：在牌列中按同花色从 K 到 A 凑齐整套，凑齐的整套自动收走，收满 8 套即获胜",
+            "使用两副牌共 104 张，开局发 10 列（前 4 列 6 张、后 6 列 5 张），剩下 50 张分 5 轮发",
+            "任何牌都可以接在大 1 点的牌上，但只有同花色、点数连续的一段才能整段移动；空列可以放任何牌",
+            "点击右下角的牌堆给每列发一张牌，有空列时不能发牌",
+            "拖动卡片移动，双击卡片自动移到最合适的位置，“提示”依次显示可行的移动",
+            "开局 500 分，每步扣 1 分，每收走一套加 100 分；撤销次数不限（Ctrl+Z）",
+            "简单难度：单色（只有黑桃）；中等难度：双色（黑桃、红桃）；困难难度：四色",
+            "自定义难度：自由选择花色数量；自适应难度：根据最近战绩自动调整",
+            "按 Esc 可以暂停计时",
+        ]
+        .map(String::from)
+        .to_vec()
+    }
+
+    fn difficulty(&mut self) -> (&mut DifficultySelection, &mut dyn DifficultyProfile) {
+        (&mut self.difficulty_selection, &mut self.custom_profile)
+    }
+
+    fn menu_face(&self) -> &'static str {
+        "assets/card_face/SpadeK.png"
+    }
+
+    /// 显示游戏界面，返回是否要返回主菜单
+    fn show(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) -> bool {
+        let mut return_to_menu = false;
+
+        // 处理过渡动画
+        if self.difficulty_selection.is_in_transition() {
+            if self.difficulty_selection.show_transition_animation(ui) {
+                self.start_game(ctx);
+            }
+            return return_to_menu;
+        }
+
+        // 更新计时器
+        if self.state == SpiderState::Playing
+            && let Some(timer) = &mut self.game_timer
+        {
+            timer.handle_pause_input(ctx);
+        }
+
+        match self.state {
+            SpiderState::DifficultySelection => {
+                let (menu_return, _) = self.show_difficulty_selection(ui);
+                return_to_menu = menu_return;
+            }
+            SpiderState::Playing => {
+                return_to_menu = self.show_game_playing(ui, ctx);
+            }
+            SpiderState::GameOver => {
+                return_to_menu = self.show_game_over(ui, ctx);
+            }
+        }
+
+        // 暂停时显示遮罩
+        if self.state == SpiderState::Playing
+            && let Some(timer) = &mut self.game_timer
+            && timer.is_paused()
+            && timer::show_pause_overlay(ctx)
+        {
+            timer.resume();
+        }
+
+        return_to_menu
+    }
+
+    /// 取出刚结束的一局结果
+    fn take_result(&mut self) -> Option<GameResult> {
+        self.finished_result.take()
+    }
+
+    /// 取出本局产生的游戏事件（用于判定成就）
+    fn take_events(&mut self) -> Vec<GameEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// 取出玩家提交的排行榜成绩
+    fn take_leaderboard_entry(&mut self) -> Option<(GameKind, LeaderboardEntry)> {
+        self.score_submission.take_entry()
+    }
+
+    /// 取出中途返回主菜单时保存的存档
+    fn take_suspended_session(&mut self) -> Option<SavedSession> {
+        self.suspended_session.take()
+    }
+
+    /// 重置到难度选择界面
+    fn reset(&mut self) {
+        self.reset_game_state();
+    }
+
+    fn save_state(&self) -> Option<SavedSession> {
+        self.snapshot().map(SavedSession::Spider)
+    }
+
+    fn restore_state(&mut self, ctx: &egui::Context, session: SavedSession) {
+        if let SavedSession::Spider(save) = session {
+            self.restore(ctx, save);
+        }
+    }
+}
+
+impl Default for SpiderGame {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 第 set 套 13 张中点数为 rank 的牌
+    fn card(set: usize, rank: usize) -> usize {
+        set * 13 + rank - 1
+    }
+
+    /// 空牌桌：没有牌列、牌堆和背面朝上的牌
+    fn empty_board(suits: usize) -> SpiderBoard {
+        let mut board = SpiderBoard::deal(suits);
+        board.stock.clear();
+        board.columns = Default::default();
+        board.hidden = [0; 10];
+        board
+    }
+
+    #[test]
+    fn full_suited_run_is_collected() {
+        let mut board = empty_board(1);
+        board.columns[0].push(card(1, 5));
+        board.columns[0].extend((1..=13).rev().map(|rank| card(0, rank)));
+        board.hidden[0] = 1;
+
+        assert!(board.collect_run(0));
+        assert_eq!(board.completed, vec![card(0, 13)]);
+        assert_eq!(board.columns[0], vec![card(1, 5)]);
+        assert_eq!(board.hidden[0], 0);
+        assert_eq!(board.score, 600);
+    }
+
+    #[test]
+    fn mixed_suit_run_is_not_collected() {
+        // 双色时第 1 套是红桃：K 到 A 中夹着一张红桃 5
+        let mut board = empty_board(2);
+        board.columns[0].extend((1..=13).rev().map(|rank| card(usize::from(rank == 5), rank)));
+
+        assert!(!board.collect_run(0));
+        assert_eq!(board.columns[0].len(), 13);
+        assert!(board.completed.is_empty());
+        assert_eq!(board.score, 500);
+    }
+
+    #[test]
+    fn cannot_deal_with_an_empty_column() {
+        let mut board = SpiderBoard::deal(1);
+        assert!(board.can_deal());
+        let column = board.columns[3].clone();
+        board.columns[3].clear();
+        assert!(!board.can_deal());
+        board.columns[3] = column;
+        board.stock.clear();
+        assert!(!board.can_deal());
+    }
+
+    #[test]
+    fn hints_prefer_same_suit_moves() {
+        // 第 0 列的黑桃 5 盖着一张背面牌，可以接到第 1 列的红桃 6 或第 2 列的黑桃 6 上
+        let mut board = empty_board(2);
+        board.columns[0] = vec![card(7, 13), card(0, 5)];
+        board.hidden[0] = 1;
+        board.columns[1].push(card(1, 6));
+        board.columns[2].push(card(0, 6));
+        // 其余各列放 K，既没有空列，也不会产生别的移动
+        for column in 3..10 {
+            board.columns[column].push(card(column - 3, 13));
+        }
+
+        let hints: Vec<_> = board.hints().iter().map(|hint| (hint.from, hint.index, hint.to)).collect();
+        assert_eq!(hints, [(0, 1, 2), (0, 1, 1)]);
+    }
+
+    #[test]
+    fn difficulty_sets_suit_count() {
+        let cases = [(GameDifficulty::Easy, 1), (GameDifficulty::Medium, 2), (GameDifficulty::Hard, 4)];
+        for (difficulty, suits) in cases {
+            let profile = SpiderProfile::preset(difficulty);
+            assert_eq!(profile.suits, suits);
+            let board = SpiderBoard::deal(profile.suits);
+            let mut used: Vec<u8> = (0..104).map(|card| board.suit(card)).collect();
+            used.sort_unstable();
+            used.dedup();
+            assert_eq!(used.len(), suits);
+        }
+        for (level, suits) in [(0.0, 1), (0.5, 2), (1.0, 4)] {
+            assert_eq!(SpiderProfile::adaptive(level).suits, suits);
+        }
+    }
+}
@@ -0,0 +1,939 @@
+// game8.rs - 斗地主，玩家对两家 AI，叫分抢地主，炸弹、王炸和春天翻倍，得分在整场内累计
+use eframe::egui;
+use crate::achievements::GameEvent;
+use crate::card::Card;
+use crate::card_game::CardGame;
+use crate::difficulty::{self, DifficultyProfile, DifficultySelection, GameDifficulty};
+use crate::doudizhu::{self, AiView, Combo};
+use crate::leaderboard::{LeaderboardEntry, ScoreSubmission};
+use crate::player_profile::{GameKind, GameResult};
+use crate::save_game::SavedSession;
+use crate::solitaire;
+use rand::Rng;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// AI 每一步行动的间隔
+const AI_STEP: Duration = Duration::from_millis(800);
+/// 卡片大小
+const CARD_W: f32 = 70.0;
+const CARD_H: f32 = 98.0;
+/// 玩家手牌、桌面出牌的叠放间距
+const HAND_SPACING: f32 = 32.0;
+const TABLE_SPACING: f32 = 22.0;
+/// 选中的牌向上抬起的高度
+const SELECTED_LIFT: f32 = 18.0;
+/// 三家的称呼（0 为玩家，1 为下家，2 为上家，按 0→1→2 的顺序出牌）
+const SEAT_NAMES: [&str; 3] = ["你", "下家", "上家"];
+
+/// 斗地主难度参数
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct DouDizhuProfile {
+    pub ai_skill: f32,   // AI 水平（0~1：越高越会拆牌、配合队友和记牌）
+    pub base_score: u32, // 底分
+}
+
+impl DifficultyProfile for DouDizhuProfile {
+    fn preset(difficulty: GameDifficulty) -> Self {
+        let ai_skill = match difficulty {
+            GameDifficulty::Easy => 0.2,
+            GameDifficulty::Medium | GameDifficulty::Custom | GameDifficulty::Adaptive => 0.6,
+            GameDifficulty::Hard => 1.0,
+        };
+        Self { ai_skill, base_score: 10 }
+    }
+
+    fn adaptive(level: f32) -> Self {
+        // 强度越高 AI 水平越高
+        Self {
+            ai_skill: difficulty::lerp(0.2, 1.0, level),
+            ..Self::preset(GameDifficulty::Medium)
+        }
+    }
+
+    fn load_preset(&mut self, difficulty: GameDifficulty) {
+        *self = Self::preset(difficulty);
+    }
+
+    fn show_editor(&mut self, ui: &mut egui::Ui) {
+        ui.add(egui::Slider::new(&mut self.ai_skill, 0.0..=1.0).text("AI水平"));
+        ui.add(egui::Slider::new(&mut self.base_score, 1..=100).text("底分"));
+    }
+}
+
+/// 一局中的阶段
+#[derive(PartialEq, Clone, Copy, Serialize, Deserialize)]
+enum RoundPhase {
+    Bidding, // 叫地主
+    Playing, // 出牌
+    Settled, // 本局已结算
+}
+
+/// 一局的牌局（存档时整体保存）
+///
+/// 54 张牌用 0..54 编号：0..52 为普通牌（编号规则同 solitaire::card_id），52 为小王、53 为大王。
+#[derive(Clone, Serialize, Deserialize)]
+struct DouDizhuRound {
+    hands: [Vec<usize>; 3],          // 三家手牌（按牌值从大到小排列）
+    bottom: Vec<usize>,              // 3 张底牌
+    phase: RoundPhase,
+    turn: usize,                     // 当前行动的一家
+    bids: [Option<u32>; 3],          // 每家的叫分（0 为不叫）
+    bid: u32,                        // 当前最高叫分
+    bidder: Option<usize>,           // 叫分最高的一家
+    landlord: Option<usize>,         // 地主
+    last_play: Option<(usize, Combo)>, // 桌面上要压的牌：谁出的、牌型
+    table: [Option<Vec<usize>>; 3],  // 每家这一轮出的牌（空表示不出）
+    played: Vec<usize>,              // 已经打出的牌
+    plays_made: [usize; 3],          // 每家出牌的次数（判断春天）
+    bombs: u32,                      // 打出的炸弹和王炸数
+    spring: bool,                    // 是否打成春天或反春
+}
+
+impl DouDizhuRound {
+    /// 洗牌，每家发 17 张，留 3 张底牌，从 first_bidder 开始叫分
+    fn deal(first_bidder: usize) -> Self {
+        let mut deck: Vec<usize> = (0..54).collect();
+        deck.shuffle(&mut rand::rng());
+        let mut hands: [Vec<usize>; 3] = Default::default();
+        for hand in &mut hands {
+            hand.extend(deck.drain(..17));
+            doudizhu::sort_hand(hand);
+        }
+        Self {
+            hands,
+            bottom: deck,
+            phase: RoundPhase::Bidding,
+            turn: first_bidder,
+            bids: [None; 3],
+            bid: 0,
+            bidder: None,
+            landlord: None,
+            last_play: None,
+            table: Default::default(),
+            played: Vec::new(),
+            plays_made: [0; 3],
+            bombs: 0,
+            spring: false,
+        }
+    }
+
+    /// 当前倍数：叫分 × 2^(炸弹数 + 春天)
+    fn multiplier(&self) -> u32 {
+        self.bid.max(1) << (self.bombs + u32::from(self.spring)).min(16)
+    }
+}
+
+/// 斗地主未完成一场的存档
+#[derive(Serialize, Deserialize)]
+pub struct DouDizhuSave {
+    difficulty: GameDifficulty,
+    adaptive_level: f32,
+    custom_profile: DouDizhuProfile,
+    round: DouDizhuRound,
+    scores: [i32; 3],
+    biggest_win: u32,
+    rounds_played: usize,
+    message: String,
+}
+
+/// 斗地主游戏状态
+#[derive(PartialEq, Clone, Copy)]
+enum DouDizhuState {
+    DifficultySelection,
+    Playing,
+}
+
+/// 斗地主游戏
+pub struct DouDizhuGame {
+    difficulty_selection: DifficultySelection,
+    state: DouDizhuState,
+
+    // 54张卡片，下标即卡片编号
+    cards: Vec<Card>,
+    // 当前这一局
+    round: DouDizhuRound,
+    // 玩家选中的牌
+    selected: Vec<usize>,
+    // 下次点击“提示”时显示第几种出法
+    next_hint: usize,
+    // 三家的累计得分
+    scores: [i32; 3],
+    // 玩家单局最高得分
+    biggest_win: u32,
+    // 已打完的局数
+    rounds_played: usize,
+    // AI 下一步行动的计时器
+    ai_timer: Option<Instant>,
+    // 显示给玩家的消息
+    message: String,
+    // 本场是否已结束
+    session_over: bool,
+
+    // 自定义难度参数
+    custom_profile: DouDizhuProfile,
+    // 刚结束的一场结果，等待主程序记录
+    finished_result: Option<GameResult>,
+    // 等待主程序处理的游戏事件
+    events: Vec<GameEvent>,
+    // 结束界面的排行榜成绩提交
+    score_submission: ScoreSubmission,
+    // 中途返回主菜单时保存的存档
+    suspended_session: Option<SavedSession>,
+}
+
+impl DouDizhuGame {
+    pub fn new() -> Self {
+        Self {
+            difficulty_selection: DifficultySelection::new(GameKind::DouDizhu),
+            state: DouDizhuState::DifficultySelection,
+            cards: Vec::new(),
+            round: DouDizhuRound::deal(0),
+            selected: Vec::new(),
+            next_hint: 0,
+            scores: [0; 3],
+            biggest_win: 0,
+            rounds_played: 0,
+            ai_timer: None,
+            message: String::new(),
+            session_over: false,
+            custom_profile: DouDizhuProfile::preset(GameDifficulty::Custom),
+            finished_result: None,
+            events: Vec::new(),
+            score_submission: ScoreSubmission::new(),
+            suspended_session: None,
+        }
+    }
+
+    /// 当前难度的参数
+    fn profile(&self) -> DouDizhuProfile {
+        self.difficulty_selection.profile(&self.custom_profile)
+    }
+
+    /// 加载54张卡片的纹理
+    fn load_cards(&mut self, ctx: &egui::Context) {
+        if self.cards.len() != 54 {
+            self.cards = solitaire::load_cards(ctx, 0..54);
+        }
+    }
+
+    /// 开始新的一场
+    fn start_session(&mut self, ctx: &egui::Context) {
+        self.reset_game_state();
+        self.load_cards(ctx);
+        self.state = DouDizhuState::Playing;
+        self.start_round();
+    }
+
+    /// 发牌开始新的一局，随机一家先叫分
+    fn start_round(&mut self) {
+        self.round = DouDizhuRound::deal(rand::rng().random_range(0..3));
+        self.selected.clear();
+        self.next_hint = 0;
+        self.ai_timer = None;
+        for card in &mut self.cards {
+            card.is_face_up = false;
+            card.animation = None;
+        }
+        self.message = format!("发牌完毕，从{}开始叫地主", SEAT_NAMES[self.round.turn]);
+    }
+
+    /// 按局面翻转卡片的正反面：AI 的手牌在结算前背面朝上，底牌在确定地主前背面朝上
+    fn sync_faces(&mut self, animate: bool) {
+        let round = &self.round;
+        let mut faces = vec![true; 54];
+        if round.phase != RoundPhase::Settled {
+            for &card in round.hands[1].iter().chain(&round.hands[2]) {
+                faces[card] = false;
+            }
+        }
+        if round.landlord.is_none() {
+            for &card in &round.bottom {
+                faces[card] = false;
+            }
+        }
+        for (card, face_up) in self.cards.iter_mut().zip(faces) {
+            if animate {
+                card.flip_to(face_up);
+            } else {
+                card.is_face_up = face_up;
+            }
+        }
+    }
+
+    /// 某家的称呼，确定地主后带上身份
+    fn seat_label(&self, seat: usize) -> String {
+        match self.round.landlord {
+            Some(landlord) if landlord == seat => format!("{}（地主）", SEAT_NAMES[seat]),
+            Some(_) => format!("{}（农民）", SEAT_NAMES[seat]),
+            None => SEAT_NAMES[seat].to_string(),
+        }
+    }
+
+    /// 一家叫分（0 为不叫）：叫 3 分或三家都叫过后确定地主，没人叫则重新发牌
+    fn place_bid(&mut self, seat: usize, bid: u32) {
+        let round = &mut self.round;
+        round.bids[seat] = Some(bid);
+        if bid > round.bid {
+            round.bid = bid;
+            round.bidder = Some(seat);
+        }
+        self.message = if bid > 0 {
+            format!("{}叫 {} 分", SEAT_NAMES[seat], bid)
+        } else {
+            format!("{}不叫", SEAT_NAMES[seat])
+        };
+
+        let all_bid = round.bids.iter().all(Option::is_some);
+        if bid < 3 && !all_bid {
+            round.turn = (seat + 1) % 3;
+            return;
+        }
+        match round.bidder {
+            Some(landlord) => self.become_landlord(landlord),
+            None => {
+                self.start_round();
+                self.message = "三家都不叫，重新发牌".to_string();
+            }
+        }
+    }
+
+    /// 确定地主：拿走底牌并首先出牌
+    fn become_landlord(&mut self, landlord: usize) {
+        let round = &mut self.round;
+        round.landlord = Some(landlord);
+        round.hands[landlord].extend(round.bottom.iter().copied());
+        doudizhu::sort_hand(&mut round.hands[landlord]);
+        round.phase = RoundPhase::Playing;
+        round.turn = landlord;
+        if landlord == 0 {
+            // 刚拿到的底牌默认选中，方便看清
+            self.selected = round.bottom.clone();
+        }
+        self.message = format!("{}以 {} 分成为地主，由地主先出牌", SEAT_NAMES[landlord], round.bid);
+    }
+
+    /// 一家出牌（调用前已检查合法）
+    fn play_cards(&mut self, seat: usize, mut cards: Vec<usize>) {
+        let Some(combo) = doudizhu::classify(&cards) else {
+            return;
+        };
+        doudizhu::sort_hand(&mut cards);
+        let round = &mut self.round;
+        round.hands[seat].retain(|card| !cards.contains(card));
+        round.played.extend(cards.iter().copied());
+        round.plays_made[seat] += 1;
+        if combo.is_bomb() {
+            round.bombs += 1;
+        }
+        round.last_play = Some((seat, combo));
+        round.table[seat] = Some(cards);
+        self.message = format!("{}出了{}", SEAT_NAMES[seat], combo.describe());
+        if combo.is_bomb() {
+            self.message.push_str(&format!("，倍数翻倍（×{}）", round.multiplier()));
+        }
+        if seat == 0 {
+            self.selected.clear();
+        }
+
+        if self.round.hands[seat].is_empty() {
+            self.settle_round(seat);
+        } else {
+            self.advance_turn();
+        }
+    }
+
+    /// 一家不出
+    fn pass(&mut self, seat: usize) {
+        self.round.table[seat] = Some(Vec::new());
+        self.message = format!("{}不出", SEAT_NAMES[seat]);
+        self.advance_turn();
+    }
+
+    /// 轮到下一家；另外两家都不出时，最后出牌的一家重新自由出牌
+    fn advance_turn(&mut self) {
+        let round = &mut self.round;
+        round.turn = (round.turn + 1) % 3;
+        if round.last_play.is_some_and(|(who, _)| who == round.turn) {
+            round.last_play = None;
+            round.table = Default::default();
+            self.message = format!("{}的牌最大，由{}出牌", SEAT_NAMES[round.turn], SEAT_NAMES[round.turn]);
+        }
+        if round.turn == 0 {
+            self.next_hint = 0;
+        }
+    }
+
+    /// 结算本局：地主赢得或输掉两份，每个农民一份；炸弹、王炸、春天各翻一倍
+    fn settle_round(&mut self, winner: usize) {
+        let round = &mut self.round;
+        let Some(landlord) = round.landlord else {
+            return;
+        };
+        let landlord_won = winner == landlord;
+        let farmers_played = (0..3).filter(|&seat| seat != landlord).any(|seat| round.plays_made[seat] > 0);
+        round.spring = if landlord_won { !farmers_played } else { round.plays_made[landlord] == 1 };
+        round.phase = RoundPhase::Settled;
+        round.last_play = None;
+
+        let unit = (self.profile().base_score * self.round.multiplier()) as i32;
+        let mut changes = [0; 3];
+        for (seat, change) in changes.iter_mut().enumerate() {
+            let sign = if (seat == landlord) == landlord_won { 1 } else { -1 };
+            *change = sign * if seat == landlord { unit * 2 } else { unit };
+        }
+        for (score, change) in self.scores.iter_mut().zip(changes) {
+            *score += change;
+        }
+        if changes[0] > 0 {
+            self.biggest_win = self.biggest_win.max(changes[0] as u32);
+            self.events.push(GameEvent::RoundWon { game: GameKind::DouDizhu, spring: self.round.spring });
+        }
+        self.rounds_played += 1;
+
+        let side = if landlord_won { "地主" } else { "农民" };
+        let mut message = format!("{}出完了牌，{}获胜！", SEAT_NAMES[winner], side);
+        if self.round.spring {
+            message.push_str(if landlord_won { "春天！" } else { "反春！" });
+        }
+        message.push_str(&format!(
+            "\n底分 {} × 叫分 {} × 炸弹 {} 个{}，本局{} {}",
+            self.profile().base_score,
+            self.round.bid,
+            self.round.bombs,
+            if self.round.spring { " × 春天" } else { "" },
+            if changes[0] >= 0 { "得" } else { "失" },
+            changes[0].abs()
+        ));
+        self.message = message;
+    }
+
+    /// 结束本场并记录战绩：累计得分为正算赢
+    fn end_session(&mut self) {
+        if self.session_over {
+            return;
+        }
+        self.session_over = true;
+        let won = self.scores[0] > 0;
+        self.difficulty_selection.record_result(won);
+
+        let difficulty = self.difficulty_selection.selected_difficulty.unwrap_or(GameDifficulty::Easy);
+        let mut result = GameResult::new(GameKind::DouDizhu, difficulty, won);
+        result.moves = Some(self.rounds_played);
+        result.net_chips = self.scores[0] as i64;
+        result.biggest_pot = self.biggest_win;
+        self.score_submission.offer(&result);
+        self.finished_result = Some(result);
+    }
+
+    /// AI 行动一步（每步之间有间隔，便于看清出牌）
+    fn update_ai(&mut self, ctx: &egui::Context) {
+        let seat = self.round.turn;
+        if self.session_over || self.round.phase == RoundPhase::Settled || seat == 0 {
+            self.ai_timer = None;
+            return;
+        }
+        let timer = *self.ai_timer.get_or_insert_with(Instant::now);
+        if timer.elapsed() < AI_STEP {
+            ctx.request_repaint_after(AI_STEP - timer.elapsed());
+            return;
+        }
+        self.ai_timer = None;
+
+        let skill = self.profile().ai_skill;
+        let round = &self.round;
+        match round.phase {
+            RoundPhase::Bidding => {
+                let bid = doudizhu::choose_bid(&round.hands[seat], round.bid, skill);
+                self.place_bid(seat, bid);
+            }
+            RoundPhase::Playing => {
+                let Some(landlord) = round.landlord else {
+                    return;
+                };
+                let view = AiView {
+                    hand: &round.hands[seat],
+                    seat,
+                    landlord,
+                    hand_sizes: [round.hands[0].len(), round.hands[1].len(), round.hands[2].len()],
+                    last_play: round.last_play,
+                    played: &round.played,
+                    skill,
+                };
+                match doudizhu::choose_play(&view) {
+                    Some(cards) => self.play_cards(seat, cards),
+                    None => self.pass(seat),
+                }
+            }
+            RoundPhase::Settled => {}
+        }
+        ctx.request_repaint();
+    }
+
+    /// 玩家选中的牌能否出：必须是合法牌型，并且能压过桌面上的牌
+    fn selection_combo(&self) -> Result<Combo, &'static str> {
+        let combo = doudizhu::classify(&self.selected).ok_or("选中的牌不是有效的牌型")?;
+        match self.round.last_play {
+            Some((_, last)) if !combo.beats(&last) => Err("选中的牌压不过上一手"),
+            _ => Ok(combo),
+        }
+    }
+
+    /// 依次选中能出的牌
+    fn show_next_hint(&mut self) {
+        let to_beat = self.round.last_play.map(|(_, combo)| combo);
+        let plays = doudizhu::candidate_plays(&self.round.hands[0], to_beat.as_ref());
+        if plays.is_empty() {
+            self.selected.clear();
+            self.message = "没有能压过上一手的牌，只能不出".to_string();
+            return;
+        }
+        let shown = self.next_hint % plays.len();
+        self.next_hint = shown + 1;
+        self.selected = plays[shown].clone();
+        self.message = format!("提示 {}/{}", shown + 1, plays.len());
+    }
+
+    /// 玩家手牌中第 index 张的位置（选中的牌抬起）
+    fn hand_card_rect(&self, area: egui::Rect, index: usize) -> egui::Rect {
+        let hand = &self.round.hands[0];
+        let width = CARD_W + hand.len().saturating_sub(1) as f32 * HAND_SPACING;
+        let x = area.center().x - width / 2.0 + index as f32 * HAND_SPACING;
+        let lift = if self.selected.contains(&hand[index]) { SELECTED_LIFT } else { 0.0 };
+        egui::Rect::from_min_size(egui::pos2(x, area.max.y - CARD_H - 10.0 - lift), egui::vec2(CARD_W, CARD_H))
+    }
+
+    /// 点击手牌切换选中
+    fn handle_hand_click(&mut self, area: egui::Rect, response: &egui::Response) {
+        if !response.clicked() || self.round.phase == RoundPhase::Settled {
+            return;
+        }
+        let Some(pointer) = response.interact_pointer_pos() else {
+            return;
+        };
+        let hand = &self.round.hands[0];
+        if let Some(index) = (0..hand.len()).rev().find(|&index| self.hand_card_rect(area, index).contains(pointer)) {
+            let card = hand[index];
+            if let Some(position) = self.selected.iter().position(|&selected| selected == card) {
+                self.selected.remove(position);
+            } else {
+                self.selected.push(card);
+            }
+        }
+    }
+
+    /// 以 anchor 为左边（或右边）画一排叠放的牌，返回这排牌的宽度
+    fn paint_row(&self, painter: &egui::Painter, cards: &[usize], anchor: egui::Pos2, align_right: bool) -> f32 {
+        let width = CARD_W + cards.len().saturating_sub(1) as f32 * TABLE_SPACING;
+        let left = if align_right { anchor.x - width } else { anchor.x };
+        for (index, &card) in cards.iter().enumerate() {
+            let min = egui::pos2(left + index as f32 * TABLE_SPACING, anchor.y);
+            self.cards[card].paint(painter, egui::Rect::from_min_size(min, egui::vec2(CARD_W, CARD_H)));
+        }
+        width
+    }
+
+    /// 某家这一轮的动作：出的牌、不出或叫分
+    fn paint_action(&self, painter: &egui::Painter, seat: usize, anchor: egui::Pos2, align_right: bool) {
+        let text_pos = anchor + egui::vec2(if align_right { -40.0 } else { 40.0 }, CARD_H / 2.0);
+        let text = |text: &str| {
+            painter.text(text_pos, egui::Align2::CENTER_CENTER, text, egui::FontId::proportional(22.0), egui::Color32::BLACK);
+        };
+        if self.round.phase == RoundPhase::Bidding {
+            match self.round.bids[seat] {
+                Some(0) => text("不叫"),
+                Some(bid) => text(&format!("{} 分", bid)),
+                None => {}
+            }
+            return;
+        }
+        match &self.round.table[seat] {
+            Some(cards) if cards.is_empty() => text("不出"),
+            Some(cards) => {
+                self.paint_row(painter, cards, anchor, align_right);
+            }
+            None => {}
+        }
+    }
+
+    /// 绘制牌桌：上方底牌，左右两家 AI，下方玩家的出牌和手牌
+    fn paint_table(&self, painter: &egui::Painter, area: egui::Rect) {
+        let round = &self.round;
+        let font = egui::FontId::proportional(16.0);
+        let label_color = |seat: usize| {
+            if round.phase != RoundPhase::Settled && round.turn == seat {
+                egui::Color32::GOLD
+            } else {
+                egui::Color32::BLACK
+            }
+        };
+
+        // 底牌
+        let bottom_width = 3.0 * CARD_W + 2.0 * 8.0;
+        for (index, &card) in round.bottom.iter().enumerate() {
+            let min = egui::pos2(area.center().x - bottom_width / 2.0 + index as f32 * (CARD_W + 8.0), area.min.y);
+            self.cards[card].paint(painter, egui::Rect::from_min_size(min, egui::vec2(CARD_W, CARD_H)));
+        }
+
+        // 两家 AI：背面一张加剩余张数，结算后亮出手牌
+        let ai_y = area.min.y + CARD_H + 40.0;
+        for (seat, align_right) in [(2, false), (1, true)] {
+            let edge = if align_right { area.max.x - 20.0 } else { area.min.x + 20.0 };
+            let back_min = egui::pos2(if align_right { edge - CARD_W } else { edge }, ai_y);
+            let back_rect = egui::Rect::from_min_size(back_min, egui::vec2(CARD_W, CARD_H));
+            let hand = &round.hands[seat];
+            let label = format!("{} 剩 {} 张  得分 {}", self.seat_label(seat), hand.len(), self.scores[seat]);
+            let align = if align_right { egui::Align2::RIGHT_BOTTOM } else { egui::Align2::LEFT_BOTTOM };
+            painter.text(egui::pos2(edge, ai_y - 6.0), align, label, font.clone(), label_color(seat));
+
+            if round.phase == RoundPhase::Settled && !hand.is_empty() {
+                // 结算后亮出剩下的牌
+                self.paint_row(painter, hand, egui::pos2(edge, ai_y), align_right);
+                continue;
+            }
+            match hand.first() {
+                Some(&card) => self.cards[card].paint(painter, back_rect),
+                None => solitaire::paint_slot(painter, back_rect, ""),
+            }
+
+            let action_x = if align_right { back_rect.min.x - 30.0 } else { back_rect.max.x + 30.0 };
+            self.paint_action(painter, seat, egui::pos2(action_x, ai_y), align_right);
+        }
+
+        // 玩家的出牌（居中）
+        let hand_top = area.max.y - CARD_H - 10.0 - SELECTED_LIFT;
+        let action_y = hand_top - CARD_H - 36.0;
+        let own_play = round.table[0].as_deref().unwrap_or(&[]);
+        let own_width = CARD_W + own_play.len().saturating_sub(1) as f32 * TABLE_SPACING;
+        let own_anchor = if own_play.is_empty() { area.center().x - 40.0 } else { area.center().x - own_width / 2.0 };
+        self.paint_action(painter, 0, egui::pos2(own_anchor, action_y), false);
+        painter.text(
+            egui::pos2(area.center().x, hand_top - 8.0),
+            egui::Align2::CENTER_BOTTOM,
+            format!("{} 剩 {} 张  得分 {}", self.seat_label(0), round.hands[0].len(), self.scores[0]),
+            font,
+            label_color(0),
+        );
+
+        // 玩家手牌
+        for (index, &card) in round.hands[0].iter().enumerate() {
+            self.cards[card].paint(painter, self.hand_card_rect(area, index));
+        }
+    }
+
+    /// 显示游戏进行界面，返回是否要返回主菜单
+    fn show_game_playing(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) -> bool {
+        let mut return_to_menu = false;
+
+        self.update_ai(ctx);
+        self.show_top_info(ui);
+        ui.vertical_centered(|ui| {
+            ui.colored_label(egui::Color32::BLACK, &self.message);
+            ui.add_space(6.0);
+            if self.session_over {
+                self.show_session_over(ui);
+            } else {
+                self.show_action_buttons(ui);
+            }
+        });
+        ui.add_space(6.0);
+
+        if !self.session_over {
+            ui.horizontal(|ui| {
+                ui.add_space(ui.available_width() - 130.0);
+                if ui.button("返回主菜单").clicked() {
+                    return_to_menu = true;
+                    self.suspended_session = self.snapshot().map(SavedSession::DouDizhu);
+                    self.reset_to_difficulty_selection();
+                }
+            });
+        }
+        if return_to_menu || self.state != DouDizhuState::Playing {
+            return return_to_menu;
+        }
+        if self.cards.len() < 54 {
+            ui.colored_label(egui::Color32::RED, "卡片图片加载失败");
+            return return_to_menu;
+        }
+
+        // 牌桌
+        let (area, response) = ui.allocate_exact_size(ui.available_size(), egui::Sense::click());
+        self.handle_hand_click(area, &response);
+        self.sync_faces(true);
+        self.paint_table(ui.painter(), area);
+
+        return_to_menu
+    }
+
+    /// 显示顶部信息：累计得分、底分和当前倍数
+    fn show_top_info(&self, ui: &mut egui::Ui) {
+        let profile = self.profile();
+        ui.horizontal(|ui| {
+            ui.vertical(|ui| {
+                let color = if self.scores[0] >= 0 { egui::Color32::GREEN } else { egui::Color32::RED };
+                ui.colored_label(color, format!("你的得分: {}", self.scores[0]));
+                ui.colored_label(egui::Color32::LIGHT_BLUE, format!("已玩局数: {}", self.rounds_played));
+            });
+            ui.add_space(30.0);
+            ui.vertical(|ui| {
+                ui.colored_label(egui::Color32::GOLD, format!("底分: {}", profile.base_score));
+                ui.colored_label(
+                    egui::Color32::GOLD,
+                    format!("叫分: {}  炸弹: {}  倍数: ×{}", self.round.bid, self.round.bombs, self.round.multiplier()),
+                );
+            });
+            if let Some(difficulty) = self.difficulty_selection.selected_difficulty {
+                ui.add_space(30.0);
+                ui.colored_label(egui::Color32::DARK_GRAY, difficulty.label());
+            }
+        });
+    }
+
+    /// 操作按钮：叫分、出牌，或结算后开始下一局
+    fn show_action_buttons(&mut self, ui: &mut egui::Ui) {
+        let button = |text: &str| egui::Button::new(text).min_size(egui::vec2(90.0, 36.0));
+        match self.round.phase {
+            RoundPhase::Bidding if self.round.turn == 0 => {
+                ui.horizontal(|ui| {
+                    ui.add_space(ui.available_width() / 2.0 - 200.0);
+                    for bid in 1..=3 {
+                        if ui.add_enabled(bid > self.round.bid, button(&format!("{} 分", bid))).clicked() {
+                            self.place_bid(0, bid);
+                        }
+                        ui.add_space(10.0);
+                    }
+                    if ui.add(button("不叫")).clicked() {
+                        self.place_bid(0, 0);
+                    }
+                });
+            }
+            RoundPhase::Playing if self.round.turn == 0 => {
+                let selection = self.selection_combo();
+                ui.horizontal(|ui| {
+                    ui.add_space(ui.available_width() / 2.0 - 210.0);
+                    if ui.add_enabled(selection.is_ok(), button("出牌")).clicked() {
+                        self.play_cards(0, self.selected.clone());
+                    }
+                    ui.add_space(10.0);
+                    if ui.add_enabled(self.round.last_play.is_some(), button("不出")).clicked() {
+                        self.selected.clear();
+                        self.pass(0);
+                    }
+                    ui.add_space(10.0);
+                    if ui.add(button("提示")).clicked() {
+                        self.show_next_hint();
+                    }
+                    ui.add_space(10.0);
+                    if ui.add(button("重选")).clicked() {
+                        self.selected.clear();
+                    }
+                });
+                match (&selection, self.selected.is_empty()) {
+                    (_, true) => {}
+                    (Ok(combo), false) => {
+                        ui.colored_label(egui::Color32::DARK_GREEN, format!("已选: {}", combo.describe()));
+                    }
+                    (Err(reason), false) => {
+                        ui.colored_label(egui::Color32::RED, *reason);
+                    }
+                }
+            }
+            RoundPhase::Bidding | RoundPhase::Playing => {
+                ui.colored_label(egui::Color32::BLUE, format!("{}思考中...", SEAT_NAMES[self.round.turn]));
+            }
+            RoundPhase::Settled => {
+                ui.horizontal(|ui| {
+                    ui.add_space(ui.available_width() / 2.0 - 130.0);
+                    if self.centered_button(ui, "下一局", 120.0, 40.0).clicked() {
+                        self.start_round();
+                    }
+                    ui.add_space(20.0);
+                    if self.centered_button(ui, "结束本场", 120.0, 40.0).clicked() {
+                        self.message = "你结束了本场。".to_string();
+                        self.end_session();
+                    }
+                });
+            }
+        }
+    }
+
+    /// 本场结束：显示成绩提交和选择难度按钮
+    fn show_session_over(&mut self, ui: &mut egui::Ui) {
+        ui.colored_label(
+            egui::Color32::BLUE,
+            format!("本场结束！共 {} 局，累计得分 {}", self.rounds_played, self.scores[0]),
+        );
+        ui.add_space(10.0);
+        self.score_submission.show(ui);
+        ui.add_space(10.0);
+        ui.horizontal(|ui| {
+            ui.add_space(ui.available_width() / 2.0 - 75.0);
+            if self.centered_button(ui, "选择难度", 150.0, 40.0).clicked() {
+                self.reset_to_difficulty_selection();
+            }
+        });
+    }
+
+    /// 创建居中的按钮
+    fn centered_button(&self, ui: &mut egui::Ui, text: &str, width: f32, height: f32) -> egui::Response {
+        ui.add_sized(
+            egui::vec2(width, height),
+            egui::Button::new(
+                egui::RichText::new(text)
+                    .text_style(egui::TextStyle::Button)
+                    .color(egui::Color32::BLACK),
+            ),
+        )
+    }
+
+    /// 保存进行中的一场，没有进行中的游戏时返回 None
+    fn snapshot(&self) -> Option<DouDizhuSave> {
+        if self.state != DouDizhuState::Playing || self.session_over {
+            return None;
+        }
+        Some(DouDizhuSave {
+            difficulty: self.difficulty_selection.selected_difficulty?,
+            adaptive_level: self.difficulty_selection.adaptive.level,
+            custom_profile: self.custom_profile,
+            round: self.round.clone(),
+            scores: self.scores,
+            biggest_win: self.biggest_win,
+            rounds_played: self.rounds_played,
+            message: self.message.clone(),
+        })
+    }
+
+    /// 从存档恢复一场
+    fn restore(&mut self, ctx: &egui::Context, save: DouDizhuSave) {
+        self.reset_game_state();
+        self.difficulty_selection.restore(save.difficulty, save.adaptive_level);
+        self.custom_profile = save.custom_profile;
+        self.load_cards(ctx);
+        self.round = save.round;
+        self.scores = save.scores;
+        self.biggest_win = save.biggest_win;
+        self.rounds_played = save.rounds_played;
+        self.message = save.message;
+        self.sync_faces(false);
+        self.state = DouDizhuState::Playing;
+    }
+
+    /// 重置到难度选择界面
+    fn reset_to_difficulty_selection(&mut self) {
+        self.difficulty_selection.reset();
+        self.state = DouDizhuState::DifficultySelection;
+        self.reset_game_state();
+    }
+
+    /// 重置游戏状态
+    fn reset_game_state(&mut self) {
+        self.selected.clear();
+        self.next_hint = 0;
+        self.scores = [0; 3];
+        self.biggest_win = 0;
+        self.rounds_played = 0;
+        self.ai_timer = None;
+        self.message.clear();
+        self.session_over = false;
+        self.score_submission.clear();
+    }
+}
+
+impl CardGame for DouDizhuGame {
+    fn kind(&self) -> GameKind {
+        GameKind::DouDizhu
+    }
+
+    fn rules(&self) -> Vec<String> {
+        [
+            "三人使用一副 54 张牌，每人 17 张，留 3 张底牌；你的下家和上家由 AI 控制",
+            "叫地主：从随机一家开始依次叫 1~3 分或不叫，只能叫比前面更高的分，叫 3 分或三家叫完后分最高的当地主，三家都不叫则重新发牌",
+            "地主拿走底牌并先出牌，另外两家是农民，先出完牌的一方获胜",
+            "牌的大小：3 < 4 < … < K < A < 2 < 小王 < 大王，顺子、连对、飞机只能连到 A",
+            "牌型：单张、对子、三张、三带一、三带二、顺子（5 张以上）、连对（3 对以上）、飞机（2 组以上连续三张，可带同样组数的单张或对子）、四带二、四带两对（带牌不能是王炸，两个炸弹一起出按较大的炸弹算四带两对）、炸弹、王炸",
+            "跟牌必须出同样牌型、同样张数且更大的牌；炸弹可以压任何非炸弹牌型，王炸最大；另外两家都不出时由最后出牌的一家重新出牌",
+            "点击手牌选中或取消，“提示”依次选中能出的牌",
+            "计分：底分 × 叫分，每打出一个炸弹或王炸翻一倍，春天（农民一张牌没出）或反春（地主只出了一手）再翻一倍；地主输赢两份，每个农民输赢一份",
+            "得分在整场内累计，选择结束本场时结算，累计得分为正为胜",
+            "简单难度：AI 只会出最小的牌；中等难度：AI 会拆牌、配合队友、留着炸弹；困难难度：AI 还会记牌、顶牌和给队友送牌",
+            "自定义难度：自由设置 AI 水平和底分；自适应难度：根据最近战绩自动调整 AI 水平",
+        ]
+        .map(String::from)
+        .to_vec()
+    }
+
+    fn difficulty(&mut self) -> (&mut DifficultySelection, &mut dyn DifficultyProfile) {
+        (&mut self.difficulty_selection, &mut self.custom_profile)
+    }
+
+    fn menu_face(&self) -> &'static str {
+        "assets/card_face/JOKER-B.png"
+    }
+
+    /// 显示游戏界面，返回是否要返回主菜单
+    fn show(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) -> bool {
+        let mut return_to_menu = false;
+
+        // 处理过渡动画
+        if self.difficulty_selection.is_in_transition() {
+            if self.difficulty_selection.show_transition_animation(ui) {
+                self.start_session(ctx);
+            }
+            return return_to_menu;
+        }
+
+        match self.state {
+            DouDizhuState::DifficultySelection => {
+                let (menu_return, _) = self.show_difficulty_selection(ui);
+                return_to_menu = menu_return;
+            }
+            DouDizhuState::Playing => {
+                return_to_menu = self.show_game_playing(ui, ctx);
+            }
+        }
+
+        return_to_menu
+    }
+
+    /// 取出刚结束的一场结果
+    fn take_result(&mut self) -> Option<GameResult> {
+        self.finished_result.take()
+    }
+
+    /// 取出本局产生的游戏事件（用于判定成就）
+    fn take_events(&mut self) -> Vec<GameEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// 取出玩家提交的排行榜成绩
+    fn take_leaderboard_entry(&mut self) -> Option<(GameKind, LeaderboardEntry)> {
+        self.score_submission.take_entry()
+    }
+
+    /// 取出中途返回主菜单时保存的存档
+    fn take_suspended_session(&mut self) -> Option<SavedSession> {
+        self.suspended_session.take()
+    }
+
+    /// 重置到难度选择界面
+    fn reset(&mut self) {
+        self.reset_to_difficulty_selection();
+    }
+
+    fn save_state(&self) -> Option<SavedSession> {
+        self.snapshot().map(SavedSession::DouDizhu)
+    }
+
+    fn restore_state(&mut self, ctx: &egui::Context, session: SavedSession) {
+        if let SavedSession::DouDizhu(save) = session {
+            self.restore(ctx, save);
+        }
+    }
+}
+
+impl Default for DouDizhuGame {
+    fn default() -> Self {
+        Self::new()
+    }
+}
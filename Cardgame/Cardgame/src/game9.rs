@@ -0,0 +1,806 @@
+// game9.rs - 锄大地，玩家对三家 AI，方片3先出，五张牌型比大小，按剩余张数计分，得分在整场内累计
+use eframe::egui;
+use crate::achievements::GameEvent;
+use crate::bigtwo::{self, AiView, Combo, PlayRules};
+use crate::card::Card;
+use crate::card_game::CardGame;
+use crate::difficulty::{self, DifficultyProfile, DifficultySelection, GameDifficulty};
+use crate::leaderboard::{LeaderboardEntry, ScoreSubmission};
+use crate::player_profile::{GameKind, GameResult};
+use crate::save_game::SavedSession;
+use crate::solitaire::{self, card_name};
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// AI 每一步行动的间隔
+const AI_STEP: Duration = Duration::from_millis(800);
+/// 卡片大小
+const CARD_W: f32 = 70.0;
+const CARD_H: f32 = 98.0;
+/// 玩家手牌、桌面出牌的叠放间距
+const HAND_SPACING: f32 = 40.0;
+const TABLE_SPACING: f32 = 22.0;
+/// 选中的牌向上抬起的高度
+const SELECTED_LIFT: f32 = 18.0;
+/// 四家的称呼（0 为玩家，1 为下家，2 为对家，3 为上家，按 0→1→2→3 的顺序出牌）
+const SEAT_NAMES: [&str; 4] = ["你", "下家", "对家", "上家"];
+
+/// 锄大地难度参数
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct BigTwoProfile {
+    pub ai_skill: f32,       // AI 水平（0~1：越高越会保留牌型、记牌和顶牌）
+    pub report_single: bool, // 下家报单（只剩一张）时，出单张必须出最大的一张
+}
+
+impl DifficultyProfile for BigTwoProfile {
+    fn preset(difficulty: GameDifficulty) -> Self {
+        let ai_skill = match difficulty {
+            GameDifficulty::Easy => 0.2,
+            GameDifficulty::Medium | GameDifficulty::Custom | GameDifficulty::Adaptive => 0.6,
+            GameDifficulty::Hard => 1.0,
+        };
+        Self { ai_skill, report_single: true }
+    }
+
+    fn adaptive(level: f32) -> Self {
+        // 强度越高 AI 水平越高
+        Self {
+            ai_skill: difficulty::lerp(0.2, 1.0, level),
+            ..Self::preset(GameDifficulty::Medium)
+        }
+    }
+
+    fn load_preset(&mut self, difficulty: GameDifficulty) {
+        *self = Self::preset(difficulty);
+    }
+
+    fn show_editor(&mut self, ui: &mut egui::Ui) {
+        ui.add(egui::Slider::new(&mut self.ai_skill, 0.0..=1.0).text("AI水平"));
+        ui.checkbox(&mut self.report_single, "下家报单时必须出最大的单张");
+    }
+}
+
+/// 一局的牌局（存档时整体保存）
+#[derive(Clone, Serialize, Deserialize)]
+struct BigTwoRound {
+    hands: [Vec<usize>; 4],            // 四家手牌（从小到大排列）
+    turn: usize,                       // 当前行动的一家
+    opening: bool,                     // 还没有人出过牌（第一手必须包含方片3）
+    last_play: Option<(usize, Combo)>, // 桌面上要压的牌：谁出的、牌型
+    table: [Option<Vec<usize>>; 4],    // 每家这一轮出的牌（空表示不出）
+    played: Vec<usize>,                // 已经打出的牌
+    settled: bool,                     // 本局是否已结算
+}
+
+impl BigTwoRound {
+    /// 洗牌，每家发 13 张，持有方片3的一家先出
+    fn deal() -> Self {
+        let mut deck: Vec<usize> = (0..52).collect();
+        deck.shuffle(&mut rand::rng());
+        let mut hands: [Vec<usize>; 4] = Default::default();
+        for hand in &mut hands {
+            hand.extend(deck.drain(..13));
+            bigtwo::sort_hand(hand);
+        }
+        let turn = hands.iter().position(|hand| hand.contains(&bigtwo::opening_card())).unwrap_or(0);
+        Self {
+            hands,
+            turn,
+            opening: true,
+            last_play: None,
+            table: Default::default(),
+            played: Vec::new(),
+            settled: false,
+        }
+    }
+}
+
+/// 锄大地未完成一场的存档
+#[derive(Serialize, Deserialize)]
+pub struct BigTwoSave {
+    difficulty: GameDifficulty,
+    adaptive_level: f32,
+    custom_profile: BigTwoProfile,
+    round: BigTwoRound,
+    scores: [i32; 4],
+    biggest_win: u32,
+    rounds_played: usize,
+    message: String,
+}
+
+/// 锄大地游戏状态
+#[derive(PartialEq, Clone, Copy)]
+enum BigTwoState {
+    DifficultySelection,
+    Playing,
+}
+
+/// 锄大地游戏
+pub struct BigTwoGame {
+    difficulty_selection: DifficultySelection,
+    state: BigTwoState,
+
+    // 52张卡片，下标即卡片编号
+    cards: Vec<Card>,
+    // 当前这一局
+    round: BigTwoRound,
+    // 玩家选中的牌
+    selected: Vec<usize>,
+    // 下次点击“提示”时显示第几种出法
+    next_hint: usize,
+    // 四家的累计得分
+    scores: [i32; 4],
+    // 玩家单局最高得分
+    biggest_win: u32,
+    // 已打完的局数
+    rounds_played: usize,
+    // AI 下一步行动的计时器
+    ai_timer: Option<Instant>,
+    // 显示给玩家的消息
+    message: String,
+    // 本场是否已结束
+    session_over: bool,
+
+    // 自定义难度参数
+    custom_profile: BigTwoProfile,
+    // 刚结束的一场结果，等待主程序记录
+    finished_result: Option<GameResult>,
+    // 等待主程序处理的游戏事件
+    events: Vec<GameEvent>,
+    // 结束界面的排行榜成绩提交
+    score_submission: ScoreSubmission,
+    // 中途返回主菜单时保存的存档
+    suspended_session: Option<SavedSession>,
+}
+
+impl BigTwoGame {
+    pub fn new() -> Self {
+        Self {
+            difficulty_selection: DifficultySelection::new(GameKind::BigTwo),
+            state: BigTwoState::DifficultySelection,
+            cards: Vec::new(),
+            round: BigTwoRound::deal(),
+            selected: Vec::new(),
+            next_hint: 0,
+            scores: [0; 4],
+            biggest_win: 0,
+            rounds_played: 0,
+            ai_timer: None,
+            message: String::new(),
+            session_over: false,
+            custom_profile: BigTwoProfile::preset(GameDifficulty::Custom),
+            finished_result: None,
+            events: Vec::new(),
+            score_submission: ScoreSubmission::new(),
+            suspended_session: None,
+        }
+    }
+
+    /// 当前难度的参数
+    fn profile(&self) -> BigTwoProfile {
+        self.difficulty_selection.profile(&self.custom_profile)
+    }
+
+    /// 开始新的一场
+    fn start_session(&mut self, ctx: &egui::Context) {
+        self.reset_game_state();
+        if self.cards.len() != 52 {
+            self.cards = solitaire::load_cards(ctx, 0..52);
+        }
+        self.state = BigTwoState::Playing;
+        self.start_round();
+    }
+
+    /// 发牌开始新的一局
+    fn start_round(&mut self) {
+        self.round = BigTwoRound::deal();
+        self.selected.clear();
+        self.next_hint = 0;
+        self.ai_timer = None;
+        for card in &mut self.cards {
+            card.is_face_up = false;
+            card.animation = None;
+        }
+        self.message = format!("发牌完毕，{}有方片3，由{}先出", SEAT_NAMES[self.round.turn], SEAT_NAMES[self.round.turn]);
+    }
+
+    /// 按局面翻转卡片的正反面：AI 的手牌在结算前背面朝上
+    fn sync_faces(&mut self, animate: bool) {
+        let round = &self.round;
+        let mut faces = vec![true; 52];
+        if !round.settled {
+            for &card in round.hands[1..].iter().flatten() {
+                faces[card] = false;
+            }
+        }
+        for (card, face_up) in self.cards.iter_mut().zip(faces) {
+            if animate {
+                card.flip_to(face_up);
+            } else {
+                card.is_face_up = face_up;
+            }
+        }
+    }
+
+    /// 某家当前的出牌限制
+    fn rules_for(&self, seat: usize) -> PlayRules {
+        let round = &self.round;
+        PlayRules {
+            to_beat: round.last_play.map(|(_, combo)| combo),
+            must_include: round.opening.then(bigtwo::opening_card),
+            highest_single: self.profile().report_single && round.hands[(seat + 1) % 4].len() == 1,
+        }
+    }
+
+    /// 一家出牌（调用前已检查合法）
+    fn play_cards(&mut self, seat: usize, mut cards: Vec<usize>) {
+        let Some(combo) = bigtwo::classify(&cards) else {
+            return;
+        };
+        bigtwo::sort_hand(&mut cards);
+        let round = &mut self.round;
+        round.hands[seat].retain(|card| !cards.contains(card));
+        round.played.extend(cards.iter().copied());
+        round.opening = false;
+        round.last_play = Some((seat, combo));
+        round.table[seat] = Some(cards);
+        self.message = format!("{}出了{}", SEAT_NAMES[seat], combo.describe());
+        if round.hands[seat].len() == 1 {
+            self.message.push_str(&format!("，{}只剩一张牌了！", SEAT_NAMES[seat]));
+        }
+        if seat == 0 {
+            self.selected.clear();
+        }
+
+        if self.round.hands[seat].is_empty() {
+            self.settle_round(seat);
+        } else {
+            self.advance_turn();
+        }
+    }
+
+    /// 一家不出
+    fn pass(&mut self, seat: usize) {
+        self.round.table[seat] = Some(Vec::new());
+        self.message = format!("{}不出", SEAT_NAMES[seat]);
+        self.advance_turn();
+    }
+
+    /// 轮到下一家；其余三家都不出时，最后出牌的一家重新自由出牌
+    fn advance_turn(&mut self) {
+        let round = &mut self.round;
+        round.turn = (round.turn + 1) % 4;
+        if round.last_play.is_some_and(|(who, _)| who == round.turn) {
+            round.last_play = None;
+            round.table = Default::default();
+            self.message = format!("没有人压得过，由{}出牌", SEAT_NAMES[round.turn]);
+        }
+        if round.turn == 0 {
+            self.next_hint = 0;
+        }
+    }
+
+    /// 结算本局：其余三家按剩余张数扣分，全部给出完牌的一家
+    fn settle_round(&mut self, winner: usize) {
+        let round = &mut self.round;
+        round.settled = true;
+        round.last_play = None;
+
+        let mut changes = [0; 4];
+        for seat in (0..4).filter(|&seat| seat != winner) {
+            let penalty = bigtwo::penalty(round.hands[seat].len());
+            changes[seat] = -penalty;
+            changes[winner] += penalty;
+        }
+        for (score, change) in self.scores.iter_mut().zip(changes) {
+            *score += change;
+        }
+        if changes[0] > 0 {
+            self.biggest_win = self.biggest_win.max(changes[0] as u32);
+        }
+        if winner == 0 {
+            self.events.push(GameEvent::RoundWon { game: GameKind::BigTwo, spring: false });
+        }
+        self.rounds_played += 1;
+
+        let details: Vec<String> = (0..4)
+            .filter(|&seat| seat != winner)
+            .map(|seat| format!("{}剩 {} 张（{}）", SEAT_NAMES[seat], round.hands[seat].len(), changes[seat]))
+            .collect();
+        self.message = format!(
+            "{}出完了牌！\n{}\n本局{} {}",
+            SEAT_NAMES[winner],
+            details.join("，"),
+            if changes[0] >= 0 { "得" } else { "失" },
+            changes[0].abs()
+        );
+    }
+
+    /// 结束本场并记录战绩：累计得分为正算赢
+    fn end_session(&mut self) {
+        if self.session_over {
+            return;
+        }
+        self.session_over = true;
+        let won = self.scores[0] > 0;
+        self.difficulty_selection.record_result(won);
+
+        let difficulty = self.difficulty_selection.selected_difficulty.unwrap_or(GameDifficulty::Easy);
+        let mut result = GameResult::new(GameKind::BigTwo, difficulty, won);
+        result.moves = Some(self.rounds_played);
+        result.net_chips = self.scores[0] as i64;
+        result.biggest_pot = self.biggest_win;
+        self.score_submission.offer(&result);
+        self.finished_result = Some(result);
+    }
+
+    /// AI 行动一步（每步之间有间隔，便于看清出牌）
+    fn update_ai(&mut self, ctx: &egui::Context) {
+        let seat = self.round.turn;
+        if self.session_over || self.round.settled || seat == 0 {
+            self.ai_timer = None;
+            return;
+        }
+        let timer = *self.ai_timer.get_or_insert_with(Instant::now);
+        if timer.elapsed() < AI_STEP {
+            ctx.request_repaint_after(AI_STEP - timer.elapsed());
+            return;
+        }
+        self.ai_timer = None;
+
+        let round = &self.round;
+        let view = AiView {
+            hand: &round.hands[seat],
+            seat,
+            hand_sizes: [0, 1, 2, 3].map(|seat| round.hands[seat].len()),
+            rules: self.rules_for(seat),
+            played: &round.played,
+            skill: self.profile().ai_skill,
+        };
+        match bigtwo::choose_play(&view) {
+            Some(cards) => self.play_cards(seat, cards),
+            None => self.pass(seat),
+        }
+        ctx.request_repaint();
+    }
+
+    /// 依次选中能出的牌
+    fn show_next_hint(&mut self) {
+        let plays = bigtwo::candidate_plays(&self.round.hands[0], &self.rules_for(0));
+        if plays.is_empty() {
+            self.selected.clear();
+            self.message = "没有能压过上一手的牌，只能不出".to_string();
+            return;
+        }
+        let shown = self.next_hint % plays.len();
+        self.next_hint = shown + 1;
+        self.selected = plays[shown].clone();
+        self.message = format!("提示 {}/{}", shown + 1, plays.len());
+    }
+
+    /// 玩家手牌中第 index 张的位置（选中的牌抬起）
+    fn hand_card_rect(&self, area: egui::Rect, index: usize) -> egui::Rect {
+        let hand = &self.round.hands[0];
+        let width = CARD_W + hand.len().saturating_sub(1) as f32 * HAND_SPACING;
+        let x = area.center().x - width / 2.0 + index as f32 * HAND_SPACING;
+        let lift = if self.selected.contains(&hand[index]) { SELECTED_LIFT } else { 0.0 };
+        egui::Rect::from_min_size(egui::pos2(x, area.max.y - CARD_H - 10.0 - lift), egui::vec2(CARD_W, CARD_H))
+    }
+
+    /// 点击手牌切换选中
+    fn handle_hand_click(&mut self, area: egui::Rect, response: &egui::Response) {
+        if !response.clicked() || self.round.settled {
+            return;
+        }
+        let Some(pointer) = response.interact_pointer_pos() else {
+            return;
+        };
+        let hand = &self.round.hands[0];
+        if let Some(index) = (0..hand.len()).rev().find(|&index| self.hand_card_rect(area, index).contains(pointer)) {
+            let card = hand[index];
+            if let Some(position) = self.selected.iter().position(|&selected| selected == card) {
+                self.selected.remove(position);
+            } else {
+                self.selected.push(card);
+            }
+        }
+    }
+
+    /// 以 anchor 为左上角画一排叠放的牌（align_right 时 anchor 为右上角）
+    fn paint_row(&self, painter: &egui::Painter, cards: &[usize], anchor: egui::Pos2, align_right: bool) {
+        let width = CARD_W + cards.len().saturating_sub(1) as f32 * TABLE_SPACING;
+        let left = if align_right { anchor.x - width } else { anchor.x };
+        for (index, &card) in cards.iter().enumerate() {
+            let min = egui::pos2(left + index as f32 * TABLE_SPACING, anchor.y);
+            self.cards[card].paint(painter, egui::Rect::from_min_size(min, egui::vec2(CARD_W, CARD_H)));
+        }
+    }
+
+    /// 某家这一轮出的牌或“不出”，以 center 为中心
+    fn paint_action(&self, painter: &egui::Painter, seat: usize, center: egui::Pos2) {
+        match &self.round.table[seat] {
+            Some(cards) if cards.is_empty() => {
+                painter.text(center, egui::Align2::CENTER_CENTER, "不出", egui::FontId::proportional(22.0), egui::Color32::BLACK);
+            }
+            Some(cards) => {
+                let width = CARD_W + cards.len().saturating_sub(1) as f32 * TABLE_SPACING;
+                self.paint_row(painter, cards, egui::pos2(center.x - width / 2.0, center.y - CARD_H / 2.0), false);
+            }
+            None => {}
+        }
+    }
+
+    /// 绘制牌桌：上、左、右三家 AI，中间各家的出牌，下方玩家的手牌
+    fn paint_table(&self, painter: &egui::Painter, area: egui::Rect) {
+        let round = &self.round;
+        let font = egui::FontId::proportional(16.0);
+        let label_color = |seat: usize| {
+            if !round.settled && round.turn == seat {
+                egui::Color32::GOLD
+            } else {
+                egui::Color32::BLACK
+            }
+        };
+        let label = |seat: usize| format!("{} 剩 {} 张  得分 {}", SEAT_NAMES[seat], round.hands[seat].len(), self.scores[seat]);
+
+        // 三家 AI：背面一张加剩余张数，结算后亮出手牌；(座位, 牌的左上角或右上角, 是否右对齐, 出牌的中心)
+        let side_y = area.min.y + CARD_H + 60.0;
+        let seats = [
+            (1, egui::pos2(area.max.x - 20.0, side_y), true, egui::pos2(area.max.x - CARD_W - 200.0, side_y + CARD_H / 2.0)),
+            (2, egui::pos2(area.center().x, area.min.y + 20.0), false, egui::pos2(area.center().x, area.min.y + CARD_H + 90.0)),
+            (3, egui::pos2(area.min.x + 20.0, side_y), false, egui::pos2(area.min.x + CARD_W + 200.0, side_y + CARD_H / 2.0)),
+        ];
+        for (seat, anchor, align_right, action_center) in seats {
+            let hand = &round.hands[seat];
+            let top_seat = seat == 2;
+            let back_min = if align_right {
+                egui::pos2(anchor.x - CARD_W, anchor.y)
+            } else if top_seat {
+                egui::pos2(anchor.x - CARD_W / 2.0, anchor.y)
+            } else {
+                anchor
+            };
+            let back_rect = egui::Rect::from_min_size(back_min, egui::vec2(CARD_W, CARD_H));
+            let (label_pos, label_align) = if top_seat {
+                (egui::pos2(back_rect.max.x + 12.0, back_rect.center().y), egui::Align2::LEFT_CENTER)
+            } else if align_right {
+                (egui::pos2(anchor.x, anchor.y - 6.0), egui::Align2::RIGHT_BOTTOM)
+            } else {
+                (egui::pos2(anchor.x, anchor.y - 6.0), egui::Align2::LEFT_BOTTOM)
+            };
+            painter.text(label_pos, label_align, label(seat), font.clone(), label_color(seat));
+
+            if round.settled && !hand.is_empty() {
+                // 结算后亮出剩下的牌
+                let width = CARD_W + (hand.len() - 1) as f32 * TABLE_SPACING;
+                let row_anchor = if top_seat { egui::pos2(anchor.x - width / 2.0, anchor.y) } else { anchor };
+                self.paint_row(painter, hand, row_anchor, align_right);
+                continue;
+            }
+            match hand.first() {
+                Some(&card) => self.cards[card].paint(painter, back_rect),
+                None => solitaire::paint_slot(painter, back_rect, ""),
+            }
+            self.paint_action(painter, seat, action_center);
+        }
+
+        // 玩家的出牌（居中）和手牌
+        let hand_top = area.max.y - CARD_H - 10.0 - SELECTED_LIFT;
+        self.paint_action(painter, 0, egui::pos2(area.center().x, hand_top - CARD_H / 2.0 - 36.0));
+        painter.text(
+            egui::pos2(area.center().x, hand_top - 8.0),
+            egui::Align2::CENTER_BOTTOM,
+            label(0),
+            font,
+            label_color(0),
+        );
+        for (index, &card) in round.hands[0].iter().enumerate() {
+            self.cards[card].paint(painter, self.hand_card_rect(area, index));
+        }
+    }
+
+    /// 显示游戏进行界面，返回是否要返回主菜单
+    fn show_game_playing(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) -> bool {
+        let mut return_to_menu = false;
+
+        self.update_ai(ctx);
+        self.show_top_info(ui);
+        ui.vertical_centered(|ui| {
+            ui.colored_label(egui::Color32::BLACK, &self.message);
+            ui.add_space(6.0);
+            if self.session_over {
+                self.show_session_over(ui);
+            } else {
+                self.show_action_buttons(ui);
+            }
+        });
+        ui.add_space(6.0);
+
+        if !self.session_over {
+            ui.horizontal(|ui| {
+                ui.add_space(ui.available_width() - 130.0);
+                if ui.button("返回主菜单").clicked() {
+                    return_to_menu = true;
+                    self.suspended_session = self.snapshot().map(SavedSession::BigTwo);
+                    self.reset_to_difficulty_selection();
+                }
+            });
+        }
+        if return_to_menu || self.state != BigTwoState::Playing {
+            return return_to_menu;
+        }
+        if self.cards.len() < 52 {
+            ui.colored_label(egui::Color32::RED, "卡片图片加载失败");
+            return return_to_menu;
+        }
+
+        // 牌桌
+        let (area, response) = ui.allocate_exact_size(ui.available_size(), egui::Sense::click());
+        self.handle_hand_click(area, &response);
+        self.sync_faces(true);
+        self.paint_table(ui.painter(), area);
+
+        return_to_menu
+    }
+
+    /// 显示顶部信息：累计得分和局数
+    fn show_top_info(&self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.vertical(|ui| {
+                let color = if self.scores[0] >= 0 { egui::Color32::GREEN } else { egui::Color32::RED };
+                ui.colored_label(color, format!("你的得分: {}", self.scores[0]));
+                ui.colored_label(egui::Color32::LIGHT_BLUE, format!("已玩局数: {}", self.rounds_played));
+            });
+            ui.add_space(30.0);
+            ui.vertical(|ui| {
+                let others: Vec<String> = (1..4).map(|seat| format!("{} {}", SEAT_NAMES[seat], self.scores[seat])).collect();
+                ui.colored_label(egui::Color32::GOLD, others.join("  "));
+                if self.profile().report_single {
+                    ui.colored_label(egui::Color32::DARK_GRAY, "下家报单时必须出最大的单张");
+                }
+            });
+            if let Some(difficulty) = self.difficulty_selection.selected_difficulty {
+                ui.add_space(30.0);
+                ui.colored_label(egui::Color32::DARK_GRAY, difficulty.label());
+            }
+        });
+    }
+
+    /// 操作按钮：出牌，或结算后开始下一局
+    fn show_action_buttons(&mut self, ui: &mut egui::Ui) {
+        let button = |text: &str| egui::Button::new(text).min_size(egui::vec2(90.0, 36.0));
+        if self.round.settled {
+            ui.horizontal(|ui| {
+                ui.add_space(ui.available_width() / 2.0 - 130.0);
+                if self.centered_button(ui, "下一局", 120.0, 40.0).clicked() {
+                    self.start_round();
+                }
+                ui.add_space(20.0);
+                if self.centered_button(ui, "结束本场", 120.0, 40.0).clicked() {
+                    self.message = "你结束了本场。".to_string();
+                    self.end_session();
+                }
+            });
+            return;
+        }
+        if self.round.turn != 0 {
+            ui.colored_label(egui::Color32::BLUE, format!("{}思考中...", SEAT_NAMES[self.round.turn]));
+            return;
+        }
+
+        let selection = self.rules_for(0).check(&self.round.hands[0], &self.selected);
+        ui.horizontal(|ui| {
+            ui.add_space(ui.available_width() / 2.0 - 210.0);
+            if ui.add_enabled(selection.is_ok(), button("出牌")).clicked() {
+                self.play_cards(0, self.selected.clone());
+            }
+            ui.add_space(10.0);
+            if ui.add_enabled(self.round.last_play.is_some(), button("不出")).clicked() {
+                self.selected.clear();
+                self.pass(0);
+            }
+            ui.add_space(10.0);
+            if ui.add(button("提示")).clicked() {
+                self.show_next_hint();
+            }
+            ui.add_space(10.0);
+            if ui.add(button("重选")).clicked() {
+                self.selected.clear();
+            }
+        });
+        match (&selection, self.selected.is_empty()) {
+            (_, true) if self.round.opening => {
+                ui.colored_label(egui::Color32::DARK_GRAY, format!("第一手必须包含{}", card_name(bigtwo::opening_card())));
+            }
+            (_, true) => {}
+            (Ok(combo), false) => {
+                ui.colored_label(egui::Color32::DARK_GREEN, format!("已选: {}", combo.describe()));
+            }
+            (Err(reason), false) => {
+                ui.colored_label(egui::Color32::RED, *reason);
+            }
+        }
+    }
+
+    /// 本场结束：显示成绩提交和选择难度按钮
+    fn show_session_over(&mut self, ui: &mut egui::Ui) {
+        ui.colored_label(
+            egui::Color32::BLUE,
+            format!("本场结束！共 {} 局，累计得分 {}", self.rounds_played, self.scores[0]),
+        );
+        ui.add_space(10.0);
+        self.score_submission.show(ui);
+        ui.add_space(10.0);
+        ui.horizontal(|ui| {
+            ui.add_space(ui.available_width() / 2.0 - 75.0);
+            if self.centered_button(ui, "选择难度", 150.0, 40.0).clicked() {
+                self.reset_to_difficulty_selection();
+            }
+        });
+    }
+
+    /// 创建居中的按钮
+    fn centered_button(&self, ui: &mut egui::Ui, text: &str, width: f32, height: f32) -> egui::Response {
+        ui.add_sized(
+            egui::vec2(width, height),
+            egui::Button::new(
+                egui::RichText::new(text)
+                    .text_style(egui::TextStyle::Button)
+                    .color(egui::Color32::BLACK),
+            ),
+        )
+    }
+
+    /// 保存进行中的一场，没有进行中的游戏时返回 None
+    fn snapshot(&self) -> Option<BigTwoSave> {
+        if self.state != BigTwoState::Playing || self.session_over {
+            return None;
+        }
+        Some(BigTwoSave {
+            difficulty: self.difficulty_selection.selected_difficulty?,
+            adaptive_level: self.difficulty_selection.adaptive.level,
+            custom_profile: self.custom_profile,
+            round: self.round.clone(),
+            scores: self.scores,
+            biggest_win: self.biggest_win,
+            rounds_played: self.rounds_played,
+            message: self.message.clone(),
+        })
+    }
+
+    /// 从存档恢复一场
+    fn restore(&mut self, ctx: &egui::Context, save: BigTwoSave) {
+        self.reset_game_state();
+        self.difficulty_selection.restore(save.difficulty, save.adaptive_level);
+        self.custom_profile = save.custom_profile;
+        if self.cards.len() != 52 {
+            self.cards = solitaire::load_cards(ctx, 0..52);
+        }
+        self.round = save.round;
+        self.scores = save.scores;
+        self.biggest_win = save.biggest_win;
+        self.rounds_played = save.rounds_played;
+        self.message = save.message;
+        self.sync_faces(false);
+        self.state = BigTwoState::Playing;
+    }
+
+    /// 重置到难度选择界面
+    fn reset_to_difficulty_selection(&mut self) {
+        self.difficulty_selection.reset();
+        self.state = BigTwoState::DifficultySelection;
+        self.reset_game_state();
+    }
+
+    /// 重置游戏状态
+    fn reset_game_state(&mut self) {
+        self.selected.clear();
+        self.next_hint = 0;
+        self.scores = [0; 4];
+        self.biggest_win = 0;
+        self.rounds_played = 0;
+        self.ai_timer = None;
+        self.message.clear();
+        self.session_over = false;
+        self.score_submission.clear();
+    }
+}
+
+impl CardGame for BigTwoGame {
+    fn kind(&self) -> GameKind {
+        GameKind::BigTwo
+    }
+
+    fn rules(&self) -> Vec<String> {
+        [
+            "四人使用一副 52 张牌，每人 13 张；你的下家、对家和上家由 AI 控制，先出完手牌的一家获胜",
+            "牌的大小：先比点数 3 < 4 < … < K < A < 2，点数相同比花色 方片 < 梅花 < 红桃 < 黑桃",
+            "持有方片3的一家先出，第一手必须包含方片3",
+            "牌型：单张、对子、三条，以及五张牌型 顺子 < 同花 < 葫芦（三条带一对）< 四带一 < 同花顺",
+            "顺子为五张连续点数，A-2-3-4-5 最小、10-J-Q-K-A 最大，不能绕过 K-A（如 J-Q-K-A-2）；同牌型的顺子比顺序上最大的一张，同花比最大的一张，葫芦比三条，四带一比四条",
+            "跟牌必须出同样张数且更大的牌，也可以不出；其余三家都不出时，由最后出牌的一家重新自由出牌",
+            "下家报单（只剩一张牌）时，出单张必须出手中最大的一张（可在自定义难度中关闭）",
+            "计分：出完牌的一家赢得其余三家的扣分，剩余不足 10 张每张扣 1 分，10~12 张每张扣 2 分，13 张每张扣 3 分",
+            "点击手牌选中或取消，“提示”依次选中能出的牌；得分在整场内累计，选择结束本场时结算，累计得分为正为胜",
+            "简单难度：AI 总是甩掉最小的牌；中等难度：AI 会保留牌型、该不出时不出；困难难度：AI 还会记牌、留着 2、有人快出完时顶大牌",
+            "自定义难度：自由设置 AI 水平和报单规则；自适应难度：根据最近战绩自动调整 AI 水平",
+        ]
+        .map(String::from)
+        .to_vec()
+    }
+
+    fn difficulty(&mut self) -> (&mut DifficultySelection, &mut dyn DifficultyProfile) {
+        (&mut self.difficulty_selection, &mut self.custom_profile)
+    }
+
+    fn menu_face(&self) -> &'static str {
+        "assets/card_face/Diamond3.png"
+    }
+
+    /// 显示游戏界面，返回是否要返回主菜单
+    fn show(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) -> bool {
+        let mut return_to_menu = false;
+
+        // 处理过渡动画
+        if self.difficulty_selection.is_in_transition() {
+            if self.difficulty_selection.show_transition_animation(ui) {
+                self.start_session(ctx);
+            }
+            return return_to_menu;
+        }
+
+        match self.state {
+            BigTwoState::DifficultySelection => {
+                let (menu_return, _) = self.show_difficulty_selection(ui);
+                return_to_menu = menu_return;
+            }
+            BigTwoState::Playing => {
+                return_to_menu = self.show_game_playing(ui, ctx);
+            }
+        }
+
+        return_to_menu
+    }
+
+    /// 取出刚结束的一场结果
+    fn take_result(&mut self) -> Option<GameResult> {
+        self.finished_result.take()
+    }
+
+    /// 取出本局产生的游戏事件（用于判定成就）
+    fn take_events(&mut self) -> Vec<GameEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// 取出玩家提交的排行榜成绩
+    fn take_leaderboard_entry(&mut self) -> Option<(GameKind, LeaderboardEntry)> {
+        self.score_submission.take_entry()
+    }
+
+    /// 取出中途返回主菜单时保存的存档
+    fn take_suspended_session(&mut self) -> Option<SavedSession> {
+        self.suspended_session.take()
+    }
+
+    /// 重置到难度选择界面
+    fn reset(&mut self) {
+        self.reset_to_difficulty_selection();
+    }
+
+    fn save_state(&self) -> Option<SavedSession> {
+        self.snapshot().map(SavedSession::BigTwo)
+    }
+
+    fn restore_state(&mut self, ctx: &egui::Context, session: SavedSession) {
+        if let SavedSession::BigTwo(save) = session {
+            self.restore(ctx, save);
+        }
+    }
+}
+
+impl Default for BigTwoGame {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,468 @@
+// hearts.rs - 红心大战的规则：传牌方向、出牌限制、吃墩判定和计分（含全收），以及 AI 传牌和出牌策略
+use crate::solitaire::{card_id, rank_of, suit_of};
+use rand::seq::{IndexedRandom, IteratorRandom};
+use serde::{Deserialize, Serialize};
+
+/// 花色编号（与卡片编号一致）
+pub const SPADES: u8 = 1;
+pub const HEARTS: u8 = 2;
+pub const CLUBS: u8 = 4;
+/// 一局的全部罚分（13 张红桃 + 黑桃Q）
+pub const TOTAL_POINTS: u32 = 26;
+
+/// 持有梅花2的一家首出第一墩
+pub fn two_of_clubs() -> usize {
+    card_id(2, CLUBS)
+}
+
+/// 黑桃Q，吃到扣 13 分
+pub fn queen_of_spades() -> usize {
+    card_id(12, SPADES)
+}
+
+/// 点数的大小：2 最小，A 最大
+pub fn rank_order(rank: u8) -> usize {
+    (rank as usize + 11) % 13
+}
+
+/// 牌在同花色内的大小
+fn card_order(card: usize) -> usize {
+    rank_order(rank_of(card))
+}
+
+/// 手牌排序：按梅花、方片、黑桃、红桃分组，组内从小到大
+pub fn sort_hand(hand: &mut [usize]) {
+    let suit_group = |suit: u8| match suit {
+        4 => 0,
+        3 => 1,
+        1 => 2,
+        _ => 3,
+    };
+    hand.sort_by_key(|&card| (suit_group(suit_of(card)), card_order(card)));
+}
+
+/// 一张牌的罚分：红桃 1 分，黑桃Q 13 分
+pub fn points(card: usize) -> u32 {
+    if suit_of(card) == HEARTS {
+        1
+    } else if card == queen_of_spades() {
+        13
+    } else {
+        0
+    }
+}
+
+/// 一墩牌的罚分
+pub fn trick_points(trick: &[(usize, usize)]) -> u32 {
+    trick.iter().map(|&(_, card)| points(card)).sum()
+}
+
+/// 吃下这一墩的一家：首出花色中最大的牌
+pub fn trick_winner(trick: &[(usize, usize)]) -> usize {
+    let lead_suit = trick.first().map_or(0, |&(_, card)| suit_of(card));
+    trick
+        .iter()
+        .filter(|&&(_, card)| suit_of(card) == lead_suit)
+        .max_by_key(|&&(_, card)| card_order(card))
+        .map_or(0, |&(seat, _)| seat)
+}
+
+/// 一局结束时各家加的分：吃到全部 26 分（全收）的一家不加分，其余三家各加 26 分
+pub fn hand_scores(taken: [u32; 4]) -> ([u32; 4], Option<usize>) {
+    match taken.iter().position(|&points| points == TOTAL_POINTS) {
+        Some(shooter) => {
+            let mut scores = [TOTAL_POINTS; 4];
+            scores[shooter] = 0;
+            (scores, Some(shooter))
+        }
+        None => (taken, None),
+    }
+}
+
+/// 传牌方向，按局轮换：左、右、对家、不传
+#[derive(PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub enum PassDirection {
+    Left,
+    Right,
+    Across,
+    Hold,
+}
+
+impl PassDirection {
+    /// 第 hand_number 局（从 0 开始）的传牌方向
+    pub fn for_hand(hand_number: usize) -> Self {
+        [Self::Left, Self::Right, Self::Across, Self::Hold][hand_number % 4]
+    }
+
+    /// 显示名称
+    pub fn label(self) -> &'static str {
+        match self {
+            PassDirection::Left => "传给左家",
+            PassDirection::Right => "传给右家",
+            PassDirection::Across => "传给对家",
+            PassDirection::Hold => "不传牌",
+        }
+    }
+
+    /// seat 的牌传给哪一家（按出牌顺序 0→1→2→3，左家是下一家）
+    pub fn target(self, seat: usize) -> Option<usize> {
+        match self {
+            PassDirection::Left => Some((seat + 1) % 4),
+            PassDirection::Right => Some((seat + 3) % 4),
+            PassDirection::Across => Some((seat + 2) % 4),
+            PassDirection::Hold => None,
+        }
+    }
+}
+
+/// 出一张牌时的限制
+#[derive(Clone, Copy)]
+pub struct TrickRules {
+    pub lead_suit: Option<u8>, // 这一墩首出的花色（None 表示由自己首出）
+    pub first_trick: bool,     // 是否为第一墩（梅花2首出，不能垫分）
+    pub hearts_broken: bool,   // 红桃是否已经破了（有人垫过红桃或黑桃Q）
+}
+
+impl TrickRules {
+    /// 手牌中能出的牌
+    pub fn legal_plays(&self, hand: &[usize]) -> Vec<usize> {
+        // 按条件筛选，没有符合条件的牌时任意出
+        let filtered = |keep: &dyn Fn(usize) -> bool| {
+            let cards: Vec<usize> = hand.iter().copied().filter(|&card| keep(card)).collect();
+            if cards.is_empty() { hand.to_vec() } else { cards }
+        };
+        match self.lead_suit {
+            None if self.first_trick && hand.contains(&two_of_clubs()) => vec![two_of_clubs()],
+            None if self.hearts_broken => hand.to_vec(),
+            None => filtered(&|card| suit_of(card) != HEARTS),
+            Some(suit) if hand.iter().any(|&card| suit_of(card) == suit) => filtered(&|card| suit_of(card) == suit),
+            Some(_) if self.first_trick => filtered(&|card| points(card) == 0),
+            Some(_) => hand.to_vec(),
+        }
+    }
+
+    /// 检查一张牌能否出，不能出时返回原因
+    pub fn check(&self, hand: &[usize], card: usize) -> Result<(), &'static str> {
+        if self.legal_plays(hand).contains(&card) {
+            return Ok(());
+        }
+        Err(match self.lead_suit {
+            None if self.first_trick => "第一墩必须由梅花2首出",
+            None => "红桃还没有破，不能首出红桃",
+            Some(suit) if hand.iter().any(|&card| suit_of(card) == suit) => "必须跟出同花色的牌",
+            Some(_) => "第一墩不能垫红桃或黑桃Q",
+        })
+    }
+}
+
+/// AI 传牌：选出要传走的 3 张牌
+///
+/// 水平低的 AI 随便传；水平高的 AI 传走黑桃Q、没有小黑桃保护的黑桃A/K 和大红桃，
+/// 最高水平还会传光短门花色，争取早点缺门垫牌。
+pub fn choose_pass(hand: &[usize], skill: f32) -> Vec<usize> {
+    let mut rng = rand::rng();
+    if skill < 0.4 {
+        return hand.iter().copied().choose_multiple(&mut rng, 3);
+    }
+
+    let suit_len = |suit: u8| hand.iter().filter(|&&card| suit_of(card) == suit).count();
+    let low_spades = hand
+        .iter()
+        .filter(|&&card| suit_of(card) == SPADES && card_order(card) < card_order(queen_of_spades()))
+        .count();
+    let danger = |card: usize| {
+        let order = card_order(card) as f32;
+        let suit = suit_of(card);
+        if suit == SPADES && order >= card_order(queen_of_spades()) as f32 {
+            // 黑桃Q 和 K、A：小黑桃多时可以留着
+            return if low_spades >= 4 { order } else { 50.0 + order };
+        }
+        let mut danger = order * if suit == HEARTS { 2.0 } else { 1.5 };
+        if skill >= 0.75 && suit != HEARTS && suit != SPADES && suit_len(suit) <= 3 {
+            // 短门花色传光后可以缺门垫牌
+            danger += 10.0;
+        }
+        danger
+    };
+
+    let mut cards = hand.to_vec();
+    cards.sort_by(|&a, &b| danger(b).total_cmp(&danger(a)));
+    cards.truncate(3);
+    cards
+}
+
+/// AI 出牌时能看到的局面
+pub struct AiView<'a> {
+    pub hand: &'a [usize],           // AI 自己的手牌
+    pub seat: usize,                 // AI 的座位
+    pub trick: &'a [(usize, usize)], // 本墩已出的牌：(座位, 牌)
+    pub rules: TrickRules,           // 出牌限制
+    pub played: &'a [usize],         // 之前各墩出过的牌（记牌用）
+    pub voids: &'a [[bool; 4]; 4],   // 已知各家缺的花色（记牌用）
+    pub taken: [u32; 4],             // 本局各家已吃到的分
+    pub skill: f32,                  // AI 水平（0~1）
+}
+
+impl AiView<'_> {
+    /// 还没见过的牌（在其他三家手中）
+    fn unseen(&self) -> Vec<usize> {
+        let seen = |card: usize| {
+            self.hand.contains(&card) || self.played.contains(&card) || self.trick.iter().any(|&(_, played)| played == card)
+        };
+        (0..52).filter(|&card| !seen(card)).collect()
+    }
+
+    /// 本墩之后还要出牌的几家
+    fn players_after(&self) -> Vec<usize> {
+        (self.trick.len() + 1..4).map(|offset| (self.trick.first().map_or(self.seat, |&(seat, _)| seat) + offset) % 4).collect()
+    }
+
+    /// 有一家吃到了目前所有的分、可能要全收时返回这一家
+    fn moon_threat(&self) -> Option<usize> {
+        let total: u32 = self.taken.iter().sum();
+        let (leader, &most) = self.taken.iter().enumerate().max_by_key(|&(_, points)| *points)?;
+        (leader != self.seat && most == total && most >= 10).then_some(leader)
+    }
+}
+
+/// AI 出牌：返回要出的一张牌
+///
+/// 水平低的 AI 随便出能出的牌（缺门时先垫黑桃Q）；水平高的 AI 首出安全的小牌，跟牌时尽量出
+/// 压不住的最大一张，缺门时垫黑桃Q和大红桃；最高水平还会记牌、记住各家缺的花色、
+/// 逼出黑桃Q，并在有人要全收时抢一墩分。
+pub fn choose_play(view: &AiView) -> Option<usize> {
+    let legal = view.rules.legal_plays(view.hand);
+    if legal.len() <= 1 {
+        return legal.first().copied();
+    }
+    let Some(&(_, lead_card)) = view.trick.first() else {
+        return choose_lead(view, &legal);
+    };
+    let lead_suit = suit_of(lead_card);
+    let following = legal.iter().all(|&card| suit_of(card) == lead_suit);
+
+    if view.skill < 0.4 {
+        if !following && legal.contains(&queen_of_spades()) {
+            return Some(queen_of_spades());
+        }
+        return legal.choose(&mut rand::rng()).copied();
+    }
+
+    let winning_seat = trick_winner(view.trick);
+    let winning_order = view
+        .trick
+        .iter()
+        .find(|&&(seat, _)| seat == winning_seat)
+        .map_or(0, |&(_, card)| card_order(card));
+    let threat = if view.skill >= 0.75 { view.moon_threat() } else { None };
+
+    if following {
+        let mut under: Vec<usize> = legal.iter().copied().filter(|&card| card_order(card) < winning_order).collect();
+        let mut over: Vec<usize> = legal.iter().copied().filter(|&card| card_order(card) > winning_order).collect();
+        under.sort_by_key(|&card| card_order(card));
+        over.sort_by_key(|&card| card_order(card));
+
+        // 有人要全收：用最小的能压住的牌抢下有分的一墩
+        if let Some(shooter) = threat
+            && winning_seat == shooter
+            && (trick_points(view.trick) > 0 || lead_suit == HEARTS)
+            && let Some(&card) = over.first()
+        {
+            return Some(card);
+        }
+        let last = view.trick.len() == 3;
+        let without_queen: Vec<usize> = over.iter().copied().filter(|&card| card != queen_of_spades()).collect();
+        let pool = if without_queen.is_empty() { &over } else { &without_queen };
+        // 最后一家出牌且这一墩没有分时，用最大的牌吃下，趁机甩掉大牌
+        if view.skill >= 0.75 && last && trick_points(view.trick) == 0 && lead_suit != HEARTS && !pool.is_empty() {
+            return pool.last().copied();
+        }
+        // 压不住时出最大的一张（手里有黑桃Q且有人出了黑桃A/K，正好垫掉）
+        if let Some(&card) = under.last() {
+            return Some(card);
+        }
+        // 只能吃下这一墩：最后一家出最大的，否则出最小的，都尽量不出黑桃Q
+        return if last { pool.last().copied() } else { pool.first().copied() };
+    }
+
+    // 缺门垫牌：有人要全收且他正在吃这一墩时不垫分
+    let unseen = view.unseen();
+    let queen_out = unseen.contains(&queen_of_spades());
+    let suit_len = |suit: u8| view.hand.iter().filter(|&&card| suit_of(card) == suit).count();
+    let discard_score = |card: usize| {
+        let mut score = card_order(card) as f32;
+        if card == queen_of_spades() {
+            score += 100.0;
+        } else if suit_of(card) == HEARTS {
+            score += 20.0;
+        } else if suit_of(card) == SPADES && card_order(card) > card_order(queen_of_spades()) && queen_out {
+            score += 40.0;
+        }
+        if view.skill >= 0.75 && suit_of(card) != HEARTS {
+            // 垫短门的牌，早点缺门
+            score += (13 - suit_len(suit_of(card))) as f32 * 0.3;
+        }
+        if threat == Some(winning_seat) && points(card) > 0 {
+            score -= 200.0;
+        }
+        score
+    };
+    legal.iter().copied().max_by(|&a, &b| discard_score(a).total_cmp(&discard_score(b)))
+}
+
+/// AI 首出：选最不容易吃到分的一张
+fn choose_lead(view: &AiView, legal: &[usize]) -> Option<usize> {
+    if view.skill < 0.4 {
+        return legal.choose(&mut rand::rng()).copied();
+    }
+    let careful = view.skill >= 0.75;
+    let unseen = view.unseen();
+    let queen_out = unseen.contains(&queen_of_spades());
+    let holds_high_spade = view
+        .hand
+        .iter()
+        .any(|&card| suit_of(card) == SPADES && card_order(card) >= card_order(queen_of_spades()));
+    let opponents = view.players_after();
+
+    let lead_score = |card: usize| {
+        let suit = suit_of(card);
+        let order = card_order(card);
+        let mut score = order as f32;
+        if suit == HEARTS {
+            score += 3.0;
+        }
+        if suit == SPADES && order >= card_order(queen_of_spades()) && queen_out && card != queen_of_spades() {
+            // 黑桃Q 还在外面时不首出黑桃A/K
+            score += 30.0;
+        }
+        if card == queen_of_spades() {
+            score += 40.0;
+        }
+        if !careful {
+            return score;
+        }
+
+        // 记牌：外面比它小的牌越多越容易吃墩
+        let others: Vec<usize> = unseen.iter().copied().filter(|&other| suit_of(other) == suit).collect();
+        let higher = others.iter().filter(|&&other| card_order(other) > order).count();
+        let lower = others.len() - higher;
+        if higher == 0 && lower > 0 {
+            score += 20.0;
+        } else {
+            score += lower as f32 * 0.5;
+        }
+        // 有人缺这门花色，会趁机垫分
+        let void_count = opponents.iter().filter(|&&seat| view.voids[seat][suit as usize - 1]).count();
+        score += void_count as f32 * 8.0;
+        // 手里没有黑桃Q、A、K 时，出小黑桃逼出黑桃Q
+        if suit == SPADES && queen_out && !holds_high_spade && order < card_order(queen_of_spades()) {
+            score -= 6.0;
+        }
+        score
+    };
+    legal.iter().copied().min_by(|&a, &b| lead_score(a).total_cmp(&lead_score(b)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 方片的花色编号
+    const DIAMONDS: u8 = 3;
+
+    /// 按 (点数, 花色) 列出一组牌
+    fn cards(list: &[(u8, u8)]) -> Vec<usize> {
+        list.iter().map(|&(rank, suit)| card_id(rank, suit)).collect()
+    }
+
+    fn sorted(mut list: Vec<usize>) -> Vec<usize> {
+        list.sort_unstable();
+        list
+    }
+
+    #[test]
+    fn two_of_clubs_leads_first_trick() {
+        let hand = cards(&[(2, CLUBS), (9, CLUBS), (5, HEARTS), (1, SPADES)]);
+        let rules = TrickRules { lead_suit: None, first_trick: true, hearts_broken: false };
+        assert_eq!(rules.legal_plays(&hand), vec![two_of_clubs()]);
+        assert!(rules.check(&hand, card_id(9, CLUBS)).is_err());
+        assert!(rules.check(&hand, two_of_clubs()).is_ok());
+    }
+
+    #[test]
+    fn hearts_cannot_lead_until_broken() {
+        let hand = cards(&[(9, CLUBS), (5, HEARTS), (12, SPADES)]);
+        let unbroken = TrickRules { lead_suit: None, first_trick: false, hearts_broken: false };
+        assert_eq!(sorted(unbroken.legal_plays(&hand)), sorted(cards(&[(9, CLUBS), (12, SPADES)])));
+        assert!(unbroken.check(&hand, card_id(5, HEARTS)).is_err());
+
+        let broken = TrickRules { hearts_broken: true, ..unbroken };
+        assert_eq!(sorted(broken.legal_plays(&hand)), sorted(hand.clone()));
+
+        // 只剩红桃时可以首出
+        let all_hearts = cards(&[(5, HEARTS), (13, HEARTS)]);
+        assert_eq!(sorted(unbroken.legal_plays(&all_hearts)), sorted(all_hearts.clone()));
+    }
+
+    #[test]
+    fn first_trick_forbids_point_cards() {
+        let hand = cards(&[(5, HEARTS), (12, SPADES), (7, DIAMONDS)]);
+        let rules = TrickRules { lead_suit: Some(CLUBS), first_trick: true, hearts_broken: false };
+        assert_eq!(rules.legal_plays(&hand), cards(&[(7, DIAMONDS)]));
+        assert!(rules.check(&hand, queen_of_spades()).is_err());
+        assert!(rules.check(&hand, card_id(5, HEARTS)).is_err());
+
+        // 手里只有罚分牌时只能垫罚分牌
+        let only_points = cards(&[(5, HEARTS), (12, SPADES)]);
+        assert_eq!(sorted(rules.legal_plays(&only_points)), sorted(only_points.clone()));
+
+        // 第一墩之后可以随便垫
+        let later = TrickRules { first_trick: false, ..rules };
+        assert_eq!(sorted(later.legal_plays(&hand)), sorted(hand.clone()));
+    }
+
+    #[test]
+    fn must_follow_suit() {
+        let hand = cards(&[(3, SPADES), (1, SPADES), (5, HEARTS), (7, DIAMONDS)]);
+        let rules = TrickRules { lead_suit: Some(SPADES), first_trick: false, hearts_broken: false };
+        assert_eq!(sorted(rules.legal_plays(&hand)), sorted(cards(&[(3, SPADES), (1, SPADES)])));
+        assert!(rules.check(&hand, card_id(7, DIAMONDS)).is_err());
+        assert!(rules.check(&hand, card_id(1, SPADES)).is_ok());
+    }
+
+    #[test]
+    fn highest_card_of_lead_suit_wins() {
+        let trick = [
+            (2, card_id(10, DIAMONDS)),
+            (3, card_id(1, SPADES)),
+            (0, card_id(13, DIAMONDS)),
+            (1, card_id(2, DIAMONDS)),
+        ];
+        assert_eq!(trick_winner(&trick), 0);
+        // A 比 K 大，其他花色再大也吃不到
+        let trick = [(1, card_id(13, CLUBS)), (2, card_id(1, CLUBS)), (3, card_id(1, HEARTS)), (0, card_id(12, CLUBS))];
+        assert_eq!(trick_winner(&trick), 2);
+        assert_eq!(trick_points(&trick), 1);
+    }
+
+    #[test]
+    fn shooting_the_moon_gives_others_full_points() {
+        assert_eq!(hand_scores([3, 13, 0, 10]), ([3, 13, 0, 10], None));
+        assert_eq!(hand_scores([0, 0, TOTAL_POINTS, 0]), ([26, 26, 0, 26], Some(2)));
+    }
+
+    #[test]
+    fn pass_targets_rotate() {
+        assert_eq!(PassDirection::for_hand(0).target(0), Some(1));
+        assert_eq!(PassDirection::for_hand(1).target(0), Some(3));
+        assert_eq!(PassDirection::for_hand(2).target(1), Some(3));
+        assert_eq!(PassDirection::for_hand(3).target(2), None);
+        assert!(PassDirection::for_hand(4) == PassDirection::Left);
+        // 每个方向都是一一对应，每家正好收到一份
+        for direction in [PassDirection::Left, PassDirection::Right, PassDirection::Across] {
+            let targets = sorted((0..4).map(|seat| direction.target(seat).unwrap()).collect());
+            assert_eq!(targets, vec![0, 1, 2, 3]);
+            assert!((0..4).all(|seat| direction.target(seat) != Some(seat)));
+        }
+    }
+}
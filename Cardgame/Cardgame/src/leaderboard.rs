@@ -0,0 +1,391 @@
+// leaderboard.rs - 本地排行榜：每款游戏每个难度保留前10名
+use crate::difficulty::GameDifficulty;
+use crate::game2::CodeRules;
+use crate::player_profile::{self, GameKind, GameResult};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+/// 排行榜文件名
+const LEADERBOARD_FILE: &str = "leaderboard.json";
+/// 每个难度保留的名次
+const TOP_N: usize = 10;
+
+/// 排行榜上的一条成绩
+#[derive(Clone, Serialize, Deserialize)]
+pub struct LeaderboardEntry {
+    pub name: String,
+    pub difficulty: GameDifficulty,
+    pub time_secs: Option<u64>, // 用时（秒）
+    pub moves: Option<usize>,   // 移动次数 / 尝试次数
+    pub net_chips: i64,         // 德州扑克 / 21点本场净赢筹码，斗地主 / 锄大地本场净得分
+    #[serde(default)]
+    pub final_score: Option<i64>, // 最终得分（红心大战罚分、克朗代克纸牌计分）
+    #[serde(default)]
+    pub code_rules: Option<CodeRules>, // 猜数字的密码规则（不同规则分开排名）
+}
+
+impl LeaderboardEntry {
+    /// 由一局结果生成成绩
+    fn from_result(name: String, result: &GameResult) -> Self {
+        Self {
+            name,
+            difficulty: result.difficulty,
+            time_secs: result.time_secs,
+            moves: result.moves,
+            net_chips: result.net_chips,
+            final_score: result.final_score,
+            code_rules: result.code_rules,
+        }
+    }
+}
+
+/// 比较两条成绩，排在前面的为 Less
+/// 神经衰弱：用时少者优先，其次移动次数少；克朗代克：有计分时得分高者优先，其次用时少；猜数字：尝试次数少者优先，其次用时少；
+/// 德州扑克和21点：净赢筹码多者优先；红心大战：最终得分少者优先
+fn compare_entries(game: GameKind, a: &LeaderboardEntry, b: &LeaderboardEntry) -> Ordering {
+    // None 排在最后
+    let key = |value: Option<u64>| value.unwrap_or(u64::MAX);
+    let time = |entry: &LeaderboardEntry| key(entry.time_secs);
+    let moves = |entry: &LeaderboardEntry| key(entry.moves.map(|count| count as u64));
+    let final_score = |entry: &LeaderboardEntry| entry.final_score.unwrap_or(i64::MAX);
+    match game {
+        GameKind::Memory | GameKind::FreeCell | GameKind::Spider => {
+            time(a).cmp(&time(b)).then(moves(a).cmp(&moves(b)))
+        }
+        GameKind::Klondike => {
+            // 没有计分的成绩排在有计分的之后
+            let score = |entry: &LeaderboardEntry| entry.final_score.unwrap_or(i64::MIN);
+            score(b)
+                .cmp(&score(a))
+                .then(time(a).cmp(&time(b)))
+                .then(moves(a).cmp(&moves(b)))
+        }
+        GameKind::GuessNumber => moves(a).cmp(&moves(b)).then(time(a).cmp(&time(b))),
+        GameKind::Hearts => final_score(a).cmp(&final_score(b)),
+        GameKind::TexasHoldem | GameKind::Blackjack | GameKind::DouDizhu | GameKind::BigTwo => b.net_chips.cmp(&a.net_chips),
+    }
+}
+
+/// 一局结果能否上榜：神经衰弱、猜数字和红心大战只记录获胜的局，德州扑克、21点、斗地主和锄大地记录每一场
+fn is_eligible(result: &GameResult) -> bool {
+    result.won || matches!(result.game, GameKind::TexasHoldem | GameKind::Blackjack | GameKind::DouDizhu | GameKind::BigTwo)
+}
+
+/// 本地排行榜
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Leaderboard {
+    entries: BTreeMap<GameKind, Vec<LeaderboardEntry>>,
+    #[serde(skip)]
+    view_game: GameKind, // 排行榜界面当前查看的游戏
+    #[serde(skip)]
+    view_filter: Option<GameDifficulty>, // 难度筛选（None 为全部难度）
+    #[serde(skip)]
+    view_rules: Option<CodeRules>, // 猜数字当前查看的密码规则
+}
+
+impl Leaderboard {
+    /// 读取排行榜
+    pub fn load() -> Self {
+        player_profile::load_data_file(LEADERBOARD_FILE)
+    }
+
+    /// 保存排行榜
+    pub fn save(&self) -> std::io::Result<()> {
+        player_profile::save_data_file(LEADERBOARD_FILE, self)
+    }
+
+    /// 加入一条成绩，每个难度（猜数字再按密码规则区分）只保留前10名
+    pub fn insert(&mut self, game: GameKind, entry: LeaderboardEntry) {
+        let difficulty = entry.difficulty;
+        let code_rules = entry.code_rules;
+        let entries = self.entries.entry(game).or_default();
+        entries.push(entry);
+        entries.sort_by(|a, b| compare_entries(game, a, b));
+
+        let mut kept = 0;
+        entries.retain(|entry| {
+            if entry.difficulty != difficulty || entry.code_rules != code_rules {
+                return true;
+            }
+            kept += 1;
+            kept <= TOP_N
+        });
+    }
+
+    /// 显示排行榜界面，返回是否要返回主菜单
+    pub fn show(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut return_to_menu = false;
+
+        ui.vertical_centered(|ui| {
+            ui.add_space(20.0);
+            ui.heading("排行榜");
+            ui.add_space(20.0);
+
+            ui.horizontal(|ui| {
+                ui.add_space(ui.available_width() / 2.0 - GameKind::ALL.len() as f32 * 40.0);
+                for game in GameKind::ALL {
+                    ui.selectable_value(&mut self.view_game, game, game.label());
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.add_space(ui.available_width() / 2.0 - 250.0);
+                ui.label("难度:");
+                ui.selectable_value(&mut self.view_filter, None, "全部");
+                for difficulty in [
+                    GameDifficulty::Easy,
+                    GameDifficulty::Medium,
+                    GameDifficulty::Hard,
+                    GameDifficulty::Custom,
+                    GameDifficulty::Adaptive,
+                ] {
+                    ui.selectable_value(&mut self.view_filter, Some(difficulty), difficulty.label());
+                }
+            });
+            let game = self.view_game;
+            if game == GameKind::GuessNumber {
+                self.show_rules_filter(ui);
+            }
+            ui.add_space(20.0);
+
+            let shown: Vec<&LeaderboardEntry> = self
+                .entries
+                .get(&game)
+                .into_iter()
+                .flatten()
+                .filter(|entry| self.view_filter.is_none_or(|difficulty| entry.difficulty == difficulty))
+                .filter(|entry| game != GameKind::GuessNumber || entry.code_rules == self.view_rules)
+                .take(TOP_N)
+                .collect();
+
+            if shown.is_empty() {
+                ui.label("暂无成绩");
+            } else {
+                ui.horizontal(|ui| {
+                    ui.add_space(ui.available_width() / 2.0 - 200.0);
+                    Self::show_table(ui, game, &shown);
+                });
+            }
+
+            ui.add_space(30.0);
+            if ui.add_sized([150.0, 40.0], egui::Button::new("返回主菜单")).clicked() {
+                return_to_menu = true;
+            }
+        });
+
+        return_to_menu
+    }
+
+    /// 猜数字的密码规则筛选：不同规则的尝试次数没有可比性，每次只看一种规则
+    fn show_rules_filter(&mut self, ui: &mut egui::Ui) {
+        let mut rule_sets: Vec<Option<CodeRules>> = Vec::new();
+        for entry in self.entries.get(&GameKind::GuessNumber).into_iter().flatten() {
+            if !rule_sets.contains(&entry.code_rules) {
+                rule_sets.push(entry.code_rules);
+            }
+        }
+        if !rule_sets.contains(&self.view_rules)
+            && let Some(&first) = rule_sets.first()
+        {
+            self.view_rules = first;
+        }
+        if rule_sets.is_empty() {
+            return;
+        }
+
+        ui.horizontal_wrapped(|ui| {
+            ui.label("密码规则:");
+            for rules in rule_sets {
+                let label = rules.map_or("未记录规则".to_string(), |rules| rules.describe());
+                ui.selectable_value(&mut self.view_rules, rules, label);
+            }
+        });
+    }
+
+    /// 显示成绩表格
+    fn show_table(ui: &mut egui::Ui, game: GameKind, entries: &[&LeaderboardEntry]) {
+        let format_time = |time: Option<u64>| time.map_or("-".to_string(), |secs| format!("{}秒", secs));
+        let format_moves = |moves: Option<usize>| moves.map_or("-".to_string(), |count| count.to_string());
+        let format_score = |score: Option<i64>| score.map_or("-".to_string(), |score| score.to_string());
+
+        egui::Grid::new("leaderboard").striped(true).spacing(egui::vec2(20.0, 4.0)).show(ui, |ui| {
+            ui.strong("名次");
+            ui.strong("玩家");
+            ui.strong("难度");
+            match game {
+                GameKind::Memory | GameKind::FreeCell | GameKind::Spider => {
+                    ui.strong("用时");
+                    ui.strong("移动次数");
+                }
+                GameKind::Klondike => {
+                    ui.strong("用时");
+                    ui.strong("移动次数");
+                    ui.strong("分数");
+                }
+                GameKind::GuessNumber => {
+                    ui.strong("尝试次数");
+                    ui.strong("用时");
+                }
+                GameKind::TexasHoldem | GameKind::Blackjack => {
+                    ui.strong("净赢筹码");
+                }
+                GameKind::DouDizhu | GameKind::BigTwo => {
+                    ui.strong("净得分");
+                }
+                GameKind::Hearts => {
+                    ui.strong("最终得分");
+                }
+            }
+            ui.end_row();
+
+            for (rank, entry) in entries.iter().enumerate() {
+                ui.label(format!("{}", rank + 1));
+                ui.label(&entry.name);
+                ui.label(entry.difficulty.label());
+                match game {
+                    GameKind::Memory | GameKind::FreeCell | GameKind::Spider => {
+                        ui.label(format_time(entry.time_secs));
+                        ui.label(format_moves(entry.moves));
+                    }
+                    GameKind::Klondike => {
+                        ui.label(format_time(entry.time_secs));
+                        ui.label(format_moves(entry.moves));
+                        ui.label(format_score(entry.final_score));
+                    }
+                    GameKind::GuessNumber => {
+                        ui.label(format_moves(entry.moves));
+                        ui.label(format_time(entry.time_secs));
+                    }
+                    GameKind::TexasHoldem | GameKind::Blackjack | GameKind::DouDizhu | GameKind::BigTwo => {
+                        ui.label(entry.net_chips.to_string());
+                    }
+                    GameKind::Hearts => {
+                        ui.label(format_score(entry.final_score));
+                    }
+                }
+                ui.end_row();
+            }
+        });
+    }
+}
+
+/// 游戏结束界面上的成绩提交：输入名字后把成绩交给主程序写入排行榜
+pub struct ScoreSubmission {
+    pub player_name: String,                          // 玩家名字（在多局之间保留）
+    pending: Option<GameResult>,                      // 等待提交的成绩
+    submitted: Option<(GameKind, LeaderboardEntry)>,  // 已提交、等待写入排行榜的成绩
+}
+
+impl ScoreSubmission {
+    pub fn new() -> Self {
+        Self {
+            player_name: "玩家".to_string(),
+            pending: None,
+            submitted: None,
+        }
+    }
+
+    /// 一局结束时调用，可以上榜的成绩会在结束界面提供名字输入
+    pub fn offer(&mut self, result: &GameResult) {
+        self.pending = is_eligible(result).then(|| result.clone());
+    }
+
+    /// 清除等待提交的成绩
+    pub fn clear(&mut self) {
+        self.pending = None;
+    }
+
+    /// 显示名字输入和提交按钮
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        if self.pending.is_none() {
+            return;
+        }
+        ui.horizontal(|ui| {
+            ui.add_space(ui.available_width() / 2.0 - 150.0);
+            ui.label("上榜名字:");
+            ui.add(egui::TextEdit::singleline(&mut self.player_name).desired_width(120.0));
+            let name = self.player_name.trim();
+            if ui.add_enabled(!name.is_empty(), egui::Button::new("提交成绩")).clicked()
+                && let Some(result) = self.pending.take()
+            {
+                let entry = LeaderboardEntry::from_result(name.to_string(), &result);
+                self.submitted = Some((result.game, entry));
+            }
+        });
+    }
+
+    /// 取出已提交的成绩
+    pub fn take_entry(&mut self) -> Option<(GameKind, LeaderboardEntry)> {
+        self.submitted.take()
+    }
+}
+
+impl Default for ScoreSubmission {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 一条猜数字成绩
+    fn guess_entry(moves: usize, code_rules: CodeRules) -> LeaderboardEntry {
+        LeaderboardEntry {
+            name: "玩家".to_string(),
+            difficulty: GameDifficulty::Custom,
+            time_secs: Some(60),
+            moves: Some(moves),
+            net_chips: 0,
+            final_score: None,
+            code_rules: Some(code_rules),
+        }
+    }
+
+    #[test]
+    fn guess_number_ranks_each_rule_set_separately() {
+        let short = CodeRules { length: 3, alphabet_size: 6, allow_repeats: false };
+        let long = CodeRules { length: 8, alphabet_size: 10, allow_repeats: true };
+        let mut leaderboard = Leaderboard::default();
+        leaderboard.insert(GameKind::GuessNumber, guess_entry(12, long));
+        for moves in 1..=TOP_N + 2 {
+            leaderboard.insert(GameKind::GuessNumber, guess_entry(moves, short));
+        }
+
+        let entries = &leaderboard.entries[&GameKind::GuessNumber];
+        let count = |rules: CodeRules| entries.iter().filter(|entry| entry.code_rules == Some(rules)).count();
+        assert_eq!(count(short), TOP_N);
+        assert_eq!(count(long), 1);
+        assert!(entries.iter().all(|entry| entry.moves <= Some(TOP_N) || entry.code_rules == Some(long)));
+    }
+
+    #[test]
+    fn klondike_ranks_by_score_before_time() {
+        let entry = |final_score: Option<i64>, time_secs: u64| LeaderboardEntry {
+            name: "玩家".to_string(),
+            difficulty: GameDifficulty::Medium,
+            time_secs: Some(time_secs),
+            moves: Some(100),
+            net_chips: 0,
+            final_score,
+            code_rules: None,
+        };
+        let mut leaderboard = Leaderboard::default();
+        leaderboard.insert(GameKind::Klondike, entry(None, 60));
+        leaderboard.insert(GameKind::Klondike, entry(Some(500), 300));
+        leaderboard.insert(GameKind::Klondike, entry(Some(700), 600));
+        leaderboard.insert(GameKind::Klondike, entry(Some(500), 200));
+
+        let ranked: Vec<_> = leaderboard.entries[&GameKind::Klondike]
+            .iter()
+            .map(|entry| (entry.final_score, entry.time_secs))
+            .collect();
+        assert_eq!(
+            ranked,
+            [(Some(700), Some(600)), (Some(500), Some(200)), (Some(500), Some(300)), (None, Some(60))]
+        );
+    }
+}
@@ -1,19 +1,48 @@
 // 声明模块
+mod achievements;
+mod animation;
+mod bigtwo;
+mod blackjack_trainer;
 mod card;
+mod card_game;
+mod code_solver;
+mod doudizhu;
+mod freecell_solver;
 mod game1;
+mod game10;
 mod game2;
 mod game3;
+mod game4;
+mod game5;
+mod game6;
+mod game7;
+mod game8;
+mod game9;
+mod hearts;
+mod leaderboard;
+mod notepad;
+mod player_profile;
+mod poker_hand;
+mod save_game;
+mod solitaire;
+mod timer;
 mod util;
 mod difficulty;
 
 // 导入依赖
+use achievements::{Achievements, GameEvent};
+use blackjack_trainer::BlackjackTrainer;
 use card::Card;
+use card_game::GameRegistry;
 use eframe::egui;
-use game1::MemoryGame;
-use game2::GuessNumberGame;
-use game3::TexasHoldemGame;
+use leaderboard::{Leaderboard, LeaderboardEntry};
+use player_profile::{GameKind, GameResult, PlayerProfile};
+use save_game::SavedSession;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
+
+/// 保存失败提示的显示时间
+const SAVE_ERROR_DURATION: Duration = Duration::from_secs(5);
 
 /// 程序主入口点
 fn main() -> eframe::Result {
@@ -62,22 +91,28 @@ fn setup_fonts(ctx: &egui::Context) {
 #[derive(PartialEq, Clone, Copy)]
 enum AppState {
     MainMenu,           // 主菜单状态
-    MemoryGame,         // 神经衰弱游戏状态
-    GuessNumberGame,    // 猜数字游戏状态
-    TexasHoldem,        // 德州扑克游戏状态
+    Game(GameKind),     // 某款游戏进行中
+    Statistics,         // 统计数据界面
+    Leaderboard,        // 排行榜界面
+    Achievements,       // 成就界面
+    Trainer,            // 21点训练界面
 }
 
 /// 主应用程序结构体
 struct CardGameApp {
-    cards: Vec<Card>,               // 主菜单卡片
+    cards: Vec<(GameKind, Card)>,   // 主菜单卡片（每款游戏一张）
     app_state: AppState,            // 应用程序状态
-    memory_game: MemoryGame,        // 神经衰弱游戏实例
-    guess_number_game: GuessNumberGame, // 猜数字游戏实例
-    texas_holdem_game: TexasHoldemGame, // 德州扑克游戏实例
+    registry: GameRegistry,         // 所有注册的游戏
     transition_timer: Option<Instant>, // 转场计时器
     transition_progress: f32,       // 转场进度
     target_game: AppState,          // 目标游戏
     background_texture: Option<egui::TextureHandle>, // 背景图片纹理
+    profile: PlayerProfile,         // 玩家档案（统计数据）
+    leaderboard: Leaderboard,       // 本地排行榜
+    achievements: Achievements,     // 成就进度
+    trainer: BlackjackTrainer,      // 21点训练
+    saved_session: Option<SavedSession>, // 最近一局未完成的游戏
+    save_error: Option<(String, Instant)>, // 最近一次保存失败的提示和出现时间
 }
 
 impl CardGameApp {
@@ -101,43 +136,147 @@ impl CardGameApp {
             None
         };
 
-        // 初始化主菜单卡片
-        
+        // 初始化游戏注册表，为每款游戏创建一张主菜单卡片
+        let mut registry = GameRegistry::new();
         let mut cards = Vec::new();
-        
-        // 创建三张卡片
-        if let Ok(card) = Card::new(ctx, 0, 1, 1, "assets/card_back/default.png", "assets/card_face/JOKER-A.png") {
-            cards.push(card);
-        }
-        
-        if let Ok(card) = Card::new(ctx, 1, 1, 1, "assets/card_back/default.png", "assets/card_face/JOKER-B.png") {
-            cards.push(card);
-        }
-        
-        if let Ok(card) = Card::new(ctx, 2, 1, 1, "assets/card_back/default.png", "assets/card_face/JOKER-A.png") {
-            cards.push(card);
+        for (index, game) in registry.games().iter().enumerate() {
+            if let Ok(card) = Card::new(ctx, index, 1, 1, "assets/card_back/default.png", game.menu_face()) {
+                cards.push((game.kind(), card));
+            }
         }
 
-        // 初始化游戏实例并设置主菜单卡片用于纹理复用
-        let mut memory_game = MemoryGame::new();
-        memory_game.set_main_menu_cards(cards.clone());
-
-        let mut texas_holdem_game = TexasHoldemGame::new();
-        texas_holdem_game.set_main_menu_cards(cards.clone());
+        // 设置主菜单卡片用于纹理复用
+        let menu_cards: Vec<Card> = cards.iter().map(|(_, card)| card.clone()).collect();
+        registry.set_main_menu_cards(&menu_cards);
 
         Self {
             cards,
             app_state: AppState::MainMenu,
-            memory_game,
-            guess_number_game: GuessNumberGame::new(),
-            texas_holdem_game,
+            registry,
             transition_timer: None,
             transition_progress: 0.0,
             target_game: AppState::MainMenu,
             background_texture,
+            profile: PlayerProfile::load(),
+            leaderboard: Leaderboard::load(),
+            achievements: Achievements::load(),
+            trainer: BlackjackTrainer::new(),
+            saved_session: SavedSession::load(),
+            save_error: None,
+        }
+    }
+
+    /// 保存失败时记下提示，在窗口底部显示一段时间
+    fn report_save_error(&mut self, what: &str, result: std::io::Result<()>) {
+        if let Err(error) = result {
+            self.save_error = Some((format!("{}失败: {}", what, error), Instant::now()));
+        }
+    }
+
+    /// 在窗口底部显示保存失败的提示
+    fn show_save_error(&mut self, ctx: &egui::Context) {
+        let Some((message, since)) = &self.save_error else {
+            return;
+        };
+        if since.elapsed() >= SAVE_ERROR_DURATION {
+            self.save_error = None;
+            return;
+        }
+        egui::Area::new(egui::Id::new("save_error"))
+            .order(egui::Order::Foreground)
+            .anchor(egui::Align2::CENTER_BOTTOM, egui::vec2(0.0, -20.0))
+            .interactable(false)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.colored_label(egui::Color32::RED, message);
+                });
+            });
+        ctx.request_repaint_after(Duration::from_millis(200));
+    }
+
+    /// 记录一局结果并保存档案
+    fn record_result(&mut self, result: Option<GameResult>) {
+        if let Some(result) = result {
+            self.profile.record(&result);
+            let saved = self.profile.save();
+            self.report_save_error("保存玩家档案", saved);
+            self.handle_events(vec![GameEvent::Finished(result)]);
+        }
+    }
+
+    /// 用游戏事件更新成就进度，有变化时保存
+    fn handle_events(&mut self, events: Vec<GameEvent>) {
+        let mut changed = false;
+        for event in &events {
+            changed |= self.achievements.handle_event(event);
+        }
+        if changed {
+            let saved = self.achievements.save();
+            self.report_save_error("保存成就进度", saved);
+        }
+    }
+
+    /// 写入一条排行榜成绩并保存
+    fn record_leaderboard_entry(&mut self, entry: Option<(GameKind, LeaderboardEntry)>) {
+        if let Some((game, entry)) = entry {
+            self.leaderboard.insert(game, entry);
+            let saved = self.leaderboard.save();
+            self.report_save_error("保存排行榜", saved);
         }
     }
 
+    /// 保存一局未完成的游戏（覆盖之前的存档）
+    fn store_session(&mut self, session: Option<SavedSession>) {
+        if let Some(session) = session {
+            let saved = session.save();
+            self.report_save_error("保存游戏进度", saved);
+            self.saved_session = Some(session);
+        }
+    }
+
+    /// 恢复存档中的游戏并直接进入游戏界面
+    fn continue_session(&mut self, ctx: &egui::Context) {
+        let Some(session) = self.saved_session.take() else {
+            return;
+        };
+        let cleared = SavedSession::clear();
+        self.report_save_error("删除游戏存档", cleared);
+        let kind = session.game();
+        if let Some(game) = self.registry.get_mut(kind) {
+            game.restore_state(ctx, session);
+            self.app_state = AppState::Game(kind);
+        }
+    }
+
+    /// 当前正在进行的游戏的存档
+    fn current_session(&self) -> Option<SavedSession> {
+        match self.app_state {
+            AppState::Game(kind) => self.registry.get(kind)?.save_state(),
+            _ => None,
+        }
+    }
+
+    /// 显示游戏界面，并处理游戏产生的结果、事件、排行榜成绩和存档
+    fn show_game(&mut self, kind: GameKind, ui: &mut egui::Ui, ctx: &egui::Context) {
+        let Some(game) = self.registry.get_mut(kind) else {
+            self.app_state = AppState::MainMenu;
+            return;
+        };
+        let return_to_menu = game.show(ui, ctx);
+        let events = game.take_events();
+        let result = game.take_result();
+        let entry = game.take_leaderboard_entry();
+        let session = game.take_suspended_session();
+
+        if return_to_menu {
+            self.app_state = AppState::MainMenu;
+        }
+        self.handle_events(events);
+        self.record_result(result);
+        self.record_leaderboard_entry(entry);
+        self.store_session(session);
+    }
+
     /// 显示主菜单界面
     fn show_main_menu(&mut self, ui: &mut egui::Ui) {
         // 处理转场动画
@@ -150,16 +289,21 @@ impl CardGameApp {
                 self.app_state = self.target_game;
                 self.transition_timer = None;
                 self.transition_progress = 0.0;
+                if let AppState::Game(kind) = self.target_game
+                    && let Some(game) = self.registry.get_mut(kind)
+                {
+                    game.reset();
+                }
                 
                 // 将所有卡片翻回背面
-                for card in &mut self.cards {
+                for (_, card) in &mut self.cards {
                     card.is_face_up = false;
                 }
             }
         }
 
         // 在整个面板上绘制黑色覆盖层
-        if let Some(_) = self.transition_timer {
+        if self.transition_timer.is_some() {
             let alpha = (self.transition_progress * 255.0) as u8;
             let darken_color = egui::Color32::from_rgba_premultiplied(0, 0, 0, alpha);
             ui.painter().rect_filled(ui.available_rect_before_wrap(), 0.0, darken_color);
@@ -176,71 +320,112 @@ impl CardGameApp {
                 ui.add_space(40.0);
             });
 
-            // 卡片显示区域
-            ui.horizontal(|ui| {
-                let available_width = ui.available_width();
-                let total_cards_width = 150.0 * 3.0 + 30.0 * 2.0;
-                let horizontal_padding = (available_width - total_cards_width) / 2.0;
-                
-                ui.add_space(horizontal_padding.max(0.0));
-                
-                for index in 0..self.cards.len() {
-                    ui.vertical(|ui| {
-                        ui.add_space(10.0);
-                        
-                        let card = &mut self.cards[index];
-                        
-                        // 基础卡片大小
-                        let base_size = egui::vec2(150.0, 220.0);
-                        
-                        // 为卡片分配空间并检测悬停
-                        let (_, card_rect) = ui.allocate_space(base_size);
-                        let is_hovered = ui.rect_contains_pointer(card_rect) && self.transition_timer.is_none();
-                        
-                        // 根据悬停状态确定显示大小
-                        let display_size = if is_hovered {
-                            base_size * 1.1 // 悬停时放大10%
-                        } else {
-                            base_size
-                        };
-                        
-                     
-                        let center_offset = (display_size - base_size) * 0.5;
-                        let card_pos = card_rect.min - center_offset;
-                        
-                        // 在正确位置显示卡片
-                        ui.allocate_ui_at_rect(
-                            egui::Rect::from_min_size(card_pos, display_size),
-                            |ui| {
-                                let response = card.show(ui, display_size);
-                                
-                                // 只在没有转场时处理点击
-                                if self.transition_timer.is_none() && response.clicked() {
-                                    // 开始转场动画
-                                    self.transition_timer = Some(Instant::now());
-                                    card.is_face_up = true; 
-                                
-                                    // 根据点击的卡片设置目标游戏
-                                    self.target_game = match index {
-                                        0 => AppState::MemoryGame,
-                                        1 => AppState::GuessNumberGame,
-                                        2 => AppState::TexasHoldem,
-                                        _ => AppState::MainMenu,
-                                    };
+            // 卡片显示区域：每款游戏一张卡片，一行放不下时自动换行
+            let base_size = egui::vec2(150.0, 220.0); // 基础卡片大小
+            let card_spacing = 30.0;
+            let cards_per_row = (((ui.available_width() + card_spacing) / (base_size.x + card_spacing)) as usize).max(1);
+            let card_count = self.cards.len();
+
+            for row_start in (0..card_count).step_by(cards_per_row) {
+                let row_end = (row_start + cards_per_row).min(card_count);
+                ui.horizontal(|ui| {
+                    let row_cards = (row_end - row_start) as f32;
+                    let row_width = base_size.x * row_cards + card_spacing * (row_cards - 1.0);
+                    let horizontal_padding = (ui.available_width() - row_width) / 2.0;
+
+                    ui.add_space(horizontal_padding.max(0.0));
+
+                    for index in row_start..row_end {
+                        ui.vertical(|ui| {
+                            ui.add_space(10.0);
+
+                            let (kind, card) = &mut self.cards[index];
+                            let kind = *kind;
+
+                            // 为卡片分配空间并检测悬停
+                            let (_, card_rect) = ui.allocate_space(base_size);
+                            let is_hovered = ui.rect_contains_pointer(card_rect) && self.transition_timer.is_none();
+
+                            // 根据悬停状态确定显示大小
+                            let display_size = if is_hovered {
+                                base_size * 1.1 // 悬停时放大10%
+                            } else {
+                                base_size
+                            };
+
+                            let center_offset = (display_size - base_size) * 0.5;
+                            let card_pos = card_rect.min - center_offset;
+
+                            // 在正确位置显示卡片
+                            ui.scope_builder(
+                                egui::UiBuilder::new().max_rect(egui::Rect::from_min_size(card_pos, display_size)),
+                                |ui| {
+                                    let response = card.show(ui, display_size);
+
+                                    // 只在没有转场时处理点击
+                                    if self.transition_timer.is_none() && response.clicked() {
+                                        // 开始转场动画
+                                        self.transition_timer = Some(Instant::now());
+                                        card.flip_to(true);
+                                        self.target_game = AppState::Game(kind);
+                                    }
                                 }
-                            }
-                        );
-                        
-                        ui.add_space(10.0);
-                    });
-                    
-                    if index < self.cards.len() - 1 {
-                        ui.add_space(30.0);
+                            );
+
+                            // 游戏名称
+                            ui.add_space(5.0);
+                            ui.allocate_ui(egui::vec2(base_size.x, 20.0), |ui| {
+                                ui.centered_and_justified(|ui| {
+                                    ui.label(kind.label());
+                                });
+                            });
+
+                            ui.add_space(10.0);
+                        });
+
+                        if index + 1 < row_end {
+                            ui.add_space(card_spacing);
+                        }
                     }
-                }
-                
-                ui.add_space(horizontal_padding.max(0.0));
-            });
+                });
+            }
+
+            // 继续上次未完成的游戏
+            if self.transition_timer.is_none()
+                && let Some(session) = &self.saved_session
+            {
+                ui.add_space(30.0);
+                let text = format!("继续游戏（{}）", session.game().label());
+                ui.horizontal(|ui| {
+                    ui.add_space(ui.available_width() / 2.0 - 100.0);
+                    if ui.add_sized([200.0, 40.0], egui::Button::new(text)).clicked() {
+                        self.continue_session(ui.ctx());
+                    }
+                });
+            }
+
+            // 统计数据、排行榜、成就和训练入口
+            if self.transition_timer.is_none() {
+                ui.add_space(20.0);
+                ui.horizontal(|ui| {
+                    ui.add_space(ui.available_width() / 2.0 - 330.0);
+                    if ui.add_sized([150.0, 40.0], egui::Button::new("统计数据")).clicked() {
+                        self.app_state = AppState::Statistics;
+                    }
+                    ui.add_space(20.0);
+                    if ui.add_sized([150.0, 40.0], egui::Button::new("排行榜")).clicked() {
+                        self.app_state = AppState::Leaderboard;
+                    }
+                    ui.add_space(20.0);
+                    if ui.add_sized([150.0, 40.0], egui::Button::new("成就")).clicked() {
+                        self.app_state = AppState::Achievements;
+                    }
+                    ui.add_space(20.0);
+                    if ui.add_sized([150.0, 40.0], egui::Button::new("21点训练")).clicked() {
+                        self.app_state = AppState::Trainer;
+                    }
+                });
+            }
 
             // 显示转场提示
             if self.transition_timer.is_some() {
@@ -254,10 +439,9 @@ impl CardGameApp {
                         egui::Color32::BLACK
                     };
                     
+
                     let game_name = match self.target_game {
-                        AppState::MemoryGame => "神经衰弱游戏",
-                        AppState::GuessNumberGame => "猜数字游戏",
-                        AppState::TexasHoldem => "德州扑克游戏",
+                        AppState::Game(kind) => self.registry.get(kind).map_or("游戏", |game| game.name()),
                         _ => "游戏",
                     };
                     
@@ -271,6 +455,12 @@ impl CardGameApp {
 }
 
 impl eframe::App for CardGameApp {
+    /// 关闭窗口时保存正在进行的游戏
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        let session = self.current_session();
+        self.store_session(session);
+    }
+
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         egui::CentralPanel::default()
             .frame(egui::Frame::NONE.fill(egui::Color32::TRANSPARENT))
@@ -293,22 +483,36 @@ impl eframe::App for CardGameApp {
                     AppState::MainMenu => {
                         self.show_main_menu(ui);
                     }
-                    AppState::MemoryGame => {
-                        if self.memory_game.show(ui, ctx) {
+                    AppState::Game(kind) => {
+                        self.show_game(kind, ui, ctx);
+                    }
+                    AppState::Statistics => {
+                        if self.profile.show_statistics(ui) {
                             self.app_state = AppState::MainMenu;
                         }
                     }
-                    AppState::GuessNumberGame => {
-                        if self.guess_number_game.show(ui, ctx) {
+                    AppState::Leaderboard => {
+                        if self.leaderboard.show(ui) {
                             self.app_state = AppState::MainMenu;
                         }
                     }
-                    AppState::TexasHoldem => {
-                        if self.texas_holdem_game.show(ui, ctx) {
+                    AppState::Achievements => {
+                        if self.achievements.show(ui) {
+                            self.app_state = AppState::MainMenu;
+                        }
+                    }
+                    AppState::Trainer => {
+                        if self.trainer.show(ui) {
                             self.app_state = AppState::MainMenu;
                         }
                     }
                 }
+
+                // 成就解锁提示（游戏进行中也会显示）
+                self.achievements.show_toasts(ctx);
+
+                // 保存失败提示（游戏进行中也会显示）
+                self.show_save_error(ctx);
             });
     }
 }
\ No newline at end of file
@@ -0,0 +1,197 @@
+// notepad.rs - 猜数字推理笔记（位置 × 数字 标记表）
+use crate::code_solver::{CodeSolver, Feedback};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+/// 笔记格子的标记
+#[derive(PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub enum NoteMark {
+    Unknown,   // 未标记
+    Excluded,  // 排除：该位置不可能是这个数字
+    Possible,  // 可能
+    Confirmed, // 确定：该位置就是这个数字
+}
+
+impl NoteMark {
+    /// 点击后的下一个标记（右键为上一个）
+    fn cycle(self, forward: bool) -> Self {
+        const ORDER: [NoteMark; 4] = [NoteMark::Unknown, NoteMark::Excluded, NoteMark::Possible, NoteMark::Confirmed];
+        let index = ORDER.iter().position(|&mark| mark == self).unwrap_or(0);
+        let next = if forward { index + 1 } else { index + ORDER.len() - 1 };
+        ORDER[next % ORDER.len()]
+    }
+
+    /// 格子中显示的符号和颜色
+    fn symbol(self) -> (&'static str, egui::Color32) {
+        match self {
+            NoteMark::Unknown => ("", egui::Color32::WHITE),
+            NoteMark::Excluded => ("✗", egui::Color32::from_rgb(230, 200, 200)),
+            NoteMark::Possible => ("?", egui::Color32::from_rgb(250, 240, 180)),
+            NoteMark::Confirmed => ("✓", egui::Color32::from_rgb(190, 235, 190)),
+        }
+    }
+}
+
+/// 推理笔记：每个位置上每个数字的标记
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DeductionNotepad {
+    marks: Vec<Vec<NoteMark>>, // marks[位置][数字]
+    pub auto_fill: bool,       // 每次猜测后自动填入推理结果
+}
+
+impl DeductionNotepad {
+    /// 创建空白笔记
+    pub fn new(length: usize, alphabet_size: u8) -> Self {
+        Self {
+            marks: vec![vec![NoteMark::Unknown; alphabet_size as usize]; length],
+            auto_fill: false,
+        }
+    }
+
+    /// 清空所有标记
+    pub fn clear(&mut self) {
+        for row in &mut self.marks {
+            row.fill(NoteMark::Unknown);
+        }
+    }
+
+    /// 根据猜测记录推理，填入确定排除和确定正确的格子（不改动其他格子）
+    pub fn apply_deductions(&mut self, solver: Option<&CodeSolver>, guesses: &[Feedback]) {
+        // 候选集合已枚举时可以精确推理：没有候选在该位置使用该数字则排除，所有候选都使用则确定
+        if let Some(candidates) = solver.and_then(|solver| solver.candidates())
+            && !candidates.is_empty()
+        {
+            for (position, row) in self.marks.iter_mut().enumerate() {
+                for (digit, mark) in row.iter_mut().enumerate() {
+                    let count = candidates.iter().filter(|code| code[position] as usize == digit).count();
+                    if count == 0 {
+                        *mark = NoteMark::Excluded;
+                    } else if count == candidates.len() {
+                        *mark = NoteMark::Confirmed;
+                    }
+                }
+            }
+            return;
+        }
+
+        // 可能性过多时只使用简单规则
+        for (guess, (a_count, b_count)) in guesses {
+            if *a_count != 0 {
+                continue;
+            }
+            for (position, &digit) in guess.iter().enumerate() {
+                if *b_count == 0 {
+                    // 0A0B：猜测中的数字在所有位置都被排除
+                    for row in &mut self.marks {
+                        row[digit as usize] = NoteMark::Excluded;
+                    }
+                } else {
+                    // 0A：每个数字都不在它所在的位置
+                    self.marks[position][digit as usize] = NoteMark::Excluded;
+                }
+            }
+        }
+    }
+
+    /// 显示笔记表格，labels 为每个数字的列标题
+    pub fn show(&mut self, ui: &mut egui::Ui, labels: &[String]) {
+        egui::Grid::new("deduction_notepad")
+            .spacing(egui::vec2(2.0, 2.0))
+            .show(ui, |ui| {
+                ui.label("");
+                for label in labels {
+                    ui.label(label);
+                }
+                ui.end_row();
+
+                for (position, row) in self.marks.iter_mut().enumerate() {
+                    ui.label(format!("第{}位", position + 1));
+                    for mark in row.iter_mut() {
+                        let (symbol, fill) = mark.symbol();
+                        let button = egui::Button::new(symbol)
+                            .fill(fill)
+                            .min_size(egui::vec2(28.0, 24.0));
+                        let response = ui.add(button);
+                        if response.clicked() {
+                            *mark = mark.cycle(true);
+                        } else if response.secondary_clicked() {
+                            *mark = mark.cycle(false);
+                        }
+                    }
+                    ui.end_row();
+                }
+            });
+        ui.label("点击格子切换标记：空白 → ✗排除 → ?可能 → ✓确定（右键反向）");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game2::CodeRules;
+
+    /// 加入所有反馈后的求解器
+    fn solver_with(rules: CodeRules, guesses: &[Feedback]) -> CodeSolver {
+        let mut solver = CodeSolver::new(rules);
+        for (guess, score) in guesses {
+            solver.add_feedback(guess, *score);
+        }
+        solver
+    }
+
+    #[test]
+    fn zero_a_zero_b_excludes_digits_everywhere() {
+        let mut notepad = DeductionNotepad::new(3, 6);
+        let guesses = vec![(vec![1, 2, 3], (0, 0)), (vec![4, 5, 0], (0, 2))];
+        notepad.apply_deductions(None, &guesses);
+        for row in &notepad.marks {
+            for digit in [1, 2, 3] {
+                assert!(row[digit] == NoteMark::Excluded);
+            }
+        }
+        // 0A2B 只排除每个数字所在的位置
+        assert!(notepad.marks[0][4] == NoteMark::Excluded);
+        assert!(notepad.marks[1][5] == NoteMark::Excluded);
+        assert!(notepad.marks[2][0] == NoteMark::Excluded);
+        assert!(notepad.marks[0][5] == NoteMark::Unknown);
+        assert!(notepad.marks[1][0] == NoteMark::Unknown);
+    }
+
+    #[test]
+    fn enumerated_candidates_confirm_position() {
+        let rules = CodeRules { length: 3, alphabet_size: 4, allow_repeats: false };
+        // 剩下 013 和 032 两种可能：第1位确定是 0
+        let guesses = vec![(vec![0, 1, 2], (2, 0)), (vec![3, 2, 1], (0, 2))];
+        let solver = solver_with(rules, &guesses);
+        assert_eq!(solver.remaining(), Some(2));
+
+        let mut notepad = DeductionNotepad::new(3, 4);
+        notepad.apply_deductions(Some(&solver), &guesses);
+        assert!(notepad.marks[0][0] == NoteMark::Confirmed);
+        for digit in 1..4 {
+            assert!(notepad.marks[0][digit] == NoteMark::Excluded);
+        }
+        // 第2位可能是 1 或 3，不做标记
+        assert!(notepad.marks[1][1] == NoteMark::Unknown);
+        assert!(notepad.marks[1][3] == NoteMark::Unknown);
+        assert!(notepad.marks[1][2] == NoteMark::Excluded);
+    }
+
+    #[test]
+    fn no_candidates_falls_back_to_simple_rules() {
+        let rules = CodeRules { length: 3, alphabet_size: 4, allow_repeats: false };
+        let guesses = vec![(vec![0, 1, 2], (3, 0)), (vec![0, 1, 2], (0, 3))];
+        let solver = solver_with(rules, &guesses);
+        assert!(solver.is_inconsistent());
+
+        let mut notepad = DeductionNotepad::new(3, 4);
+        notepad.apply_deductions(Some(&solver), &guesses);
+        // 没有候选时不会把所有格子都排除，只用 0A 的规则
+        assert!(notepad.marks.iter().flatten().all(|&mark| mark != NoteMark::Confirmed));
+        assert!(notepad.marks[0][0] == NoteMark::Excluded);
+        assert!(notepad.marks[1][1] == NoteMark::Excluded);
+        assert!(notepad.marks[2][2] == NoteMark::Excluded);
+        assert!(notepad.marks[0][1] == NoteMark::Unknown);
+        assert_eq!(notepad.marks.iter().flatten().filter(|&&mark| mark == NoteMark::Excluded).count(), 3);
+    }
+}
@@ -0,0 +1,290 @@
+// player_profile.rs - 玩家档案：按游戏和难度保存的统计数据（本地 JSON 文件）
+use crate::difficulty::GameDifficulty;
+use crate::game2::CodeRules;
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// 档案文件名
+const PROFILE_FILE: &str = "profile.json";
+
+/// 本地数据文件路径：优先使用系统的用户数据目录，否则使用当前目录
+fn data_file_path(file_name: &str) -> PathBuf {
+    match dirs::data_dir() {
+        Some(dir) => dir.join("cardgame").join(file_name),
+        None => PathBuf::from(file_name),
+    }
+}
+
+/// 把数据以 JSON 格式写入本地数据文件
+pub fn save_data_file<T: Serialize>(file_name: &str, data: &T) -> std::io::Result<()> {
+    let path = data_file_path(file_name);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let text = serde_json::to_string_pretty(data).map_err(std::io::Error::other)?;
+    std::fs::write(path, text)
+}
+
+/// 读取本地数据文件，文件不存在或损坏时返回默认值
+pub fn load_data_file<T: for<'de> Deserialize<'de> + Default>(file_name: &str) -> T {
+    std::fs::read_to_string(data_file_path(file_name))
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+/// 删除本地数据文件（文件不存在时忽略）
+pub fn remove_data_file(file_name: &str) -> std::io::Result<()> {
+    match std::fs::remove_file(data_file_path(file_name)) {
+        Err(error) if error.kind() != std::io::ErrorKind::NotFound => Err(error),
+        _ => Ok(()),
+    }
+}
+
+/// 游戏种类
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Default, Serialize, Deserialize)]
+pub enum GameKind {
+    #[default]
+    Memory,      // 神经衰弱
+    GuessNumber, // 猜数字
+    TexasHoldem, // 德州扑克
+    Blackjack,   // 21点
+    Klondike,    // 克朗代克纸牌
+    FreeCell,    // 空当接龙
+    Spider,      // 蜘蛛纸牌
+    DouDizhu,    // 斗地主
+    BigTwo,      // 锄大地
+    Hearts,      // 红心大战
+}
+
+impl GameKind {
+    /// 所有游戏
+    pub const ALL: [GameKind; 10] = [
+        GameKind::Memory,
+        GameKind::GuessNumber,
+        GameKind::TexasHoldem,
+        GameKind::Blackjack,
+        GameKind::Klondike,
+        GameKind::FreeCell,
+        GameKind::Spider,
+        GameKind::DouDizhu,
+        GameKind::BigTwo,
+        GameKind::Hearts,
+    ];
+
+    /// 游戏名称
+    pub fn label(self) -> &'static str {
+        match self {
+            GameKind::Memory => "神经衰弱",
+            GameKind::GuessNumber => "猜数字",
+            GameKind::TexasHoldem => "德州扑克",
+            GameKind::Blackjack => "21点",
+            GameKind::Klondike => "克朗代克纸牌",
+            GameKind::FreeCell => "空当接龙",
+            GameKind::Spider => "蜘蛛纸牌",
+            GameKind::DouDizhu => "斗地主",
+            GameKind::BigTwo => "锄大地",
+            GameKind::Hearts => "红心大战",
+        }
+    }
+}
+
+/// 一局游戏的结果，由各游戏在结束时产生，交给主程序记录
+#[derive(Clone)]
+pub struct GameResult {
+    pub game: GameKind,
+    pub difficulty: GameDifficulty,
+    pub won: bool,
+    pub time_secs: Option<u64>, // 用时（秒）
+    pub moves: Option<usize>,   // 移动次数 / 尝试次数
+    pub net_chips: i64,         // 德州扑克 / 21点本场净赢筹码，斗地主 / 锄大地本场净得分
+    pub biggest_pot: u32,       // 德州扑克本场最大底池 / 21点单局最大赢额 / 斗地主 / 锄大地单局最高得分
+    pub final_score: Option<i64>, // 最终得分（红心大战罚分、克朗代克纸牌计分）
+    pub moon_shots: u32,        // 红心大战本场全收次数
+    pub code_rules: Option<CodeRules>, // 猜数字的密码规则
+}
+
+impl GameResult {
+    /// 创建一局结果，其余字段按游戏补充
+    pub fn new(game: GameKind, difficulty: GameDifficulty, won: bool) -> Self {
+        Self {
+            game,
+            difficulty,
+            won,
+            time_secs: None,
+            moves: None,
+            net_chips: 0,
+            biggest_pot: 0,
+            final_score: None,
+            moon_shots: 0,
+            code_rules: None,
+        }
+    }
+}
+
+/// 某个游戏某个难度的统计数据
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DifficultyStats {
+    pub played: u32,                  // 游戏局数
+    pub wins: u32,                    // 获胜局数
+    pub best_time_secs: Option<u64>,  // 获胜的最短用时
+    pub fewest_moves: Option<usize>,  // 获胜的最少移动 / 尝试次数
+    pub net_chips: i64,               // 累计净赢筹码
+    pub biggest_pot: u32,             // 最大底池 / 单局最大赢额
+    pub lowest_score: Option<i64>,    // 获胜的最低最终得分（红心大战）
+    pub most_moon_shots: u32,         // 单场最多全收次数（红心大战）
+}
+
+impl DifficultyStats {
+    /// 记录一局结果
+    fn record(&mut self, result: &GameResult) {
+        self.played += 1;
+        self.net_chips += result.net_chips;
+        self.biggest_pot = self.biggest_pot.max(result.biggest_pot);
+        self.most_moon_shots = self.most_moon_shots.max(result.moon_shots);
+        if result.won {
+            self.wins += 1;
+            if let Some(time) = result.time_secs {
+                self.best_time_secs = Some(self.best_time_secs.map_or(time, |best| best.min(time)));
+            }
+            if let Some(moves) = result.moves {
+                self.fewest_moves = Some(self.fewest_moves.map_or(moves, |best| best.min(moves)));
+            }
+            if let Some(score) = result.final_score
+                && result.game == GameKind::Hearts
+            {
+                self.lowest_score = Some(self.lowest_score.map_or(score, |best| best.min(score)));
+            }
+        }
+    }
+}
+
+/// 玩家档案
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlayerProfile {
+    stats: BTreeMap<GameKind, BTreeMap<GameDifficulty, DifficultyStats>>,
+}
+
+impl PlayerProfile {
+    /// 读取档案，文件不存在或损坏时使用空档案
+    pub fn load() -> Self {
+        load_data_file(PROFILE_FILE)
+    }
+
+    /// 保存档案
+    pub fn save(&self) -> std::io::Result<()> {
+        save_data_file(PROFILE_FILE, self)
+    }
+
+    /// 记录一局结果
+    pub fn record(&mut self, result: &GameResult) {
+        self.stats
+            .entry(result.game)
+            .or_default()
+            .entry(result.difficulty)
+            .or_default()
+            .record(result);
+    }
+
+    /// 显示统计界面，返回是否要返回主菜单
+    pub fn show_statistics(&self, ui: &mut egui::Ui) -> bool {
+        let mut return_to_menu = false;
+
+        ui.vertical_centered(|ui| {
+            ui.add_space(20.0);
+            ui.heading("统计数据");
+            ui.add_space(20.0);
+
+            egui::ScrollArea::vertical().max_height(ui.available_height() - 80.0).show(ui, |ui| {
+                for game in GameKind::ALL {
+                    ui.colored_label(egui::Color32::LIGHT_BLUE, game.label());
+                    ui.add_space(5.0);
+                    match self.stats.get(&game).filter(|stats| !stats.is_empty()) {
+                        Some(stats) => Self::show_game_stats(ui, game, stats),
+                        None => {
+                            ui.label("还没有游戏记录");
+                        }
+                    }
+                    ui.add_space(20.0);
+                }
+            });
+
+            ui.add_space(10.0);
+            if ui.add_sized([150.0, 40.0], egui::Button::new("返回主菜单")).clicked() {
+                return_to_menu = true;
+            }
+        });
+
+        return_to_menu
+    }
+
+    /// 显示一款游戏各难度的统计表格
+    fn show_game_stats(ui: &mut egui::Ui, game: GameKind, stats: &BTreeMap<GameDifficulty, DifficultyStats>) {
+        let format_time = |time: Option<u64>| time.map_or("-".to_string(), |secs| format!("{}秒", secs));
+        let format_moves = |moves: Option<usize>| moves.map_or("-".to_string(), |count| count.to_string());
+
+        egui::Grid::new(game.label()).striped(true).spacing(egui::vec2(20.0, 4.0)).show(ui, |ui| {
+            ui.strong("难度");
+            ui.strong("局数");
+            ui.strong("胜场");
+            ui.strong("胜率");
+            match game {
+                GameKind::Memory | GameKind::Klondike | GameKind::FreeCell | GameKind::Spider => {
+                    ui.strong("最短用时");
+                    ui.strong("最少移动");
+                }
+                GameKind::GuessNumber => {
+                    ui.strong("最短用时");
+                    ui.strong("最少尝试");
+                }
+                GameKind::TexasHoldem => {
+                    ui.strong("净赢筹码");
+                    ui.strong("最大底池");
+                }
+                GameKind::Blackjack => {
+                    ui.strong("净赢筹码");
+                    ui.strong("单局最大赢额");
+                }
+                GameKind::DouDizhu | GameKind::BigTwo => {
+                    ui.strong("净得分");
+                    ui.strong("单局最高得分");
+                }
+                GameKind::Hearts => {
+                    ui.strong("最低得分");
+                    ui.strong("单场最多全收");
+                }
+            }
+            ui.end_row();
+
+            for (difficulty, entry) in stats {
+                ui.label(difficulty.label());
+                ui.label(entry.played.to_string());
+                ui.label(entry.wins.to_string());
+                ui.label(format!("{:.0}%", entry.wins as f32 * 100.0 / entry.played.max(1) as f32));
+                match game {
+                    GameKind::Memory
+                    | GameKind::GuessNumber
+                    | GameKind::Klondike
+                    | GameKind::FreeCell
+                    | GameKind::Spider => {
+                        ui.label(format_time(entry.best_time_secs));
+                        ui.label(format_moves(entry.fewest_moves));
+                    }
+                    GameKind::TexasHoldem | GameKind::Blackjack | GameKind::DouDizhu | GameKind::BigTwo => {
+                        ui.label(entry.net_chips.to_string());
+                        ui.label(entry.biggest_pot.to_string());
+                    }
+                    GameKind::Hearts => {
+                        ui.label(entry.lowest_score.map_or("-".to_string(), |score| score.to_string()));
+                        ui.label(entry.most_moon_shots.to_string());
+                    }
+                }
+                ui.end_row();
+            }
+        });
+    }
+}
@@ -0,0 +1,226 @@
+// poker_hand.rs - 五张牌的牌型判断和比较（德州扑克比牌、锄大地的五张牌型共用）
+
+/// 手牌结果
+pub struct HandResult {
+    pub hand_strength: HandStrength,
+    pub high_cards: Vec<u8>, // 同牌型时依次比较的关键点数
+}
+
+/// 手牌强度枚举
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum HandStrength {
+    HighCard,
+    OnePair,
+    TwoPair,
+    ThreeOfAKind,
+    Straight,
+    Flush,
+    FullHouse,
+    FourOfAKind,
+    StraightFlush,
+}
+
+impl HandStrength {
+    pub fn to_u8(self) -> u8 {
+        match self {
+            HandStrength::HighCard => 0,
+            HandStrength::OnePair => 1,
+            HandStrength::TwoPair => 2,
+            HandStrength::ThreeOfAKind => 3,
+            HandStrength::Straight => 4,
+            HandStrength::Flush => 5,
+            HandStrength::FullHouse => 6,
+            HandStrength::FourOfAKind => 7,
+            HandStrength::StraightFlush => 8,
+        }
+    }
+
+    pub fn name(self) -> String {
+        match self {
+            HandStrength::HighCard => "高牌".to_string(),
+            HandStrength::OnePair => "一对".to_string(),
+            HandStrength::TwoPair => "两对".to_string(),
+            HandStrength::ThreeOfAKind => "三条".to_string(),
+            HandStrength::Straight => "顺子".to_string(),
+            HandStrength::Flush => "同花".to_string(),
+            HandStrength::FullHouse => "葫芦".to_string(),
+            HandStrength::FourOfAKind => "四条".to_string(),
+            HandStrength::StraightFlush => "同花顺".to_string(),
+        }
+    }
+}
+
+/// 评估5张牌的手牌，每张牌为 (点数, 花色)
+pub fn evaluate_five_card_hand(cards: &[(u8, u8)]) -> HandResult {
+    let mut ranks: Vec<u8> = cards.iter().map(|&(rank, _)| rank).collect();
+    let suits: Vec<u8> = cards.iter().map(|&(_, suit)| suit).collect();
+
+    ranks.sort_by(|a, b| b.cmp(a)); // 降序排列
+
+    let is_flush = suits.iter().all(|&s| s == suits[0]);
+    let is_straight = is_straight(&ranks);
+
+    // 检查同花顺
+    if is_flush && is_straight {
+        return HandResult {
+            hand_strength: HandStrength::StraightFlush,
+            high_cards: vec![ranks[0]],
+        };
+    }
+
+    // 检查四条
+    if let Some(quad_rank) = has_n_of_a_kind(&ranks, 4) {
+        let kicker = *ranks.iter().find(|&&r| r != quad_rank).unwrap_or(&1);
+        return HandResult {
+            hand_strength: HandStrength::FourOfAKind,
+            high_cards: vec![quad_rank, kicker],
+        };
+    }
+
+    // 检查葫芦
+    if let (Some(three_rank), Some(two_rank)) = (has_n_of_a_kind(&ranks, 3), has_n_of_a_kind(&ranks, 2))
+        && three_rank != two_rank
+    {
+        return HandResult {
+            hand_strength: HandStrength::FullHouse,
+            high_cards: vec![three_rank, two_rank],
+        };
+    }
+
+    // 检查同花
+    if is_flush {
+        return HandResult {
+            hand_strength: HandStrength::Flush,
+            high_cards: ranks,
+        };
+    }
+
+    // 检查顺子
+    if is_straight {
+        return HandResult {
+            hand_strength: HandStrength::Straight,
+            high_cards: vec![ranks[0]],
+        };
+    }
+
+    // 检查三条
+    if let Some(three_rank) = has_n_of_a_kind(&ranks, 3) {
+        let mut kickers: Vec<u8> = ranks.iter().filter(|&&r| r != three_rank).cloned().collect();
+        kickers.sort_by(|a, b| b.cmp(a));
+        kickers.truncate(2);
+        kickers.insert(0, three_rank);
+        return HandResult {
+            hand_strength: HandStrength::ThreeOfAKind,
+            high_cards: kickers,
+        };
+    }
+
+    // 检查两对
+    if let Some(pairs) = get_pairs(&ranks)
+        && pairs.len() >= 2
+    {
+        let mut high_cards = vec![pairs[0], pairs[1]];
+        let kicker = *ranks.iter().find(|&&r| r != pairs[0] && r != pairs[1]).unwrap_or(&1);
+        high_cards.push(kicker);
+        return HandResult {
+            hand_strength: HandStrength::TwoPair,
+            high_cards,
+        };
+    }
+
+    // 检查一对
+    if let Some(pair_rank) = has_n_of_a_kind(&ranks, 2) {
+        let mut kickers: Vec<u8> = ranks.iter().filter(|&&r| r != pair_rank).cloned().collect();
+        kickers.sort_by(|a, b| b.cmp(a));
+        kickers.truncate(3);
+        kickers.insert(0, pair_rank);
+        return HandResult {
+            hand_strength: HandStrength::OnePair,
+            high_cards: kickers,
+        };
+    }
+
+    // 高牌
+    HandResult {
+        hand_strength: HandStrength::HighCard,
+        high_cards: ranks,
+    }
+}
+
+/// 检查是否是顺子
+fn is_straight(ranks: &[u8]) -> bool {
+    let mut sorted_ranks = ranks.to_vec();
+    sorted_ranks.sort();
+    sorted_ranks.dedup();
+
+    if sorted_ranks.len() < 5 {
+        return false;
+    }
+
+    // 检查普通顺子
+    for i in 0..=sorted_ranks.len() - 5 {
+        if sorted_ranks[i + 4] - sorted_ranks[i] == 4 {
+            return true;
+        }
+    }
+
+    // 检查A-2-3-4-5和10-J-Q-K-A顺子
+    let has_all = |wanted: [u8; 5]| wanted.iter().all(|rank| sorted_ranks.contains(rank));
+    has_all([1, 2, 3, 4, 5]) || has_all([1, 10, 11, 12, 13])
+}
+
+/// 检查是否有N张相同点数的牌
+fn has_n_of_a_kind(ranks: &[u8], n: usize) -> Option<u8> {
+    let mut count_map = std::collections::HashMap::new();
+    for &rank in ranks {
+        *count_map.entry(rank).or_insert(0) += 1;
+    }
+
+    for (&rank, &count) in &count_map {
+        if count == n {
+            return Some(rank);
+        }
+    }
+    None
+}
+
+/// 获取所有对子
+fn get_pairs(ranks: &[u8]) -> Option<Vec<u8>> {
+    let mut count_map = std::collections::HashMap::new();
+    for &rank in ranks {
+        *count_map.entry(rank).or_insert(0) += 1;
+    }
+
+    let mut pairs: Vec<u8> = count_map
+        .iter()
+        .filter(|&(_, &count)| count >= 2)
+        .map(|(&rank, _)| rank)
+        .collect();
+
+    pairs.sort_by(|a, b| b.cmp(a));
+
+    if pairs.is_empty() {
+        None
+    } else {
+        Some(pairs)
+    }
+}
+
+/// 比较两手牌的结果
+pub fn compare_hand_results(hand1: &HandResult, hand2: &HandResult) -> std::cmp::Ordering {
+    // 先比较手牌强度
+    let strength_cmp = hand1.hand_strength.to_u8().cmp(&hand2.hand_strength.to_u8());
+    if strength_cmp != std::cmp::Ordering::Equal {
+        return strength_cmp;
+    }
+
+    // 相同手牌强度时，比较关键牌
+    for (h1, h2) in hand1.high_cards.iter().zip(hand2.high_cards.iter()) {
+        let card_cmp = h1.cmp(h2);
+        if card_cmp != std::cmp::Ordering::Equal {
+            return card_cmp;
+        }
+    }
+
+    std::cmp::Ordering::Equal
+}
@@ -0,0 +1,114 @@
+// save_game.rs - 未完成游戏的存档：中途返回主菜单或关闭窗口时保存，主菜单“继续游戏”恢复
+use crate::animation::CardAnimationKind;
+use crate::card::Card;
+use crate::game1::MemorySave;
+use crate::game10::HeartsSave;
+use crate::game2::GuessNumberSave;
+use crate::game3::TexasHoldemSave;
+use crate::game4::BlackjackSave;
+use crate::game5::KlondikeSave;
+use crate::game6::FreeCellSave;
+use crate::game7::SpiderSave;
+use crate::game8::DouDizhuSave;
+use crate::game9::BigTwoSave;
+use crate::player_profile::{self, GameKind};
+use crate::util;
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+/// 存档文件名
+const SESSION_FILE: &str = "session.json";
+/// 卡片背面图片
+const CARD_BACK_PATH: &str = "assets/card_back/default.png";
+
+/// 卡片的存档数据（纹理在恢复时重新加载）
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct SavedCard {
+    pub id: usize,
+    pub rank: u8,
+    pub suit: u8,
+    pub is_face_up: bool,
+}
+
+impl SavedCard {
+    /// 记录一张卡片，正在飞走（已配对）的卡片返回 None
+    pub fn from_card(card: &Card) -> Option<Self> {
+        if card.animation.is_some_and(|anim| anim.kind == CardAnimationKind::FlyAway) {
+            return None;
+        }
+        Some(Self {
+            id: card.id,
+            rank: card.rank,
+            suit: card.suit,
+            is_face_up: card.is_face_up,
+        })
+    }
+
+    /// 重新创建卡片，保持存档时的正反面（不播放翻牌动画）
+    pub fn restore(&self, ctx: &egui::Context, face_path: &str) -> Option<Card> {
+        let mut card = Card::new(ctx, self.id, self.rank, self.suit, CARD_BACK_PATH, face_path).ok()?;
+        card.is_face_up = self.is_face_up;
+        Some(card)
+    }
+}
+
+/// 记录一组卡片
+pub fn save_cards<'a>(cards: impl IntoIterator<Item = &'a Card>) -> Vec<SavedCard> {
+    cards.into_iter().filter_map(SavedCard::from_card).collect()
+}
+
+/// 重新创建一组普通扑克牌
+pub fn restore_cards(ctx: &egui::Context, cards: &[SavedCard]) -> Vec<Card> {
+    cards
+        .iter()
+        .filter_map(|saved| saved.restore(ctx, &util::get_card_image_path(saved.rank, saved.suit)))
+        .collect()
+}
+
+/// 一局未完成的游戏
+#[derive(Serialize, Deserialize)]
+pub enum SavedSession {
+    Memory(MemorySave),
+    GuessNumber(GuessNumberSave),
+    TexasHoldem(TexasHoldemSave),
+    Blackjack(BlackjackSave),
+    Klondike(KlondikeSave),
+    FreeCell(FreeCellSave),
+    Spider(SpiderSave),
+    DouDizhu(DouDizhuSave),
+    BigTwo(BigTwoSave),
+    Hearts(HeartsSave),
+}
+
+impl SavedSession {
+    /// 存档所属的游戏
+    pub fn game(&self) -> GameKind {
+        match self {
+            SavedSession::Memory(_) => GameKind::Memory,
+            SavedSession::GuessNumber(_) => GameKind::GuessNumber,
+            SavedSession::TexasHoldem(_) => GameKind::TexasHoldem,
+            SavedSession::Blackjack(_) => GameKind::Blackjack,
+            SavedSession::Klondike(_) => GameKind::Klondike,
+            SavedSession::FreeCell(_) => GameKind::FreeCell,
+            SavedSession::Spider(_) => GameKind::Spider,
+            SavedSession::DouDizhu(_) => GameKind::DouDizhu,
+            SavedSession::BigTwo(_) => GameKind::BigTwo,
+            SavedSession::Hearts(_) => GameKind::Hearts,
+        }
+    }
+
+    /// 读取存档，没有存档或存档损坏时返回 None
+    pub fn load() -> Option<Self> {
+        player_profile::load_data_file(SESSION_FILE)
+    }
+
+    /// 保存存档（只保留最近一局）
+    pub fn save(&self) -> std::io::Result<()> {
+        player_profile::save_data_file(SESSION_FILE, self)
+    }
+
+    /// 删除存档
+    pub fn clear() -> std::io::Result<()> {
+        player_profile::remove_data_file(SESSION_FILE)
+    }
+}
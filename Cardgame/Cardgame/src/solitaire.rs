@@ -0,0 +1,122 @@
+// solitaire.rs - 接龙类游戏的公共部分：卡片编号、整副牌加载、牌列间距和拖放绘制
+use eframe::egui;
+use crate::card::{BLACK_JOKER, Card, RED_JOKER};
+use crate::util::get_card_image_path;
+
+/// 小王、大王的卡片编号（0..52 为普通牌）
+pub const BLACK_JOKER_ID: usize = 52;
+pub const RED_JOKER_ID: usize = 53;
+/// 卡片背面图片
+pub const CARD_BACK_PATH: &str = "assets/card_back/default.png";
+/// 卡片大小
+pub const CARD_W: f32 = 86.0;
+pub const CARD_H: f32 = 120.0;
+/// 牌列中背面 / 正面卡片的叠放间距（列太长时按比例压缩）
+pub const HIDDEN_OFFSET: f32 = 12.0;
+pub const SHOWN_OFFSET: f32 = 26.0;
+
+/// 由卡片编号得到点数（编号 = (花色-1)×13 + 点数-1）
+pub fn rank_of(id: usize) -> u8 {
+    (id % 13) as u8 + 1
+}
+
+/// 由卡片编号得到花色
+pub fn suit_of(id: usize) -> u8 {
+    (id / 13) as u8 + 1
+}
+
+/// 由点数和花色得到卡片编号
+pub fn card_id(rank: u8, suit: u8) -> usize {
+    (suit as usize - 1) * 13 + rank as usize - 1
+}
+
+/// 是否为红色花色（红桃、方片）
+pub fn is_red(id: usize) -> bool {
+    matches!(suit_of(id), 2 | 3)
+}
+
+/// 卡片的中文名称，如“红桃7”
+pub fn card_name(id: usize) -> String {
+    let suit = match suit_of(id) {
+        1 => "黑桃",
+        2 => "红桃",
+        3 => "方片",
+        _ => "梅花",
+    };
+    let rank = match rank_of(id) {
+        1 => "A".to_string(),
+        11 => "J".to_string(),
+        12 => "Q".to_string(),
+        13 => "K".to_string(),
+        rank => rank.to_string(),
+    };
+    format!("{}{}", suit, rank)
+}
+
+/// 按牌面编号依次加载卡片纹理（第 i 张的卡片 id 为 i），编号 52、53 为小王、大王，加载失败的牌会缺失
+pub fn load_cards(ctx: &egui::Context, faces: impl IntoIterator<Item = usize>) -> Vec<Card> {
+    faces
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, face)| {
+            let (rank, suit) = match face {
+                BLACK_JOKER_ID => (BLACK_JOKER, 0),
+                RED_JOKER_ID => (RED_JOKER, 0),
+                _ => (rank_of(face), suit_of(face)),
+            };
+            Card::new(ctx, index, rank, suit, CARD_BACK_PATH, &get_card_image_path(rank, suit)).ok()
+        })
+        .collect()
+}
+
+/// 牌列中背面 / 正面卡片的间距，列太长放不下时压缩到可用高度内
+pub fn column_offsets(len: usize, hidden: usize, available: f32) -> (f32, f32) {
+    let gaps = len.saturating_sub(1);
+    let hidden_gaps = hidden.min(gaps) as f32;
+    let shown_gaps = gaps as f32 - hidden_gaps;
+    let needed = hidden_gaps * HIDDEN_OFFSET + shown_gaps * SHOWN_OFFSET;
+    let scale = if needed > available && needed > 0.0 {
+        (available / needed).max(0.2)
+    } else {
+        1.0
+    };
+    (HIDDEN_OFFSET * scale, SHOWN_OFFSET * scale)
+}
+
+/// 正在拖动的一叠牌
+pub struct DragState<P> {
+    pub from: P,
+    pub index: usize,            // 拖动的第一张在这叠牌中的位置
+    pub grab_offset: egui::Vec2, // 按下位置相对第一张牌左上角的偏移
+}
+
+impl<P: PartialEq> DragState<P> {
+    /// 某叠牌的第 index 张是否正被拖动
+    pub fn contains(drag: &Option<Self>, pile: P, index: usize) -> bool {
+        drag.as_ref().is_some_and(|drag| drag.from == pile && index >= drag.index)
+    }
+
+    /// 拖动的第一张牌当前的位置
+    pub fn rect(&self, pointer: egui::Pos2) -> egui::Rect {
+        egui::Rect::from_min_size(pointer - self.grab_offset, egui::vec2(CARD_W, CARD_H))
+    }
+}
+
+/// 绘制空牌位的边框和提示文字
+pub fn paint_slot(painter: &egui::Painter, rect: egui::Rect, label: &str) {
+    let color = egui::Color32::from_white_alpha(140);
+    painter.rect_stroke(rect, 6.0, egui::Stroke::new(1.5, color), egui::StrokeKind::Inside);
+    if !label.is_empty() {
+        painter.text(rect.center(), egui::Align2::CENTER_CENTER, label, egui::FontId::proportional(28.0), color);
+    }
+}
+
+/// 绘制提示时高亮卡片的边框
+pub fn paint_highlight(painter: &egui::Painter, rect: egui::Rect) {
+    painter.rect_stroke(rect.expand(2.0), 6.0, egui::Stroke::new(3.0, egui::Color32::GOLD), egui::StrokeKind::Outside);
+}
+
+/// 绘制拖动中卡片的画笔（最上层，不被其他卡片遮挡）
+pub fn drag_painter(ctx: &egui::Context) -> egui::Painter {
+    ctx.layer_painter(egui::LayerId::new(egui::Order::Tooltip, egui::Id::new("solitaire_drag")))
+}
@@ -0,0 +1,293 @@
+// timer.rs - 通用倒计时（支持暂停/继续、加时/扣时、预警阈值）
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// 默认预警阈值：剩余30秒变黄
+const DEFAULT_WARNING: Duration = Duration::from_secs(30);
+/// 默认紧急阈值：剩余10秒变红
+const DEFAULT_CRITICAL: Duration = Duration::from_secs(10);
+
+/// 剩余时间的预警等级
+#[derive(PartialEq, Clone, Copy)]
+pub enum TimerLevel {
+    Normal,   // 正常
+    Warning,  // 预警
+    Critical, // 紧急
+}
+
+/// 倒计时的存档数据（用于保存和恢复未完成的游戏）
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct TimerSnapshot {
+    total: Duration,
+    elapsed: Duration,
+    warning: Duration,
+    critical: Duration,
+}
+
+/// 游戏倒计时
+#[derive(Clone)]
+pub struct GameTimer {
+    total: Duration,                // 总时间（含加时和扣时）
+    elapsed_before: Duration,       // 上次暂停前累计已用时间
+    running_since: Option<Instant>, // 正在计时的起点，None 表示已暂停
+    warning: Duration,              // 预警阈值
+    critical: Duration,             // 紧急阈值
+}
+
+impl GameTimer {
+    /// 创建并立即开始计时
+    pub fn start(total: Duration) -> Self {
+        Self {
+            total,
+            elapsed_before: Duration::ZERO,
+            running_since: Some(Instant::now()),
+            warning: DEFAULT_WARNING,
+            critical: DEFAULT_CRITICAL,
+        }
+    }
+
+    /// 创建并立即开始正计时（没有时间限制，只统计已用时间）
+    pub fn stopwatch() -> Self {
+        Self::start(Duration::MAX)
+    }
+
+    /// 设置预警阈值
+    pub fn with_thresholds(mut self, warning: Duration, critical: Duration) -> Self {
+        self.warning = warning;
+        self.critical = critical;
+        self
+    }
+
+    /// 生成存档数据
+    pub fn snapshot(&self) -> TimerSnapshot {
+        TimerSnapshot {
+            total: self.total,
+            elapsed: self.elapsed(),
+            warning: self.warning,
+            critical: self.critical,
+        }
+    }
+
+    /// 从存档恢复，恢复后处于暂停状态，等待玩家继续
+    pub fn restore(snapshot: TimerSnapshot) -> Self {
+        Self {
+            total: snapshot.total,
+            elapsed_before: snapshot.elapsed,
+            running_since: None,
+            warning: snapshot.warning,
+            critical: snapshot.critical,
+        }
+    }
+
+    /// 已用时间（不含暂停时间）
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed_before + self.running_since.map_or(Duration::ZERO, |since| since.elapsed())
+    }
+
+    /// 剩余时间
+    pub fn remaining(&self) -> Duration {
+        self.total.saturating_sub(self.elapsed())
+    }
+
+    /// 时间是否已经用完
+    pub fn is_expired(&self) -> bool {
+        self.remaining().is_zero()
+    }
+
+    /// 是否处于暂停状态
+    pub fn is_paused(&self) -> bool {
+        self.running_since.is_none()
+    }
+
+    /// 暂停计时
+    pub fn pause(&mut self) {
+        if let Some(since) = self.running_since.take() {
+            self.elapsed_before += since.elapsed();
+        }
+    }
+
+    /// 继续计时
+    pub fn resume(&mut self) {
+        if self.running_since.is_none() {
+            self.running_since = Some(Instant::now());
+        }
+    }
+
+    /// 切换暂停/继续
+    pub fn toggle_pause(&mut self) {
+        if self.is_paused() {
+            self.resume();
+        } else {
+            self.pause();
+        }
+    }
+
+    /// 加时（正计时的总时间为 Duration::MAX，加时后保持不变）
+    pub fn add_time(&mut self, bonus: Duration) {
+        self.total = self.total.saturating_add(bonus);
+    }
+
+    /// 扣时
+    pub fn subtract_time(&mut self, penalty: Duration) {
+        self.total = self.total.saturating_sub(penalty);
+    }
+
+    /// 当前的预警等级
+    pub fn level(&self) -> TimerLevel {
+        let remaining = self.remaining();
+        if remaining <= self.critical {
+            TimerLevel::Critical
+        } else if remaining <= self.warning {
+            TimerLevel::Warning
+        } else {
+            TimerLevel::Normal
+        }
+    }
+
+    /// 剩余时间的显示颜色
+    pub fn color(&self) -> egui::Color32 {
+        match self.level() {
+            TimerLevel::Normal => egui::Color32::BLACK,
+            TimerLevel::Warning => egui::Color32::YELLOW,
+            TimerLevel::Critical => egui::Color32::RED,
+        }
+    }
+
+    /// 剩余时间的显示文字（超过一分钟显示分秒）
+    pub fn remaining_text(&self) -> String {
+        format_duration(self.remaining())
+    }
+
+    /// 已用时间的显示文字（超过一分钟显示分秒）
+    pub fn elapsed_text(&self) -> String {
+        format_duration(self.elapsed())
+    }
+
+    /// 处理 Esc 键和窗口失去焦点：Esc 切换暂停，失去焦点自动暂停
+    pub fn handle_pause_input(&mut self, ctx: &egui::Context) {
+        let (escape, focused) = ctx.input(|input| (input.key_pressed(egui::Key::Escape), input.focused));
+        if escape {
+            self.toggle_pause();
+        } else if !focused {
+            self.pause();
+        }
+        // 计时中需要持续刷新倒计时显示
+        if !self.is_paused() {
+            ctx.request_repaint_after(Duration::from_millis(100));
+        }
+    }
+}
+
+/// 时间的显示文字（超过一分钟显示分秒）
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    if seconds >= 60 {
+        format!("{}分{}秒", seconds / 60, seconds % 60)
+    } else {
+        format!("{}秒", seconds)
+    }
+}
+
+/// 显示暂停遮罩（覆盖整个窗口并拦截点击），返回是否点击了“继续游戏”
+pub fn show_pause_overlay(ctx: &egui::Context) -> bool {
+    let screen = ctx.content_rect();
+    let mut resume = false;
+    egui::Area::new(egui::Id::new("pause_overlay"))
+        .order(egui::Order::Foreground)
+        .fixed_pos(screen.min)
+        .show(ctx, |ui| {
+            // 铺满窗口的半透明背景，同时吃掉下层的点击
+            ui.allocate_rect(screen, egui::Sense::click());
+            ui.painter().rect_filled(screen, 0.0, egui::Color32::from_black_alpha(180));
+
+            let panel = egui::Rect::from_center_size(screen.center(), egui::vec2(240.0, 140.0));
+            ui.scope_builder(egui::UiBuilder::new().max_rect(panel), |ui| {
+                ui.vertical_centered(|ui| {
+                    ui.label(egui::RichText::new("游戏已暂停").size(28.0).color(egui::Color32::WHITE));
+                    ui.add_space(20.0);
+                    if ui.add_sized([150.0, 40.0], egui::Button::new("继续游戏")).clicked() {
+                        resume = true;
+                    }
+                    ui.add_space(5.0);
+                    ui.label(egui::RichText::new("按 Esc 继续").color(egui::Color32::LIGHT_GRAY));
+                });
+            });
+        });
+    resume
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 处于暂停状态、已用时间固定的倒计时
+    fn paused(total_secs: u64, elapsed_secs: u64) -> GameTimer {
+        GameTimer::restore(TimerSnapshot {
+            total: Duration::from_secs(total_secs),
+            elapsed: Duration::from_secs(elapsed_secs),
+            warning: DEFAULT_WARNING,
+            critical: DEFAULT_CRITICAL,
+        })
+    }
+
+    #[test]
+    fn paused_timer_does_not_advance() {
+        let mut timer = paused(60, 20);
+        assert!(timer.is_paused());
+        std::thread::sleep(Duration::from_millis(20));
+        assert_eq!(timer.elapsed(), Duration::from_secs(20));
+        assert_eq!(timer.remaining(), Duration::from_secs(40));
+
+        timer.resume();
+        assert!(!timer.is_paused());
+        std::thread::sleep(Duration::from_millis(20));
+        timer.pause();
+        let elapsed = timer.elapsed();
+        assert!(elapsed >= Duration::from_millis(20_020));
+        std::thread::sleep(Duration::from_millis(20));
+        assert_eq!(timer.elapsed(), elapsed);
+
+        timer.toggle_pause();
+        assert!(!timer.is_paused());
+        timer.toggle_pause();
+        assert!(timer.is_paused());
+    }
+
+    #[test]
+    fn add_and_subtract_time_change_remaining() {
+        let mut timer = paused(60, 20);
+        timer.add_time(Duration::from_secs(15));
+        assert_eq!(timer.remaining(), Duration::from_secs(55));
+        timer.subtract_time(Duration::from_secs(25));
+        assert_eq!(timer.remaining(), Duration::from_secs(30));
+        // 扣时超过剩余时间时直接到时
+        timer.subtract_time(Duration::from_secs(600));
+        assert_eq!(timer.remaining(), Duration::ZERO);
+        assert!(timer.is_expired());
+    }
+
+    #[test]
+    fn stopwatch_add_time_saturates() {
+        let mut timer = GameTimer::stopwatch();
+        timer.add_time(Duration::from_secs(10));
+        assert!(!timer.is_expired());
+        assert!(timer.level() == TimerLevel::Normal);
+    }
+
+    #[test]
+    fn level_follows_thresholds() {
+        assert!(paused(60, 0).level() == TimerLevel::Normal);
+        assert!(paused(60, 30).level() == TimerLevel::Warning);
+        assert!(paused(60, 31).level() == TimerLevel::Warning);
+        assert!(paused(60, 50).level() == TimerLevel::Critical);
+        assert!(paused(60, 60).level() == TimerLevel::Critical);
+
+        let custom = paused(20, 5).with_thresholds(Duration::from_secs(10), Duration::from_secs(3));
+        assert!(custom.level() == TimerLevel::Normal);
+        let custom = paused(20, 12).with_thresholds(Duration::from_secs(10), Duration::from_secs(3));
+        assert!(custom.level() == TimerLevel::Warning);
+        let custom = paused(20, 18).with_thresholds(Duration::from_secs(10), Duration::from_secs(3));
+        assert!(custom.level() == TimerLevel::Critical);
+    }
+}
@@ -1,5 +1,13 @@
-  /// 根据rank和suit获取图片文件名
+use crate::card::{BLACK_JOKER, RED_JOKER};
+
+  /// 根据rank和suit获取图片文件名（小王、大王不看花色）
 pub fn get_card_image_path(rank: u8, suit: u8) -> String {
+        match rank {
+            BLACK_JOKER => return "assets/card_face/JOKER-B.png".to_string(),
+            RED_JOKER => return "assets/card_face/JOKER-A.png".to_string(),
+            _ => {}
+        }
+
         let suit_name = match suit {
             1 => "Spade",   // 黑桃
             2 => "Heart",   // 红桃